@@ -2,21 +2,16 @@
 # 任务管理器 Shell 集成脚本
 # 用法: source ~/.config/tasks-prompt.sh
 
-# 获取任务统计
+# 获取任务统计（今日截止、逾期、番茄钟剩余时间）
+# prompt-segment 只读取缓存文件，不打开数据库，不会拖慢提示符
 get_task_stats() {
     if ! command -v tasks &> /dev/null; then
         return
     fi
 
-    local task_count=$(tasks list 2>/dev/null | wc -l)
-    local urgent_count=$(tasks list 2>/dev/null | grep -c '🔴' || echo 0)
-
-    if [ "$task_count" -gt 0 ]; then
-        echo -n "📋 $task_count"
-        if [ "$urgent_count" -gt 0 ]; then
-            echo -n " 🔴 $urgent_count"
-        fi
-        echo -n " "
+    local segment=$(tasks prompt-segment 2>/dev/null)
+    if [ -n "$segment" ]; then
+        echo -n "$segment "
     fi
 }
 
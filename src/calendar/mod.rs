@@ -0,0 +1,213 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::error::{Error, Result};
+use crate::models::{BusyEvent, Priority, Task, TaskStatus};
+
+/// 从 .ics（iCalendar）文本中解析忙碌时段
+///
+/// 只读取 VEVENT 的 UID/SUMMARY/DTSTART/DTEND，忽略标记为空闲（TRANSP:TRANSPARENT）
+/// 和已取消的事件。带 TZID 参数的时间按本地时区处理，全天事件占用当天整天。
+/// 不展开重复规则（RRULE），需要时请在日历软件中导出展开后的事件。
+pub fn parse_ics(content: &str) -> Result<Vec<BusyEvent>> {
+    if !content.contains("BEGIN:VCALENDAR") {
+        return Err(Error::Validation("不是有效的 iCalendar 文件".to_string()));
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<EventBuilder> = None;
+
+    for line in unfold(content) {
+        let (name, params, value) = match split_property(&line) {
+            Some(parts) => parts,
+            None => continue,
+        };
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", _) if value == "VEVENT" => current = Some(EventBuilder::default()),
+            ("END", Some(_)) if value == "VEVENT" => {
+                if let Some(event) = current.take().and_then(EventBuilder::build) {
+                    events.push(event);
+                }
+            }
+            ("UID", Some(event)) => event.uid = Some(value),
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape(&value)),
+            ("DTSTART", Some(event)) => event.start = parse_time(&params, &value),
+            ("DTEND", Some(event)) => event.end = parse_time(&params, &value),
+            ("TRANSP", Some(event)) => event.free = value == "TRANSPARENT",
+            ("STATUS", Some(event)) => event.cancelled = value == "CANCELLED",
+            _ => {}
+        }
+    }
+
+    events.sort_by_key(|e| e.start);
+    Ok(events)
+}
+
+/// 把有截止时间的任务导出为 iCalendar 文本（tasks export-ics）
+///
+/// 每个任务是一个在截止时间结束的 VEVENT，时长为预计耗时（没有时为 30 分钟），
+/// 设置了提醒时间的未完成任务带一个 VALARM。UID 由任务 ID 和创建时间组成，
+/// 重复导入同一个日历时日历软件会更新而不是重复添加事件。
+pub fn to_ics(tasks: &[Task]) -> String {
+    let stamp = format_utc(Utc::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//terminator-task//tasks//ZH".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Tasks".to_string(),
+    ];
+
+    for task in tasks {
+        let due = match task.due_date {
+            Some(due) => due,
+            None => continue,
+        };
+        let start = due - chrono::Duration::minutes(task.estimate_minutes.unwrap_or(30).max(1));
+        let completed = task.status == TaskStatus::Completed;
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:task-{}-{}@terminator-task",
+            task.id.unwrap_or(0),
+            task.created_at.timestamp()
+        ));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", format_utc(start)));
+        lines.push(format!("DTEND:{}", format_utc(due)));
+        let summary = if completed { format!("✓ {}", task.title) } else { task.title.clone() };
+        lines.push(format!("SUMMARY:{}", escape(&summary)));
+        if let Some(description) = task.description.as_deref().filter(|d| !d.is_empty()) {
+            lines.push(format!("DESCRIPTION:{}", escape(description)));
+        }
+        if !task.tags.is_empty() {
+            let tags: Vec<String> = task.tags.iter().map(|t| escape(t)).collect();
+            lines.push(format!("CATEGORIES:{}", tags.join(",")));
+        }
+        lines.push(format!(
+            "PRIORITY:{}",
+            match task.priority {
+                Priority::High => 1,
+                Priority::Medium => 5,
+                Priority::Low => 9,
+            }
+        ));
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push(format!("LAST-MODIFIED:{}", format_utc(task.updated_at)));
+        if let Some(reminder) = task.reminder_time.filter(|_| !completed) {
+            lines.push("BEGIN:VALARM".to_string());
+            lines.push("ACTION:DISPLAY".to_string());
+            lines.push(format!("TRIGGER;VALUE=DATE-TIME:{}", format_utc(reminder)));
+            lines.push(format!("DESCRIPTION:{}", escape(&task.title)));
+            lines.push("END:VALARM".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line)).collect::<Vec<_>>().join("")
+}
+
+fn format_utc(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// 转义文本值中的特殊字符
+fn escape(text: &str) -> String {
+    text.replace('\r', "")
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// 按规范把超过 75 字节的行折叠（续行以空格开头），行尾为 CRLF；不在 UTF-8 字符中间断开
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += len;
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[derive(Default)]
+struct EventBuilder {
+    uid: Option<String>,
+    summary: Option<String>,
+    start: Option<(DateTime<Utc>, bool)>,
+    end: Option<(DateTime<Utc>, bool)>,
+    free: bool,
+    cancelled: bool,
+}
+
+impl EventBuilder {
+    fn build(self) -> Option<BusyEvent> {
+        if self.free || self.cancelled {
+            return None;
+        }
+        let (start, all_day) = self.start?;
+        // 没有 DTEND 时：全天事件占用一天，其他事件视为一小时
+        let end = match self.end {
+            Some((end, _)) => end,
+            None if all_day => start + chrono::Duration::days(1),
+            None => start + chrono::Duration::hours(1),
+        };
+        Some(BusyEvent {
+            uid: self.uid.unwrap_or_else(|| start.to_rfc3339()),
+            summary: self.summary.unwrap_or_else(|| "忙碌".to_string()),
+            start,
+            end,
+        })
+    }
+}
+
+/// 展开折叠行（以空格或制表符开头的行是上一行的延续）
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// 拆分属性行 `NAME;PARAM=X:VALUE` 为 (NAME, 参数部分, VALUE)
+fn split_property(line: &str) -> Option<(String, String, String)> {
+    let (head, value) = line.split_once(':')?;
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name.to_uppercase(), params.to_uppercase(), value.trim().to_string()))
+}
+
+/// 解析时间，返回 (UTC 时间, 是否为全天)
+fn parse_time(params: &str, value: &str) -> Option<(DateTime<Utc>, bool)> {
+    if params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME") {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        let local = Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest()?;
+        return Some((local.with_timezone(&Utc), true));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((Utc.from_utc_datetime(&naive), false));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let local = Local.from_local_datetime(&naive).earliest()?;
+    Some((local.with_timezone(&Utc), false))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
@@ -0,0 +1,590 @@
+use directories::ProjectDirs;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+use crate::models::{Priority, Task, TaskStatus};
+
+/// 用户配置（config.toml），所有字段均有默认值，文件不存在时使用默认配置
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub icons: IconConfig,
+    /// 任务标题模板（名称 → 标题），支持 {date}/{week}/{month}/{year} 占位符
+    pub templates: BTreeMap<String, String>,
+    /// 自动标签规则，创建任务时按顺序匹配
+    pub rules: Vec<AutoTagRule>,
+    /// 每日/每周任务容量，设定DDL时超出则提示
+    pub capacity: CapacityConfig,
+    /// 键位方案：vim（默认）/ emacs / arrows，界面中可用 :set keymap= 覆盖
+    pub keymap: KeymapPreset,
+    /// 界面模式：vim（默认，模态）/ simple（无模式、无命令行，始终显示操作提示）
+    pub ui_mode: UiMode,
+    /// 轮询/计时间隔，用于在响应速度和耗电之间取舍
+    pub intervals: IntervalsConfig,
+    /// 按预计耗时提醒最晚开始时间
+    pub start_nag: StartNagConfig,
+    /// 相对时间（"3天前" / "3d ago"）的语言：auto（默认，按 LANG 判断）/ zh / en
+    pub locale: Locale,
+    /// taskd 的通知方式
+    pub notifications: NotificationsConfig,
+    /// 界面配色，界面中可用 :theme 覆盖
+    pub theme: ThemeConfig,
+    /// 远程模式（taskd --listen / tasks --remote）的访问令牌
+    pub remote: RemoteConfig,
+}
+
+/// 远程模式的共享令牌：服务器端（taskd --listen）要求请求带 `Authorization: Bearer <token>`，
+/// 客户端（tasks --remote）自动带上。监听非本机地址时必须设置
+/// ```toml
+/// [remote]
+/// token = "a-long-random-string"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RemoteConfig {
+    pub token: Option<String>,
+}
+
+impl RemoteConfig {
+    /// 去掉首尾空白后的令牌，空字符串视为未设置
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref().map(str::trim).filter(|t| !t.is_empty())
+    }
+}
+
+/// 界面配色：选择内置主题（dark / light / solarized），再按需覆盖单个颜色
+/// ```toml
+/// [theme]
+/// preset = "solarized"
+/// accent = "#ffaf00"   # 颜色名（red/yellow/lightblue...）或 #RRGGBB
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// 内置主题名，不设置时为 dark
+    pub preset: Option<String>,
+    /// 标题、强调
+    pub accent: Option<String>,
+    /// 边框
+    pub border: Option<String>,
+    /// 提示文字
+    pub muted: Option<String>,
+    /// 次要文字
+    pub secondary: Option<String>,
+    /// 正文
+    pub text: Option<String>,
+    /// 对话框背景
+    pub background: Option<String>,
+    /// 选中行背景
+    pub selection: Option<String>,
+    /// 成功
+    pub success: Option<String>,
+    /// 错误、逾期
+    pub danger: Option<String>,
+    /// 即将到期
+    pub warning: Option<String>,
+    /// 标签、番茄钟
+    pub highlight: Option<String>,
+    /// 项目、命令行
+    pub info: Option<String>,
+}
+
+/// taskd 的通知方式
+/// ```toml
+/// [notifications]
+/// backend = "auto"                          # auto / desktop / webhook / terminal
+/// webhook_url = "https://ntfy.sh/my-tasks"  # 收到 JSON {"title", "body"} 的 POST
+/// ```
+/// auto：有桌面通知服务时用桌面通知，否则（如通过 SSH 运行、没有 DBus）有 webhook_url 时用 webhook，
+/// 再否则输出到 taskd 所在的终端
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    pub backend: NotifyBackend,
+    pub webhook_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyBackend {
+    #[default]
+    Auto,
+    Desktop,
+    Webhook,
+    Terminal,
+}
+
+/// 有预计耗时和截止时间的任务，在 截止 − 预计耗时 − 缓冲 时提醒开始
+/// ```toml
+/// [start_nag]
+/// buffer_minutes = 30
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StartNagConfig {
+    pub enabled: bool,
+    pub buffer_minutes: i64,
+}
+
+impl Default for StartNagConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            buffer_minutes: 30,
+        }
+    }
+}
+
+/// 轮询/计时间隔（超出范围的值会被限制在安全范围内）
+/// ```toml
+/// [intervals]
+/// poll_ms = 100            # TUI 事件轮询间隔（10 ~ 1000）
+/// tick_ms = 1000           # 番茄钟刷新间隔（200 ~ 5000，计时始终按真实经过的秒数）
+/// status_timeout_secs = 3  # 状态栏消息显示时长（1 ~ 60）
+/// daemon_check_secs = 60   # 守护进程写心跳、归档便签等的间隔（10 ~ 600），提醒按时间准时发送
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct IntervalsConfig {
+    pub poll_ms: u64,
+    pub tick_ms: u64,
+    pub status_timeout_secs: u64,
+    pub daemon_check_secs: u64,
+}
+
+impl Default for IntervalsConfig {
+    fn default() -> Self {
+        Self {
+            poll_ms: 100,
+            tick_ms: 1000,
+            status_timeout_secs: 3,
+            daemon_check_secs: 60,
+        }
+    }
+}
+
+impl IntervalsConfig {
+    pub fn poll(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.poll_ms.clamp(10, 1000))
+    }
+
+    pub fn tick(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.tick_ms.clamp(200, 5000))
+    }
+
+    pub fn status_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.status_timeout_secs.clamp(1, 60))
+    }
+
+    pub fn daemon_check(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.daemon_check_secs.clamp(10, 600))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UiMode {
+    #[default]
+    Vim,
+    Simple,
+}
+
+/// 显示语言（目前只影响相对时间）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Auto,
+    Zh,
+    En,
+}
+
+impl Locale {
+    /// auto 时按 LC_ALL / LC_TIME / LANG 判断，不是中文环境时使用英文
+    pub fn resolve(self) -> Locale {
+        if self != Locale::Auto {
+            return self;
+        }
+        let lang = ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|key| std::env::var(key).ok())
+            .find(|value| !value.is_empty());
+        match lang {
+            Some(lang) if !lang.starts_with("zh") && lang != "C" && lang != "POSIX" => Locale::En,
+            _ => Locale::Zh,
+        }
+    }
+}
+
+/// 键位方案，在 vim 键位之外提供不依赖模式切换的替代按键
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapPreset {
+    #[default]
+    Vim,
+    Emacs,
+    Arrows,
+}
+
+impl KeymapPreset {
+    pub const ALL: [KeymapPreset; 3] = [KeymapPreset::Vim, KeymapPreset::Emacs, KeymapPreset::Arrows];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == s.to_lowercase())
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeymapPreset::Vim => "vim",
+            KeymapPreset::Emacs => "emacs",
+            KeymapPreset::Arrows => "arrows",
+        }
+    }
+}
+
+/// 任务容量（截止在同一天/同一周的未完成任务数上限，不设置则不提示）
+/// ```toml
+/// [capacity]
+/// daily = 6
+/// weekly = 25
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CapacityConfig {
+    pub daily: Option<usize>,
+    pub weekly: Option<usize>,
+}
+
+impl CapacityConfig {
+    /// 当日/当周任务数（含正在安排的任务）超出容量时返回提示
+    pub fn warning(&self, day_count: usize, week_count: usize) -> Option<String> {
+        match (self.daily, self.weekly) {
+            (Some(daily), _) if day_count > daily => {
+                Some(format!("当天已有 {} 个任务，超出每日容量 {}", day_count, daily))
+            }
+            (_, Some(weekly)) if week_count > weekly => {
+                Some(format!("当周已有 {} 个任务，超出每周容量 {}", week_count, weekly))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 自动标签规则：标题匹配正则时添加标签、设置优先级和/或放入项目
+/// ```toml
+/// [[rules]]
+/// pattern = "(?i)bug"
+/// tags = ["dev"]
+/// priority = "high"
+/// project = "工作"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct AutoTagRule {
+    /// 加载配置时编译，正则无效时配置加载失败
+    #[serde(deserialize_with = "deserialize_regex")]
+    pub pattern: Regex,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// high / medium / low
+    #[serde(default)]
+    pub priority: Option<String>,
+    /// 项目名称，创建任务时不存在则自动创建
+    #[serde(default)]
+    pub project: Option<String>,
+}
+
+fn deserialize_regex<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Regex, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    Regex::new(&pattern).map_err(|e| {
+        serde::de::Error::custom(format!("自动标签规则的正则无效 {}: {}", pattern, e))
+    })
+}
+
+/// 单个图标的显示方式：图标文本（emoji 或普通字母）和可选颜色
+#[derive(Debug, Clone, Deserialize)]
+pub struct IconStyle {
+    pub icon: String,
+    /// 颜色名（red/yellow/lightblue...）或 #RRGGBB
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+impl IconStyle {
+    fn new(icon: &str) -> Self {
+        Self {
+            icon: icon.to_string(),
+            color: None,
+        }
+    }
+
+    /// 为终端输出添加 ANSI 颜色（仅支持基本颜色名，未识别的颜色原样输出）
+    pub fn ansi_painted(&self) -> String {
+        match self.color.as_deref().and_then(ansi_color_code) {
+            Some(code) => format!("\x1b[{}m{}\x1b[0m", code, self.icon),
+            None => self.icon.clone(),
+        }
+    }
+}
+
+/// 优先级图标配置
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PriorityIcons {
+    pub high: IconStyle,
+    pub medium: IconStyle,
+    pub low: IconStyle,
+}
+
+impl Default for PriorityIcons {
+    fn default() -> Self {
+        Self {
+            high: IconStyle::new("🔴"),
+            medium: IconStyle::new("🟡"),
+            low: IconStyle::new("🟢"),
+        }
+    }
+}
+
+/// 状态图标配置
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StatusIcons {
+    pub todo: IconStyle,
+    pub in_progress: IconStyle,
+    pub completed: IconStyle,
+    pub waiting: IconStyle,
+}
+
+impl Default for StatusIcons {
+    fn default() -> Self {
+        Self {
+            todo: IconStyle::new("⭕"),
+            in_progress: IconStyle::new("🔄"),
+            completed: IconStyle::new("✅"),
+            waiting: IconStyle::new("⏳"),
+        }
+    }
+}
+
+/// 图标配置
+/// ```toml
+/// [icons.priority]
+/// high = { icon = "H", color = "red" }
+/// [icons.status]
+/// completed = { icon = "[x]" }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct IconConfig {
+    pub priority: PriorityIcons,
+    pub status: StatusIcons,
+}
+
+impl IconConfig {
+    pub fn priority(&self, priority: Priority) -> &IconStyle {
+        match priority {
+            Priority::High => &self.priority.high,
+            Priority::Medium => &self.priority.medium,
+            Priority::Low => &self.priority.low,
+        }
+    }
+
+    pub fn status(&self, status: TaskStatus) -> &IconStyle {
+        match status {
+            TaskStatus::Todo => &self.status.todo,
+            TaskStatus::InProgress => &self.status.in_progress,
+            TaskStatus::Completed => &self.status.completed,
+            TaskStatus::Waiting => &self.status.waiting,
+        }
+    }
+}
+
+impl Config {
+    /// 按名称查找标题模板
+    pub fn template(&self, name: &str) -> Option<&str> {
+        self.templates.get(name).map(|s| s.as_str())
+    }
+
+    /// 对新任务应用自动标签规则的标签和优先级（所有创建入口都应在校验前调用）
+    pub fn apply_rules(&self, task: &mut Task) {
+        for rule in &self.rules {
+            if !rule.pattern.is_match(&task.title) {
+                continue;
+            }
+            for tag in &rule.tags {
+                task.add_tag(tag);
+            }
+            if let Some(priority) = rule.priority.as_deref().and_then(Priority::parse) {
+                task.priority = priority;
+            }
+        }
+    }
+
+    /// 规则为该标题指定的项目（多条规则匹配时后面的优先），
+    /// 校验通过后交给 [`Database::create_task_in_project`](crate::db::Database::create_task_in_project) 与任务一起写入
+    pub fn rule_project(&self, title: &str) -> Option<&str> {
+        self.rules
+            .iter()
+            .rev()
+            .filter(|rule| rule.pattern.is_match(title))
+            .find_map(|rule| rule.project.as_deref())
+    }
+
+    /// 加载配置文件（profile 有独立配置时优先使用），文件不存在时返回默认配置
+    pub fn load(profile: Option<&str>) -> Result<Self> {
+        let config: Self = match config_path(profile) {
+            Some(path) if path.exists() => {
+                let content = std::fs::read_to_string(&path)?;
+                toml::from_str(&content).map_err(|e| {
+                    Error::Validation(format!("配置文件 {} 解析失败: {}", path.display(), e))
+                })?
+            }
+            _ => Self::default(),
+        };
+        config.validate_rules()?;
+        Ok(config)
+    }
+
+    /// 检查规则的优先级是否有效（正则在解析时已编译）
+    fn validate_rules(&self) -> Result<()> {
+        for rule in &self.rules {
+            if let Some(priority) = &rule.priority {
+                if Priority::parse(priority).is_none() {
+                    return Err(Error::Validation(format!(
+                        "自动标签规则的优先级无效: {}（可选 high/medium/low）",
+                        priority
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn project_dirs() -> Result<ProjectDirs> {
+    ProjectDirs::from("com", "terminator-task", "tasks").ok_or_else(|| {
+        Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "failed to get project directories",
+        ))
+    })
+}
+
+/// 校验 profile 名称：仅允许字母、数字、- 和 _
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Validation(format!(
+            "无效的 profile 名称: {}（仅允许字母、数字、- 和 _）",
+            name
+        )))
+    }
+}
+
+/// 数据目录，每个 profile 使用独立子目录（数据库、守护进程文件均放在这里），不存在时自动创建
+pub fn data_dir(profile: Option<&str>) -> Result<PathBuf> {
+    let base = project_dirs()?.data_dir().to_path_buf();
+    let dir = match profile {
+        Some(name) => {
+            validate_profile_name(name)?;
+            base.join("profiles").join(name)
+        }
+        None => base,
+    };
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// 默认数据库路径
+pub fn db_path(profile: Option<&str>) -> Result<PathBuf> {
+    Ok(data_dir(profile)?.join("tasks.db"))
+}
+
+/// 配置文件路径：profile 存在独立配置文件时使用它，否则回退到全局配置
+pub fn config_path(profile: Option<&str>) -> Option<PathBuf> {
+    let config_dir = project_dirs().ok()?.config_dir().to_path_buf();
+    if let Some(name) = profile {
+        let profile_config = config_dir.join("profiles").join(name).join("config.toml");
+        if profile_config.exists() {
+            return Some(profile_config);
+        }
+    }
+    Some(config_dir.join("config.toml"))
+}
+
+/// 列出已创建的 profile（按名称排序）
+pub fn list_profiles() -> Result<Vec<String>> {
+    let dir = project_dirs()?.data_dir().join("profiles");
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut profiles = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            profiles.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    profiles.sort();
+    Ok(profiles)
+}
+
+/// 创建 profile 的数据目录，返回其数据库路径（default 为保留名称，已存在时报错）
+pub fn create_profile(name: &str) -> Result<PathBuf> {
+    validate_profile_name(name)?;
+    if name == "default" || list_profiles()?.iter().any(|p| p == name) {
+        return Err(Error::Validation(format!("profile 已存在: {}", name)));
+    }
+    db_path(Some(name))
+}
+
+/// 删除 profile 的数据目录（数据库、守护进程文件）和独立配置
+pub fn delete_profile(name: &str) -> Result<()> {
+    validate_profile_name(name)?;
+    if name == "default" {
+        return Err(Error::Validation("不能删除默认 profile".to_string()));
+    }
+    if !list_profiles()?.iter().any(|p| p == name) {
+        return Err(Error::Validation(format!("profile 不存在: {}", name)));
+    }
+    let dirs = project_dirs()?;
+    std::fs::remove_dir_all(dirs.data_dir().join("profiles").join(name))?;
+    let config_dir = dirs.config_dir().join("profiles").join(name);
+    if config_dir.exists() {
+        std::fs::remove_dir_all(config_dir)?;
+    }
+    Ok(())
+}
+
+/// 颜色名到 ANSI 前景色代码
+fn ansi_color_code(name: &str) -> Option<&'static str> {
+    let code = match name.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+        "black" => "30",
+        "red" => "31",
+        "green" => "32",
+        "yellow" => "33",
+        "blue" => "34",
+        "magenta" => "35",
+        "cyan" => "36",
+        "gray" | "grey" => "37",
+        "darkgray" | "darkgrey" => "90",
+        "lightred" => "91",
+        "lightgreen" => "92",
+        "lightyellow" => "93",
+        "lightblue" => "94",
+        "lightmagenta" => "95",
+        "lightcyan" => "96",
+        "white" => "97",
+        _ => return None,
+    };
+    Some(code)
+}
@@ -0,0 +1,98 @@
+// taskd 端的本地 IPC：监听套接字、处理 tasks 发来的命令，以及写入 PID 和心跳文件
+// （协议、客户端和文件的读取在共用的 ipc 模块中）
+
+use chrono::{DateTime, Utc};
+use interprocess::local_socket::{prelude::*, ListenerOptions};
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::ipc::{self, Request, Response};
+
+/// 读取请求、写回响应的时限，防止连上后不发数据的客户端一直占着线程
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+/// 同时处理的连接数上限，超过时直接回复错误，不再为其创建线程
+const MAX_CONNECTIONS: usize = 16;
+
+impl Response {
+    pub fn ok(message: impl Into<String>) -> Self {
+        Self { ok: true, message: message.into() }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self { ok: false, message: message.into() }
+    }
+}
+
+/// 写入当前进程的 PID
+pub fn write_pid(db_path: &Path) -> Result<()> {
+    Ok(std::fs::write(ipc::pid_path(db_path), std::process::id().to_string())?)
+}
+
+/// 写入心跳时间
+pub fn write_heartbeat(db_path: &Path, now: DateTime<Utc>) -> Result<()> {
+    Ok(std::fs::write(ipc::heartbeat_path(db_path), now.to_rfc3339())?)
+}
+
+/// 创建监听套接字（守护进程启动时调用）
+///
+/// 上次异常退出留下的套接字文件会被清理；已有守护进程在监听同一数据库时返回错误
+pub fn listen(db_path: &Path) -> Result<LocalSocketListener> {
+    if LocalSocketStream::connect(ipc::socket_name(db_path)?).is_ok() {
+        return Err(Error::Validation("已有 taskd 在运行（同一数据库）".to_string()));
+    }
+    #[cfg(unix)]
+    {
+        let _ = std::fs::remove_file(db_path.with_extension("sock"));
+    }
+    Ok(ListenerOptions::new().name(ipc::socket_name(db_path)?).create_sync()?)
+}
+
+/// 接受连接（阻塞，守护进程在单独的线程中调用）；每个连接在自己的线程中处理，
+/// 连上后不发送请求的客户端不会挡住其他连接，最多同时处理 MAX_CONNECTIONS 个
+pub fn serve(listener: LocalSocketListener, handle: impl Fn(Request) -> Response + Send + Sync + 'static) {
+    let handle = Arc::new(handle);
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let mut stream = match stream.and_then(|stream| set_timeouts(&stream).map(|_| stream)) {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!("IPC connection failed: {}", e);
+                continue;
+            }
+        };
+        if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            active.fetch_sub(1, Ordering::SeqCst);
+            tracing::warn!("Too many IPC connections, rejecting one");
+            let _ = ipc::write_line(&mut stream, &Response::error("taskd 繁忙，请稍后重试"));
+            continue;
+        }
+        let handle = handle.clone();
+        let active = active.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = serve_connection(stream, |request| handle(request)) {
+                tracing::warn!("IPC connection failed: {}", e);
+            }
+            active.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+}
+
+fn set_timeouts(stream: &LocalSocketStream) -> std::io::Result<()> {
+    stream.set_recv_timeout(Some(CONNECTION_TIMEOUT))?;
+    stream.set_send_timeout(Some(CONNECTION_TIMEOUT))
+}
+
+/// 处理一个连接：读取请求，交给 handle 处理后写回响应
+fn serve_connection(stream: LocalSocketStream, handle: impl FnOnce(Request) -> Response) -> Result<()> {
+    let mut stream = BufReader::new(stream);
+    let response = match ipc::read_line::<Request>(&mut stream) {
+        Ok(Some(request)) => handle(request),
+        Ok(None) => return Ok(()),
+        Err(e) => Response::error(e.to_string()),
+    };
+    ipc::write_line(stream.get_mut(), &response)
+}
@@ -1,14 +1,21 @@
 use anyhow::Result;
-use clap::Parser;
-use directories::ProjectDirs;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use std::sync::{Arc, Mutex};
-use tokio::time::{sleep, Duration};
+use tokio::time::{sleep, Duration, Instant};
 
 // 引用主crate的模块（通过路径）
+#[path = "../clock/mod.rs"]
+mod clock;
+#[path = "../config/mod.rs"]
+mod config;
 #[path = "../db/mod.rs"]
 mod db;
+#[path = "../error/mod.rs"]
+mod error;
+#[path = "../ipc/mod.rs"]
+mod ipc;
 #[path = "../models/mod.rs"]
 mod models;
 #[path = "../notify/mod.rs"]
@@ -17,23 +24,81 @@ mod notify;
 mod notes;
 #[path = "../pomodoro/mod.rs"]
 mod pomodoro;
+#[path = "../prompt/mod.rs"]
+mod prompt;
+#[path = "../sound/mod.rs"]
+mod sound;
 
-use db::Database;
-use models::TaskStatus;
+mod ipc_server;
+mod server;
+
+use clock::Clock;
+use db::Storage;
+use models::{Task, TaskStatus};
 use notify::NotificationManager;
 
+/// 同一轮检查中超过该数量的提醒合并为一条汇总通知，避免通知轰炸
+const DIGEST_THRESHOLD: usize = 3;
+/// 汇总通知中列出的任务数
+const DIGEST_MAX_TITLES: usize = 5;
+/// 启动时最多补发多久以前错过的提醒（小时）：守护进程停止期间到期、还没发送过的提醒
+const MISSED_REMINDER_HOURS: i64 = 24;
+/// 提醒晚于原定时间超过该分钟数时，通知中注明原定时间
+const LATE_REMINDER_MINUTES: i64 = 2;
+/// 监督模式下连续崩溃后的最长重启等待时间（秒）
+const MAX_RESTART_BACKOFF_SECS: u64 = 300;
+/// 运行超过该时长（秒）后再崩溃，重启等待时间从头计算
+const STABLE_RUN_SECS: u64 = 600;
+/// 检查数据库是否被 TUI / CLI 修改（data_version）的间隔，只是一条 PRAGMA，开销很小
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 检查循环被唤醒的原因
+enum Wake {
+    /// 到了提醒时间或下一轮检查
+    Timer,
+    /// IPC 通知或数据库被其他进程修改
+    Changed,
+}
+
 // 守护进程结构
 pub struct Daemon {
-    db: Arc<Mutex<Database>>,
+    db: Arc<Mutex<Box<dyn Storage>>>,
+    // 数据库路径，退出时据此删除 PID 文件和套接字
+    db_path: PathBuf,
     notifier: NotificationManager,
+    clock: Arc<dyn Clock>,
+    // 上次检查提醒的时间，两次检查之间（包括休眠期间）到期的提醒都会被发送
+    last_check: Mutex<DateTime<Utc>>,
+    // 心跳、归档等每轮任务的间隔，也是提前安排提醒的范围（config.toml 的 [intervals] daemon_check_secs）
+    check_interval: Duration,
+    // shell 提示符片段缓存文件，每轮检查时刷新待办数
+    prompt_cache: PathBuf,
+    // 最晚开始提醒的缓冲时间，None 表示关闭（config.toml 的 [start_nag]）
+    start_buffer: Option<chrono::Duration>,
+    // 收到 IPC reload 时唤醒检查循环，不必等到下一轮
+    wake: tokio::sync::Notify,
 }
 
 impl Daemon {
     pub fn new(db_path: PathBuf) -> Result<Self> {
-        let db = Database::open(db_path)?;
+        Self::with_clock(db_path, clock::system())
+    }
+
+    /// 使用指定时钟创建（测试中传入 SimulatedClock 以快进提醒窗口）
+    pub fn with_clock(db_path: PathBuf, clock: Arc<dyn Clock>) -> Result<Self> {
+        let prompt_cache = prompt::cache_path(&db_path);
+        let db: Box<dyn Storage> = Box::new(db::Database::open(&db_path)?);
+        let last_check = missed_since(&*db, &db_path, clock.now())?;
         Ok(Self {
             db: Arc::new(Mutex::new(db)),
+            db_path,
             notifier: NotificationManager::new(),
+            clock,
+            last_check: Mutex::new(last_check),
+            check_interval: Duration::from_secs(60),
+            prompt_cache,
+            start_buffer: Some(chrono::Duration::minutes(30)),
+            wake: tokio::sync::Notify::new(),
         })
     }
 
@@ -41,47 +106,474 @@ impl Daemon {
     pub async fn run(&self) -> Result<()> {
         tracing::info!("Task daemon started");
 
+        let mut data_version = self.db.lock().unwrap().data_version()?;
+
+        loop {
+            // 写入心跳文件（不写数据库，以免 TUI 每轮都重新加载），TUI 启动时据此判断守护进程是否在运行
+            if let Err(e) = self.heartbeat() {
+                tracing::error!("Error writing heartbeat: {}", e);
+            }
+
+            // 刷新提示符片段中的待办数
+            if let Err(e) = self.refresh_prompt_segment() {
+                tracing::error!("Error refreshing prompt segment: {}", e);
+            }
+
+            // 周日晚上发送本周目标总结
+            if let Err(e) = self.send_goal_summary() {
+                tracing::error!("Error sending weekly goal summary: {}", e);
+            }
+
+            // 归档到期的便签
+            if let Err(e) = self.archive_expired_notes() {
+                tracing::error!("Error archiving expired notes: {}", e);
+            }
+
+            // 清除回收站中超过保留期的任务和便签
+            if let Err(e) = self.purge_trash() {
+                tracing::error!("Error purging trash: {}", e);
+            }
+
+            // 以上每轮（默认每分钟）执行一次；其间只加载下一轮之前要触发的提醒，在最近的提醒时间
+            // 准时醒来。TUI / CLI 通过 IPC 通知或数据库被修改时立即重新检查、重新安排
+            let next_round = Instant::now() + self.check_interval;
+            loop {
+                if let Err(e) = self.check_reminders().await {
+                    tracing::error!("Error checking reminders: {}", e);
+                }
+                let wake_at = match self.next_reminder_at(next_round) {
+                    Ok(Some(at)) => at,
+                    Ok(None) => next_round,
+                    Err(e) => {
+                        tracing::error!("Error scheduling reminders: {}", e);
+                        next_round
+                    }
+                };
+                match self.wait_until(wake_at, &mut data_version).await {
+                    Wake::Timer if Instant::now() >= next_round => break,
+                    Wake::Timer => {}
+                    Wake::Changed => {
+                        if let Err(e) = self.refresh_prompt_segment() {
+                            tracing::error!("Error refreshing prompt segment: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// 下一轮检查（deadline）之前最早的提醒、跟进或最晚开始时间，换算为定时器的时刻
+    fn next_reminder_at(&self, deadline: Instant) -> Result<Option<Instant>> {
+        let now = self.clock.now();
+        let until = now + chrono::Duration::from_std(deadline.saturating_duration_since(Instant::now()))?;
+        let tasks = self.db.lock().unwrap().get_reminder_tasks(now, until)?;
+        let next = tasks
+            .iter()
+            .flat_map(|task| {
+                [
+                    task.reminder_time,
+                    task.follow_up_at.filter(|_| task.status == TaskStatus::Waiting),
+                    self.start_buffer
+                        .filter(|_| task.status == TaskStatus::Todo)
+                        .and_then(|buffer| task.latest_start(buffer)),
+                ]
+            })
+            .flatten()
+            .filter(|at| *at > now && *at <= until)
+            .min();
+        Ok(next.map(|at| Instant::now() + (at - now).to_std().unwrap_or_default()))
+    }
+
+    /// 等到 wake_at；其间收到 IPC 通知或其他进程修改了数据库时提前返回
+    async fn wait_until(&self, wake_at: Instant, data_version: &mut i64) -> Wake {
         loop {
-            // 检查提醒
-            if let Err(e) = self.check_reminders().await {
-                tracing::error!("Error checking reminders: {}", e);
+            let now = Instant::now();
+            if now >= wake_at {
+                return Wake::Timer;
+            }
+            tokio::select! {
+                _ = sleep((wake_at - now).min(WATCH_INTERVAL)) => {}
+                _ = self.wake.notified() => return Wake::Changed,
+            }
+            let version = self.db.lock().unwrap().data_version();
+            match version {
+                Ok(version) if version != *data_version => {
+                    *data_version = version;
+                    return Wake::Changed;
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Error checking for database changes: {}", e),
+            }
+        }
+    }
+
+    /// 监督模式：提醒循环 panic 时记录日志、发送恢复通知并重启，避免提醒静默中断
+    ///
+    /// 连续崩溃时重启等待时间按 2 的幂增长（最长 5 分钟），稳定运行 10 分钟后重置
+    pub async fn supervise(self: Arc<Self>) -> Result<()> {
+        let mut crashes: u32 = 0;
+        loop {
+            let started = std::time::Instant::now();
+            let daemon = self.clone();
+            let error = match tokio::spawn(async move { daemon.run().await }).await {
+                Ok(result) => return result,
+                Err(e) if e.is_panic() => panic_message(e.into_panic()),
+                Err(e) => return Err(e.into()),
+            };
+
+            if started.elapsed().as_secs() >= STABLE_RUN_SECS {
+                crashes = 0;
             }
+            crashes += 1;
+            let backoff = 2u64.saturating_pow(crashes).min(MAX_RESTART_BACKOFF_SECS);
+            tracing::error!("Reminder loop crashed ({}), restarting in {}s: {}", crashes, backoff, error);
+            sleep(Duration::from_secs(backoff)).await;
+
+            // panic 时持有的锁会被标记为中毒，重启前清除（连接本身仍然可用，未提交的事务已回滚）
+            self.db.clear_poison();
+            self.last_check.clear_poison();
+            if let Err(e) = self
+                .notifier
+                .send_notification("任务守护进程", "任务守护进程已从错误中恢复，提醒继续运行")
+            {
+                tracing::error!("Failed to send recovery notification: {}", e);
+            }
+        }
+    }
+
+    /// 处理 TUI / CLI 通过 IPC 发来的命令（在 IPC 线程中调用）
+    fn handle_ipc(self: &Arc<Self>, request: ipc::Request, runtime: &tokio::runtime::Handle) -> ipc::Response {
+        match request {
+            ipc::Request::Reload => {
+                self.wake.notify_one();
+                ipc::Response::ok("Reminders reloaded")
+            }
+            ipc::Request::Ping => ipc::Response::ok(format!("taskd running (pid {})", std::process::id())),
+            ipc::Request::Shutdown => {
+                tracing::info!("Shutdown requested over IPC");
+                ipc::cleanup(&self.db_path);
+                // 稍后退出，先让 IPC 线程把回复写回去
+                std::thread::spawn(|| {
+                    std::thread::sleep(Duration::from_millis(100));
+                    std::process::exit(0);
+                });
+                ipc::Response::ok("taskd stopped")
+            }
+            ipc::Request::Snooze { task_id, minutes } => match self.snooze(task_id, minutes) {
+                Ok(at) => {
+                    self.wake.notify_one();
+                    ipc::Response::ok(format!(
+                        "Reminder for task {} snoozed until {}",
+                        task_id,
+                        at.with_timezone(&chrono::Local).format("%H:%M")
+                    ))
+                }
+                Err(e) => ipc::Response::error(e.to_string()),
+            },
+            ipc::Request::StartPomodoro { task_id, minutes } => {
+                if !(1..=120).contains(&minutes) {
+                    return ipc::Response::error("Pomodoro length must be between 1 and 120 minutes");
+                }
+                let session = models::PomodoroSession {
+                    id: None,
+                    task_id,
+                    start_time: self.clock.now(),
+                    end_time: None,
+                    duration_minutes: minutes as i32,
+                    completed: false,
+                };
+                let id = match self.db.lock().unwrap().create_pomodoro(&session) {
+                    Ok(id) => id,
+                    Err(e) => return ipc::Response::error(e.to_string()),
+                };
+                // 到时记录完成并发送通知
+                let daemon = self.clone();
+                runtime.spawn(async move {
+                    sleep(Duration::from_secs(minutes as u64 * 60)).await;
+                    if let Err(e) = daemon.finish_pomodoro(id, task_id) {
+                        tracing::error!("Error finishing pomodoro: {}", e);
+                    }
+                });
+                ipc::Response::ok(format!("Pomodoro started ({} minutes)", minutes))
+            }
+        }
+    }
+
+    /// 把任务的提醒改到 minutes 分钟后
+    fn snooze(&self, task_id: i64, minutes: i64) -> Result<DateTime<Utc>> {
+        let db = self.db.lock().unwrap();
+        let mut task = db.get_task(task_id)?;
+        let now = self.clock.now();
+        let at = now + chrono::Duration::minutes(minutes.max(1));
+        task.reminder_time = Some(at);
+        task.updated_at = now;
+        db.update_task(&task)?;
+        Ok(at)
+    }
+
+    fn finish_pomodoro(&self, id: i64, task_id: Option<i64>) -> Result<()> {
+        {
+            let db = self.db.lock().unwrap();
+            db.complete_pomodoro(id)?;
+            if let Some(task_id) = task_id {
+                db.add_task_pomodoro(task_id)?;
+            }
+            play_sound(&**db, sound::Alert::Pomodoro);
+        }
+        self.notifier.send_pomodoro_complete(false)?;
+        Ok(())
+    }
+
+    /// 更新提示符缓存中的今日截止/逾期数（保留 TUI 写入的番茄钟状态）
+    fn refresh_prompt_segment(&self) -> Result<()> {
+        let tasks = self.db.lock().unwrap().get_all_tasks()?;
+        let mut segment = prompt::PromptSegment::load(&self.prompt_cache).unwrap_or_default();
+        segment.set_counts(&tasks, self.clock.now());
+        segment.save(&self.prompt_cache)?;
+        Ok(())
+    }
+
+    /// 周日 20:00 之后发送一次本周目标达成情况（每周只发一次）
+    fn send_goal_summary(&self) -> Result<()> {
+        use chrono::{Datelike, Timelike};
+
+        let now = self.clock.now().with_timezone(&chrono::Local);
+        if now.weekday() != chrono::Weekday::Sun || now.hour() < 20 {
+            return Ok(());
+        }
+        let db = self.db.lock().unwrap();
+        if is_paused(&**db, self.clock.now())? {
+            return Ok(());
+        }
+        let week = now.date_naive().format("%G-W%V").to_string();
+        if db.get_config("goals_summary_week")?.as_deref() == Some(week.as_str()) {
+            return Ok(());
+        }
+        let (start, end) = models::local_week_bounds(now.date_naive());
+        let goals = db.get_weekly_goals(start, end)?;
+        if !goals.is_empty() {
+            let hit = goals.iter().filter(|g| g.hit()).count();
+            let lines: Vec<String> = goals
+                .iter()
+                .map(|g| format!("{} #{} {}/{}", if g.hit() { "✅" } else { "❌" }, g.tag, g.done, g.target))
+                .collect();
+            self.notifier.send_notification(
+                &format!("本周目标：达成 {}/{}", hit, goals.len()),
+                &lines.join("\n"),
+            )?;
+        }
+        db.set_config("goals_summary_week", &week)?;
+        Ok(())
+    }
+
+    fn heartbeat(&self) -> Result<()> {
+        ipc_server::write_heartbeat(&self.db_path, self.clock.now())?;
+        Ok(())
+    }
+
+    /// 归档已到期的便签
+    fn archive_expired_notes(&self) -> Result<()> {
+        let db = self.db.lock().unwrap();
+        let count = db.archive_expired_notes(self.clock.now())?;
+        if count > 0 {
+            tracing::info!("Archived {} expired notes", count);
+        }
+        Ok(())
+    }
 
-            // 每分钟检查一次
-            sleep(Duration::from_secs(60)).await;
+    /// 清除回收站中超过 TRASH_RETENTION_DAYS 天的项目
+    fn purge_trash(&self) -> Result<()> {
+        let db = self.db.lock().unwrap();
+        let before = self.clock.now() - chrono::Duration::days(db::TRASH_RETENTION_DAYS);
+        let count = db.purge_trash(before)?;
+        if count > 0 {
+            tracing::info!("Purged {} items from trash", count);
         }
+        Ok(())
     }
 
     /// 检查并发送提醒
     async fn check_reminders(&self) -> Result<()> {
         let db = self.db.lock().unwrap();
-        let tasks = db.get_all_tasks()?;
-        let now = Utc::now();
-
-        for task in tasks {
-            if task.status != TaskStatus::Completed {
-                if let Some(reminder_time) = task.reminder_time {
-                    // 如果提醒时间在过去1分钟内，发送提醒
-                    if reminder_time <= now && (now - reminder_time).num_minutes() < 1 {
-                        let body = format!(
-                            "截止时间: {}",
-                            task.due_date
-                                .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
-                                .unwrap_or_else(|| "无".to_string())
-                        );
-
-                        if let Err(e) = self.notifier.send_task_reminder(&task.title, &body) {
-                            tracing::error!("Failed to send reminder: {}", e);
-                        }
+        let now = self.clock.now();
+        let since = std::mem::replace(&mut *self.last_check.lock().unwrap(), now);
+        // 休假期间到期的提醒直接丢弃，回来后不会一次性收到一堆逾期通知
+        // （守护进程停止期间休假已结束时，休假结束前到期的提醒也不补发）
+        let since = match db.get_pause_until()? {
+            Some(until) if until > now => return Ok(()),
+            Some(until) => since.max(until),
+            None => since,
+        };
+        let tasks = db.get_reminder_tasks(since, now)?;
+
+        // 跟进时间到了的等待中任务
+        for task in tasks.iter().filter(|task| {
+            task.status == TaskStatus::Waiting
+                && task.follow_up_at.is_some_and(|t| t > since && t <= now)
+        }) {
+            let body = format!(
+                "等待自: {}，该跟进了",
+                task.waiting_since
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "未知".to_string())
+            );
+            if let Err(e) = self.notifier.send_task_reminder(&format!("跟进: {}", task.title), &body) {
+                tracing::error!("Failed to send follow-up reminder: {}", e);
+            }
+        }
+
+        // 按预计耗时推算的最晚开始时间到了、但还没开始的任务
+        if let Some(buffer) = self.start_buffer {
+            for task in tasks.iter().filter(|task| matches!(task.status, TaskStatus::Todo)) {
+                let start = match task.latest_start(buffer) {
+                    Some(start) if start > since && start <= now => start,
+                    _ => continue,
+                };
+                let body = format!(
+                    "预计耗时 {}，截止 {}，最晚 {} 开始",
+                    models::format_estimate(task.estimate_minutes.unwrap_or(0)),
+                    task.due_date
+                        .map(|d| d.with_timezone(&chrono::Local).format("%m-%d %H:%M").to_string())
+                        .unwrap_or_default(),
+                    start.with_timezone(&chrono::Local).format("%H:%M")
+                );
+                if let Err(e) = self.notifier.send_task_reminder(&format!("该开始了: {}", task.title), &body) {
+                    tracing::error!("Failed to send start reminder: {}", e);
+                }
+            }
+        }
+
+        // 提醒时间落在上次检查之后、现在之前的未完成任务；已发送过的提醒（如守护进程重启后
+        // 检查窗口重叠）不再重复发送，推迟后提醒时间改变，会再次提醒
+        let mut due = Vec::new();
+        for task in tasks.into_iter().filter(|task| task.status != TaskStatus::Completed) {
+            match (task.id, task.reminder_time) {
+                (Some(id), Some(at)) if at > since && at <= now && !db.reminder_sent(id, at)? => due.push(task),
+                _ => {}
+            }
+        }
+
+        if due.len() > DIGEST_THRESHOLD {
+            let titles: Vec<&str> = due.iter().map(|task| task.title.as_str()).collect();
+            match self.notifier.send_reminder_digest(&titles, DIGEST_MAX_TITLES) {
+                Ok(()) => {
+                    for task in &due {
+                        log_reminder(&**db, task, now)?;
                     }
+                    play_sound(&**db, sound::Alert::Reminder);
+                }
+                Err(e) => tracing::error!("Failed to send reminder digest: {}", e),
+            }
+            return Ok(());
+        }
+
+        let mut sent = false;
+        for task in due {
+            let mut body = format!(
+                "截止时间: {}",
+                task.due_date
+                    .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "无".to_string())
+            );
+            // 守护进程停止期间错过、启动后补发的提醒
+            if let Some(at) = task.reminder_time.filter(|at| now - *at > chrono::Duration::minutes(LATE_REMINDER_MINUTES)) {
+                body.push_str(&format!("（原定 {} 提醒）", at.with_timezone(&chrono::Local).format("%m-%d %H:%M")));
+            }
+
+            match self.notifier.send_task_reminder(&task.title, &body) {
+                Ok(()) => {
+                    log_reminder(&**db, &task, now)?;
+                    sent = true;
                 }
+                Err(e) => tracing::error!("Failed to send reminder: {}", e),
             }
         }
+        // 同一轮发送了多条提醒时只响一次
+        if sent {
+            play_sound(&**db, sound::Alert::Reminder);
+        }
 
         Ok(())
     }
 }
 
+/// 按数据库中的提示音设置（tasks sound）在后台播放提示音
+fn play_sound(db: &dyn Storage, alert: sound::Alert) {
+    match sound::SoundSettings::load(db) {
+        Ok(settings) => sound::play_in_background(settings, alert),
+        Err(e) => tracing::warn!("Failed to load sound settings: {}", e),
+    }
+}
+
+/// 记录任务当前提醒时间的提醒已发送
+fn log_reminder(db: &dyn Storage, task: &Task, sent_at: DateTime<Utc>) -> Result<()> {
+    if let (Some(id), Some(at)) = (task.id, task.reminder_time) {
+        db.log_reminder(id, at, sent_at)?;
+    }
+    Ok(())
+}
+
+/// 启动时第一次检查提醒的起点：上次心跳（守护进程停止前最后一次检查）之后到期的提醒都会补发，
+/// 最多补发 MISSED_REMINDER_HOURS 以内的；没有心跳（第一次运行）时只检查最近一分钟
+fn missed_since(db: &dyn Storage, db_path: &std::path::Path, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    // 旧版本把心跳写在数据库的配置项中，升级后第一次启动时仍据此补发
+    let heartbeat = match ipc::read_heartbeat(db_path) {
+        Some(heartbeat) => Some(heartbeat),
+        None => db
+            .get_config("daemon_heartbeat")?
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|t| t.with_timezone(&Utc)),
+    };
+    let since = match heartbeat {
+        Some(heartbeat) => heartbeat.max(now - chrono::Duration::hours(MISSED_REMINDER_HOURS)).min(now),
+        None => now - chrono::Duration::minutes(1),
+    };
+    if now - since > chrono::Duration::minutes(1) {
+        tracing::info!("Checking reminders missed since {}", since);
+    }
+    Ok(since)
+}
+
+/// 是否处于休假模式（tasks pause 设置的结束时间还没到）
+fn is_paused(db: &dyn Storage, now: DateTime<Utc>) -> Result<bool> {
+    Ok(db.get_pause_until()?.is_some_and(|until| until > now))
+}
+
+/// taskd status：根据心跳判断守护进程是否在运行，并显示它选择的通知方式
+fn print_status(db_path: &std::path::Path) -> Result<()> {
+    let db = db::Database::open(db_path)?;
+    let now = Utc::now();
+    match ipc::read_heartbeat(db_path) {
+        Some(t) if now - t <= chrono::Duration::minutes(5) => {
+            println!("taskd: running (last check {}s ago)", (now - t).num_seconds())
+        }
+        Some(t) => println!(
+            "taskd: not running (last seen {})",
+            t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+        ),
+        None => println!("taskd: never started"),
+    }
+    println!(
+        "notifications: {}",
+        db.get_config("notify_backend")?.unwrap_or_else(|| "unknown".to_string())
+    );
+    if let Some(until) = db.get_pause_until()?.filter(|until| *until > now) {
+        println!("paused until: {}", until.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+    }
+    Ok(())
+}
+
+/// 提取 panic 信息（panic!("...") 的参数为 &str 或 String）
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
 #[derive(Parser)]
 #[command(name = "taskd")]
 #[command(about = "Task manager daemon", long_about = None)]
@@ -90,9 +582,30 @@ struct Cli {
     #[arg(short, long)]
     db_path: Option<PathBuf>,
 
-    /// Enable debug logging
+    /// Profile name; each profile has its own database and config
     #[arg(short, long)]
+    profile: Option<String>,
+
+    /// Enable debug logging
+    #[arg(long)]
     debug: bool,
+
+    /// Serve the HTTP API for `tasks --remote` on this address (e.g. 127.0.0.1:7878)
+    #[arg(long)]
+    listen: Option<String>,
+
+    /// Restart the reminder loop after a crash and send a recovery notification
+    #[arg(long)]
+    supervise: bool,
+
+    #[command(subcommand)]
+    command: Option<DaemonCommand>,
+}
+
+#[derive(Subcommand)]
+enum DaemonCommand {
+    /// Show whether taskd is running and which notification backend it uses
+    Status,
 }
 
 #[tokio::main]
@@ -100,25 +613,124 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     // 设置日志
+    // RUST_LOG 优先，如 RUST_LOG=tasks::db=trace 输出每条语句的耗时
     let log_level = if cli.debug { "debug" } else { "info" };
+    let filter = std::env::var("RUST_LOG").unwrap_or_else(|_| log_level.to_string());
     tracing_subscriber::fmt()
-        .with_env_filter(log_level)
+        .with_env_filter(filter)
         .init();
+    // 调试模式下记录慢查询（超过 100ms 的语句输出警告）
+    db::timing::set_enabled(cli.debug);
+
+    // 确定数据库路径（按 profile 隔离）
+    let db_path = match cli.db_path {
+        Some(path) => path,
+        None => config::db_path(cli.profile.as_deref())?,
+    };
 
-    // 确定数据库路径
-    let db_path = cli.db_path.unwrap_or_else(|| {
-        let proj_dirs = ProjectDirs::from("com", "terminator-task", "tasks")
-            .expect("Failed to get project directories");
-        let data_dir = proj_dirs.data_dir();
-        std::fs::create_dir_all(data_dir).expect("Failed to create data directory");
-        data_dir.join("tasks.db")
-    });
+    if let Some(DaemonCommand::Status) = cli.command {
+        return print_status(&db_path);
+    }
 
     tracing::info!("Using database: {:?}", db_path);
 
     // 创建并运行守护进程
-    let daemon = Daemon::new(db_path)?;
-    daemon.run().await?;
+    let config = config::Config::load(cli.profile.as_deref())?;
+    let mut daemon = Daemon::new(db_path.clone())?;
+    daemon.check_interval = config.intervals.daemon_check();
+    // 没有桌面通知服务时（如通过 SSH 运行）按配置改用 webhook 或终端输出
+    let backend = notify::Backend::detect(&config.notifications);
+    tracing::info!("Notification backend: {}", backend.describe());
+    daemon.db.lock().unwrap().set_config("notify_backend", &backend.describe())?;
+    daemon.notifier = NotificationManager::with_backend(backend);
+    tracing::info!("Alert sound: {}", sound::SoundSettings::load(&**daemon.db.lock().unwrap())?.describe());
+    daemon.start_buffer = config
+        .start_nag
+        .enabled
+        .then(|| chrono::Duration::minutes(config.start_nag.buffer_minutes.max(0)));
+    let daemon = Arc::new(daemon);
+
+    // 启动 HTTP 接口（与提醒检查共用同一个数据库连接）
+    if let Some(addr) = cli.listen {
+        let db = daemon.db.clone();
+        tokio::spawn(async move {
+            if let Err(e) = server::serve(addr, db, config).await {
+                tracing::error!("HTTP API stopped: {}", e);
+            }
+        });
+    }
+
+    // 本地 IPC：TUI / CLI 修改数据后推送命令；无法监听时只记录日志，提醒照常轮询
+    match ipc_server::listen(&db_path) {
+        Ok(listener) => {
+            // 只有拿到套接字的实例写 PID 文件，不会覆盖已在运行的 taskd 的
+            if let Err(e) = ipc_server::write_pid(&db_path) {
+                tracing::warn!("Could not write PID file: {}", e);
+            }
+            let daemon = daemon.clone();
+            let runtime = tokio::runtime::Handle::current();
+            std::thread::spawn(move || {
+                ipc_server::serve(listener, move |request| daemon.handle_ipc(request, &runtime))
+            });
+        }
+        Err(e) => tracing::warn!("IPC socket unavailable: {}", e),
+    }
+
+    if cli.supervise {
+        daemon.supervise().await?;
+    } else {
+        daemon.run().await?;
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use clock::SimulatedClock;
+
+    /// 每分钟检查一次，返回提醒被发送时模拟时钟的时间
+    async fn run_checks(
+        daemon: &Daemon,
+        clock: &SimulatedClock,
+        id: i64,
+        at: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let mut sent_at = None;
+        while clock.now() < until {
+            clock.advance(chrono::Duration::minutes(1));
+            daemon.check_reminders().await.unwrap();
+            if sent_at.is_none() && daemon.db.lock().unwrap().reminder_sent(id, at).unwrap() {
+                sent_at = Some(clock.now());
+            }
+        }
+        sent_at
+    }
+
+    #[tokio::test]
+    async fn reminders_fire_inside_window_across_dst_change() {
+        let dir = tempfile::tempdir().unwrap();
+        // 2024-11-03 美国东部时间回拨：本地 01:30 出现两次（05:30Z 和 06:30Z）
+        let first = Utc.with_ymd_and_hms(2024, 11, 3, 5, 30, 0).unwrap();
+        let second = Utc.with_ymd_and_hms(2024, 11, 3, 6, 30, 0).unwrap();
+        let clock = SimulatedClock::new(Utc.with_ymd_and_hms(2024, 11, 3, 4, 0, 0).unwrap());
+        let mut daemon = Daemon::with_clock(dir.path().join("tasks.db"), Arc::new(clock.clone())).unwrap();
+        daemon.notifier = NotificationManager::with_backend(notify::Backend::Terminal);
+
+        let ids: Vec<i64> = [first, second]
+            .into_iter()
+            .map(|at| {
+                let mut task = Task::new("dst".to_string());
+                task.reminder_time = Some(at);
+                daemon.db.lock().unwrap().create_task(&task).unwrap()
+            })
+            .collect();
+
+        // 提醒在 since < t <= now 的那一轮发送，不早也不晚
+        assert_eq!(run_checks(&daemon, &clock, ids[0], first, first).await, Some(first));
+        assert_eq!(run_checks(&daemon, &clock, ids[1], second, second).await, Some(second));
+    }
+}
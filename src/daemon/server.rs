@@ -0,0 +1,251 @@
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::config::Config;
+use crate::db::Storage;
+use crate::error::Error;
+use crate::models::Task;
+
+/// 请求体最大长度，防止异常请求占满内存
+const MAX_BODY_LEN: usize = 1024 * 1024;
+/// 请求行和单个请求头的最大长度
+const MAX_LINE_LEN: usize = 8 * 1024;
+/// 请求头的最大个数
+const MAX_HEADERS: usize = 64;
+/// 读完整个请求（请求头和请求体）的时限，防止连接一直占着不发数据
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// HTTP 接口（供 `tasks --remote` 使用）
+///
+/// - `GET  /tasks`               获取所有任务
+/// - `POST /tasks`               创建任务（请求体为 Task JSON，至少包含 title）
+/// - `POST /tasks/{id}/complete` 标记任务完成
+///
+/// 配置了 `[remote] token` 时每个请求都要带 `Authorization: Bearer <token>`；
+/// 没有令牌时只允许监听本机地址
+pub async fn serve(addr: String, db: Arc<Mutex<Box<dyn Storage>>>, config: Config) -> Result<()> {
+    let config = Arc::new(config);
+    let listener = TcpListener::bind(&addr).await?;
+    if config.remote.token().is_none() && !listener.local_addr()?.ip().is_loopback() {
+        anyhow::bail!(
+            "refusing to listen on {} without authentication; set [remote] token in config.toml or listen on 127.0.0.1",
+            addr
+        );
+    }
+    tracing::info!("HTTP API listening on {}", addr);
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let db = db.clone();
+        let config = config.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, db, config).await {
+                tracing::debug!("Connection from {} failed: {}", peer, e);
+            }
+        });
+    }
+}
+
+/// 请求行和请求头中用到的部分
+struct RequestHead {
+    method: String,
+    path: String,
+    content_length: usize,
+    authorization: Option<String>,
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    db: Arc<Mutex<Box<dyn Storage>>>,
+    config: Arc<Config>,
+) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let request = tokio::time::timeout(REQUEST_TIMEOUT, async {
+        let head = read_head(&mut reader)
+            .await
+            .map_err(|status| (status, error_body(reason_phrase(status))))?;
+        if !authorized(config.remote.token(), head.authorization.as_deref()) {
+            return Err((401, error_body("missing or invalid token")));
+        }
+        if head.content_length > MAX_BODY_LEN {
+            return Err((413, error_body("request body too large")));
+        }
+        let mut buf = vec![0u8; head.content_length];
+        reader
+            .read_exact(&mut buf)
+            .await
+            .map_err(|_| (400, error_body("incomplete request body")))?;
+        Ok((head, String::from_utf8_lossy(&buf).to_string()))
+    })
+    .await;
+
+    let (status, body) = match request {
+        Err(_) => (408, error_body("timed out reading request")),
+        Ok(Err(response)) => response,
+        // 数据库访问是阻塞的，放到阻塞线程池里，不占用异步运行时的工作线程
+        Ok(Ok((head, request_body))) => {
+            tokio::task::spawn_blocking(move || {
+                route(&head.method, &head.path, &request_body, &db, &config)
+            })
+            .await?
+        }
+    };
+
+    let mut response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        reason_phrase(status),
+        body.len()
+    );
+    if status == 401 {
+        response.push_str("WWW-Authenticate: Bearer\r\n");
+    }
+    response.push_str("\r\n");
+    response.push_str(&body);
+    reader.get_mut().write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+/// 读取请求行和请求头；超长或格式错误时返回对应的状态码
+async fn read_head(reader: &mut BufReader<TcpStream>) -> std::result::Result<RequestHead, u16> {
+    let request_line = read_line(reader).await?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(400);
+    };
+    let mut head = RequestHead {
+        method: method.to_string(),
+        path: path.to_string(),
+        content_length: 0,
+        authorization: None,
+    };
+
+    for _ in 0..MAX_HEADERS {
+        let line = read_line(reader).await?;
+        if line.is_empty() {
+            return Ok(head);
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                head.content_length = value.trim().parse().map_err(|_| 400u16)?;
+            } else if name.eq_ignore_ascii_case("authorization") {
+                head.authorization = Some(value.trim().to_string());
+            }
+        }
+    }
+    Err(431)
+}
+
+/// 读取一行（去掉行尾的 CRLF），最多 MAX_LINE_LEN 字节
+async fn read_line(reader: &mut BufReader<TcpStream>) -> std::result::Result<String, u16> {
+    let mut line = String::new();
+    let read = reader
+        .take(MAX_LINE_LEN as u64 + 1)
+        .read_line(&mut line)
+        .await
+        .map_err(|_| 400u16)?;
+    if read > MAX_LINE_LEN {
+        return Err(431);
+    }
+    // 连接在一行中间断开
+    if read > 0 && !line.ends_with('\n') {
+        return Err(400);
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// 校验 `Authorization: Bearer <token>`；没有配置令牌时不校验
+fn authorized(token: Option<&str>, header: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    let Some(given) = header.and_then(|h| h.strip_prefix("Bearer ")).map(str::trim) else {
+        return false;
+    };
+    // 逐字节比较全部内容，耗时不随第一个不同字节的位置变化
+    given.len() == token.len()
+        && given.bytes().zip(token.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// 分发请求，返回状态码和 JSON 响应体
+fn route(
+    method: &str,
+    path: &str,
+    body: &str,
+    db: &Arc<Mutex<Box<dyn Storage>>>,
+    config: &Config,
+) -> (u16, String) {
+    let db = db.lock().unwrap();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    let result = match (method, segments.as_slice()) {
+        ("GET", ["tasks"]) => db
+            .get_all_tasks()
+            .map(|tasks| (200, serde_json::to_string(&tasks).unwrap_or_default())),
+        ("POST", ["tasks"]) => match serde_json::from_str::<serde_json::Value>(body) {
+            Ok(value) => create_task(&**db, &value, config),
+            Err(e) => return (400, error_body(&format!("invalid JSON: {}", e))),
+        },
+        ("POST", ["tasks", id, "complete"]) => match id.parse::<i64>() {
+            Ok(id) => complete_task(&**db, id).map(|_| (200, "{}".to_string())),
+            Err(_) => return (400, error_body("invalid task id")),
+        },
+        _ => return (404, error_body("not found")),
+    };
+
+    match result {
+        Ok(response) => response,
+        Err(e @ Error::NotFound { .. }) => (404, error_body(&e.to_string())),
+        Err(e @ Error::Validation(_)) => (400, error_body(&e.to_string())),
+        Err(e) => {
+            tracing::error!("API request {} {} failed: {}", method, path, e);
+            (500, error_body(&e.to_string()))
+        }
+    }
+}
+
+fn create_task(
+    db: &dyn Storage,
+    value: &serde_json::Value,
+    config: &Config,
+) -> crate::error::Result<(u16, String)> {
+    let title = value["title"].as_str().unwrap_or("").trim().to_string();
+    // 允许只传 title；完整的 Task JSON 会保留其余字段
+    let task = serde_json::from_value::<Task>(value.clone()).unwrap_or_else(|_| Task::new(title.clone()));
+    let mut task = Task { title, ..task };
+    config.apply_rules(&mut task);
+    task.validate()?;
+
+    // 请求中已指定项目时不用规则的项目覆盖
+    let project = task.project_id.is_none().then(|| config.rule_project(&task.title)).flatten();
+    let id = db.create_task_in_project(&task, project)?;
+    Ok((201, serde_json::json!({ "id": id }).to_string()))
+}
+
+fn complete_task(db: &dyn Storage, id: i64) -> crate::error::Result<()> {
+    let mut task = db.get_task(id)?;
+    db.complete_task(&mut task)?;
+    Ok(())
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
@@ -0,0 +1,235 @@
+// 自然语言日期解析："tomorrow 5pm"、"next friday 18:00"、"in 3 days"、"明天 18:00"，
+// 命令行（tasks add --due）和界面命令（:ddl）共用
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+
+/// 只给出日期时的默认时间（当天结束）
+const DEFAULT_HOUR: u32 = 23;
+const DEFAULT_MINUTE: u32 = 59;
+
+/// 解析截止时间，无法识别时返回 None
+///
+/// 日期：today / tomorrow / 今天 / 明天 / 后天、friday（今天之后最近的周五）、
+/// next friday（下周的周五）、next week（下周一）、next month（下月 1 日）、
+/// 2025-03-14、3/14、03-14（已过时取明年）；时间：17:00、5pm、5:30pm、noon、midnight。
+/// 相对时长 in 3 days / in 2 hours / 3d / 12h / 30m / 2w 从现在算起。
+/// 只有日期时为当天 23:59，只有时间时为今天（已过则为明天）的该时间。
+pub fn parse(input: &str, now: DateTime<Local>) -> Option<DateTime<Utc>> {
+    let input = input.trim().to_lowercase();
+    let words: Vec<&str> = input.split_whitespace().filter(|w| *w != "at" && *w != "on").collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    if let Some(duration) = parse_relative(&words) {
+        return Some((now + duration).with_timezone(&Utc));
+    }
+
+    let today = now.date_naive();
+    let mut date: Option<NaiveDate> = None;
+    let mut time: Option<NaiveTime> = None;
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+        // 两个词的说法：next friday / next week / next month / this friday
+        if let Some(next) = words.get(i + 1) {
+            let parsed = match word {
+                "next" => parse_next(next, today),
+                "this" => parse_weekday(next).map(|w| upcoming(today, w, true)),
+                _ => None,
+            };
+            if let Some(parsed) = parsed {
+                date = Some(set_once(date, parsed)?);
+                i += 2;
+                continue;
+            }
+            // 5 pm
+            if let Some(parsed) = parse_time(&format!("{}{}", word, next)).filter(|_| matches!(*next, "am" | "pm")) {
+                time = Some(set_once(time, parsed)?);
+                i += 2;
+                continue;
+            }
+        }
+
+        if let Some(parsed) = parse_day(word, today) {
+            date = Some(set_once(date, parsed)?);
+        } else if let Some(parsed) = parse_time(word) {
+            time = Some(set_once(time, parsed)?);
+        } else if let Some((parsed_date, parsed_time)) = parse_chinese(word, today) {
+            date = Some(set_once(date, parsed_date)?);
+            if let Some(parsed_time) = parsed_time {
+                time = Some(set_once(time, parsed_time)?);
+            }
+        } else {
+            return None;
+        }
+        i += 1;
+    }
+
+    let date = match (date, time) {
+        (Some(date), _) => date,
+        // 只有时间：今天，已经过了就是明天
+        (None, Some(time)) if today.and_time(time) > now.naive_local() => today,
+        (None, Some(_)) => today + Duration::days(1),
+        (None, None) => return None,
+    };
+    let time = time.unwrap_or_else(|| NaiveTime::from_hms_opt(DEFAULT_HOUR, DEFAULT_MINUTE, 0).unwrap());
+    Local
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// 同一部分（日期或时间）只能出现一次
+fn set_once<T>(current: Option<T>, value: T) -> Option<T> {
+    match current {
+        Some(_) => None,
+        None => Some(value),
+    }
+}
+
+/// in 3 days / 3 days / 3d / 12h / 30m / 2w
+fn parse_relative(words: &[&str]) -> Option<Duration> {
+    let words = match words.first() {
+        Some(&"in") => &words[1..],
+        _ => words,
+    };
+    let (amount, unit) = match words {
+        [compact] => {
+            let split = compact.find(|c: char| !c.is_ascii_digit())?;
+            compact.split_at(split)
+        }
+        [amount, unit] => (*amount, *unit),
+        _ => return None,
+    };
+    let amount: i64 = amount.parse().ok().filter(|n| *n > 0)?;
+    let duration = match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" | "分钟" => Duration::minutes(amount),
+        "h" | "hr" | "hrs" | "hour" | "hours" | "小时" => Duration::hours(amount),
+        "d" | "day" | "days" | "天" => Duration::days(amount),
+        "w" | "week" | "weeks" | "周" => Duration::weeks(amount),
+        _ => return None,
+    };
+    Some(duration)
+}
+
+/// 单个词的日期
+fn parse_day(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match word {
+        "today" | "tonight" => return Some(today),
+        "tomorrow" | "tmr" => return Some(today + Duration::days(1)),
+        _ => {}
+    }
+    if let Some(weekday) = parse_weekday(word) {
+        return Some(upcoming(today, weekday, false));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+        return Some(date);
+    }
+    // 3/14、03-14：不带年份，已经过去时取明年
+    let (month, day) = word.split_once('/').or_else(|| word.split_once('-'))?;
+    let (month, day) = (month.parse().ok()?, day.parse().ok()?);
+    let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if date < today {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+    } else {
+        Some(date)
+    }
+}
+
+/// next friday（下周的周五）/ next week（下周一）/ next month（下月 1 日）
+fn parse_next(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match word {
+        "week" => Some(next_week(today, Weekday::Mon)),
+        "month" => {
+            let first = today.with_day(1)?;
+            first.checked_add_months(chrono::Months::new(1))
+        }
+        _ => parse_weekday(word).map(|w| next_week(today, w)),
+    }
+}
+
+/// 下周（从周一算起）的某个星期几
+fn next_week(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let next_monday = today + Duration::days(7 - today.weekday().num_days_from_monday() as i64);
+    next_monday + Duration::days(weekday.num_days_from_monday() as i64)
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    let weekday = match word {
+        "monday" | "mon" => Weekday::Mon,
+        "tuesday" | "tue" | "tues" => Weekday::Tue,
+        "wednesday" | "wed" => Weekday::Wed,
+        "thursday" | "thu" | "thur" | "thurs" => Weekday::Thu,
+        "friday" | "fri" => Weekday::Fri,
+        "saturday" | "sat" => Weekday::Sat,
+        "sunday" | "sun" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+/// 最近的某个星期几；include_today 为 false 时不含今天（1~7 天后）
+fn upcoming(today: NaiveDate, weekday: Weekday, include_today: bool) -> NaiveDate {
+    let diff = (weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64).rem_euclid(7);
+    let diff = if diff == 0 && !include_today { 7 } else { diff };
+    today + Duration::days(diff)
+}
+
+/// 17:00、5pm、5:30pm、noon、midnight
+fn parse_time(word: &str) -> Option<NaiveTime> {
+    match word {
+        "noon" | "中午" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(23, 59, 0),
+        "morning" => return NaiveTime::from_hms_opt(9, 0, 0),
+        "evening" => return NaiveTime::from_hms_opt(18, 0, 0),
+        _ => {}
+    }
+    let (clock, offset) = if let Some(clock) = word.strip_suffix("am") {
+        (clock, Some(0))
+    } else if let Some(clock) = word.strip_suffix("pm") {
+        (clock, Some(12))
+    } else {
+        (word, None)
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        // 不带冒号的纯数字只有带 am/pm 时才是时间
+        None if offset.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// 今天 / 明天 / 后天 / 周五 / 下周五，可以紧跟时间（明天18:00）
+fn parse_chinese(word: &str, today: NaiveDate) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    const DAYS: [(&str, i64); 3] = [("今天", 0), ("明天", 1), ("后天", 2)];
+    const WEEKDAYS: [&str; 7] = ["一", "二", "三", "四", "五", "六", "日"];
+
+    let (date, rest) = if let Some((prefix, offset)) = DAYS.iter().find(|(p, _)| word.starts_with(p)) {
+        (today + Duration::days(*offset), &word[prefix.len()..])
+    } else {
+        let (is_next, rest) = match word.strip_prefix("下") {
+            Some(rest) => (true, rest),
+            None => (false, word),
+        };
+        let rest = rest.strip_prefix("周").or_else(|| rest.strip_prefix("星期"))?;
+        let index = WEEKDAYS.iter().position(|d| rest.starts_with(d))?;
+        let weekday = Weekday::try_from(index as u8).ok()?;
+        let date = if is_next {
+            next_week(today, weekday)
+        } else {
+            upcoming(today, weekday, true)
+        };
+        (date, &rest[WEEKDAYS[index].len()..])
+    };
+    if rest.is_empty() {
+        return Some((date, None));
+    }
+    Some((date, Some(parse_time(rest)?)))
+}
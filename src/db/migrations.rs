@@ -0,0 +1,503 @@
+// 数据库迁移：PRAGMA user_version 记录已执行的迁移数，打开数据库时按顺序执行尚未执行的迁移，
+// 每个迁移在自己的事务中执行并更新 user_version。修改表结构时在 MIGRATIONS 末尾追加一个迁移，
+// 已发布的迁移不再修改。
+//
+// 引入迁移之前的数据库 user_version 为 0，但结构可能是之前任意版本建的，
+// 所以前面这些迁移都可以重复执行（IF NOT EXISTS、列不存在时才添加）
+
+use rusqlite::Connection;
+
+use crate::error::{Error, Result};
+
+type Migration = fn(&Connection) -> rusqlite::Result<()>;
+
+/// 按顺序执行的迁移（说明, 函数），第 n 个执行完后 user_version 为 n
+const MIGRATIONS: &[(&str, Migration)] = &[
+    ("initial schema", initial_schema),
+    ("tags", tags),
+    ("change log and comments", changes_and_comments),
+    ("note ordering, archiving and expiry", note_columns),
+    ("waiting, follow-up and estimates", task_planning_columns),
+    ("busy events, weekly goals and usage stats", planning_tables),
+    ("recurring tasks", recurrence),
+    ("projects", projects),
+    ("task archive", task_archive),
+    ("reminder log", reminders_log),
+    ("time entries", time_entries),
+    ("task dependencies", task_dependencies),
+    ("note color and pinning", note_appearance),
+    ("note tags", note_tags),
+    ("trash", trash),
+    ("task sync ids", task_uuids),
+    ("dangling task links", dangling_task_links),
+];
+
+/// 程序支持的最新数据库版本
+pub const LATEST_VERSION: usize = MIGRATIONS.len();
+
+/// 数据库当前的版本（已执行的迁移数）
+pub fn version(conn: &Connection) -> Result<usize> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    Ok(version.max(0) as usize)
+}
+
+/// 执行尚未执行的迁移，返回执行的数量
+///
+/// 数据库由更新版本的程序创建时返回错误，避免旧程序写坏新的表结构
+pub fn migrate(conn: &Connection) -> Result<usize> {
+    let current = version(conn)?;
+    if current > LATEST_VERSION {
+        return Err(Error::Validation(format!(
+            "数据库版本 {} 比当前程序支持的版本 {} 新，请升级 tasks",
+            current, LATEST_VERSION
+        )));
+    }
+
+    for (index, (name, migration)) in MIGRATIONS.iter().enumerate().skip(current) {
+        let tx = super::begin_write(conn)?;
+        migration(&tx).map_err(|e| Error::Validation(format!("数据库迁移 {}（{}）失败: {}", index + 1, name, e)))?;
+        // PRAGMA 不支持参数绑定
+        tx.execute_batch(&format!("PRAGMA user_version = {}", index + 1))?;
+        tx.commit()?;
+        tracing::info!("database migrated to version {} ({})", index + 1, name);
+    }
+    Ok(LATEST_VERSION - current)
+}
+
+/// 列不存在时添加（user_version 为 0 的旧数据库可能已经有该列）
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    if !columns.iter().any(|c| c == column) {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(())
+}
+
+/// 1: 任务、便签、番茄钟和配置项
+fn initial_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS tasks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            description TEXT,
+            priority INTEGER NOT NULL,
+            status INTEGER NOT NULL,
+            due_date TEXT,
+            reminder_time TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            completed_at TEXT,
+            pomodoro_count INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS notes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            content TEXT NOT NULL,
+            task_id INTEGER,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE SET NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS pomodoro_sessions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER,
+            start_time TEXT NOT NULL,
+            end_time TEXT,
+            duration_minutes INTEGER NOT NULL,
+            completed INTEGER NOT NULL,
+            FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE SET NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS config (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_tasks_due_date ON tasks(due_date);
+        CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
+        CREATE INDEX IF NOT EXISTS idx_tasks_priority ON tasks(priority);
+        CREATE INDEX IF NOT EXISTS idx_notes_task_id ON notes(task_id);
+        "#,
+    )
+}
+
+/// 2: 标签（多对多）
+fn tags(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE
+        );
+
+        CREATE TABLE IF NOT EXISTS task_tags (
+            task_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            PRIMARY KEY(task_id, tag_id),
+            FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+            FOREIGN KEY(tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_task_tags_tag_id ON task_tags(tag_id);
+        "#,
+    )
+}
+
+/// 3: 变更记录（同步、活动记录）和任务评论
+fn changes_and_comments(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS changes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            action TEXT NOT NULL,
+            diff TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS comments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL,
+            body TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_comments_task_id ON comments(task_id);
+        CREATE INDEX IF NOT EXISTS idx_changes_created_at ON changes(created_at);
+        CREATE INDEX IF NOT EXISTS idx_changes_entity ON changes(entity, entity_id);
+        "#,
+    )
+}
+
+/// 4: 便签手动排序、归档和到期时间
+fn note_columns(conn: &Connection) -> rusqlite::Result<()> {
+    add_column(conn, "notes", "position", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(conn, "notes", "archived", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(conn, "notes", "expires_at", "TEXT")
+}
+
+/// 5: 等待中状态的跟进时间和预计耗时
+fn task_planning_columns(conn: &Connection) -> rusqlite::Result<()> {
+    add_column(conn, "tasks", "waiting_since", "TEXT")?;
+    add_column(conn, "tasks", "follow_up_at", "TEXT")?;
+    add_column(conn, "tasks", "estimate_minutes", "INTEGER")
+}
+
+/// 6: 日历忙碌时段、每周目标和功能使用统计
+fn planning_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS busy_events (
+            uid TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            start_time TEXT NOT NULL,
+            end_time TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS weekly_goals (
+            tag TEXT PRIMARY KEY,
+            target INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS usage_stats (
+            name TEXT PRIMARY KEY,
+            count INTEGER NOT NULL DEFAULT 0,
+            last_used TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_busy_events_start ON busy_events(start_time);
+        "#,
+    )
+}
+
+/// 7: 重复规则
+fn recurrence(conn: &Connection) -> rusqlite::Result<()> {
+    add_column(conn, "tasks", "recurrence", "TEXT")
+}
+
+/// 8: 项目
+fn projects(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS projects (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            archived INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL
+        );
+        "#,
+    )?;
+    add_column(conn, "tasks", "project_id", "INTEGER REFERENCES projects(id) ON DELETE SET NULL")
+}
+
+/// 9: 任务归档
+fn task_archive(conn: &Connection) -> rusqlite::Result<()> {
+    add_column(conn, "tasks", "archived_at", "TEXT")
+}
+
+/// 10: 已发送的提醒（守护进程据此避免重复发送同一个提醒）
+fn reminders_log(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS reminders_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL,
+            reminder_time TEXT NOT NULL,
+            sent_at TEXT NOT NULL,
+            FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_reminders_log_task ON reminders_log(task_id, reminder_time);
+        "#,
+    )
+}
+
+/// 11: 任务计时记录（与番茄钟无关的开始/停止计时，ended_at 为空表示正在计时）
+fn time_entries(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS time_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL,
+            started_at TEXT NOT NULL,
+            ended_at TEXT,
+            FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_time_entries_task ON time_entries(task_id);
+        CREATE INDEX IF NOT EXISTS idx_time_entries_started ON time_entries(started_at);
+        "#,
+    )
+}
+
+/// 12: 任务依赖（task_id 依赖 depends_on_id，依赖未完成时 task_id 不能完成）
+fn task_dependencies(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS task_dependencies (
+            task_id INTEGER NOT NULL,
+            depends_on_id INTEGER NOT NULL,
+            PRIMARY KEY (task_id, depends_on_id),
+            FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+            FOREIGN KEY(depends_on_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_task_dependencies_depends_on ON task_dependencies(depends_on_id);
+        "#,
+    )
+}
+
+/// 13: 便签颜色和置顶
+fn note_appearance(conn: &Connection) -> rusqlite::Result<()> {
+    add_column(conn, "notes", "pinned", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column(conn, "notes", "color", "TEXT")
+}
+
+/// 14: 便签标签（与任务共用 tags 表）
+fn note_tags(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS note_tags (
+            note_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            PRIMARY KEY(note_id, tag_id),
+            FOREIGN KEY(note_id) REFERENCES notes(id) ON DELETE CASCADE,
+            FOREIGN KEY(tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_note_tags_tag_id ON note_tags(tag_id);
+        "#,
+    )
+}
+
+/// 15: 回收站（删除的任务和便签的 JSON 快照，恢复时按原 ID 写回）
+fn trash(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS trash (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            data TEXT NOT NULL,
+            deleted_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_trash_deleted_at ON trash(deleted_at);
+        "#,
+    )
+}
+
+/// 16: 任务的同步标识（UUID），之前同步时用创建时间对应各设备上的同一个任务
+///
+/// 已有任务沿用创建时间作为标识，已经同步过的设备之间仍能对应；创建时间重复的任务另外生成 UUID
+fn task_uuids(conn: &Connection) -> rusqlite::Result<()> {
+    add_column(conn, "tasks", "uuid", "TEXT")?;
+
+    let mut stmt = conn.prepare("SELECT id, created_at FROM tasks WHERE uuid IS NULL ORDER BY id")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let mut used = std::collections::HashSet::new();
+    for (id, created_at) in rows {
+        // 与之前的 created_at.to_rfc3339() 保持一致
+        let key = chrono::DateTime::parse_from_rfc3339(&created_at)
+            .map(|t| t.with_timezone(&chrono::Utc).to_rfc3339())
+            .unwrap_or(created_at);
+        let uuid = if used.insert(key.clone()) { key } else { uuid::Uuid::new_v4().to_string() };
+        conn.execute("UPDATE tasks SET uuid = ?1 WHERE id = ?2", rusqlite::params![uuid, id])?;
+    }
+
+    conn.execute_batch("CREATE UNIQUE INDEX IF NOT EXISTS idx_tasks_uuid ON tasks(uuid);")
+}
+
+/// 17: 清除指向已删除任务的便签和番茄钟关联
+///
+/// 之前没有开启外键检查，删除任务后这些 task_id 仍指向不存在的任务，开启后更新这些行会失败
+fn dangling_task_links(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        UPDATE notes SET task_id = NULL
+        WHERE task_id IS NOT NULL AND task_id NOT IN (SELECT id FROM tasks);
+
+        UPDATE pomodoro_sessions SET task_id = NULL
+        WHERE task_id IS NOT NULL AND task_id NOT IN (SELECT id FROM tasks);
+        "#,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).unwrap();
+        let columns = stmt.query_map([], |row| row.get(1)).unwrap();
+        columns.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn migrates_empty_database_to_latest() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert_eq!(migrate(&conn).unwrap(), LATEST_VERSION);
+        assert_eq!(version(&conn).unwrap(), LATEST_VERSION);
+        // 已是最新版本时不再执行
+        assert_eq!(migrate(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn reruns_migrations_on_pre_migration_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        // 引入迁移之前 init_schema 建的库：user_version 为 0，部分列已由 ensure_column 添加
+        initial_schema(&conn).unwrap();
+        tags(&conn).unwrap();
+        conn.execute_batch(
+            r#"
+            ALTER TABLE notes ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE tasks ADD COLUMN waiting_since TEXT;
+            ALTER TABLE tasks ADD COLUMN recurrence TEXT;
+            INSERT INTO tasks (title, priority, status, created_at, updated_at, recurrence)
+            VALUES ('old task', 1, 0, '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', 'weekly');
+            "#,
+        )
+        .unwrap();
+        assert_eq!(version(&conn).unwrap(), 0);
+
+        assert_eq!(migrate(&conn).unwrap(), LATEST_VERSION);
+        assert_eq!(version(&conn).unwrap(), LATEST_VERSION);
+
+        let task_columns = columns(&conn, "tasks");
+        for column in ["waiting_since", "recurrence", "project_id", "archived_at"] {
+            assert_eq!(task_columns.iter().filter(|c| *c == column).count(), 1, "{}", column);
+        }
+        assert!(columns(&conn, "notes").iter().any(|c| c == "pinned"));
+        let recurrence: String = conn
+            .query_row("SELECT recurrence FROM tasks WHERE title = 'old task'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(recurrence, "weekly");
+    }
+
+    /// 执行到名为 name 的迁移之前为止（模拟旧版本建的库）
+    fn migrate_before(conn: &Connection, name: &str) {
+        let before = MIGRATIONS.iter().position(|(n, _)| *n == name).unwrap();
+        for (index, (_, migration)) in MIGRATIONS.iter().enumerate().take(before) {
+            migration(conn).unwrap();
+            conn.execute_batch(&format!("PRAGMA user_version = {}", index + 1)).unwrap();
+        }
+    }
+
+    #[test]
+    fn backfills_task_sync_ids_from_created_at() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_before(&conn, "task sync ids");
+        // 导入两次同一个文件时创建时间相同
+        conn.execute_batch(
+            r#"
+            INSERT INTO tasks (title, priority, status, created_at, updated_at)
+            VALUES ('a', 1, 0, '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z'),
+                   ('b', 1, 0, '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z');
+            "#,
+        )
+        .unwrap();
+
+        migrate(&conn).unwrap();
+        let uuid = |title: &str| -> String {
+            conn.query_row("SELECT uuid FROM tasks WHERE title = ?1", [title], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(uuid("a"), "2024-01-01T00:00:00+00:00");
+        assert!(uuid::Uuid::parse_str(&uuid("b")).is_ok());
+    }
+
+    #[test]
+    fn clears_links_to_deleted_tasks() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_before(&conn, "dangling task links");
+        // 外键检查关闭时删除的任务留下的关联
+        conn.execute_batch(
+            r#"
+            PRAGMA foreign_keys = OFF;
+            INSERT INTO tasks (id, title, priority, status, created_at, updated_at)
+            VALUES (1, 'kept', 1, 0, '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z');
+            INSERT INTO notes (title, content, task_id, created_at, updated_at)
+            VALUES ('linked', '', 1, '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z'),
+                   ('dangling', '', 2, '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z');
+            INSERT INTO pomodoro_sessions (task_id, start_time, duration_minutes, completed)
+            VALUES (2, '2024-01-01T00:00:00Z', 25, 1);
+            PRAGMA foreign_keys = ON;
+            "#,
+        )
+        .unwrap();
+
+        migrate(&conn).unwrap();
+        let note_task = |title: &str| -> Option<i64> {
+            conn.query_row("SELECT task_id FROM notes WHERE title = ?1", [title], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(note_task("linked"), Some(1));
+        assert_eq!(note_task("dangling"), None);
+        let pomodoro_task: Option<i64> = conn
+            .query_row("SELECT task_id FROM pomodoro_sessions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(pomodoro_task, None);
+    }
+
+    #[test]
+    fn rejects_database_from_newer_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!("PRAGMA user_version = {}", LATEST_VERSION + 1))
+            .unwrap();
+        assert!(matches!(migrate(&conn), Err(Error::Validation(_))));
+        // 不会执行任何迁移
+        assert!(columns(&conn, "tasks").is_empty());
+    }
+}
@@ -1,83 +1,201 @@
-use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension, Row};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::time::Duration;
 
-use crate::models::{Note, PomodoroSession, Priority, Task, TaskStatus};
+use crate::error::{Error, Result};
+use crate::models::{BusyEvent, Change, ChangeAction, Comment, Note, PomodoroSession, Priority, Project, Recurrence, Task, TaskStatus, TimeEntry, TrashItem, UsageStat, WeeklyGoal};
+
+mod migrations;
+mod reindex;
+mod storage;
+pub mod timing;
+
+pub use reindex::{ReindexJob, REINDEX_BATCH};
+pub use storage::Storage;
+
+/// 每个连接缓存的预编译语句数（略多于 Database 中固定 SQL 的数量）
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+/// 其他连接（守护进程、TUI、CLI）持有写锁时最多等待的时间
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 等待超时后仍然 SQLITE_BUSY 时的重试次数
+const BUSY_RETRIES: u32 = 3;
+
+/// 数据库超过该大小时建议执行压缩（启动检查中提示）
+pub const COMPACT_SUGGEST_BYTES: u64 = 64 * 1024 * 1024;
+
+/// 回收站中的任务和便签保留的天数
+pub const TRASH_RETENTION_DAYS: i64 = 30;
 
 pub struct Database {
     conn: Connection,
 }
 
+/// 压缩前后的数据库大小
+#[derive(Debug, Clone, Copy)]
+pub struct CompactReport {
+    pub before: u64,
+    pub after: u64,
+}
+
+impl CompactReport {
+    /// 如 "12.3 MB → 8.1 MB（释放 4.2 MB）"
+    pub fn describe(&self) -> String {
+        format!(
+            "{} → {}（释放 {}）",
+            format_size(self.before),
+            format_size(self.after),
+            format_size(self.before.saturating_sub(self.after))
+        )
+    }
+}
+
+/// 孤立数据的处理方式（tasks db orphans --fix）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrphanFix {
+    /// 重新关联到指定任务
+    Relink(i64),
+    /// 解除关联（便签和番茄钟记录保留）
+    Detach,
+    /// 删除
+    Delete,
+}
+
+/// 删除任务时保存的快照（回收站和撤销删除）：除任务和评论外，还包括删除时被级联删除或解除关联的数据，
+/// 恢复时一起写回
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TaskSnapshot {
+    pub task: Task,
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    /// 该任务依赖的任务
+    #[serde(default)]
+    pub depends_on: Vec<i64>,
+    /// 依赖该任务的任务
+    #[serde(default)]
+    pub dependents: Vec<i64>,
+    /// 已发送的提醒（提醒时间, 发送时间），恢复后不再重复提醒
+    #[serde(default)]
+    pub reminders_sent: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    /// 关联到该任务的便签
+    #[serde(default)]
+    pub note_ids: Vec<i64>,
+    /// 关联到该任务的番茄钟记录
+    #[serde(default)]
+    pub pomodoro_ids: Vec<i64>,
+}
+
+impl TaskSnapshot {
+    /// 只有任务本身的快照（新建的任务还没有关联数据）
+    pub fn new(task: Task) -> Self {
+        Self {
+            task,
+            comments: Vec::new(),
+            time_entries: Vec::new(),
+            depends_on: Vec::new(),
+            dependents: Vec::new(),
+            reminders_sent: Vec::new(),
+            note_ids: Vec::new(),
+            pomodoro_ids: Vec::new(),
+        }
+    }
+}
+
+/// tasks list 的过滤条件，未设置的条件不参与查询
+#[derive(Debug, Clone, Default)]
+pub struct TaskQuery {
+    pub status: Option<TaskStatus>,
+    pub priority: Option<Priority>,
+    /// 截止时间早于该时刻
+    pub due_before: Option<DateTime<Utc>>,
+    /// 截止时间不早于该时刻
+    pub due_after: Option<DateTime<Utc>>,
+    /// 在该时刻已逾期（截止时间已过且未完成）
+    pub overdue_at: Option<DateTime<Utc>>,
+    pub project_id: Option<i64>,
+    /// 标签名（不带 #）
+    pub tag: Option<String>,
+    pub sort: TaskSort,
+}
+
+/// tasks list 的排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TaskSort {
+    /// 优先级从高到低，同优先级按截止时间（与 get_all_tasks 一致）
+    #[default]
+    Priority,
+    /// 截止时间从早到晚，没有截止时间的排在最后
+    Due,
+    /// 创建时间从新到旧
+    Created,
+    /// 标题字母序
+    Title,
+}
+
+impl TaskSort {
+    fn order_by(self) -> &'static str {
+        match self {
+            TaskSort::Priority => "priority DESC, due_date ASC",
+            TaskSort::Due => "due_date IS NULL, due_date ASC, priority DESC",
+            TaskSort::Created => "created_at DESC",
+            TaskSort::Title => "title COLLATE NOCASE ASC",
+        }
+    }
+}
+
 impl Database {
     /// 打开或创建数据库
+    ///
+    /// 守护进程和 TUI 可能同时打开同一个文件，打开时遇到数据库被锁定会稍等后重试
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let conn = Connection::open(path).context("Failed to open database")?;
+        let path = path.as_ref();
+        retry_busy(|| Self::open_once(path))
+    }
+
+    fn open_once(path: &Path) -> Result<Self> {
+        let mut conn = Connection::open(path)?;
+        // 每条语句的耗时交给 timing 模块，用于慢查询日志
+        conn.profile(Some(timing::record));
+        // TUI 整个运行期间只用一个连接，常用语句缓存后不必每次重新编译
+        conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+        // 其他进程正在写入时等待，而不是立即返回 SQLITE_BUSY
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        // SQLite 默认不检查外键，打开后表结构中的 ON DELETE CASCADE / SET NULL 才会生效
+        conn.pragma_update(None, "foreign_keys", true)?;
+        // WAL：读写互不阻塞，TUI 长时间持有连接时守护进程和 CLI 仍可读写
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
         let db = Self { conn };
-        db.init_schema()?;
+        migrations::migrate(&db.conn)?;
         Ok(db)
     }
 
-    /// 初始化数据库schema
-    fn init_schema(&self) -> Result<()> {
-        self.conn.execute_batch(
-            r#"
-            CREATE TABLE IF NOT EXISTS tasks (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                title TEXT NOT NULL,
-                description TEXT,
-                priority INTEGER NOT NULL,
-                status INTEGER NOT NULL,
-                due_date TEXT,
-                reminder_time TEXT,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL,
-                completed_at TEXT,
-                pomodoro_count INTEGER NOT NULL DEFAULT 0
-            );
-
-            CREATE TABLE IF NOT EXISTS notes (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                title TEXT NOT NULL,
-                content TEXT NOT NULL,
-                task_id INTEGER,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL,
-                FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE SET NULL
-            );
-
-            CREATE TABLE IF NOT EXISTS pomodoro_sessions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                task_id INTEGER,
-                start_time TEXT NOT NULL,
-                end_time TEXT,
-                duration_minutes INTEGER NOT NULL,
-                completed INTEGER NOT NULL,
-                FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE SET NULL
-            );
-
-            CREATE TABLE IF NOT EXISTS config (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL
-            );
-
-            CREATE INDEX IF NOT EXISTS idx_tasks_due_date ON tasks(due_date);
-            CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
-            CREATE INDEX IF NOT EXISTS idx_tasks_priority ON tasks(priority);
-            CREATE INDEX IF NOT EXISTS idx_notes_task_id ON notes(task_id);
-            "#,
-        )?;
-        Ok(())
+    /// 打开内存数据库（不落盘，进程退出后丢失），用于测试和临时数据
+    pub fn open_in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        let db = Self { conn };
+        migrations::migrate(&db.conn)?;
+        Ok(db)
     }
 
     // ==================== Task CRUD ====================
 
-    /// 创建任务
+    /// 创建任务（任务、标签和变更记录在同一个事务中写入）
+    ///
+    /// 总是生成新的 UUID（task 可能是复制的已有任务）；同步时保留其他设备的标识用 set_task_uuid
     pub fn create_task(&self, task: &Task) -> Result<i64> {
+        let tx = begin_write(&self.conn)?;
+        let uuid = uuid::Uuid::new_v4().to_string();
         self.conn.execute(
             "INSERT INTO tasks (title, description, priority, status, due_date, reminder_time,
-                               created_at, updated_at, pomodoro_count)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                               created_at, updated_at, completed_at, pomodoro_count,
+                               waiting_since, follow_up_at, estimate_minutes, recurrence, project_id, archived_at, uuid)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
             params![
                 task.title,
                 task.description,
@@ -87,70 +205,301 @@ impl Database {
                 task.reminder_time.map(|d| d.to_rfc3339()),
                 task.created_at.to_rfc3339(),
                 task.updated_at.to_rfc3339(),
+                task.completed_at.map(|d| d.to_rfc3339()),
                 task.pomodoro_count,
+                task.waiting_since.map(|d| d.to_rfc3339()),
+                task.follow_up_at.map(|d| d.to_rfc3339()),
+                task.estimate_minutes,
+                task.recurrence.map(|r| r.to_rule()),
+                task.project_id,
+                task.archived_at.map(|d| d.to_rfc3339()),
+                uuid,
             ],
         )?;
-        Ok(self.conn.last_insert_rowid())
+        let id = self.conn.last_insert_rowid();
+        self.set_task_tags(id, &task.tags)?;
+        let created = Task { id: Some(id), uuid: Some(uuid), ..task.clone() };
+        self.record_change("task", id, ChangeAction::Insert, &to_json(&created))?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// 创建任务；project 为项目名称时在同一个事务中查找或创建该项目并放入其中
+    /// （自动标签规则和 --project 指定的项目在任务校验通过后才创建，任务写入失败时一并回滚）
+    pub fn create_task_in_project(&self, task: &Task, project: Option<&str>) -> Result<i64> {
+        let Some(project) = project else {
+            return self.create_task(task);
+        };
+        let tx = begin_write(&self.conn)?;
+        let project_id = self.ensure_project(project)?;
+        let id = self.create_task(&Task { project_id: Some(project_id), ..task.clone() })?;
+        tx.commit()?;
+        Ok(id)
     }
 
     /// 获取所有任务
     pub fn get_all_tasks(&self) -> Result<Vec<Task>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT id, title, description, priority, status, due_date, reminder_time,
-                    created_at, updated_at, completed_at, pomodoro_count
+                    created_at, updated_at, completed_at, pomodoro_count, waiting_since, follow_up_at, estimate_minutes,
+                    recurrence, project_id, archived_at, uuid
              FROM tasks
+             WHERE archived_at IS NULL
              ORDER BY priority DESC, due_date ASC",
         )?;
 
-        let tasks = stmt
-            .query_map([], |row| {
-                Ok(Task {
-                    id: Some(row.get(0)?),
-                    title: row.get(1)?,
-                    description: row.get(2)?,
-                    priority: match row.get::<_, i32>(3)? {
-                        1 => Priority::Low,
-                        2 => Priority::Medium,
-                        _ => Priority::High,
-                    },
-                    status: match row.get::<_, i32>(4)? {
-                        0 => TaskStatus::Todo,
-                        1 => TaskStatus::InProgress,
-                        _ => TaskStatus::Completed,
-                    },
-                    due_date: row
-                        .get::<_, Option<String>>(5)?
-                        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                        .map(|dt| dt.with_timezone(&Utc)),
-                    reminder_time: row
-                        .get::<_, Option<String>>(6)?
-                        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                        .map(|dt| dt.with_timezone(&Utc)),
-                    created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                        .unwrap()
-                        .with_timezone(&Utc),
-                    updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(8)?)
-                        .unwrap()
-                        .with_timezone(&Utc),
-                    completed_at: row
-                        .get::<_, Option<String>>(9)?
-                        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                        .map(|dt| dt.with_timezone(&Utc)),
-                    pomodoro_count: row.get(10)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+        let mut tasks = stmt
+            .query_map([], Self::task_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        self.fill_tags(&mut tasks)?;
+
+        Ok(tasks)
+    }
+
+    /// 已归档的任务（最近归档的在前）
+    pub fn get_archived_tasks(&self) -> Result<Vec<Task>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, title, description, priority, status, due_date, reminder_time,
+                    created_at, updated_at, completed_at, pomodoro_count, waiting_since, follow_up_at, estimate_minutes,
+                    recurrence, project_id, archived_at, uuid
+             FROM tasks
+             WHERE archived_at IS NOT NULL
+             ORDER BY archived_at DESC",
+        )?;
+
+        let mut tasks = stmt
+            .query_map([], Self::task_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        self.fill_tags(&mut tasks)?;
+
+        Ok(tasks)
+    }
+
+    /// 所有任务，包括已归档的（同步、导出时使用）
+    pub fn get_all_tasks_with_archived(&self) -> Result<Vec<Task>> {
+        let mut tasks = self.get_all_tasks()?;
+        tasks.extend(self.get_archived_tasks()?);
+        Ok(tasks)
+    }
+
+    /// 获取截止时间在 [start, end) 内的任务（按截止时间排序）
+    pub fn get_tasks_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<Task>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, title, description, priority, status, due_date, reminder_time,
+                    created_at, updated_at, completed_at, pomodoro_count, waiting_since, follow_up_at, estimate_minutes,
+                    recurrence, project_id, archived_at, uuid
+             FROM tasks
+             WHERE due_date >= ?1 AND due_date < ?2 AND archived_at IS NULL
+             ORDER BY due_date ASC",
+        )?;
+
+        let mut tasks = stmt
+            .query_map(params![start.to_rfc3339(), end.to_rfc3339()], Self::task_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        self.fill_tags(&mut tasks)?;
+
+        Ok(tasks)
+    }
+
+    /// 提醒时间或跟进时间在 (since, until] 内的未完成任务，以及设置了预计耗时、DDL 在 since 之后的任务
+    /// （最晚开始时间由调用方计算）；守护进程只加载这些任务，而不是每轮读取全部任务
+    pub fn get_reminder_tasks(&self, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<Task>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, title, description, priority, status, due_date, reminder_time,
+                    created_at, updated_at, completed_at, pomodoro_count, waiting_since, follow_up_at, estimate_minutes,
+                    recurrence, project_id, archived_at, uuid
+             FROM tasks
+             WHERE archived_at IS NULL AND status != ?3
+               AND ((reminder_time > ?1 AND reminder_time <= ?2)
+                    OR (follow_up_at > ?1 AND follow_up_at <= ?2)
+                    OR (estimate_minutes IS NOT NULL AND due_date > ?1))",
+        )?;
+
+        let mut tasks = stmt
+            .query_map(
+                params![since.to_rfc3339(), until.to_rfc3339(), TaskStatus::Completed as i32],
+                Self::task_from_row,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        self.fill_tags(&mut tasks)?;
+
+        Ok(tasks)
+    }
+
+    /// SQLite 的 data_version：其他连接（TUI、CLI）提交修改后会变化，本连接的写入不会
+    pub fn data_version(&self) -> Result<i64> {
+        Ok(self.conn.query_row("PRAGMA data_version", [], |row| row.get(0))?)
+    }
+
+    /// 按条件查询未归档的任务（tasks list 的过滤和排序），条件都以参数绑定
+    pub fn query_tasks(&self, query: &TaskQuery) -> Result<Vec<Task>> {
+        let mut conditions = vec!["archived_at IS NULL".to_string()];
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        let mut bind = |conditions: &mut Vec<String>, sql: &str, value: Box<dyn rusqlite::ToSql>| {
+            values.push(value);
+            conditions.push(sql.replace('?', &format!("?{}", values.len())));
+        };
+
+        if let Some(status) = query.status {
+            bind(&mut conditions, "status = ?", Box::new(status as i32));
+        }
+        if let Some(priority) = query.priority {
+            bind(&mut conditions, "priority = ?", Box::new(priority as i32));
+        }
+        if let Some(before) = query.due_before {
+            bind(&mut conditions, "due_date < ?", Box::new(before.to_rfc3339()));
+        }
+        if let Some(after) = query.due_after {
+            bind(&mut conditions, "due_date >= ?", Box::new(after.to_rfc3339()));
+        }
+        if let Some(now) = query.overdue_at {
+            bind(&mut conditions, "due_date < ?", Box::new(now.to_rfc3339()));
+            bind(&mut conditions, "status != ?", Box::new(TaskStatus::Completed as i32));
+        }
+        if let Some(project_id) = query.project_id {
+            bind(&mut conditions, "project_id = ?", Box::new(project_id));
+        }
+        if let Some(tag) = query.tag.as_deref() {
+            bind(
+                &mut conditions,
+                "id IN (SELECT tt.task_id FROM task_tags tt JOIN tags t ON t.id = tt.tag_id WHERE t.name = ?)",
+                Box::new(tag.to_string()),
+            );
+        }
+
+        let sql = format!(
+            "SELECT id, title, description, priority, status, due_date, reminder_time,
+                    created_at, updated_at, completed_at, pomodoro_count, waiting_since, follow_up_at, estimate_minutes,
+                    recurrence, project_id, archived_at, uuid
+             FROM tasks
+             WHERE {}
+             ORDER BY {}",
+            conditions.join(" AND "),
+            query.sort.order_by()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut tasks = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), Self::task_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        self.fill_tags(&mut tasks)?;
 
         Ok(tasks)
     }
 
-    /// 更新任务
+    /// 按ID获取任务
+    pub fn get_task(&self, id: i64) -> Result<Task> {
+        let mut task = self
+            .conn
+            .prepare_cached(
+                "SELECT id, title, description, priority, status, due_date, reminder_time,
+                        created_at, updated_at, completed_at, pomodoro_count, waiting_since, follow_up_at, estimate_minutes,
+                        recurrence, project_id, archived_at, uuid
+                 FROM tasks
+                 WHERE id = ?1",
+            )?
+            .query_row(params![id], Self::task_from_row)
+            .optional()?
+            .ok_or(Error::NotFound { entity: "task", id })?;
+        task.tags = self.get_task_tags(id)?;
+        Ok(task)
+    }
+
+    /// 按 UUID 查找任务（同步时的跨设备标识）
+    pub fn find_task_by_uuid(&self, uuid: &str) -> Result<Option<Task>> {
+        let mut task = self
+            .conn
+            .query_row(
+                "SELECT id, title, description, priority, status, due_date, reminder_time,
+                        created_at, updated_at, completed_at, pomodoro_count, waiting_since, follow_up_at, estimate_minutes,
+                        recurrence, project_id, archived_at, uuid
+                 FROM tasks
+                 WHERE uuid = ?1",
+                params![uuid],
+                Self::task_from_row,
+            )
+            .optional()?;
+        if let Some(task) = task.as_mut() {
+            task.tags = self.get_task_tags(task.id.unwrap_or(0))?;
+        }
+        Ok(task)
+    }
+
+    /// 设置任务的 UUID（同步时新建的任务沿用其他设备上的标识）
+    pub fn set_task_uuid(&self, id: i64, uuid: &str) -> Result<()> {
+        self.conn
+            .execute("UPDATE tasks SET uuid = ?1 WHERE id = ?2", params![uuid, id])?;
+        Ok(())
+    }
+
+    /// 将查询行映射为任务（列顺序需与上面的 SELECT 保持一致）
+    fn task_from_row(row: &Row) -> rusqlite::Result<Task> {
+        Ok(Task {
+            id: Some(row.get(0)?),
+            title: row.get(1)?,
+            description: row.get(2)?,
+            priority: match row.get::<_, i32>(3)? {
+                1 => Priority::Low,
+                2 => Priority::Medium,
+                _ => Priority::High,
+            },
+            status: match row.get::<_, i32>(4)? {
+                0 => TaskStatus::Todo,
+                1 => TaskStatus::InProgress,
+                3 => TaskStatus::Waiting,
+                _ => TaskStatus::Completed,
+            },
+            due_date: row
+                .get::<_, Option<String>>(5)?
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            reminder_time: row
+                .get::<_, Option<String>>(6)?
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(8)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            completed_at: row
+                .get::<_, Option<String>>(9)?
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            pomodoro_count: row.get(10)?,
+            tags: Vec::new(),
+            waiting_since: row
+                .get::<_, Option<String>>(11)?
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            follow_up_at: row
+                .get::<_, Option<String>>(12)?
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            estimate_minutes: row.get(13)?,
+            recurrence: row
+                .get::<_, Option<String>>(14)?
+                .and_then(|s| Recurrence::parse(&s)),
+            project_id: row.get(15)?,
+            archived_at: row
+                .get::<_, Option<String>>(16)?
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            uuid: row.get(17)?,
+        })
+    }
+
+    /// 更新任务（任务、标签和变更记录在同一个事务中写入）
     pub fn update_task(&self, task: &Task) -> Result<()> {
+        let tx = begin_write(&self.conn)?;
+        let old = self.get_task(task.id.unwrap_or(0))?;
         self.conn.execute(
             "UPDATE tasks SET title = ?1, description = ?2, priority = ?3, status = ?4,
                             due_date = ?5, reminder_time = ?6, updated_at = ?7,
-                            completed_at = ?8, pomodoro_count = ?9
-             WHERE id = ?10",
+                            completed_at = ?8, pomodoro_count = ?9,
+                            waiting_since = ?10, follow_up_at = ?11, estimate_minutes = ?12,
+                            recurrence = ?13, project_id = ?14, archived_at = ?15
+             WHERE id = ?16",
             params![
                 task.title,
                 task.description,
@@ -161,92 +510,706 @@ impl Database {
                 task.updated_at.to_rfc3339(),
                 task.completed_at.map(|d| d.to_rfc3339()),
                 task.pomodoro_count,
+                task.waiting_since.map(|d| d.to_rfc3339()),
+                task.follow_up_at.map(|d| d.to_rfc3339()),
+                task.estimate_minutes,
+                task.recurrence.map(|r| r.to_rule()),
+                task.project_id,
+                task.archived_at.map(|d| d.to_rfc3339()),
                 task.id,
             ],
         )?;
+        self.set_task_tags(task.id.unwrap_or(0), &task.tags)?;
+        let diff = json_diff(&to_json(&old), &to_json(task));
+        self.record_change("task", task.id.unwrap_or(0), ChangeAction::Update, &diff)?;
+        tx.commit()?;
         Ok(())
     }
 
-    /// 删除任务
+    /// 在一个事务中执行 f，f 返回错误时所有写入都会回滚（TUI 的批量操作使用）
+    pub fn in_transaction<T>(&self, f: impl FnOnce(&Self) -> Result<T>) -> Result<T> {
+        let tx = begin_write(&self.conn)?;
+        let value = f(self)?;
+        tx.commit()?;
+        Ok(value)
+    }
+
+    /// 删除任务（连同标签、评论和变更记录在同一个事务中）
     pub fn delete_task(&self, id: i64) -> Result<()> {
+        let tx = begin_write(&self.conn)?;
+        let old = self.get_task(id)?;
+        self.conn
+            .execute("DELETE FROM task_tags WHERE task_id = ?1", params![id])?;
+        self.conn
+            .execute("DELETE FROM comments WHERE task_id = ?1", params![id])?;
         self.conn
             .execute("DELETE FROM tasks WHERE id = ?1", params![id])?;
+        self.record_change("task", id, ChangeAction::Delete, &to_json(&old))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 彻底删除已归档的任务；before 不为空时只删除在该时间之前归档的，返回删除的数量
+    pub fn purge_archived_tasks(&self, before: Option<DateTime<Utc>>) -> Result<usize> {
+        let mut purged = 0;
+        for task in self.get_archived_tasks()? {
+            if before.is_none_or(|before| task.archived_at.is_some_and(|at| at < before)) {
+                self.delete_task(task.id.unwrap_or(0))?;
+                purged += 1;
+            }
+        }
+        Ok(purged)
+    }
+
+    /// 删除前保存任务及其关联数据
+    pub fn snapshot_task(&self, id: i64) -> Result<TaskSnapshot> {
+        let ids = |sql: &str| -> Result<Vec<i64>> {
+            Ok(self
+                .conn
+                .prepare_cached(sql)?
+                .query_map(params![id], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<_>, _>>()?)
+        };
+        let reminders_sent = self
+            .conn
+            .prepare_cached("SELECT reminder_time, sent_at FROM reminders_log WHERE task_id = ?1 ORDER BY id")?
+            .query_map(params![id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter_map(|(reminder, sent)| {
+                let reminder = DateTime::parse_from_rfc3339(&reminder).ok()?.with_timezone(&Utc);
+                let sent = DateTime::parse_from_rfc3339(&sent).ok()?.with_timezone(&Utc);
+                Some((reminder, sent))
+            })
+            .collect();
+        Ok(TaskSnapshot {
+            task: self.get_task(id)?,
+            comments: self.get_comments(id)?,
+            time_entries: self.get_task_time_entries(id)?,
+            depends_on: ids("SELECT depends_on_id FROM task_dependencies WHERE task_id = ?1 ORDER BY depends_on_id")?,
+            dependents: ids("SELECT task_id FROM task_dependencies WHERE depends_on_id = ?1 ORDER BY task_id")?,
+            reminders_sent,
+            note_ids: ids("SELECT id FROM notes WHERE task_id = ?1 ORDER BY id")?,
+            pomodoro_ids: ids("SELECT id FROM pomodoro_sessions WHERE task_id = ?1 ORDER BY id")?,
+        })
+    }
+
+    /// 按原 ID 恢复已删除的任务及其关联数据（回收站和 TUI 撤销删除）
+    ///
+    /// 依赖的另一方已被删除时跳过该依赖；期间已关联到其他任务的便签和番茄钟记录保持不变
+    pub fn restore_task(&self, snapshot: &TaskSnapshot) -> Result<()> {
+        let task = &snapshot.task;
+        let id = task
+            .id
+            .ok_or_else(|| Error::Validation("任务缺少 ID".to_string()))?;
+        let tx = begin_write(&self.conn)?;
+        self.conn.execute(
+            "INSERT INTO tasks (id, title, description, priority, status, due_date, reminder_time,
+                               created_at, updated_at, completed_at, pomodoro_count,
+                               waiting_since, follow_up_at, estimate_minutes, recurrence, project_id, archived_at, uuid)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+            params![
+                id,
+                task.title,
+                task.description,
+                task.priority as i32,
+                task.status as i32,
+                task.due_date.map(|d| d.to_rfc3339()),
+                task.reminder_time.map(|d| d.to_rfc3339()),
+                task.created_at.to_rfc3339(),
+                task.updated_at.to_rfc3339(),
+                task.completed_at.map(|d| d.to_rfc3339()),
+                task.pomodoro_count,
+                task.waiting_since.map(|d| d.to_rfc3339()),
+                task.follow_up_at.map(|d| d.to_rfc3339()),
+                task.estimate_minutes,
+                task.recurrence.map(|r| r.to_rule()),
+                task.project_id,
+                task.archived_at.map(|d| d.to_rfc3339()),
+                task.uuid.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            ],
+        )?;
+        self.set_task_tags(id, &task.tags)?;
+        self.conn
+            .prepare_cached("DELETE FROM trash WHERE entity = 'task' AND entity_id = ?1")?
+            .execute(params![id])?;
+        for comment in &snapshot.comments {
+            self.conn.execute(
+                "INSERT INTO comments (id, task_id, body, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![comment.id, id, comment.body, comment.created_at.to_rfc3339()],
+            )?;
+        }
+        for entry in &snapshot.time_entries {
+            self.conn
+                .prepare_cached("INSERT INTO time_entries (id, task_id, started_at, ended_at) VALUES (?1, ?2, ?3, ?4)")?
+                .execute(params![
+                    entry.id,
+                    id,
+                    entry.started_at.to_rfc3339(),
+                    entry.ended_at.map(|d| d.to_rfc3339()),
+                ])?;
+        }
+        let dependencies = snapshot
+            .depends_on
+            .iter()
+            .map(|&other| (id, other))
+            .chain(snapshot.dependents.iter().map(|&other| (other, id)));
+        for (task_id, depends_on) in dependencies {
+            self.conn
+                .prepare_cached(
+                    "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_id)
+                     SELECT ?1, ?2 WHERE EXISTS (SELECT 1 FROM tasks WHERE id = ?1)
+                                     AND EXISTS (SELECT 1 FROM tasks WHERE id = ?2)",
+                )?
+                .execute(params![task_id, depends_on])?;
+        }
+        for (reminder_time, sent_at) in &snapshot.reminders_sent {
+            self.log_reminder(id, *reminder_time, *sent_at)?;
+        }
+        for note_id in &snapshot.note_ids {
+            self.conn
+                .prepare_cached("UPDATE notes SET task_id = ?1 WHERE id = ?2 AND task_id IS NULL")?
+                .execute(params![id, note_id])?;
+        }
+        for session_id in &snapshot.pomodoro_ids {
+            self.conn
+                .prepare_cached("UPDATE pomodoro_sessions SET task_id = ?1 WHERE id = ?2 AND task_id IS NULL")?
+                .execute(params![id, session_id])?;
+        }
+        self.record_change("task", id, ChangeAction::Insert, &to_json(task))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    // ==================== Comments ====================
+
+    /// 给任务添加评论
+    pub fn add_comment(&self, comment: &Comment) -> Result<i64> {
+        // 确认任务存在
+        self.get_task(comment.task_id)?;
+        self.conn.execute(
+            "INSERT INTO comments (task_id, body, created_at) VALUES (?1, ?2, ?3)",
+            params![comment.task_id, comment.body, comment.created_at.to_rfc3339()],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        let created = Comment { id: Some(id), ..comment.clone() };
+        self.record_change("comment", id, ChangeAction::Insert, &to_json(&created))?;
+        Ok(id)
+    }
+
+    /// 获取任务的评论（按时间顺序）
+    pub fn get_comments(&self, task_id: i64) -> Result<Vec<Comment>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, task_id, body, created_at FROM comments
+             WHERE task_id = ?1
+             ORDER BY created_at ASC, id ASC",
+        )?;
+        let comments = stmt
+            .query_map(params![task_id], |row| {
+                Ok(Comment {
+                    id: Some(row.get(0)?),
+                    task_id: row.get(1)?,
+                    body: row.get(2)?,
+                    created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
+                        .unwrap()
+                        .with_timezone(&Utc),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(comments)
+    }
+
+    // ==================== Tags ====================
+
+    /// 获取任务的标签（按名称排序）
+    pub fn get_task_tags(&self, task_id: i64) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT t.name FROM task_tags tt
+             JOIN tags t ON t.id = tt.tag_id
+             WHERE tt.task_id = ?1
+             ORDER BY t.name",
+        )?;
+
+        let tags = stmt
+            .query_map(params![task_id], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+
+        Ok(tags)
+    }
+
+    /// 替换任务的标签
+    fn set_task_tags(&self, task_id: i64, tags: &[String]) -> Result<()> {
+        self.conn
+            .prepare_cached("DELETE FROM task_tags WHERE task_id = ?1")?
+            .execute(params![task_id])?;
+        for tag in tags {
+            self.conn
+                .prepare_cached("INSERT OR IGNORE INTO tags (name) VALUES (?1)")?
+                .execute(params![tag])?;
+            self.conn
+                .prepare_cached(
+                    "INSERT OR IGNORE INTO task_tags (task_id, tag_id)
+                     SELECT ?1, id FROM tags WHERE name = ?2",
+                )?
+                .execute(params![task_id, tag])?;
+        }
+        Ok(())
+    }
+
+    /// 一次查询为任务列表填充标签
+    fn fill_tags(&self, tasks: &mut [Task]) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT tt.task_id, t.name FROM task_tags tt
+             JOIN tags t ON t.id = tt.tag_id
+             ORDER BY t.name",
+        )?;
+
+        let mut by_task: HashMap<i64, Vec<String>> = HashMap::new();
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+        for row in rows {
+            let (task_id, name) = row?;
+            by_task.entry(task_id).or_default().push(name);
+        }
+
+        for task in tasks.iter_mut() {
+            if let Some(tags) = task.id.and_then(|id| by_task.remove(&id)) {
+                task.tags = tags;
+            }
+        }
+        Ok(())
+    }
+
+    // ==================== Projects ====================
+
+    /// 创建项目（名称不能为空或与已有项目重复）
+    pub fn create_project(&self, name: &str) -> Result<i64> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::Validation("项目名称不能为空".to_string()));
+        }
+        if self.find_project(name)?.is_some() {
+            return Err(Error::Validation(format!("项目已存在: {}", name)));
+        }
+        self.conn.execute(
+            "INSERT INTO projects (name, archived, created_at) VALUES (?1, 0, ?2)",
+            params![name, Utc::now().to_rfc3339()],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// 按名称查找项目，不存在时创建（同步和导入时按名称对应项目）
+    pub fn ensure_project(&self, name: &str) -> Result<i64> {
+        match self.find_project(name)? {
+            Some(project) => Ok(project.id.unwrap_or(0)),
+            None => self.create_project(name),
+        }
+    }
+
+    /// 所有项目（按名称排序），include_archived 为 false 时不含已归档项目
+    pub fn get_projects(&self, include_archived: bool) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, name, archived, created_at FROM projects
+             WHERE archived = 0 OR ?1
+             ORDER BY name",
+        )?;
+        let projects = stmt
+            .query_map(params![include_archived], Self::project_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(projects)
+    }
+
+    /// 按名称查找项目（含已归档）
+    pub fn find_project(&self, name: &str) -> Result<Option<Project>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT id, name, archived, created_at FROM projects WHERE name = ?1",
+                params![name.trim()],
+                Self::project_from_row,
+            )
+            .optional()?)
+    }
+
+    /// 归档或恢复项目，项目中的任务不受影响
+    pub fn set_project_archived(&self, id: i64, archived: bool) -> Result<()> {
+        let changed = self.conn.execute(
+            "UPDATE projects SET archived = ?1 WHERE id = ?2",
+            params![archived as i32, id],
+        )?;
+        if changed == 0 {
+            return Err(Error::NotFound { entity: "project", id });
+        }
         Ok(())
     }
 
+    fn project_from_row(row: &Row) -> rusqlite::Result<Project> {
+        Ok(Project {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+            archived: row.get::<_, i32>(2)? != 0,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
+                .unwrap()
+                .with_timezone(&Utc),
+        })
+    }
+
     // ==================== Note CRUD ====================
 
+    /// 关联的任务已不存在时返回 None：便签和番茄钟记录在任务删除后保留，写入时不再关联它
+    /// （开启外键检查后，指向不存在的任务会让整个写入失败）
+    fn existing_task_id(&self, task_id: Option<i64>) -> Result<Option<i64>> {
+        let Some(id) = task_id else {
+            return Ok(None);
+        };
+        let exists = self
+            .conn
+            .prepare_cached("SELECT 1 FROM tasks WHERE id = ?1")?
+            .exists(params![id])?;
+        Ok(exists.then_some(id))
+    }
+
     /// 创建便签
     pub fn create_note(&self, note: &Note) -> Result<i64> {
+        let tx = begin_write(&self.conn)?;
+        let task_id = self.existing_task_id(note.task_id)?;
         self.conn.execute(
-            "INSERT INTO notes (title, content, task_id, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO notes (title, content, task_id, created_at, updated_at, archived, expires_at, pinned, color, position)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, (SELECT COALESCE(MIN(position), 0) - 1 FROM notes))",
             params![
                 note.title,
                 note.content,
-                note.task_id,
+                task_id,
                 note.created_at.to_rfc3339(),
                 note.updated_at.to_rfc3339(),
+                note.archived as i32,
+                note.expires_at.map(|d| d.to_rfc3339()),
+                note.pinned as i32,
+                note.color,
             ],
         )?;
-        Ok(self.conn.last_insert_rowid())
+        let id = self.conn.last_insert_rowid();
+        self.set_note_tags(id, &note.tags)?;
+        let created = Note { id: Some(id), task_id, ..note.clone() };
+        self.record_change("note", id, ChangeAction::Insert, &to_json(&created))?;
+        tx.commit()?;
+        Ok(id)
     }
 
     /// 获取所有便签
     pub fn get_all_notes(&self) -> Result<Vec<Note>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, title, content, task_id, created_at, updated_at
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, title, content, task_id, created_at, updated_at, archived, expires_at, pinned, color
+             FROM notes
+             ORDER BY pinned DESC, position ASC, updated_at DESC",
+        )?;
+
+        let mut notes = stmt
+            .query_map([], Self::note_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        self.fill_note_tags(&mut notes)?;
+        Ok(notes)
+    }
+
+    /// 关联到任务的便签（包括已归档的）
+    pub fn get_task_notes(&self, task_id: i64) -> Result<Vec<Note>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, title, content, task_id, created_at, updated_at, archived, expires_at, pinned, color
              FROM notes
+             WHERE task_id = ?1
              ORDER BY updated_at DESC",
         )?;
 
-        let notes = stmt
-            .query_map([], |row| {
-                Ok(Note {
-                    id: Some(row.get(0)?),
-                    title: row.get(1)?,
-                    content: row.get(2)?,
-                    task_id: row.get(3)?,
-                    created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
-                        .unwrap()
-                        .with_timezone(&Utc),
-                    updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                        .unwrap()
-                        .with_timezone(&Utc),
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+        let mut notes = stmt
+            .query_map(params![task_id], Self::note_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
+        self.fill_note_tags(&mut notes)?;
         Ok(notes)
     }
 
+    /// 按ID获取便签
+    pub fn get_note(&self, id: i64) -> Result<Note> {
+        let mut note = self
+            .conn
+            .query_row(
+                "SELECT id, title, content, task_id, created_at, updated_at, archived, expires_at, pinned, color
+                 FROM notes
+                 WHERE id = ?1",
+                params![id],
+                Self::note_from_row,
+            )
+            .optional()?
+            .ok_or(Error::NotFound { entity: "note", id })?;
+        self.fill_note_tags(std::slice::from_mut(&mut note))?;
+        Ok(note)
+    }
+
+    /// 将查询行映射为便签（列顺序需与上面的 SELECT 保持一致）
+    fn note_from_row(row: &Row) -> rusqlite::Result<Note> {
+        Ok(Note {
+            id: Some(row.get(0)?),
+            title: row.get(1)?,
+            content: row.get(2)?,
+            task_id: row.get(3)?,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            archived: row.get::<_, i32>(6)? != 0,
+            expires_at: row
+                .get::<_, Option<String>>(7)?
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            pinned: row.get::<_, i32>(8)? != 0,
+            color: row.get(9)?,
+            tags: Vec::new(),
+        })
+    }
+
+    /// 替换便签的标签
+    fn set_note_tags(&self, note_id: i64, tags: &[String]) -> Result<()> {
+        self.conn
+            .prepare_cached("DELETE FROM note_tags WHERE note_id = ?1")?
+            .execute(params![note_id])?;
+        for tag in tags {
+            self.conn
+                .prepare_cached("INSERT OR IGNORE INTO tags (name) VALUES (?1)")?
+                .execute(params![tag])?;
+            self.conn
+                .prepare_cached(
+                    "INSERT OR IGNORE INTO note_tags (note_id, tag_id)
+                     SELECT ?1, id FROM tags WHERE name = ?2",
+                )?
+                .execute(params![note_id, tag])?;
+        }
+        Ok(())
+    }
+
+    /// 一次查询为便签列表填充标签
+    fn fill_note_tags(&self, notes: &mut [Note]) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT nt.note_id, t.name FROM note_tags nt
+             JOIN tags t ON t.id = nt.tag_id
+             ORDER BY t.name",
+        )?;
+
+        let mut by_note: HashMap<i64, Vec<String>> = HashMap::new();
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+        for row in rows {
+            let (note_id, name) = row?;
+            by_note.entry(note_id).or_default().push(name);
+        }
+
+        for note in notes.iter_mut() {
+            if let Some(tags) = note.id.and_then(|id| by_note.remove(&id)) {
+                note.tags = tags;
+            }
+        }
+        Ok(())
+    }
+
     /// 更新便签
     pub fn update_note(&self, note: &Note) -> Result<()> {
+        let tx = begin_write(&self.conn)?;
+        let old = self.get_note(note.id.unwrap_or(0))?;
+        let note = &Note { task_id: self.existing_task_id(note.task_id)?, ..note.clone() };
         self.conn.execute(
-            "UPDATE notes SET title = ?1, content = ?2, task_id = ?3, updated_at = ?4
-             WHERE id = ?5",
+            "UPDATE notes SET title = ?1, content = ?2, task_id = ?3, updated_at = ?4,
+                              archived = ?5, expires_at = ?6, pinned = ?7, color = ?8
+             WHERE id = ?9",
             params![
                 note.title,
                 note.content,
                 note.task_id,
                 note.updated_at.to_rfc3339(),
+                note.archived as i32,
+                note.expires_at.map(|d| d.to_rfc3339()),
+                note.pinned as i32,
+                note.color,
                 note.id,
             ],
         )?;
+        self.set_note_tags(note.id.unwrap_or(0), &note.tags)?;
+        let diff = json_diff(&to_json(&old), &to_json(note));
+        self.record_change("note", note.id.unwrap_or(0), ChangeAction::Update, &diff)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 归档所有已到期的便签，返回归档数量
+    pub fn archive_expired_notes(&self, now: DateTime<Utc>) -> Result<usize> {
+        let expired: Vec<Note> = self
+            .get_all_notes()?
+            .into_iter()
+            .filter(|n| n.is_expired(now))
+            .collect();
+
+        for note in &expired {
+            let archived = Note {
+                archived: true,
+                updated_at: now,
+                ..note.clone()
+            };
+            self.update_note(&archived)?;
+        }
+        Ok(expired.len())
+    }
+
+    /// 按给定的ID顺序重新排列便签
+    pub fn reorder_notes(&self, ids: &[i64]) -> Result<()> {
+        let tx = begin_write(&self.conn)?;
+        for (position, id) in ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE notes SET position = ?1 WHERE id = ?2",
+                params![position as i64, id],
+            )?;
+        }
+        tx.commit()?;
         Ok(())
     }
 
-    /// 删除便签
+    /// 删除便签（连同标签和变更记录在同一个事务中）
     pub fn delete_note(&self, id: i64) -> Result<()> {
+        let tx = begin_write(&self.conn)?;
+        let old = self.get_note(id)?;
+        self.conn
+            .execute("DELETE FROM note_tags WHERE note_id = ?1", params![id])?;
         self.conn
             .execute("DELETE FROM notes WHERE id = ?1", params![id])?;
+        self.record_change("note", id, ChangeAction::Delete, &to_json(&old))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    // ==================== Trash ====================
+
+    /// 把任务（连同评论、计时、依赖等关联数据）移到回收站，返回保存的快照
+    pub fn trash_task(&self, id: i64) -> Result<TaskSnapshot> {
+        let tx = begin_write(&self.conn)?;
+        let snapshot = self.snapshot_task(id)?;
+        self.insert_trash("task", id, &snapshot.task.title, &to_json(&snapshot))?;
+        self.delete_task(id)?;
+        tx.commit()?;
+        Ok(snapshot)
+    }
+
+    /// 把便签移到回收站
+    pub fn trash_note(&self, id: i64) -> Result<()> {
+        let tx = begin_write(&self.conn)?;
+        let note = self.get_note(id)?;
+        self.insert_trash("note", id, &note.title, &to_json(&note))?;
+        self.delete_note(id)?;
+        tx.commit()?;
         Ok(())
     }
 
+    fn insert_trash(&self, entity: &str, entity_id: i64, title: &str, data: &serde_json::Value) -> Result<()> {
+        self.conn
+            .prepare_cached(
+                "INSERT INTO trash (entity, entity_id, title, data, deleted_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?
+            .execute(params![entity, entity_id, title, data.to_string(), Utc::now().to_rfc3339()])?;
+        Ok(())
+    }
+
+    /// 回收站中的项目（最近删除的在前）
+    pub fn get_trash(&self) -> Result<Vec<TrashItem>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, entity, entity_id, title, deleted_at FROM trash
+             ORDER BY deleted_at DESC, id DESC",
+        )?;
+        let items = stmt
+            .query_map([], Self::trash_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+
+    fn trash_from_row(row: &Row) -> rusqlite::Result<TrashItem> {
+        Ok(TrashItem {
+            id: row.get(0)?,
+            entity: row.get(1)?,
+            entity_id: row.get(2)?,
+            title: row.get(3)?,
+            deleted_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
+                .unwrap()
+                .with_timezone(&Utc),
+        })
+    }
+
+    /// 从回收站恢复（按原 ID 写回），返回恢复的项目
+    pub fn restore_from_trash(&self, id: i64) -> Result<TrashItem> {
+        let (item, data) = self
+            .conn
+            .query_row(
+                "SELECT id, entity, entity_id, title, deleted_at, data FROM trash WHERE id = ?1",
+                params![id],
+                |row| Ok((Self::trash_from_row(row)?, row.get::<_, String>(5)?)),
+            )
+            .optional()?
+            .ok_or(Error::NotFound { entity: "trash", id })?;
+        let corrupt = |e: serde_json::Error| Error::Validation(format!("回收站中的数据无法读取: {}", e));
+
+        let tx = begin_write(&self.conn)?;
+        if item.is_task() {
+            let snapshot: TaskSnapshot = serde_json::from_str(&data).map_err(corrupt)?;
+            self.restore_task(&snapshot)?;
+        } else {
+            let note: Note = serde_json::from_str(&data).map_err(corrupt)?;
+            self.restore_note(&note)?;
+        }
+        tx.commit()?;
+        Ok(item)
+    }
+
+    /// 按原 ID 恢复已删除的便签（排在便签墙最前面）
+    pub fn restore_note(&self, note: &Note) -> Result<()> {
+        let id = note
+            .id
+            .ok_or_else(|| Error::Validation("便签缺少 ID".to_string()))?;
+        // 便签删除后关联的任务也可能被删除了
+        let note = &Note { task_id: self.existing_task_id(note.task_id)?, ..note.clone() };
+        self.conn.execute(
+            "INSERT INTO notes (id, title, content, task_id, created_at, updated_at, archived, expires_at, pinned, color, position)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, (SELECT COALESCE(MIN(position), 0) - 1 FROM notes))",
+            params![
+                id,
+                note.title,
+                note.content,
+                note.task_id,
+                note.created_at.to_rfc3339(),
+                note.updated_at.to_rfc3339(),
+                note.archived as i32,
+                note.expires_at.map(|d| d.to_rfc3339()),
+                note.pinned as i32,
+                note.color,
+            ],
+        )?;
+        self.set_note_tags(id, &note.tags)?;
+        self.conn
+            .prepare_cached("DELETE FROM trash WHERE entity = 'note' AND entity_id = ?1")?
+            .execute(params![id])?;
+        self.record_change("note", id, ChangeAction::Insert, &to_json(note))?;
+        Ok(())
+    }
+
+    /// 清除在 before 之前移到回收站的项目，返回清除的数量
+    pub fn purge_trash(&self, before: DateTime<Utc>) -> Result<usize> {
+        let count = self
+            .conn
+            .prepare_cached("DELETE FROM trash WHERE deleted_at < ?1")?
+            .execute(params![before.to_rfc3339()])?;
+        Ok(count)
+    }
+
     // ==================== Pomodoro Sessions ====================
 
     /// 创建番茄钟会话
     pub fn create_pomodoro(&self, session: &PomodoroSession) -> Result<i64> {
+        // 番茄钟进行期间任务可能已被删除
+        let session = &PomodoroSession { task_id: self.existing_task_id(session.task_id)?, ..session.clone() };
         self.conn.execute(
             "INSERT INTO pomodoro_sessions (task_id, start_time, end_time, duration_minutes, completed)
              VALUES (?1, ?2, ?3, ?4, ?5)",
@@ -258,21 +1221,39 @@ impl Database {
                 session.completed as i32,
             ],
         )?;
-        Ok(self.conn.last_insert_rowid())
+        let id = self.conn.last_insert_rowid();
+        let created = PomodoroSession { id: Some(id), ..session.clone() };
+        self.record_change("pomodoro", id, ChangeAction::Insert, &to_json(&created))?;
+        Ok(id)
     }
 
     /// 完成番茄钟会话
     pub fn complete_pomodoro(&self, id: i64) -> Result<()> {
-        self.conn.execute(
+        let end_time = Utc::now().to_rfc3339();
+        let changed = self.conn.execute(
             "UPDATE pomodoro_sessions SET end_time = ?1, completed = 1 WHERE id = ?2",
-            params![Utc::now().to_rfc3339(), id],
+            params![end_time, id],
         )?;
+        if changed > 0 {
+            let diff = serde_json::json!({
+                "completed": { "old": false, "new": true },
+                "end_time": { "old": null, "new": end_time },
+            });
+            self.record_change("pomodoro", id, ChangeAction::Update, &diff)?;
+        }
         Ok(())
     }
 
+    /// 任务完成一个番茄钟后累加其计数（与 reindex 按已完成会话重算的结果一致）
+    pub fn add_task_pomodoro(&self, task_id: i64) -> Result<()> {
+        let mut task = self.get_task(task_id)?;
+        task.pomodoro_count += 1;
+        self.update_task(&task)
+    }
+
     /// 获取任务的番茄钟记录
     pub fn get_task_pomodoros(&self, task_id: i64) -> Result<Vec<PomodoroSession>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT id, task_id, start_time, end_time, duration_minutes, completed
              FROM pomodoro_sessions
              WHERE task_id = ?1
@@ -280,26 +1261,44 @@ impl Database {
         )?;
 
         let sessions = stmt
-            .query_map(params![task_id], |row| {
-                Ok(PomodoroSession {
-                    id: Some(row.get(0)?),
-                    task_id: row.get(1)?,
-                    start_time: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                        .unwrap()
-                        .with_timezone(&Utc),
-                    end_time: row
-                        .get::<_, Option<String>>(3)?
-                        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                        .map(|dt| dt.with_timezone(&Utc)),
-                    duration_minutes: row.get(4)?,
-                    completed: row.get::<_, i32>(5)? != 0,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+            .query_map(params![task_id], Self::session_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(sessions)
     }
 
+    /// 获取所有番茄钟记录（按开始时间排序，用于导出）
+    pub fn get_all_pomodoros(&self) -> Result<Vec<PomodoroSession>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, task_id, start_time, end_time, duration_minutes, completed
+             FROM pomodoro_sessions
+             ORDER BY start_time",
+        )?;
+
+        let sessions = stmt
+            .query_map([], Self::session_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(sessions)
+    }
+
+    /// 将查询行映射为番茄钟记录（列顺序需与上面的 SELECT 保持一致）
+    fn session_from_row(row: &Row) -> rusqlite::Result<PomodoroSession> {
+        Ok(PomodoroSession {
+            id: Some(row.get(0)?),
+            task_id: row.get(1)?,
+            start_time: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            end_time: row
+                .get::<_, Option<String>>(3)?
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            duration_minutes: row.get(4)?,
+            completed: row.get::<_, i32>(5)? != 0,
+        })
+    }
+
     /// 获取今日完成的番茄钟统计
     pub fn get_today_pomodoro_stats(&self) -> Result<(usize, usize)> {
         let today_start = chrono::Local::now()
@@ -310,7 +1309,7 @@ impl Database {
             .unwrap()
             .with_timezone(&Utc);
 
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT COUNT(*), SUM(duration_minutes)
              FROM pomodoro_sessions
              WHERE completed = 1 AND start_time >= ?1",
@@ -324,11 +1323,539 @@ impl Database {
         Ok((count as usize, total_minutes.unwrap_or(0) as usize))
     }
 
+    // ==================== Change Log ====================
+
+    /// 记录一条变更
+    fn record_change(
+        &self,
+        entity: &str,
+        entity_id: i64,
+        action: ChangeAction,
+        diff: &serde_json::Value,
+    ) -> Result<()> {
+        // 只改了 updated_at 之类的无意义更新不记录
+        if action == ChangeAction::Update && diff.as_object().is_some_and(|d| d.is_empty()) {
+            return Ok(());
+        }
+        self.conn.execute(
+            "INSERT INTO changes (entity, entity_id, action, diff, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                entity,
+                entity_id,
+                action.as_str(),
+                diff.to_string(),
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 获取最近的变更（按时间倒序）
+    pub fn get_changes(&self, limit: usize) -> Result<Vec<Change>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, entity, entity_id, action, diff, created_at
+             FROM changes
+             ORDER BY id DESC
+             LIMIT ?1",
+        )?;
+
+        let changes = stmt
+            .query_map(params![limit as i64], Self::change_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(changes)
+    }
+
+    /// 获取时间范围内的变更（按时间倒序）
+    pub fn get_changes_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Change>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, entity, entity_id, action, diff, created_at
+             FROM changes
+             WHERE created_at >= ?1 AND created_at < ?2
+             ORDER BY id DESC",
+        )?;
+
+        let changes = stmt
+            .query_map(
+                params![start.to_rfc3339(), end.to_rfc3339()],
+                Self::change_from_row,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(changes)
+    }
+
+    /// 变更日志中记录的任务删除时间（按同步标识查找，同步时作为删除操作的时间）
+    pub fn task_deleted_at(&self, uuid: &str) -> Result<Option<DateTime<Utc>>> {
+        let deleted_at = self
+            .conn
+            .prepare_cached(
+                "SELECT created_at FROM changes
+                 WHERE entity = 'task' AND action = ?1 AND json_extract(diff, '$.uuid') = ?2
+                 ORDER BY id DESC
+                 LIMIT 1",
+            )?
+            .query_row(params![ChangeAction::Delete.as_str(), uuid], |row| row.get::<_, String>(0))
+            .optional()?;
+        Ok(deleted_at
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc)))
+    }
+
+    /// 将查询行映射为变更记录（列顺序需与上面的 SELECT 保持一致）
+    fn change_from_row(row: &Row) -> rusqlite::Result<Change> {
+        Ok(Change {
+            id: row.get(0)?,
+            entity: row.get(1)?,
+            entity_id: row.get(2)?,
+            action: ChangeAction::parse(&row.get::<_, String>(3)?).unwrap_or(ChangeAction::Update),
+            diff: serde_json::from_str(&row.get::<_, String>(4)?).unwrap_or_default(),
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
+                .unwrap()
+                .with_timezone(&Utc),
+        })
+    }
+
+    // ==================== Maintenance ====================
+
+    /// 数据库文件大小（页数 × 页大小）
+    pub fn size_bytes(&self) -> Result<u64> {
+        let page_count: i64 = self.conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size: i64 = self.conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        Ok((page_count * page_size) as u64)
+    }
+
+    /// 更新查询统计并重建数据库文件，回收已删除数据占用的空间
+    pub fn compact(&self) -> Result<CompactReport> {
+        let before = self.size_bytes()?;
+        self.conn.execute_batch("ANALYZE; VACUUM;")?;
+        let after = self.size_bytes()?;
+        Ok(CompactReport { before, after })
+    }
+
+    // ==================== Health ====================
+
+    /// 任务和便签中最近的修改时间
+    pub fn latest_updated_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let latest: Option<String> = self.conn.query_row(
+            "SELECT MAX(t) FROM (SELECT MAX(updated_at) AS t FROM tasks UNION ALL SELECT MAX(updated_at) FROM notes)",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(latest
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc)))
+    }
+
+    /// 关联任务已不存在的便签数量
+    pub fn count_orphaned_notes(&self) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM notes WHERE task_id IS NOT NULL AND task_id NOT IN (SELECT id FROM tasks)",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// 解除便签与已删除任务的关联，返回处理的便签数
+    pub fn clear_orphaned_notes(&self) -> Result<usize> {
+        let count = self.conn.execute(
+            "UPDATE notes SET task_id = NULL WHERE task_id IS NOT NULL AND task_id NOT IN (SELECT id FROM tasks)",
+            [],
+        )?;
+        Ok(count)
+    }
+
+    /// 关联任务已不存在的便签
+    pub fn get_orphaned_notes(&self) -> Result<Vec<Note>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, title, content, task_id, created_at, updated_at, archived, expires_at, pinned, color
+             FROM notes
+             WHERE task_id IS NOT NULL AND task_id NOT IN (SELECT id FROM tasks)
+             ORDER BY id",
+        )?;
+        let mut notes = stmt
+            .query_map([], Self::note_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        self.fill_note_tags(&mut notes)?;
+        Ok(notes)
+    }
+
+    /// 关联任务已不存在的番茄钟记录（未绑定任务的记录是正常的，不算在内）
+    pub fn get_orphaned_sessions(&self) -> Result<Vec<PomodoroSession>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, task_id, start_time, end_time, duration_minutes, completed
+             FROM pomodoro_sessions
+             WHERE task_id IS NOT NULL AND task_id NOT IN (SELECT id FROM tasks)
+             ORDER BY start_time",
+        )?;
+        let sessions = stmt
+            .query_map([], Self::session_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(sessions)
+    }
+
+    /// 处理所有孤立的便签和番茄钟记录，返回 (便签数, 番茄钟记录数)
+    pub fn fix_orphans(&self, fix: OrphanFix) -> Result<(usize, usize)> {
+        const ORPHANED: &str = "task_id IS NOT NULL AND task_id NOT IN (SELECT id FROM tasks)";
+
+        if let OrphanFix::Relink(task_id) = fix {
+            self.get_task(task_id)?;
+        }
+        let tx = begin_write(&self.conn)?;
+        let counts = match fix {
+            OrphanFix::Relink(task_id) => (
+                tx.execute(
+                    &format!("UPDATE notes SET task_id = ?1, updated_at = ?2 WHERE {}", ORPHANED),
+                    params![task_id, Utc::now().to_rfc3339()],
+                )?,
+                tx.execute(&format!("UPDATE pomodoro_sessions SET task_id = ?1 WHERE {}", ORPHANED), params![task_id])?,
+            ),
+            OrphanFix::Detach => (
+                tx.execute(&format!("UPDATE notes SET task_id = NULL WHERE {}", ORPHANED), [])?,
+                tx.execute(&format!("UPDATE pomodoro_sessions SET task_id = NULL WHERE {}", ORPHANED), [])?,
+            ),
+            OrphanFix::Delete => (
+                tx.execute(&format!("DELETE FROM notes WHERE {}", ORPHANED), [])?,
+                tx.execute(&format!("DELETE FROM pomodoro_sessions WHERE {}", ORPHANED), [])?,
+            ),
+        };
+        tx.commit()?;
+        Ok(counts)
+    }
+
+    // ==================== Reminder log ====================
+
+    /// 任务在该提醒时间的提醒是否已经发送过（推迟后提醒时间改变，视为新的提醒）
+    pub fn reminder_sent(&self, task_id: i64, reminder_time: DateTime<Utc>) -> Result<bool> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT 1 FROM reminders_log WHERE task_id = ?1 AND reminder_time = ?2 LIMIT 1")?;
+        Ok(stmt.exists(params![task_id, reminder_time.to_rfc3339()])?)
+    }
+
+    /// 记录已发送的提醒
+    pub fn log_reminder(&self, task_id: i64, reminder_time: DateTime<Utc>, sent_at: DateTime<Utc>) -> Result<()> {
+        self.conn
+            .prepare_cached("INSERT INTO reminders_log (task_id, reminder_time, sent_at) VALUES (?1, ?2, ?3)")?
+            .execute(params![task_id, reminder_time.to_rfc3339(), sent_at.to_rfc3339()])?;
+        Ok(())
+    }
+
+    // ==================== Time entries ====================
+
+    /// 开始给任务计时；同一时间只有一个计时，正在给其他任务计时时先停止
+    pub fn start_time_entry(&self, task_id: i64, now: DateTime<Utc>) -> Result<TimeEntry> {
+        self.get_task(task_id)?;
+        let tx = begin_write(&self.conn)?;
+        tx.execute(
+            "UPDATE time_entries SET ended_at = ?1 WHERE ended_at IS NULL",
+            params![now.to_rfc3339()],
+        )?;
+        tx.execute(
+            "INSERT INTO time_entries (task_id, started_at) VALUES (?1, ?2)",
+            params![task_id, now.to_rfc3339()],
+        )?;
+        let id = tx.last_insert_rowid();
+        tx.commit()?;
+        Ok(TimeEntry { id: Some(id), task_id, started_at: now, ended_at: None })
+    }
+
+    /// 停止正在进行的计时，返回停止的记录
+    pub fn stop_time_entry(&self, now: DateTime<Utc>) -> Result<Option<TimeEntry>> {
+        let Some(mut entry) = self.running_time_entry()? else {
+            return Ok(None);
+        };
+        self.conn.execute(
+            "UPDATE time_entries SET ended_at = ?1 WHERE id = ?2",
+            params![now.to_rfc3339(), entry.id],
+        )?;
+        entry.ended_at = Some(now);
+        Ok(Some(entry))
+    }
+
+    /// 正在进行的计时
+    pub fn running_time_entry(&self) -> Result<Option<TimeEntry>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, task_id, started_at, ended_at FROM time_entries
+             WHERE ended_at IS NULL
+             ORDER BY started_at DESC
+             LIMIT 1",
+        )?;
+        Ok(stmt.query_row([], Self::time_entry_from_row).optional()?)
+    }
+
+    /// 各任务已结束的计时累计秒数（不含正在进行的计时）
+    pub fn get_tracked_seconds(&self) -> Result<HashMap<i64, i64>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, task_id, started_at, ended_at FROM time_entries WHERE ended_at IS NOT NULL",
+        )?;
+        let mut totals = HashMap::new();
+        for entry in stmt.query_map([], Self::time_entry_from_row)? {
+            let entry = entry?;
+            *totals.entry(entry.task_id).or_insert(0) += entry.seconds(Utc::now());
+        }
+        Ok(totals)
+    }
+
+    /// 任务的计时记录（最近的在前）
+    pub fn get_task_time_entries(&self, task_id: i64) -> Result<Vec<TimeEntry>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, task_id, started_at, ended_at FROM time_entries
+             WHERE task_id = ?1
+             ORDER BY started_at DESC",
+        )?;
+        let entries = stmt
+            .query_map(params![task_id], Self::time_entry_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// 与 [start, end) 有重叠的计时记录（按开始时间排序）
+    pub fn get_time_entries_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<TimeEntry>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, task_id, started_at, ended_at FROM time_entries
+             WHERE started_at < ?2 AND (ended_at IS NULL OR ended_at > ?1)
+             ORDER BY started_at",
+        )?;
+        let entries = stmt
+            .query_map(params![start.to_rfc3339(), end.to_rfc3339()], Self::time_entry_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// 将查询行映射为计时记录（列顺序需与上面的 SELECT 保持一致）
+    fn time_entry_from_row(row: &Row) -> rusqlite::Result<TimeEntry> {
+        Ok(TimeEntry {
+            id: Some(row.get(0)?),
+            task_id: row.get(1)?,
+            started_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            ended_at: row
+                .get::<_, Option<String>>(3)?
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+        })
+    }
+
+    // ==================== Dependencies ====================
+
+    /// 让 task_id 依赖 depends_on（depends_on 完成前 task_id 不能完成），不允许循环依赖
+    pub fn add_dependency(&self, task_id: i64, depends_on: i64) -> Result<()> {
+        if task_id == depends_on {
+            return Err(Error::Validation("任务不能依赖自己".to_string()));
+        }
+        self.get_task(task_id)?;
+        self.get_task(depends_on)?;
+        // depends_on 直接或间接依赖 task_id 时会形成循环
+        let cycle: bool = self.conn.query_row(
+            "WITH RECURSIVE chain(id) AS (
+                 SELECT depends_on_id FROM task_dependencies WHERE task_id = ?1
+                 UNION
+                 SELECT d.depends_on_id FROM task_dependencies d JOIN chain c ON d.task_id = c.id
+             )
+             SELECT EXISTS (SELECT 1 FROM chain WHERE id = ?2)",
+            params![depends_on, task_id],
+            |row| row.get(0),
+        )?;
+        if cycle {
+            return Err(Error::Validation(format!("#{} 已经（直接或间接）依赖 #{}，不能形成循环依赖", depends_on, task_id)));
+        }
+        self.conn
+            .prepare_cached("INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_id) VALUES (?1, ?2)")?
+            .execute(params![task_id, depends_on])?;
+        Ok(())
+    }
+
+    /// 删除依赖，返回是否存在该依赖
+    pub fn remove_dependency(&self, task_id: i64, depends_on: i64) -> Result<bool> {
+        let removed = self
+            .conn
+            .prepare_cached("DELETE FROM task_dependencies WHERE task_id = ?1 AND depends_on_id = ?2")?
+            .execute(params![task_id, depends_on])?;
+        Ok(removed > 0)
+    }
+
+    /// 任务依赖的任务（未归档的）
+    pub fn get_dependencies(&self, task_id: i64) -> Result<Vec<Task>> {
+        self.related_tasks(
+            "SELECT depends_on_id FROM task_dependencies WHERE task_id = ?1 ORDER BY depends_on_id",
+            task_id,
+        )
+    }
+
+    /// 依赖该任务的任务（未归档的）
+    pub fn get_dependents(&self, task_id: i64) -> Result<Vec<Task>> {
+        self.related_tasks(
+            "SELECT task_id FROM task_dependencies WHERE depends_on_id = ?1 ORDER BY task_id",
+            task_id,
+        )
+    }
+
+    fn related_tasks(&self, sql: &str, task_id: i64) -> Result<Vec<Task>> {
+        let ids = self
+            .conn
+            .prepare_cached(sql)?
+            .query_map(params![task_id], |row| row.get::<_, i64>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let mut tasks = Vec::new();
+        for id in ids {
+            let task = self.get_task(id)?;
+            if task.archived_at.is_none() {
+                tasks.push(task);
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// 还有未完成依赖的任务 ID（已归档的依赖不再阻塞）
+    pub fn get_blocked_task_ids(&self) -> Result<HashSet<i64>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT DISTINCT d.task_id FROM task_dependencies d
+             JOIN tasks t ON t.id = d.depends_on_id
+             WHERE t.status != ?1 AND t.archived_at IS NULL",
+        )?;
+        let ids = stmt
+            .query_map(params![TaskStatus::Completed as i32], |row| row.get(0))?
+            .collect::<std::result::Result<HashSet<i64>, _>>()?;
+        Ok(ids)
+    }
+
+    // ==================== Busy events ====================
+
+    /// 用新导入的日历事件替换全部忙碌时段
+    pub fn replace_busy_events(&self, events: &[BusyEvent]) -> Result<()> {
+        let tx = begin_write(&self.conn)?;
+        tx.execute("DELETE FROM busy_events", [])?;
+        for event in events {
+            tx.execute(
+                "INSERT INTO busy_events (uid, summary, start_time, end_time) VALUES (?1, ?2, ?3, ?4)",
+                params![event.uid, event.summary, event.start.to_rfc3339(), event.end.to_rfc3339()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 获取与 [start, end) 重叠的忙碌时段（按开始时间排序）
+    pub fn get_busy_events_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<BusyEvent>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT uid, summary, start_time, end_time FROM busy_events
+             WHERE start_time < ?2 AND end_time > ?1
+             ORDER BY start_time ASC",
+        )?;
+        let events = stmt
+            .query_map(params![start.to_rfc3339(), end.to_rfc3339()], |row| {
+                Ok(BusyEvent {
+                    uid: row.get(0)?,
+                    summary: row.get(1)?,
+                    start: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
+                        .unwrap()
+                        .with_timezone(&Utc),
+                    end: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
+                        .unwrap()
+                        .with_timezone(&Utc),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(events)
+    }
+
+    // ==================== Weekly goals ====================
+
+    /// 设置标签（项目）的每周番茄钟目标
+    pub fn set_weekly_goal(&self, tag: &str, target: usize) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO weekly_goals (tag, target) VALUES (?1, ?2)
+             ON CONFLICT(tag) DO UPDATE SET target = excluded.target",
+            params![tag, target as i64],
+        )?;
+        Ok(())
+    }
+
+    /// 删除目标，返回是否存在
+    pub fn remove_weekly_goal(&self, tag: &str) -> Result<bool> {
+        let count = self.conn.execute("DELETE FROM weekly_goals WHERE tag = ?1", params![tag])?;
+        Ok(count > 0)
+    }
+
+    /// 获取所有目标及 [start, end) 内完成的番茄钟数（按绑定任务的标签统计）
+    pub fn get_weekly_goals(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<WeeklyGoal>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT g.tag, g.target,
+                    (SELECT COUNT(*)
+                     FROM pomodoro_sessions p
+                     JOIN task_tags tt ON tt.task_id = p.task_id
+                     JOIN tags t ON t.id = tt.tag_id
+                     WHERE t.name = g.tag AND p.completed = 1
+                       AND p.start_time >= ?1 AND p.start_time < ?2)
+             FROM weekly_goals g
+             ORDER BY g.tag",
+        )?;
+        let goals = stmt
+            .query_map(params![start.to_rfc3339(), end.to_rfc3339()], |row| {
+                Ok(WeeklyGoal {
+                    tag: row.get(0)?,
+                    target: row.get::<_, i64>(1)? as usize,
+                    done: row.get::<_, i64>(2)? as usize,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(goals)
+    }
+
+    // ==================== Usage ====================
+
+    /// 累加使用次数
+    pub fn add_usage(&self, counts: &[(String, u64)], now: DateTime<Utc>) -> Result<()> {
+        let tx = begin_write(&self.conn)?;
+        for (name, count) in counts {
+            tx.execute(
+                "INSERT INTO usage_stats (name, count, last_used) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(name) DO UPDATE SET count = count + excluded.count, last_used = excluded.last_used",
+                params![name, *count as i64, now.to_rfc3339()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 获取使用统计（按次数降序）
+    pub fn get_usage(&self) -> Result<Vec<UsageStat>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT name, count, last_used FROM usage_stats ORDER BY count DESC, name ASC")?;
+        let stats = stmt
+            .query_map([], |row| {
+                Ok(UsageStat {
+                    name: row.get(0)?,
+                    count: row.get(1)?,
+                    last_used: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
+                        .unwrap()
+                        .with_timezone(&Utc),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(stats)
+    }
+
+    /// 清空使用统计
+    pub fn clear_usage(&self) -> Result<()> {
+        self.conn.execute("DELETE FROM usage_stats", [])?;
+        Ok(())
+    }
+
     // ==================== Config ====================
 
     /// 获取配置项
     pub fn get_config(&self, key: &str) -> Result<Option<String>> {
-        let mut stmt = self.conn.prepare("SELECT value FROM config WHERE key = ?1")?;
+        let mut stmt = self.conn.prepare_cached("SELECT value FROM config WHERE key = ?1")?;
         let result = stmt.query_row(params![key], |row| row.get(0)).ok();
         Ok(result)
     }
@@ -342,6 +1869,12 @@ impl Database {
         Ok(())
     }
 
+    /// 删除配置项
+    pub fn delete_config(&self, key: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM config WHERE key = ?1", params![key])?;
+        Ok(())
+    }
+
     /// 获取番茄钟配置
     pub fn get_pomodoro_config(&self) -> Result<(i32, i32)> {
         let work = self.get_config("pomodoro_work_duration")?
@@ -359,4 +1892,166 @@ impl Database {
         self.set_config("pomodoro_break_duration", &break_duration.to_string())?;
         Ok(())
     }
+
+    /// 获取番茄钟长休息配置 (长休息时长, 每轮番茄钟数, 本轮已完成数)
+    pub fn get_pomodoro_cycle(&self) -> Result<(i32, u32, u32)> {
+        let long_break = self.get_config("pomodoro_long_break_duration")?
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(15);
+        let cycle_length = self.get_config("pomodoro_cycle_length")?
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(4);
+        let position = self.get_config("pomodoro_cycle_position")?
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        Ok((long_break, cycle_length, position))
+    }
+
+    /// 保存番茄钟长休息配置
+    pub fn save_pomodoro_cycle(&self, long_break_duration: i32, cycle_length: u32) -> Result<()> {
+        self.set_config("pomodoro_long_break_duration", &long_break_duration.to_string())?;
+        self.set_config("pomodoro_cycle_length", &cycle_length.to_string())?;
+        Ok(())
+    }
+
+    /// 保存本轮已完成的番茄钟数（重启 TUI 后继续当前一轮）
+    pub fn save_pomodoro_cycle_position(&self, completed_in_cycle: u32) -> Result<()> {
+        self.set_config("pomodoro_cycle_position", &completed_in_cycle.to_string())
+    }
+
+    /// TUI 保存的进行中的番茄钟（内容无效时视为没有）
+    pub fn get_pomodoro_state(&self) -> Result<Option<crate::pomodoro::PomodoroSnapshot>> {
+        Ok(self.get_config("pomodoro_state")?.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// 保存进行中的番茄钟，None 表示已结束或取消
+    pub fn save_pomodoro_state(&self, snapshot: Option<&crate::pomodoro::PomodoroSnapshot>) -> Result<()> {
+        match snapshot {
+            Some(snapshot) => {
+                let json = serde_json::to_string(snapshot).map_err(|e| Error::Validation(e.to_string()))?;
+                self.set_config("pomodoro_state", &json)
+            }
+            None => self.delete_config("pomodoro_state"),
+        }
+    }
+}
+
+/// 开始写事务：BEGIN IMMEDIATE 在开始时就获取写锁，锁被占用时由 busy_timeout 等待；
+/// 默认的 DEFERRED 事务从读升级为写时若有其他写入者，SQLite 会直接返回 SQLITE_BUSY
+///
+/// 已在事务中时（如 in_transaction 中调用 trash_task）改用保存点，随外层事务一起提交或回滚
+fn begin_write(conn: &Connection) -> rusqlite::Result<WriteTx<'_>> {
+    let nested = !conn.is_autocommit();
+    if nested {
+        conn.execute_batch("SAVEPOINT write_tx")?;
+    } else {
+        conn.execute_batch("BEGIN IMMEDIATE")?;
+    }
+    Ok(WriteTx { conn, nested, finished: false })
+}
+
+/// begin_write 开始的事务（或保存点），没有 commit 就被丢弃时回滚
+struct WriteTx<'conn> {
+    conn: &'conn Connection,
+    nested: bool,
+    finished: bool,
+}
+
+impl WriteTx<'_> {
+    fn commit(mut self) -> rusqlite::Result<()> {
+        self.finished = true;
+        if self.nested {
+            self.conn.execute_batch("RELEASE write_tx")
+        } else {
+            self.conn.execute_batch("COMMIT")
+        }
+    }
+}
+
+impl std::ops::Deref for WriteTx<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+    }
+}
+
+impl Drop for WriteTx<'_> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let rollback = if self.nested {
+            "ROLLBACK TO write_tx; RELEASE write_tx"
+        } else {
+            "ROLLBACK"
+        };
+        if let Err(e) = self.conn.execute_batch(rollback) {
+            tracing::warn!("rollback failed: {}", e);
+        }
+    }
+}
+
+/// 执行 f，数据库被其他连接锁定（等待 busy_timeout 后仍然 SQLITE_BUSY）时稍等后重试
+pub fn retry_busy<T>(mut f: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 0;
+    loop {
+        match f() {
+            Err(e) if is_busy(&e) && attempt < BUSY_RETRIES => {
+                attempt += 1;
+                tracing::warn!("database is locked, retrying ({}/{})", attempt, BUSY_RETRIES);
+                std::thread::sleep(Duration::from_millis(100 * u64::from(attempt)));
+            }
+            result => return result,
+        }
+    }
+}
+
+fn is_busy(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Db(rusqlite::Error::SqliteFailure(e, _))
+            if matches!(e.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// 以 B/KB/MB/GB 显示字节数
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// 序列化为 JSON（模型均可序列化，失败时记为 null）
+fn to_json<T: serde::Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+/// 对比两条记录，返回变化的字段：{字段: {"old": .., "new": ..}}（忽略 updated_at）
+fn json_diff(old: &serde_json::Value, new: &serde_json::Value) -> serde_json::Value {
+    let mut diff = serde_json::Map::new();
+    if let (Some(old), Some(new)) = (old.as_object(), new.as_object()) {
+        for (key, new_value) in new {
+            if key == "updated_at" {
+                continue;
+            }
+            let old_value = old.get(key).unwrap_or(&serde_json::Value::Null);
+            if old_value != new_value {
+                diff.insert(
+                    key.clone(),
+                    serde_json::json!({ "old": old_value, "new": new_value }),
+                );
+            }
+        }
+    }
+    serde_json::Value::Object(diff)
 }
@@ -0,0 +1,72 @@
+// 重建索引和派生数据（tasks db reindex / :reindex），用于大量导入之后
+//
+// 任务的番茄钟数（tasks.pomodoro_count）是从 pomodoro_sessions 推算的缓存，
+// 按批处理，每批之间可以让出数据库，避免长时间占用锁影响 TUI 和守护进程
+
+use rusqlite::params;
+
+use super::Database;
+use crate::error::Result;
+
+/// 每批处理的任务数
+pub const REINDEX_BATCH: usize = 200;
+
+/// 分批执行的重建任务
+pub struct ReindexJob {
+    task_ids: Vec<i64>,
+    done: usize,
+    /// 番茄钟数被修正的任务数
+    pub fixed: usize,
+    indexes_rebuilt: bool,
+}
+
+impl ReindexJob {
+    pub fn new(db: &Database) -> Result<Self> {
+        let mut stmt = db.conn.prepare("SELECT id FROM tasks ORDER BY id")?;
+        let task_ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<i64>, _>>()?;
+        Ok(Self {
+            task_ids,
+            done: 0,
+            fixed: 0,
+            indexes_rebuilt: false,
+        })
+    }
+
+    /// 已处理数和总数（重建 SQLite 索引算作最后一步）
+    pub fn progress(&self) -> (usize, usize) {
+        (self.done + self.indexes_rebuilt as usize, self.task_ids.len() + 1)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.indexes_rebuilt
+    }
+
+    /// 处理下一批，全部完成后返回 false
+    pub fn step(&mut self, db: &Database, batch: usize) -> Result<bool> {
+        if self.done < self.task_ids.len() {
+            let end = (self.done + batch.max(1)).min(self.task_ids.len());
+            let tx = super::begin_write(&db.conn)?;
+            for id in &self.task_ids[self.done..end] {
+                self.fixed += tx.execute(
+                    "UPDATE tasks SET pomodoro_count = (
+                         SELECT COUNT(*) FROM pomodoro_sessions WHERE task_id = ?1 AND completed = 1
+                     )
+                     WHERE id = ?1 AND pomodoro_count != (
+                         SELECT COUNT(*) FROM pomodoro_sessions WHERE task_id = ?1 AND completed = 1
+                     )",
+                    params![id],
+                )?;
+            }
+            tx.commit()?;
+            self.done = end;
+            return Ok(true);
+        }
+        if !self.indexes_rebuilt {
+            db.conn.execute_batch("REINDEX; ANALYZE;")?;
+            self.indexes_rebuilt = true;
+        }
+        Ok(false)
+    }
+}
@@ -0,0 +1,189 @@
+// 存储后端抽象：守护进程等只依赖 Storage trait，不直接依赖 SQLite，
+// 以后可以增加其他后端（JSON 目录、服务端模式用的 Postgres、测试用的内存库）
+
+use chrono::{DateTime, Utc};
+
+use super::Database;
+use crate::error::{Error, Result};
+use crate::models::{PomodoroSession, Task, TaskStatus, WeeklyGoal};
+
+/// 任务、番茄钟和配置项的基本读写（便签只有到期归档）
+///
+/// 只包含各后端都能实现的操作；统计、压缩等依赖 SQL 的功能仍由 [`Database`] 直接提供
+///
+/// 两个程序共用本模块：taskd 通过 trait 调用大部分方法，tasks 多数直接调用 Database 的同名方法，
+/// 各自只用到其中一部分，所以不对未使用的方法发出警告
+#[allow(dead_code)]
+pub trait Storage: Send {
+    // 任务
+    fn create_task(&self, task: &Task) -> Result<i64>;
+    fn get_all_tasks(&self) -> Result<Vec<Task>>;
+    /// 可能在 (since, until] 内触发提醒、跟进或最晚开始提醒的未完成任务（可以多返回，调用方再筛选）
+    fn get_reminder_tasks(&self, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<Task>>;
+    fn get_task(&self, id: i64) -> Result<Task>;
+    fn update_task(&self, task: &Task) -> Result<()>;
+    /// 任务依赖的任务（未归档的）
+    fn get_dependencies(&self, task_id: i64) -> Result<Vec<Task>>;
+    /// 创建任务；project 为项目名称时在同一个事务中查找或创建该项目并放入其中
+    fn create_task_in_project(&self, task: &Task, project: Option<&str>) -> Result<i64>;
+
+    /// 任务还有未完成的依赖时返回 [`Error::Validation`]，消息中列出这些依赖
+    fn check_dependencies(&self, task_id: i64) -> Result<()> {
+        let blockers: Vec<String> = self
+            .get_dependencies(task_id)?
+            .iter()
+            .filter(|t| t.status != TaskStatus::Completed)
+            .map(|t| format!("#{} {}", t.id.unwrap_or(0), t.title))
+            .collect();
+        if blockers.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Validation(format!("🔒 需先完成依赖任务: {}", blockers.join(", "))))
+        }
+    }
+
+    /// 标记任务完成；重复任务同时创建下一次并返回其 ID
+    ///
+    /// 重复规则随之移到新任务上，把旧任务改回未完成再完成时不会重复生成；
+    /// 还有未完成的依赖时返回错误（见 [`Storage::check_dependencies`]）
+    fn complete_task(&self, task: &mut Task) -> Result<Option<i64>> {
+        if let Some(id) = task.id {
+            self.check_dependencies(id)?;
+        }
+        let now = Utc::now();
+        let next = task.next_occurrence(now);
+        task.status = TaskStatus::Completed;
+        task.completed_at = Some(now);
+        task.updated_at = now;
+        task.recurrence = None;
+        self.update_task(task)?;
+        match next {
+            Some(next) => Ok(Some(self.create_task(&next)?)),
+            None => Ok(None),
+        }
+    }
+
+    // 便签
+    /// 归档到期的便签，返回归档数
+    fn archive_expired_notes(&self, now: DateTime<Utc>) -> Result<usize>;
+
+    // 回收站
+    /// 清除在 before 之前移到回收站的任务和便签，返回清除数
+    fn purge_trash(&self, before: DateTime<Utc>) -> Result<usize>;
+
+    // 番茄钟
+    fn create_pomodoro(&self, session: &PomodoroSession) -> Result<i64>;
+    fn complete_pomodoro(&self, id: i64) -> Result<()>;
+    /// 任务完成的番茄钟数加一
+    fn add_task_pomodoro(&self, task_id: i64) -> Result<()>;
+    /// [start, end) 内各标签的每周目标和完成数
+    fn get_weekly_goals(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<WeeklyGoal>>;
+
+    // 提醒记录
+    /// 任务在该提醒时间的提醒是否已经发送过
+    fn reminder_sent(&self, task_id: i64, reminder_time: DateTime<Utc>) -> Result<bool>;
+    /// 记录已发送的提醒
+    fn log_reminder(&self, task_id: i64, reminder_time: DateTime<Utc>, sent_at: DateTime<Utc>) -> Result<()>;
+
+    // 配置项
+    fn get_config(&self, key: &str) -> Result<Option<String>>;
+    fn set_config(&self, key: &str, value: &str) -> Result<()>;
+    fn delete_config(&self, key: &str) -> Result<()>;
+
+    /// 数据版本：其他进程修改数据后变化，守护进程据此发现修改，不必轮询全部数据
+    fn data_version(&self) -> Result<i64>;
+
+    /// 休假模式的结束时间（未设置时为 None，调用方需自行与当前时间比较）
+    fn get_pause_until(&self) -> Result<Option<DateTime<Utc>>> {
+        Ok(self
+            .get_config("pause_until")?
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|t| t.with_timezone(&Utc)))
+    }
+
+    /// 设置或清除休假模式
+    fn set_pause_until(&self, until: Option<DateTime<Utc>>) -> Result<()> {
+        match until {
+            Some(until) => self.set_config("pause_until", &until.to_rfc3339()),
+            None => self.delete_config("pause_until"),
+        }
+    }
+}
+
+impl Storage for Database {
+    fn create_task(&self, task: &Task) -> Result<i64> {
+        Database::create_task(self, task)
+    }
+
+    fn get_all_tasks(&self) -> Result<Vec<Task>> {
+        Database::get_all_tasks(self)
+    }
+
+    fn get_reminder_tasks(&self, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<Task>> {
+        Database::get_reminder_tasks(self, since, until)
+    }
+
+    fn get_task(&self, id: i64) -> Result<Task> {
+        Database::get_task(self, id)
+    }
+
+    fn update_task(&self, task: &Task) -> Result<()> {
+        Database::update_task(self, task)
+    }
+
+    fn get_dependencies(&self, task_id: i64) -> Result<Vec<Task>> {
+        Database::get_dependencies(self, task_id)
+    }
+
+    fn create_task_in_project(&self, task: &Task, project: Option<&str>) -> Result<i64> {
+        Database::create_task_in_project(self, task, project)
+    }
+
+    fn archive_expired_notes(&self, now: DateTime<Utc>) -> Result<usize> {
+        Database::archive_expired_notes(self, now)
+    }
+
+    fn purge_trash(&self, before: DateTime<Utc>) -> Result<usize> {
+        Database::purge_trash(self, before)
+    }
+
+    fn create_pomodoro(&self, session: &PomodoroSession) -> Result<i64> {
+        Database::create_pomodoro(self, session)
+    }
+
+    fn complete_pomodoro(&self, id: i64) -> Result<()> {
+        Database::complete_pomodoro(self, id)
+    }
+
+    fn add_task_pomodoro(&self, task_id: i64) -> Result<()> {
+        Database::add_task_pomodoro(self, task_id)
+    }
+
+    fn get_weekly_goals(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<WeeklyGoal>> {
+        Database::get_weekly_goals(self, start, end)
+    }
+
+    fn reminder_sent(&self, task_id: i64, reminder_time: DateTime<Utc>) -> Result<bool> {
+        Database::reminder_sent(self, task_id, reminder_time)
+    }
+
+    fn log_reminder(&self, task_id: i64, reminder_time: DateTime<Utc>, sent_at: DateTime<Utc>) -> Result<()> {
+        Database::log_reminder(self, task_id, reminder_time, sent_at)
+    }
+
+    fn get_config(&self, key: &str) -> Result<Option<String>> {
+        Database::get_config(self, key)
+    }
+
+    fn set_config(&self, key: &str, value: &str) -> Result<()> {
+        Database::set_config(self, key, value)
+    }
+
+    fn delete_config(&self, key: &str) -> Result<()> {
+        Database::delete_config(self, key)
+    }
+
+    fn data_version(&self) -> Result<i64> {
+        Database::data_version(self)
+    }
+}
@@ -0,0 +1,69 @@
+// 慢查询记录：SQLite 每条语句执行完后回调，超过阈值的记入内存中的环形日志并输出 tracing 警告，
+// 用于在大数据库上定位性能问题（TUI 中 :timing 开启）
+
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 内存中保留的慢查询条数
+const MAX_ENTRIES: usize = 50;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static THRESHOLD_MS: AtomicU64 = AtomicU64::new(100);
+static SLOW_LOG: Mutex<VecDeque<SlowQuery>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug, Clone)]
+pub struct SlowQuery {
+    pub sql: String,
+    pub elapsed: Duration,
+    pub at: DateTime<Utc>,
+}
+
+/// 开启/关闭慢查询记录（关闭时只输出 trace 级日志）
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_threshold(threshold: Duration) {
+    THRESHOLD_MS.store(threshold.as_millis() as u64, Ordering::Relaxed);
+}
+
+pub fn threshold() -> Duration {
+    Duration::from_millis(THRESHOLD_MS.load(Ordering::Relaxed))
+}
+
+/// 最近的慢查询（最新的在前）
+pub fn recent() -> Vec<SlowQuery> {
+    SLOW_LOG.lock().map(|log| log.iter().rev().cloned().collect()).unwrap_or_default()
+}
+
+/// 在 `since` 之后记录的最慢的一条
+pub fn slowest_since(since: DateTime<Utc>) -> Option<SlowQuery> {
+    let log = SLOW_LOG.lock().ok()?;
+    log.iter().filter(|q| q.at >= since).max_by_key(|q| q.elapsed).cloned()
+}
+
+/// 传给 Connection::profile 的回调
+pub(super) fn record(sql: &str, elapsed: Duration) {
+    tracing::trace!(target: "tasks::db", elapsed_ms = elapsed.as_millis() as u64, sql, "query");
+    if !enabled() || elapsed < threshold() {
+        return;
+    }
+    tracing::warn!(target: "tasks::db", elapsed_ms = elapsed.as_millis() as u64, sql, "slow query");
+    if let Ok(mut log) = SLOW_LOG.lock() {
+        if log.len() >= MAX_ENTRIES {
+            log.pop_front();
+        }
+        log.push_back(SlowQuery {
+            sql: sql.split_whitespace().collect::<Vec<_>>().join(" "),
+            elapsed,
+            at: Utc::now(),
+        });
+    }
+}
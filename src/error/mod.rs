@@ -0,0 +1,33 @@
+use thiserror::Error;
+
+/// 库层统一错误类型
+/// 二进制（CLI/TUI/守护进程）仍使用 anyhow，库层返回该类型以便调用方按错误种类处理
+#[derive(Debug, Error)]
+pub enum Error {
+    /// SQLite 访问失败
+    #[error("database error: {0}")]
+    Db(#[from] rusqlite::Error),
+
+    /// 按ID查找的实体不存在
+    #[error("{entity} #{id} not found")]
+    NotFound { entity: &'static str, id: i64 },
+
+    /// 输入数据不合法（消息直接展示给用户）
+    #[error("{0}")]
+    Validation(String),
+
+    /// 文件读写失败
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// 同步相关错误
+    #[error("sync error: {0}")]
+    Sync(String),
+
+    /// 远程服务器请求失败
+    #[error("remote error: {0}")]
+    Remote(String),
+}
+
+/// 库层 Result 别名
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,306 @@
+// 数据导入（tasks import）：读取 tasks export 导出的 JSON / CSV，或只有 title,due,priority 的简单 CSV
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use super::Export;
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::models::{Priority, Recurrence, Task, TaskStatus};
+
+/// 导入结果
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub tasks_added: usize,
+    pub tasks_skipped: usize,
+    pub notes_added: usize,
+    pub notes_skipped: usize,
+    pub pomodoros_added: usize,
+    pub pomodoros_skipped: usize,
+    /// 无法导入的行（"第 N 行: 原因"），其余行照常导入
+    pub errors: Vec<String>,
+}
+
+/// 按扩展名导入文件（.json 为 tasks export 的完整导出，其余按 CSV 处理）
+///
+/// 标题和创建时间都相同的任务/便签视为重复并跳过；简单 CSV 没有 created_at 列时，
+/// 改为按标题和截止时间判断，这样同一个文件导入两次不会产生重复
+pub fn import_file(db: &Database, path: &Path) -> Result<ImportReport> {
+    let text = std::fs::read_to_string(path)?;
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    if is_json {
+        let data: Export = serde_json::from_str(&text)
+            .map_err(|e| Error::Validation(format!("无法解析 JSON: {}", e)))?;
+        if data.version > super::FORMAT_VERSION {
+            return Err(Error::Validation(format!(
+                "导出文件版本 {} 比当前支持的版本 {} 新，请先升级 tasks",
+                data.version,
+                super::FORMAT_VERSION
+            )));
+        }
+        import_export(db, data)
+    } else {
+        import_csv(db, &text)
+    }
+}
+
+fn import_export(db: &Database, data: Export) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+    let existing = db.get_all_tasks_with_archived()?;
+    let mut seen: HashMap<(String, DateTime<Utc>), i64> = existing
+        .iter()
+        .filter_map(|t| Some(((t.title.clone(), t.created_at), t.id?)))
+        .collect();
+    // 导出文件中的任务 ID → 本地任务 ID，用于重新关联便签和番茄钟
+    let mut id_map: HashMap<i64, i64> = HashMap::new();
+    // 导出文件中的项目 ID → 本地项目 ID（按名称对应，不存在时创建）
+    let mut project_map: HashMap<i64, i64> = HashMap::new();
+    for project in &data.projects {
+        if let Some(old_id) = project.id {
+            // 本地已有同名项目时沿用它（包括归档状态）
+            let id = match db.find_project(&project.name)?.and_then(|p| p.id) {
+                Some(id) => id,
+                None => {
+                    let id = db.create_project(&project.name)?;
+                    if project.archived {
+                        db.set_project_archived(id, true)?;
+                    }
+                    id
+                }
+            };
+            project_map.insert(old_id, id);
+        }
+    }
+
+    for task in data.tasks {
+        let old_id = task.id;
+        let key = (task.title.clone(), task.created_at);
+        let new_id = match seen.get(&key) {
+            Some(id) => {
+                report.tasks_skipped += 1;
+                *id
+            }
+            None => {
+                if let Err(e) = task.validate() {
+                    report.errors.push(format!("任务 \"{}\": {}", task.title, e));
+                    continue;
+                }
+                let project_id = task.project_id.and_then(|id| project_map.get(&id).copied());
+                let id = db.create_task(&Task { id: None, project_id, ..task })?;
+                seen.insert(key, id);
+                report.tasks_added += 1;
+                id
+            }
+        };
+        if let Some(old_id) = old_id {
+            id_map.insert(old_id, new_id);
+        }
+    }
+
+    let existing_notes: HashSet<(String, DateTime<Utc>)> = db
+        .get_all_notes()?
+        .into_iter()
+        .map(|n| (n.title, n.created_at))
+        .collect();
+    for mut note in data.notes {
+        if existing_notes.contains(&(note.title.clone(), note.created_at)) {
+            report.notes_skipped += 1;
+            continue;
+        }
+        note.id = None;
+        note.task_id = note.task_id.and_then(|id| id_map.get(&id).copied());
+        db.create_note(&note)?;
+        report.notes_added += 1;
+    }
+
+    let existing_sessions: HashSet<DateTime<Utc>> =
+        db.get_all_pomodoros()?.into_iter().map(|p| p.start_time).collect();
+    for mut session in data.pomodoros {
+        if existing_sessions.contains(&session.start_time) {
+            report.pomodoros_skipped += 1;
+            continue;
+        }
+        session.id = None;
+        session.task_id = session.task_id.and_then(|id| id_map.get(&id).copied());
+        db.create_pomodoro(&session)?;
+        report.pomodoros_added += 1;
+    }
+
+    Ok(report)
+}
+
+fn import_csv(db: &Database, text: &str) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+    let mut rows = parse_csv(text).into_iter().peekable();
+
+    // 有表头时按列名取值，否则按 title,due,priority 的顺序
+    let has_header = rows
+        .peek()
+        .and_then(|(_, first)| first.first())
+        .is_some_and(|f| f.trim().eq_ignore_ascii_case("title"));
+    let header: Vec<String> = match rows.next_if(|_| has_header) {
+        Some((_, header)) => header.iter().map(|f| f.trim().to_lowercase()).collect(),
+        None => vec!["title".to_string(), "due".to_string(), "priority".to_string()],
+    };
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let columns = CsvColumns {
+        title: column("title"),
+        due: column("due"),
+        priority: column("priority"),
+        status: column("status"),
+        tags: column("tags"),
+        project: column("project"),
+        created_at: column("created_at"),
+        completed_at: column("completed_at"),
+        estimate: column("estimate_minutes"),
+        recurrence: column("recurrence"),
+        description: column("description"),
+    };
+
+    let existing = db.get_all_tasks_with_archived()?;
+    let mut by_created: HashSet<(String, DateTime<Utc>)> =
+        existing.iter().map(|t| (t.title.clone(), t.created_at)).collect();
+    let mut by_due: HashSet<(String, Option<DateTime<Utc>>)> =
+        existing.iter().map(|t| (t.title.clone(), t.due_date)).collect();
+
+    for (line, row) in rows {
+        if row.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        let mut task = match columns.task(&row) {
+            Ok(task) => task,
+            Err(e) => {
+                report.errors.push(format!("第 {} 行: {}", line, e));
+                continue;
+            }
+        };
+
+        let duplicate = if columns.created_at.is_some() {
+            !by_created.insert((task.title.clone(), task.created_at))
+        } else {
+            !by_due.insert((task.title.clone(), task.due_date))
+        };
+        if duplicate {
+            report.tasks_skipped += 1;
+            continue;
+        }
+        if let Some(project) = columns.project.and_then(|i| row.get(i)).map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            task.project_id = Some(db.ensure_project(project)?);
+        }
+        db.create_task(&task)?;
+        report.tasks_added += 1;
+    }
+
+    Ok(report)
+}
+
+/// CSV 各列的位置（None 表示文件中没有该列）
+struct CsvColumns {
+    title: Option<usize>,
+    due: Option<usize>,
+    priority: Option<usize>,
+    status: Option<usize>,
+    tags: Option<usize>,
+    project: Option<usize>,
+    created_at: Option<usize>,
+    completed_at: Option<usize>,
+    estimate: Option<usize>,
+    recurrence: Option<usize>,
+    description: Option<usize>,
+}
+
+impl CsvColumns {
+    fn task(&self, row: &[String]) -> std::result::Result<Task, String> {
+        let field = |col: Option<usize>| {
+            col.and_then(|i| row.get(i))
+                .map(|f| f.trim())
+                .filter(|f| !f.is_empty())
+        };
+
+        let mut task = Task::new(field(self.title).unwrap_or_default().to_string());
+        if let Some(due) = field(self.due) {
+            task.due_date = Some(parse_time(due).ok_or_else(|| format!("无法识别的截止时间 {}", due))?);
+        }
+        if let Some(priority) = field(self.priority) {
+            task.priority = Priority::parse(priority).ok_or_else(|| format!("无法识别的优先级 {}", priority))?;
+        }
+        if let Some(status) = field(self.status) {
+            task.status = TaskStatus::parse(status).ok_or_else(|| format!("无法识别的状态 {}", status))?;
+        }
+        for tag in field(self.tags).unwrap_or_default().split_whitespace() {
+            task.add_tag(tag);
+        }
+        if let Some(created) = field(self.created_at) {
+            task.created_at = parse_time(created).ok_or_else(|| format!("无法识别的创建时间 {}", created))?;
+            task.updated_at = task.created_at;
+        }
+        task.completed_at = field(self.completed_at).and_then(parse_time);
+        if task.status == TaskStatus::Completed && task.completed_at.is_none() {
+            task.completed_at = Some(Utc::now());
+        }
+        task.estimate_minutes = field(self.estimate).and_then(|m| m.parse().ok());
+        if let Some(rule) = field(self.recurrence) {
+            task.recurrence = Some(Recurrence::parse(rule).ok_or_else(|| format!("无法识别的重复规则 {}", rule))?);
+        }
+        task.description = field(self.description).map(|d| d.to_string());
+        task.validate().map_err(|e| e.to_string())?;
+        Ok(task)
+    }
+}
+
+/// 解析时间：RFC 3339、本地 YYYY-MM-DD HH:MM，或只有日期（当天 23:59 截止）
+fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Some(t.with_timezone(&Utc));
+    }
+    let local = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?.and_hms_opt(23, 59, 0))?;
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// 按 RFC 4180 解析 CSV（支持引号内的逗号、换行和 "" 转义），返回 (起始行号, 字段)
+fn parse_csv(text: &str) -> Vec<(usize, Vec<String>)> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut row_start = 1;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push((row_start, std::mem::take(&mut row)));
+                line += 1;
+                row_start = line;
+            }
+            ('\n', true) => {
+                field.push(c);
+                line += 1;
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push((row_start, row));
+    }
+    rows
+}
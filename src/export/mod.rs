@@ -0,0 +1,209 @@
+// 数据导出（tasks export）：把任务、便签和番茄钟记录导出为 JSON / CSV / Markdown，
+// 用于备份或分享；JSON 可以由 tasks import 完整读回（见 import.rs）
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Database;
+use crate::error::Result;
+use crate::models::{Note, PomodoroSession, Project, Task, TaskStatus};
+
+mod import;
+
+pub use import::import_file;
+
+/// 导出文件格式版本，结构不兼容时递增
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    /// 只包含任务（一张表，方便在表格软件中打开）
+    Csv,
+    Markdown,
+}
+
+/// 导出范围：状态只作用于任务，时间范围按创建时间（番茄钟按开始时间）筛选
+#[derive(Debug, Default)]
+pub struct ExportFilter {
+    pub status: Option<TaskStatus>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl ExportFilter {
+    fn in_range(&self, at: DateTime<Utc>) -> bool {
+        self.since.is_none_or(|since| at >= since) && self.until.is_none_or(|until| at < until)
+    }
+}
+
+/// 导出的全部数据（JSON 格式即此结构）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Export {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// 任务通过 project_id 引用，导入时按名称重新对应到本地项目
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub notes: Vec<Note>,
+    #[serde(default)]
+    pub pomodoros: Vec<PomodoroSession>,
+}
+
+impl Export {
+    /// 按过滤条件从数据库读取
+    pub fn collect(db: &Database, filter: &ExportFilter) -> Result<Self> {
+        let mut tasks: Vec<Task> = db
+            .get_all_tasks_with_archived()?
+            .into_iter()
+            .filter(|t| filter.status.is_none_or(|s| t.status == s) && filter.in_range(t.created_at))
+            .collect();
+        tasks.sort_by_key(|t| t.created_at);
+        let notes = db
+            .get_all_notes()?
+            .into_iter()
+            .filter(|n| filter.in_range(n.created_at))
+            .collect();
+        let pomodoros = db
+            .get_all_pomodoros()?
+            .into_iter()
+            .filter(|p| filter.in_range(p.start_time))
+            .collect();
+
+        Ok(Self {
+            version: FORMAT_VERSION,
+            exported_at: Utc::now(),
+            projects: db.get_projects(true)?,
+            tasks,
+            notes,
+            pomodoros,
+        })
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            // 只含普通字段和字符串键，序列化不会失败
+            Format::Json => serde_json::to_string_pretty(self).unwrap_or_default() + "\n",
+            Format::Csv => self.to_csv(),
+            Format::Markdown => self.to_markdown(),
+        }
+    }
+
+    /// CSV 前三列为 title,due,priority，与 tasks import 接受的简单 CSV 相同
+    fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "title,due,priority,status,tags,project,created_at,completed_at,estimate_minutes,recurrence,description\n",
+        );
+        for task in &self.tasks {
+            let fields = [
+                task.title.clone(),
+                format_local(task.due_date),
+                task.priority.as_str().to_string(),
+                task.status.as_str().to_string(),
+                task.tags.join(" "),
+                self.project_name(task.project_id).unwrap_or_default().to_string(),
+                task.created_at.to_rfc3339(),
+                task.completed_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                task.estimate_minutes.map(|m| m.to_string()).unwrap_or_default(),
+                task.recurrence.map(|r| r.to_rule()).unwrap_or_default(),
+                task.description.clone().unwrap_or_default(),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    fn project_name(&self, id: Option<i64>) -> Option<&str> {
+        let id = id?;
+        self.projects.iter().find(|p| p.id == Some(id)).map(|p| p.name.as_str())
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = vec![
+            format!("# 任务导出 {}", self.exported_at.with_timezone(&Local).format("%Y-%m-%d %H:%M")),
+            String::new(),
+            format!("## 任务（{}）", self.tasks.len()),
+            String::new(),
+        ];
+        for task in &self.tasks {
+            let mut line = format!(
+                "- [{}] {}",
+                if task.status == TaskStatus::Completed { "x" } else { " " },
+                task.title
+            );
+            line.push_str(&format!(" ({}", task.priority.as_str()));
+            if let Some(due) = task.due_date {
+                line.push_str(&format!("，截止 {}", due.with_timezone(&Local).format("%Y-%m-%d %H:%M")));
+            }
+            if let Some(rule) = task.recurrence {
+                line.push_str(&format!("，{}", rule.describe()));
+            }
+            line.push(')');
+            for tag in &task.tags {
+                line.push_str(&format!(" #{}", tag));
+            }
+            if let Some(project) = self.project_name(task.project_id) {
+                line.push_str(&format!(" 📁{}", project));
+            }
+            out.push(line);
+            if let Some(description) = task.description.as_deref().filter(|d| !d.trim().is_empty()) {
+                for desc_line in description.lines() {
+                    out.push(format!("  > {}", desc_line));
+                }
+            }
+        }
+
+        out.push(String::new());
+        out.push(format!("## 便签（{}）", self.notes.len()));
+        for note in &self.notes {
+            out.push(String::new());
+            out.push(format!("### {}{}", note.title, if note.archived { "（已归档）" } else { "" }));
+            out.push(String::new());
+            out.push(note.content.trim_end().to_string());
+        }
+
+        out.push(String::new());
+        out.push(format!("## 番茄钟（{}）", self.pomodoros.len()));
+        out.push(String::new());
+        out.push("| 开始 | 时长 | 任务 | 完成 |".to_string());
+        out.push("| --- | --- | --- | --- |".to_string());
+        for session in &self.pomodoros {
+            let task = session
+                .task_id
+                .map(|id| match self.tasks.iter().find(|t| t.id == Some(id)) {
+                    Some(task) => task.title.replace('|', "\\|"),
+                    None => format!("#{}", id),
+                })
+                .unwrap_or_default();
+            out.push(format!(
+                "| {} | {} 分钟 | {} | {} |",
+                session.start_time.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                session.duration_minutes,
+                task,
+                if session.completed { "✓" } else { "" }
+            ));
+        }
+        out.push(String::new());
+        out.join("\n")
+    }
+}
+
+/// 本地时间 YYYY-MM-DD HH:MM，空值为空字符串
+fn format_local(time: Option<DateTime<Utc>>) -> String {
+    time.map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// 按 RFC 4180 转义：含逗号、引号或换行时加引号，引号写两次
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
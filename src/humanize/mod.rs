@@ -0,0 +1,73 @@
+// 相对时间（"3天前" / "3d ago"），任务/便签详情和命令行输出共用
+
+use chrono::{DateTime, Utc};
+
+use crate::config::Locale;
+
+/// 把时间显示为相对于 `now` 的时长，未来的时间显示为"3天后" / "in 3d"
+pub fn age(then: DateTime<Utc>, now: DateTime<Utc>, locale: Locale) -> String {
+    let seconds = (now - then).num_seconds();
+    let future = seconds < 0;
+    let (amount, zh_unit, en_unit) = match duration(seconds.unsigned_abs()) {
+        Some(parts) => parts,
+        None => {
+            return match locale.resolve() {
+                Locale::En => "just now".to_string(),
+                _ => "刚刚".to_string(),
+            }
+        }
+    };
+
+    match (locale.resolve(), future) {
+        (Locale::En, false) => format!("{}{} ago", amount, en_unit),
+        (Locale::En, true) => format!("in {}{}", amount, en_unit),
+        (_, false) => format!("{}{}前", amount, zh_unit),
+        (_, true) => format!("{}{}后", amount, zh_unit),
+    }
+}
+
+/// 距截止时间的相对描述，如"3小时后到期" / "逾期2天"（"due in 3h" / "2d overdue"）
+pub fn due(due: DateTime<Utc>, now: DateTime<Utc>, locale: Locale) -> String {
+    let seconds = (due - now).num_seconds();
+    let overdue = seconds < 0;
+    let (amount, zh_unit, en_unit) = match duration(seconds.unsigned_abs()) {
+        Some(parts) => parts,
+        None => {
+            return match locale.resolve() {
+                Locale::En => "due now".to_string(),
+                _ => "即将到期".to_string(),
+            }
+        }
+    };
+
+    match (locale.resolve(), overdue) {
+        (Locale::En, false) => format!("due in {}{}", amount, en_unit),
+        (Locale::En, true) => format!("{}{} overdue", amount, en_unit),
+        (_, false) => format!("{}{}后到期", amount, zh_unit),
+        (_, true) => format!("逾期{}{}", amount, zh_unit),
+    }
+}
+
+/// 把秒数换算为 (数量, 中文单位, 英文单位)，不足一分钟时返回 None
+fn duration(seconds: u64) -> Option<(u64, &'static str, &'static str)> {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    Some(match seconds {
+        s if s < MINUTE => return None,
+        s if s < HOUR => (s / MINUTE, "分钟", "m"),
+        s if s < DAY => (s / HOUR, "小时", "h"),
+        s if s < 7 * DAY => (s / DAY, "天", "d"),
+        s if s < 30 * DAY => (s / (7 * DAY), "周", "w"),
+        s if s < 365 * DAY => (s / (30 * DAY), "个月", "mo"),
+        s => (s / (365 * DAY), "年", "y"),
+    })
+}
+
+/// 创建/更新时间的一行摘要，如"创建于 3天前，更新于 2小时前"
+pub fn audit(created: DateTime<Utc>, updated: DateTime<Utc>, now: DateTime<Utc>, locale: Locale) -> String {
+    match locale.resolve() {
+        Locale::En => format!("created {}, updated {}", age(created, now, locale), age(updated, now, locale)),
+        _ => format!("创建于 {}，更新于 {}", age(created, now, locale), age(updated, now, locale)),
+    }
+}
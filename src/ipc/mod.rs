@@ -0,0 +1,135 @@
+// 守护进程 IPC：taskd 在本地套接字（Unix 域套接字 / Windows 命名管道）上接收 TUI 和 CLI 的命令，
+// 修改数据后可以立即通知守护进程，而不必等它下一轮轮询数据库
+//
+// 协议：每个连接发送一行 JSON 请求，守护进程回复一行 JSON 响应后关闭连接
+//
+// 这里是 tasks 和 taskd 共用的部分（客户端、套接字名称、PID 和心跳文件的读取）；
+// 监听和处理连接在 taskd 的 ipc_server 模块中
+
+use interprocess::local_socket::{prelude::*, GenericFilePath, Name};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+/// 单条消息的最大长度，防止异常的客户端占满内存
+const MAX_MESSAGE_LEN: usize = 64 * 1024;
+
+/// 客户端等待守护进程读取请求和回复的时限：守护进程卡住时 CLI 不会一直等下去
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 发给守护进程的命令
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    /// 数据已修改，立即重新检查提醒
+    Reload,
+    /// 开始番茄钟，结束时由守护进程记录并发送通知
+    StartPomodoro { task_id: Option<i64>, minutes: u32 },
+    /// 把任务的提醒推迟若干分钟（从现在算起）
+    Snooze { task_id: i64, minutes: i64 },
+    /// 确认守护进程在运行，回复其进程 ID
+    Ping,
+    /// 退出守护进程（tasks daemon stop）
+    Shutdown,
+}
+
+/// 守护进程的回复
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    pub message: String,
+}
+
+/// 套接字名称：Unix 上为数据库旁的 tasks.sock，Windows 上为按数据库路径区分的命名管道，各 profile 互不影响
+#[cfg(unix)]
+pub(crate) fn socket_name(db_path: &Path) -> Result<Name<'static>> {
+    Ok(db_path.with_extension("sock").to_fs_name::<GenericFilePath>()?)
+}
+
+#[cfg(windows)]
+pub(crate) fn socket_name(db_path: &Path) -> Result<Name<'static>> {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    db_path.hash(&mut hasher);
+    let pipe = format!(r"\\.\pipe\taskd-{:016x}", hasher.finish());
+    Ok(pipe.to_fs_name::<GenericFilePath>()?)
+}
+
+/// 向正在运行的守护进程发送命令；守护进程未运行或超时未回复时返回 Io 错误
+pub fn send(db_path: &Path, request: &Request) -> Result<Response> {
+    let stream = LocalSocketStream::connect(socket_name(db_path)?)?;
+    stream.set_recv_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_send_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut stream = BufReader::new(stream);
+    write_line(stream.get_mut(), request)?;
+    read_line(&mut stream)?.ok_or_else(|| Error::Validation("守护进程没有回复".to_string()))
+}
+
+/// 通知守护进程数据已修改；守护进程未运行时静默忽略（下一轮轮询仍会读到修改）
+pub fn notify_reload(db_path: &Path) {
+    let _ = send(db_path, &Request::Reload);
+}
+
+/// 在后台线程中通知守护进程（TUI 使用）：守护进程卡住时不会阻塞界面
+pub fn notify_reload_in_background(db_path: &Path) {
+    let db_path = db_path.to_path_buf();
+    std::thread::spawn(move || notify_reload(&db_path));
+}
+
+/// PID 文件：数据库旁的 tasks.pid，taskd 启动时写入、收到 Shutdown 时删除
+pub fn pid_path(db_path: &Path) -> std::path::PathBuf {
+    db_path.with_extension("pid")
+}
+
+/// 读取 PID 文件；文件不存在或内容无效时为 None（taskd 被强制结束时文件可能残留）
+pub fn read_pid(db_path: &Path) -> Option<u32> {
+    std::fs::read_to_string(pid_path(db_path)).ok()?.trim().parse().ok()
+}
+
+/// 心跳文件：数据库旁的 tasks.heartbeat，taskd 每轮检查时写入当前时间，据此判断它是否在运行。
+/// 不写进数据库，否则每次心跳都会改变 data_version，让 TUI 以为数据被修改而重新加载
+pub fn heartbeat_path(db_path: &Path) -> std::path::PathBuf {
+    db_path.with_extension("heartbeat")
+}
+
+/// 读取上次心跳时间；taskd 从未运行过时为 None
+pub fn read_heartbeat(db_path: &Path) -> Option<DateTime<Utc>> {
+    let content = std::fs::read_to_string(heartbeat_path(db_path)).ok()?;
+    DateTime::parse_from_rfc3339(content.trim()).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// 删除 PID 文件和套接字文件（守护进程退出前调用）
+pub fn cleanup(db_path: &Path) {
+    let _ = std::fs::remove_file(pid_path(db_path));
+    #[cfg(unix)]
+    {
+        let _ = std::fs::remove_file(db_path.with_extension("sock"));
+    }
+}
+
+pub(crate) fn write_line<T: Serialize>(stream: &mut LocalSocketStream, value: &T) -> Result<()> {
+    let mut line = serde_json::to_string(value).map_err(|e| Error::Validation(e.to_string()))?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    stream.flush()?;
+    Ok(())
+}
+
+pub(crate) fn read_line<T: for<'de> Deserialize<'de>>(stream: &mut BufReader<LocalSocketStream>) -> Result<Option<T>> {
+    let mut line = String::new();
+    let read = stream.take(MAX_MESSAGE_LEN as u64 + 1).read_line(&mut line)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if read > MAX_MESSAGE_LEN {
+        return Err(Error::Validation("IPC 消息过长".to_string()));
+    }
+    serde_json::from_str(line.trim())
+        .map(Some)
+        .map_err(|e| Error::Validation(format!("无效的 IPC 消息: {}", e)))
+}
@@ -1,17 +1,34 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use directories::ProjectDirs;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
+mod calendar;
+mod clock;
+mod config;
+mod dateparse;
 mod db;
+mod error;
+mod export;
+mod humanize;
+mod ipc;
 mod models;
 mod notify;
 mod notes;
 mod pomodoro;
+mod prompt;
+mod qr;
+mod remote;
+mod service;
+mod sheet;
+mod sound;
+mod sync;
 mod ui;
+mod update;
 
-use db::Database;
-use models::{Note, Task};
+use config::Config;
+use db::{Database, Storage};
+use models::{Project, Task, TaskStatus};
 
 #[derive(Parser)]
 #[command(name = "tasks")]
@@ -21,6 +38,14 @@ struct Cli {
     #[arg(short, long)]
     db_path: Option<PathBuf>,
 
+    /// Profile name; each profile has its own database and config
+    #[arg(short, long)]
+    profile: Option<String>,
+
+    /// Use a remote taskd server (started with `taskd --listen`) instead of the local database
+    #[arg(long)]
+    remote: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -30,90 +55,1869 @@ enum Commands {
     /// Launch the TUI interface
     Show,
 
+    /// Read-only full-screen dashboard (today, stats, pomodoro) for a wall monitor
+    Dashboard {
+        /// Seconds each page stays on screen
+        #[arg(long, default_value_t = 15)]
+        cycle: u64,
+    },
+
     /// Add a new task
     Add {
-        /// Task title
-        title: String,
+        /// Task title ({date}, {week}, {month} and {year} are expanded)
+        #[arg(required_unless_present = "template")]
+        title: Option<String>,
+
+        /// Create the task from a title template defined in config.toml
+        #[arg(short, long, conflicts_with = "title")]
+        template: Option<String>,
+
+        /// Add a tag (repeatable: --tag work --tag home)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Repeat rule: daily, weekdays, weekly, monthly, yearly, 2w, 3d, 6mo or FREQ=WEEKLY;INTERVAL=2
+        #[arg(long)]
+        repeat: Option<String>,
+
+        /// Put the task into a project (created if it does not exist)
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Due date: "tomorrow 5pm", "next friday 18:00", "in 3 days", 2025-03-14 17:00 (date only means 23:59)
+        #[arg(long)]
+        due: Option<String>,
+
+        /// Priority: high, medium or low (h/m/l); rules in config.toml apply when omitted
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// Reminder: time before the due date (30m, 2h, 1d) or a time like "tomorrow 9am"
+        #[arg(long)]
+        remind: Option<String>,
+
+        /// Task description
+        #[arg(long)]
+        desc: Option<String>,
     },
 
-    /// List all tasks
-    List,
+    /// List tasks, optionally filtered and sorted
+    List {
+        /// Only show tasks with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Only show tasks in this project
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Only show tasks with this status (todo, in-progress, waiting, completed)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Only show tasks with this priority (high, medium, low)
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// Only show tasks due before this time ("friday", "in 3 days", 2025-03-14)
+        #[arg(long)]
+        due_before: Option<String>,
+
+        /// Only show tasks due at or after this time
+        #[arg(long)]
+        due_after: Option<String>,
+
+        /// Only show unfinished tasks whose due date has passed
+        #[arg(long)]
+        overdue: bool,
+
+        /// Sort order
+        #[arg(long, value_enum, default_value = "priority")]
+        sort: SortArg,
+
+        /// Print the tasks as JSON
+        #[arg(long)]
+        json: bool,
+    },
 
     /// Mark a task as completed
     Complete {
         /// Task ID
         id: i64,
+
+        /// Schedule a follow-up task (YYYY-MM-DD, tomorrow, 2d, 12h)
+        #[arg(long)]
+        follow_up: Option<String>,
+
+        /// Title of the follow-up task (defaults to "跟进: <title>")
+        #[arg(long, requires = "follow_up")]
+        follow_up_title: Option<String>,
+    },
+
+    /// Set how long a task will take; taskd reminds you when it is time to start
+    Estimate {
+        /// Task ID
+        id: i64,
+
+        /// Duration (90m, 2h, 1h30m) or "off" to clear
+        duration: String,
+    },
+
+    /// Push a task's reminder forward (from now); taskd reminds you again then
+    Snooze {
+        /// Task ID
+        id: i64,
+
+        /// How long to wait (10m, 1h, 1h30m, 1d)
+        duration: String,
+    },
+
+    /// Add a comment to a task, or list its comments when no text is given
+    Comment {
+        /// Task ID
+        id: i64,
+
+        /// Comment text
+        text: Option<String>,
+    },
+
+    /// Print a short status for shell prompts (due today, overdue, pomodoro) from a cache file
+    PromptSegment {
+        /// Use plain ASCII markers instead of emoji
+        #[arg(long)]
+        ascii: bool,
+    },
+
+    /// Show a task as a QR code (JSON) for moving it to a phone
+    Qr {
+        /// Task ID
+        id: i64,
+    },
+
+    /// Show recent activity (defaults to yesterday and today)
+    Log {
+        /// First day to include (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Last day to include (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+    },
+
+    /// Print a daily sheet (tasks with checkboxes, time blocks, notes area) as plain text
+    Print {
+        /// Day to print: today, tomorrow or YYYY-MM-DD
+        #[arg(long, default_value = "today")]
+        day: String,
+
+        /// Write the sheet to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Export tasks, notes and pomodoro sessions (JSON, CSV or Markdown)
+    Export {
+        /// Output format (csv contains tasks only)
+        #[arg(short, long, value_enum, default_value = "json")]
+        format: ExportFormatArg,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Only export tasks with this status (todo, in-progress, waiting, completed)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// First day to include, by creation time (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Last day to include, by creation time (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+    },
+
+    /// Export tasks with due dates as an iCalendar (.ics) file for calendar apps
+    ExportIcs {
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Also include completed tasks
+        #[arg(long)]
+        completed: bool,
+    },
+
+    /// Import a `tasks export` JSON/CSV file, or a simple CSV of title,due,priority
+    Import {
+        /// File to import (.json is read as a full export, anything else as CSV)
+        file: PathBuf,
+    },
+
+    /// Sync tasks through an encrypted change log in a shared folder
+    Sync {
+        /// Sync folder (Dropbox/Syncthing/...); remembered after the first use
+        dir: Option<PathBuf>,
+    },
+
+    /// Pause reminders while you are away (vacation mode)
+    Pause {
+        /// Last day away: reminders resume at the start of the next day (YYYY-MM-DD), or a duration (3d, 12h)
+        #[arg(long)]
+        until: String,
+    },
+
+    /// End vacation mode early
+    Resume,
+
+    /// Alert sound when pomodoros end and reminders fire; shows the settings without a subcommand
+    Sound {
+        #[command(subcommand)]
+        action: Option<SoundCommands>,
+    },
+
+    /// Start, stop or check taskd, or send a command to it over its local socket
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonCommands,
+    },
+
+    /// Manage projects (lists that group tasks)
+    Project {
+        #[command(subcommand)]
+        action: ProjectCommands,
+    },
+
+    /// Weekly pomodoro targets per tag (project)
+    Goal {
+        #[command(subcommand)]
+        action: GoalCommands,
+    },
+
+    /// Summaries of tracked time
+    Report {
+        #[command(subcommand)]
+        action: ReportCommands,
+    },
+
+    /// Import busy times from a calendar export (read-only)
+    Calendar {
+        #[command(subcommand)]
+        action: CalendarCommands,
+    },
+
+    /// Database maintenance
+    Db {
+        #[command(subcommand)]
+        action: DbCommands,
+    },
+
+    /// Separate workspaces, each with its own database and config (select one with --profile)
+    Profile {
+        #[command(subcommand)]
+        action: ProfileCommands,
+    },
+
+    /// Manage this installation
+    #[command(name = "self")]
+    SelfManage {
+        #[command(subcommand)]
+        action: SelfCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum SelfCommands {
+    /// Check GitHub for a newer release (only runs when you ask; nothing is checked in the background)
+    CheckUpdate,
+}
+
+#[derive(Subcommand)]
+enum SoundCommands {
+    /// Play a sound when pomodoros end and reminders fire
+    On,
+
+    /// Turn alert sounds off
+    Off,
+
+    /// Set the volume
+    Volume {
+        /// 0-100
+        volume: u8,
+    },
+
+    /// Play a wav/ogg/mp3 file instead of the built-in tone (no path: back to the built-in tone)
+    File {
+        path: Option<PathBuf>,
+    },
+
+    /// Play the sound now
+    Test,
+}
+
+#[derive(Subcommand)]
+enum GoalCommands {
+    /// Set a weekly pomodoro target for a tag, e.g. `tasks goal set projectx 4`
+    Set {
+        /// Tag (project) name
+        tag: String,
+
+        /// Pomodoros per week
+        pomodoros: usize,
+    },
+
+    /// Remove a weekly goal
+    Remove {
+        /// Tag (project) name
+        tag: String,
+    },
+
+    /// Show this week's progress
+    List,
+}
+
+#[derive(Subcommand)]
+enum ReportCommands {
+    /// Time tracked per task (started/stopped with `T` or `:track` in the TUI); today by default
+    Time {
+        /// This week (Monday to Sunday) instead of today
+        #[arg(long)]
+        week: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DaemonCommands {
+    /// Start taskd in the background (PID file and log next to the database)
+    Start {
+        /// Also serve the HTTP API on this address (e.g. 127.0.0.1:7878)
+        #[arg(long)]
+        listen: Option<String>,
+
+        /// Restart the reminder loop after a crash
+        #[arg(long)]
+        supervise: bool,
+    },
+
+    /// Stop the running taskd
+    Stop,
+
+    /// Show whether taskd is running and its PID
+    Status,
+
+    /// Stop taskd if it is running, then start it again
+    Restart {
+        /// Also serve the HTTP API on this address (e.g. 127.0.0.1:7878)
+        #[arg(long)]
+        listen: Option<String>,
+
+        /// Restart the reminder loop after a crash
+        #[arg(long)]
+        supervise: bool,
+    },
+
+    /// Run taskd at login: write and enable a systemd user unit (Linux) or launchd agent (macOS)
+    Install,
+
+    /// Disable and remove the service written by `tasks daemon install`
+    Uninstall,
+
+    /// Make taskd re-check reminders now instead of at its next poll
+    Reload,
+
+    /// Start a pomodoro tracked by taskd, which notifies you when it ends
+    Pomodoro {
+        /// Task the pomodoro is for
+        #[arg(long)]
+        task: Option<i64>,
+
+        /// Length in minutes
+        #[arg(long, default_value_t = 25)]
+        minutes: u32,
+    },
+
+    /// Push a task's reminder back by some minutes from now
+    Snooze {
+        /// Task ID
+        id: i64,
+
+        /// Minutes to wait before reminding again
+        #[arg(long, default_value_t = 10)]
+        minutes: i64,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProjectCommands {
+    /// Create a project
+    Add {
+        /// Project name
+        name: String,
+    },
+
+    /// List projects with their open task counts
+    List {
+        /// Include archived projects
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Archive a project (its tasks are kept)
+    Archive {
+        /// Project name
+        name: String,
+
+        /// Restore an archived project
+        #[arg(long)]
+        undo: bool,
     },
 }
 
+#[derive(Subcommand)]
+enum CalendarCommands {
+    /// Import busy events from an .ics file, replacing the previous import
+    Import {
+        /// Path to the .ics file
+        file: PathBuf,
+    },
+
+    /// Remove all imported busy events
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum ProfileCommands {
+    /// List profiles; the active one is marked with *
+    List,
+
+    /// Create a profile and initialise its database
+    Create {
+        /// Profile name (letters, digits, - and _)
+        name: String,
+    },
+
+    /// Delete a profile together with its database and config
+    Delete {
+        /// Profile name
+        name: String,
+
+        /// Don't ask for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DbCommands {
+    /// Run VACUUM and ANALYZE to reclaim space and refresh query statistics
+    Compact,
+
+    /// Rebuild indexes and derived data (pomodoro counts, prompt cache) in small batches, e.g. after large imports
+    Reindex {
+        /// Pause between batches in milliseconds, so a running TUI or taskd is not blocked
+        #[arg(long, default_value_t = 50)]
+        pause_ms: u64,
+    },
+
+    /// List notes and pomodoro sessions that point at deleted tasks; asks how to fix them unless --fix is given
+    Orphans {
+        /// Fix without asking: relink (to --task), detach or delete
+        #[arg(long, value_enum)]
+        fix: Option<OrphanFixArg>,
+
+        /// Task to relink orphans to (with --fix relink)
+        #[arg(long)]
+        task: Option<i64>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormatArg {
+    Json,
+    Csv,
+    Md,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SortArg {
+    Priority,
+    Due,
+    Created,
+    Title,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OrphanFixArg {
+    Relink,
+    Detach,
+    Delete,
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    // 确定数据库路径
-    let db_path = cli.db_path.unwrap_or_else(|| {
-        let proj_dirs = ProjectDirs::from("com", "terminator-task", "tasks")
-            .expect("Failed to get project directories");
-        let data_dir = proj_dirs.data_dir();
-        std::fs::create_dir_all(data_dir).expect("Failed to create data directory");
-        data_dir.join("tasks.db")
-    });
+    // 确定数据库路径（按 profile 隔离）
+    let profile = cli.profile;
+    let explicit_db_path = cli.db_path.is_some();
+    let db_path = match cli.db_path {
+        Some(path) => path,
+        None => config::db_path(profile.as_deref())?,
+    };
 
     let db_path_str = db_path.to_string_lossy().to_string();
+    let config = Config::load(profile.as_deref())?;
+
+    if let Some(url) = cli.remote {
+        return run_remote(&url, cli.command, &config);
+    }
 
     match cli.command {
         Some(Commands::Show) | None => {
             // 启动TUI
-            ui::run_app(db_path_str)?;
+            ui::run_app(db_path_str, config, profile, explicit_db_path)?;
         }
-        Some(Commands::Add { title }) => {
+        Some(Commands::Dashboard { cycle }) => {
+            ui::run_dashboard(db_path_str, std::time::Duration::from_secs(cycle.max(1)))?;
+        }
+        Some(Commands::Add { title, template, tags, repeat, project, due, priority, remind, desc }) => {
+            let options = TaskOptions {
+                tags: &tags,
+                repeat: repeat.as_deref(),
+                due: due.as_deref(),
+                priority: priority.as_deref(),
+                remind: remind.as_deref(),
+                desc: desc.as_deref(),
+            };
+            let task = new_task(title, template, &options, &config)?;
+            // --project 优先于自动标签规则指定的项目
+            let project = project.as_deref().or_else(|| config.rule_project(&task.title));
             let db = Database::open(&db_path)?;
-            let task = Task::new(title);
-            let id = db.create_task(&task)?;
+            let id = db.create_task_in_project(&task, project)?;
             println!("✅ Task created with ID: {}", id);
+            if let Some(due) = task.due_date {
+                println!("📅 Due: {}", due.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+            }
+            if let Some(at) = task.reminder_time {
+                println!("⏰ Reminder: {}", at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+            }
+            if let Some(rule) = task.recurrence {
+                println!("🔁 Repeats: {}", rule.to_rule());
+            }
+            if let Some(project) = project {
+                println!("📁 Project: {}", project.trim());
+            }
+        }
+        Some(Commands::List { tag, project, status, priority, due_before, due_after, overdue, sort, json }) => {
+            let filters = ListFilters {
+                tag: tag.as_deref(),
+                status: status.as_deref(),
+                priority: priority.as_deref(),
+                due_before: due_before.as_deref(),
+                due_after: due_after.as_deref(),
+                overdue,
+                sort,
+            };
+            let mut query = match filters.to_query() {
+                Ok(query) => query,
+                Err(e) => {
+                    println!("❌ {}", e);
+                    return Ok(());
+                }
+            };
+            let db = Database::open(&db_path)?;
+            if let Some(name) = project.as_deref() {
+                match db.find_project(name)? {
+                    Some(project) => query.project_id = project.id,
+                    None => {
+                        println!("❌ Project not found: {}", name);
+                        return Ok(());
+                    }
+                }
+            }
+            let tasks = db.query_tasks(&query)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&tasks)?);
+            } else {
+                print_tasks(&tasks, &config);
+            }
+        }
+        Some(Commands::Complete { id, follow_up, follow_up_title }) => {
+            let follow_up = match follow_up.as_deref().map(|s| (s, models::parse_follow_up(s))) {
+                Some((s, None)) => {
+                    println!("❌ Invalid follow-up time: {} (use YYYY-MM-DD, tomorrow, 2d or 12h)", s);
+                    return Ok(());
+                }
+                Some((_, at)) => at,
+                None => None,
+            };
+            let db = Database::open(&db_path)?;
+
+            match db.get_task(id) {
+                Ok(mut task) => {
+                    let blockers: Vec<String> = db
+                        .get_dependencies(id)?
+                        .iter()
+                        .filter(|t| t.status != TaskStatus::Completed)
+                        .map(|t| format!("#{} {}", t.id.unwrap_or(0), t.title))
+                        .collect();
+                    if !blockers.is_empty() {
+                        println!("🔒 Task {} is blocked by unfinished tasks: {}", id, blockers.join(", "));
+                        return Ok(());
+                    }
+                    let next = db.complete_task(&mut task)?;
+                    println!("✅ Task {} marked as completed", id);
+                    if let Some(next_id) = next {
+                        println!("🔁 Next occurrence created with ID: {}", next_id);
+                    }
+
+                    if let Some(at) = follow_up {
+                        let follow = task.follow_up_task(follow_up_title.as_deref(), at);
+                        follow.validate()?;
+                        let follow_id = db.create_task(&follow)?;
+                        db.add_comment(&models::Comment::new(id, format!("已安排跟进任务 #{}", follow_id)))?;
+                        println!(
+                            "📅 Follow-up task {} scheduled for {}",
+                            follow_id,
+                            at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                        );
+                    }
+                }
+                Err(error::Error::NotFound { .. }) => {
+                    println!("❌ Task {} not found", id);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Some(Commands::Comment { id, text }) => {
+            let db = Database::open(&db_path)?;
+            let result = match text {
+                Some(text) if !text.trim().is_empty() => db
+                    .add_comment(&models::Comment::new(id, text.trim().to_string()))
+                    .map(|_| println!("💬 Comment added to task {}", id)),
+                _ => db.get_task(id).and_then(|_| db.get_comments(id)).map(|comments| {
+                    if comments.is_empty() {
+                        println!("No comments on task {}", id);
+                    }
+                    for c in comments {
+                        println!(
+                            "{} ({})  {}",
+                            c.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                            humanize::age(c.created_at, chrono::Utc::now(), config.locale),
+                            c.body
+                        );
+                    }
+                }),
+            };
+            match result {
+                Ok(()) => {}
+                Err(error::Error::NotFound { .. }) => println!("❌ Task {} not found", id),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Some(Commands::Log { since, until }) => {
+            let today = chrono::Local::now().date_naive();
+            let since = match since.as_deref().map(parse_date).transpose() {
+                Ok(date) => date.unwrap_or(today - chrono::Duration::days(1)),
+                Err(e) => {
+                    println!("❌ {}", e);
+                    return Ok(());
+                }
+            };
+            let until = match until.as_deref().map(parse_date).transpose() {
+                Ok(date) => date.unwrap_or(today),
+                Err(e) => {
+                    println!("❌ {}", e);
+                    return Ok(());
+                }
+            };
+
+            let db = Database::open(&db_path)?;
+            let changes = db.get_changes_between(
+                local_day_start(since),
+                local_day_start(until + chrono::Duration::days(1)),
+            )?;
+            print_activity(&db, &changes);
+        }
+        Some(Commands::Estimate { id, duration }) => {
+            let minutes = match duration.as_str() {
+                "off" => None,
+                other => match models::parse_estimate(other) {
+                    Some(minutes) => Some(minutes),
+                    None => {
+                        println!("❌ Invalid duration: {} (use 90m, 2h or 1h30m)", other);
+                        return Ok(());
+                    }
+                },
+            };
+            let db = Database::open(&db_path)?;
+            match db.get_task(id) {
+                Ok(mut task) => {
+                    task.estimate_minutes = minutes;
+                    task.updated_at = chrono::Utc::now();
+                    db.update_task(&task)?;
+                    let buffer = chrono::Duration::minutes(config.start_nag.buffer_minutes.max(0));
+                    match (minutes, task.latest_start(buffer)) {
+                        (Some(m), Some(start)) => println!(
+                            "✅ Estimate set to {}; start by {}",
+                            models::format_estimate(m),
+                            start.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                        ),
+                        (Some(m), None) => println!("✅ Estimate set to {}", models::format_estimate(m)),
+                        (None, _) => println!("✅ Estimate cleared"),
+                    }
+                }
+                Err(_) => println!("❌ Task {} not found", id),
+            }
+        }
+        Some(Commands::Snooze { id, duration }) => {
+            let offset = match models::parse_reminder_offset(&duration) {
+                Some(offset) => offset,
+                None => {
+                    println!("❌ Invalid duration: {} (use 10m, 1h, 1h30m or 1d)", duration);
+                    return Ok(());
+                }
+            };
+            let db = Database::open(&db_path)?;
+            match db.get_task(id) {
+                Ok(mut task) => {
+                    let now = chrono::Utc::now();
+                    let at = now + offset;
+                    task.reminder_time = Some(at);
+                    task.updated_at = now;
+                    db.update_task(&task)?;
+                    ipc::notify_reload(&db_path);
+                    println!(
+                        "⏰ Reminder for task {} snoozed until {}",
+                        id,
+                        at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                    );
+                }
+                Err(_) => println!("❌ Task {} not found", id),
+            }
+        }
+        Some(Commands::PromptSegment { ascii }) => {
+            // 只读缓存文件；缓存不存在时（TUI 和守护进程都没运行过）才查询一次数据库
+            let path = prompt::cache_path(&db_path);
+            let segment = match prompt::PromptSegment::load(&path) {
+                Some(segment) => segment,
+                None => {
+                    let db = Database::open(&db_path)?;
+                    let mut segment = prompt::PromptSegment::default();
+                    segment.set_counts(&db.get_all_tasks()?, chrono::Utc::now());
+                    let _ = segment.save(&path);
+                    segment
+                }
+            };
+            let text = segment.render(chrono::Utc::now(), ascii);
+            if !text.is_empty() {
+                println!("{}", text);
+            }
         }
-        Some(Commands::List) => {
+        Some(Commands::Qr { id }) => {
             let db = Database::open(&db_path)?;
+            let task = match db.get_task(id) {
+                Ok(task) => task,
+                Err(_) => {
+                    println!("❌ Task {} not found", id);
+                    return Ok(());
+                }
+            };
+            match qr::render(&qr::task_payload(&task)) {
+                Ok(code) => {
+                    println!("{}", code);
+                    println!("[{}] {}", id, task.title);
+                }
+                Err(e) => println!("❌ {}", e),
+            }
+        }
+        Some(Commands::Print { day, output }) => {
+            let today = chrono::Local::now().date_naive();
+            let date = match day.as_str() {
+                "today" => today,
+                "tomorrow" => today + chrono::Duration::days(1),
+                other => match parse_date(other) {
+                    Ok(date) => date,
+                    Err(e) => {
+                        println!("❌ {}", e);
+                        return Ok(());
+                    }
+                },
+            };
+
+            let db = Database::open(&db_path)?;
+            let day_start = local_day_start(date);
+            let day_end = local_day_start(date + chrono::Duration::days(1));
             let tasks = db.get_all_tasks()?;
+            let busy = db.get_busy_events_between(day_start, day_end)?;
+            let text = sheet::daily_sheet(date, day_start, day_end, &tasks, &busy);
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, text)?;
+                    println!("✅ 已写入 {}", path.display());
+                }
+                None => print!("{}", text),
+            }
+        }
+        Some(Commands::Export { format, output, status, since, until }) => {
+            let status = match status.as_deref().map(|s| (s, models::TaskStatus::parse(s))) {
+                Some((s, None)) => {
+                    println!("❌ Invalid status: {} (use todo, in-progress, waiting or completed)", s);
+                    return Ok(());
+                }
+                Some((_, status)) => status,
+                None => None,
+            };
+            let (since, until) = match (
+                since.as_deref().map(parse_date).transpose(),
+                until.as_deref().map(parse_date).transpose(),
+            ) {
+                (Ok(since), Ok(until)) => (since, until),
+                (Err(e), _) | (_, Err(e)) => {
+                    println!("❌ {}", e);
+                    return Ok(());
+                }
+            };
+            let filter = export::ExportFilter {
+                status,
+                since: since.map(local_day_start),
+                until: until.map(|d| local_day_start(d + chrono::Duration::days(1))),
+            };
+            let format = match format {
+                ExportFormatArg::Json => export::Format::Json,
+                ExportFormatArg::Csv => export::Format::Csv,
+                ExportFormatArg::Md => export::Format::Markdown,
+            };
+
+            let db = Database::open(&db_path)?;
+            let data = export::Export::collect(&db, &filter)?;
+            let text = data.render(format);
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, text)?;
+                    if format == export::Format::Csv {
+                        println!("✅ Exported {} tasks to {}", data.tasks.len(), path.display());
+                    } else {
+                        println!(
+                            "✅ Exported {} tasks, {} notes, {} pomodoro sessions to {}",
+                            data.tasks.len(),
+                            data.notes.len(),
+                            data.pomodoros.len(),
+                            path.display()
+                        );
+                    }
+                }
+                None => print!("{}", text),
+            }
+        }
+        Some(Commands::ExportIcs { output, completed }) => {
+            let db = Database::open(&db_path)?;
+            let tasks: Vec<Task> = db
+                .get_all_tasks()?
+                .into_iter()
+                .filter(|t| t.due_date.is_some() && (completed || t.status != TaskStatus::Completed))
+                .collect();
+            let text = calendar::to_ics(&tasks);
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, text)?;
+                    println!("✅ Exported {} tasks to {}", tasks.len(), path.display());
+                }
+                None => print!("{}", text),
+            }
+        }
+        Some(Commands::Import { file }) => {
+            let db = Database::open(&db_path)?;
+            let report = match export::import_file(&db, &file) {
+                Ok(report) => report,
+                Err(error::Error::Validation(e)) => {
+                    println!("❌ {}", e);
+                    return Ok(());
+                }
+                Err(e) => return Err(e.into()),
+            };
+            println!(
+                "✅ Imported {} tasks ({} duplicates skipped)",
+                report.tasks_added, report.tasks_skipped
+            );
+            if report.notes_added + report.notes_skipped > 0 {
+                println!("   {} notes ({} duplicates skipped)", report.notes_added, report.notes_skipped);
+            }
+            if report.pomodoros_added + report.pomodoros_skipped > 0 {
+                println!(
+                    "   {} pomodoro sessions ({} duplicates skipped)",
+                    report.pomodoros_added, report.pomodoros_skipped
+                );
+            }
+            for error in &report.errors {
+                println!("⚠️  {}", error);
+            }
+        }
+        Some(Commands::Sync { dir }) => {
+            let db = Database::open(&db_path)?;
 
-            if tasks.is_empty() {
-                println!("No tasks found.");
+            let dir = match dir {
+                Some(dir) => {
+                    db.set_config("sync_dir", &dir.to_string_lossy())?;
+                    dir
+                }
+                None => match db.get_config("sync_dir")? {
+                    Some(dir) => PathBuf::from(dir),
+                    None => {
+                        println!("❌ 请指定同步目录：tasks sync <DIR>");
+                        return Ok(());
+                    }
+                },
+            };
+
+            let passphrase = read_sync_passphrase()?;
+            let engine = sync::SyncEngine::new(&db, &dir, &passphrase)?;
+            let report = engine.run()?;
+            println!(
+                "✅ 同步完成：推送 {} 条变更，合并 {} 条变更",
+                report.pushed, report.applied
+            );
+        }
+        Some(Commands::Pause { until }) => {
+            let until = match parse_date(&until) {
+                Ok(date) => local_day_start(date + chrono::Duration::days(1)),
+                Err(_) => match models::parse_follow_up(&until) {
+                    Some(t) => t,
+                    None => {
+                        println!("❌ Invalid date: {} (use YYYY-MM-DD, 3d or 12h)", until);
+                        return Ok(());
+                    }
+                },
+            };
+            if until <= chrono::Utc::now() {
+                println!("❌ The pause must end in the future");
+                return Ok(());
+            }
+            let db = Database::open(&db_path)?;
+            db.set_pause_until(Some(until))?;
+            println!(
+                "🏖 休假模式已开启，{} 前不会发送提醒",
+                until.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            );
+        }
+        Some(Commands::Daemon { action }) => {
+            let request = match action {
+                DaemonCommands::Start { listen, supervise } => {
+                    return daemon_start(&db_path, profile.as_deref(), listen.as_deref(), supervise);
+                }
+                DaemonCommands::Stop => return daemon_stop(&db_path).map(|_| ()),
+                DaemonCommands::Status => {
+                    daemon_status(&db_path);
+                    return Ok(());
+                }
+                DaemonCommands::Restart { listen, supervise } => {
+                    if !daemon_stop(&db_path)? {
+                        return Ok(());
+                    }
+                    return daemon_start(&db_path, profile.as_deref(), listen.as_deref(), supervise);
+                }
+                DaemonCommands::Install => return daemon_install(&db_path, profile.as_deref()),
+                DaemonCommands::Uninstall => {
+                    match service::uninstall(profile.as_deref()) {
+                        Ok(Some(path)) => println!("✅ Removed {}", path.display()),
+                        Ok(None) => println!("No taskd service installed."),
+                        Err(e) => println!("❌ {}", e),
+                    }
+                    return Ok(());
+                }
+                DaemonCommands::Reload => ipc::Request::Reload,
+                DaemonCommands::Pomodoro { task, minutes } => ipc::Request::StartPomodoro { task_id: task, minutes },
+                DaemonCommands::Snooze { id, minutes } => ipc::Request::Snooze { task_id: id, minutes },
+            };
+            match ipc::send(&db_path, &request) {
+                Ok(response) if response.ok => println!("✅ {}", response.message),
+                Ok(response) => println!("❌ {}", response.message),
+                Err(error::Error::Io(_)) => println!("❌ taskd is not running (start it with `tasks daemon start`)"),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Some(Commands::Resume) => {
+            let db = Database::open(&db_path)?;
+            match db.get_pause_until()? {
+                Some(until) if until > chrono::Utc::now() => {
+                    db.set_pause_until(None)?;
+                    println!("✅ 休假模式已结束，提醒恢复");
+                }
+                _ => {
+                    db.set_pause_until(None)?;
+                    println!("Not paused.");
+                }
+            }
+        }
+        Some(Commands::Report { action: ReportCommands::Time { week } }) => {
+            let db = Database::open(&db_path)?;
+            let now = chrono::Utc::now();
+            let today = chrono::Local::now().date_naive();
+            let (start, end, label) = if week {
+                let (start, end) = models::local_week_bounds(today);
+                (start, end, "this week")
             } else {
-                for task in tasks {
-                    let status_icon = match task.status {
-                        models::TaskStatus::Completed => "✅",
-                        models::TaskStatus::InProgress => "🔄",
-                        models::TaskStatus::Todo => "⭕",
-                    };
-                    let priority_icon = match task.priority {
-                        models::Priority::High => "🔴",
-                        models::Priority::Medium => "🟡",
-                        models::Priority::Low => "🟢",
+                let start = local_day_start(today);
+                (start, start + chrono::Duration::days(1), "today")
+            };
+
+            // 按任务汇总，跨越区间边界的记录只计区间内的部分
+            let mut totals: Vec<(i64, i64, bool)> = Vec::new();
+            for entry in db.get_time_entries_between(start, end)? {
+                let seconds = entry.seconds_between(start, end, now);
+                match totals.iter_mut().find(|(id, _, _)| *id == entry.task_id) {
+                    Some(total) => {
+                        total.1 += seconds;
+                        total.2 |= entry.ended_at.is_none();
+                    }
+                    None => totals.push((entry.task_id, seconds, entry.ended_at.is_none())),
+                }
+            }
+            if totals.is_empty() {
+                println!("No time tracked {}. Press T on a task in the TUI (or :track start) to start tracking.", label);
+                return Ok(());
+            }
+            totals.sort_by(|a, b| b.1.cmp(&a.1));
+
+            let last_day = (end - chrono::Duration::days(1)).with_timezone(&chrono::Local);
+            if week {
+                println!(
+                    "⏱ Time tracked {} ({} – {})",
+                    label,
+                    start.with_timezone(&chrono::Local).format("%m-%d"),
+                    last_day.format("%m-%d")
+                );
+            } else {
+                println!("⏱ Time tracked {} ({})", label, last_day.format("%Y-%m-%d"));
+            }
+            for (task_id, seconds, running) in &totals {
+                let title = db.get_task(*task_id).map(|t| t.title).unwrap_or_default();
+                println!(
+                    "  {:>7}  #{} {}{}",
+                    models::format_tracked(*seconds),
+                    task_id,
+                    title,
+                    if *running { " (running)" } else { "" }
+                );
+            }
+            let total: i64 = totals.iter().map(|(_, seconds, _)| seconds).sum();
+            println!("  {:>7}  total", models::format_tracked(total));
+        }
+        Some(Commands::Sound { action }) => {
+            let db = Database::open(&db_path)?;
+            let mut settings = sound::SoundSettings::load(&db)?;
+            match action {
+                None => {}
+                Some(SoundCommands::On) => settings.enabled = true,
+                Some(SoundCommands::Off) => settings.enabled = false,
+                Some(SoundCommands::Volume { volume }) => {
+                    if volume > sound::MAX_VOLUME {
+                        println!("❌ Volume must be between 0 and {}", sound::MAX_VOLUME);
+                        return Ok(());
+                    }
+                    settings.volume = volume;
+                }
+                Some(SoundCommands::File { path }) => {
+                    if let Some(path) = &path {
+                        if !path.is_file() {
+                            println!("❌ File not found: {}", path.display());
+                            return Ok(());
+                        }
+                    }
+                    settings.file = path.map(|p| p.canonicalize().unwrap_or(p));
+                }
+                Some(SoundCommands::Test) => {
+                    if !cfg!(feature = "sound") {
+                        println!("Built without the `sound` feature; using the terminal bell.");
+                    }
+                    sound::play(&settings, sound::Alert::Reminder)?;
+                    return Ok(());
+                }
+            }
+            settings.save(&db)?;
+            println!("🔔 {}", settings.describe());
+        }
+        Some(Commands::Project { action }) => {
+            let db = Database::open(&db_path)?;
+            match action {
+                ProjectCommands::Add { name } => match db.create_project(&name) {
+                    Ok(id) => println!("✅ Project {} created with ID: {}", name.trim(), id),
+                    Err(error::Error::Validation(e)) => println!("❌ {}", e),
+                    Err(e) => return Err(e.into()),
+                },
+                ProjectCommands::List { all } => {
+                    let projects = db.get_projects(all)?;
+                    if projects.is_empty() {
+                        println!("No projects yet. Create one with `tasks project add <name>`.");
+                    }
+                    let tasks = db.get_all_tasks()?;
+                    for project in projects {
+                        let open = tasks
+                            .iter()
+                            .filter(|t| t.project_id == project.id && t.status != TaskStatus::Completed)
+                            .count();
+                        println!(
+                            "📁 {} ({} open){}",
+                            project.name,
+                            open,
+                            if project.archived { " [archived]" } else { "" }
+                        );
+                    }
+                }
+                ProjectCommands::Archive { name, undo } => match db.find_project(&name)? {
+                    Some(Project { id: Some(id), .. }) => {
+                        db.set_project_archived(id, !undo)?;
+                        if undo {
+                            println!("✅ Project {} restored", name.trim());
+                        } else {
+                            println!("✅ Project {} archived", name.trim());
+                        }
+                    }
+                    _ => println!("❌ Project not found: {}", name),
+                },
+            }
+        }
+        Some(Commands::Profile { action }) => match action {
+            ProfileCommands::List => {
+                let active = profile.as_deref().unwrap_or("default");
+                let mut profiles = vec!["default".to_string()];
+                profiles.extend(config::list_profiles()?);
+                for name in profiles {
+                    let path = config::db_path((name != "default").then_some(name.as_str()))?;
+                    let marker = if name == active { "*" } else { " " };
+                    println!("{} {:<16} {}", marker, name, path.display());
+                }
+            }
+            ProfileCommands::Create { name } => match config::create_profile(&name) {
+                Ok(path) => {
+                    Database::open(&path)?;
+                    println!("✅ Profile {} created ({})", name, path.display());
+                    println!("   Use it with `tasks --profile {}` and `taskd --profile {}`", name, name);
+                }
+                Err(error::Error::Validation(e)) => println!("❌ {}", e),
+                Err(e) => return Err(e.into()),
+            },
+            ProfileCommands::Delete { name, yes } => {
+                if profile.as_deref() == Some(name.as_str()) {
+                    println!("❌ Can't delete the active profile {}", name);
+                    return Ok(());
+                }
+                if !yes {
+                    if !std::io::stdin().is_terminal() {
+                        println!("❌ Pass --yes to delete profile {} without confirmation", name);
+                        return Ok(());
+                    }
+                    print!("Delete profile {} with all its tasks, notes and settings? [y/N]: ", name);
+                    std::io::Write::flush(&mut std::io::stdout())?;
+                    let mut answer = String::new();
+                    std::io::stdin().read_line(&mut answer)?;
+                    if !matches!(answer.trim(), "y" | "Y" | "yes") {
+                        println!("Cancelled.");
+                        return Ok(());
+                    }
+                }
+                match config::delete_profile(&name) {
+                    Ok(()) => println!("✅ Profile {} deleted", name),
+                    Err(error::Error::Validation(e)) => println!("❌ {}", e),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        },
+        Some(Commands::Goal { action }) => {
+            let db = Database::open(&db_path)?;
+            match action {
+                GoalCommands::Set { tag, pomodoros } => {
+                    let tag = tag.trim().trim_start_matches('#').to_string();
+                    if tag.is_empty() || pomodoros == 0 {
+                        println!("❌ Tag must not be empty and the target must be at least 1");
+                        return Ok(());
+                    }
+                    db.set_weekly_goal(&tag, pomodoros)?;
+                    println!("✅ 每周目标: #{} {} 个番茄钟", tag, pomodoros);
+                }
+                GoalCommands::Remove { tag } => {
+                    let tag = tag.trim().trim_start_matches('#');
+                    if db.remove_weekly_goal(tag)? {
+                        println!("✅ 已删除目标 #{}", tag);
+                    } else {
+                        println!("❌ 没有 #{} 的目标", tag);
+                    }
+                }
+                GoalCommands::List => {
+                    let (start, end) = models::local_week_bounds(chrono::Local::now().date_naive());
+                    let goals = db.get_weekly_goals(start, end)?;
+                    if goals.is_empty() {
+                        println!("No weekly goals. Use `tasks goal set <tag> <pomodoros>`.");
+                    }
+                    for goal in goals {
+                        println!("{} #{:<16} {}", if goal.hit() { "✅" } else { "  " }, goal.tag, goal.bar(20));
+                    }
+                }
+            }
+        }
+        Some(Commands::Calendar { action }) => {
+            let db = Database::open(&db_path)?;
+            match action {
+                CalendarCommands::Import { file } => {
+                    let content = std::fs::read_to_string(&file)?;
+                    let events = match calendar::parse_ics(&content) {
+                        Ok(events) => events,
+                        Err(e) => {
+                            println!("❌ {}", e);
+                            return Ok(());
+                        }
                     };
-                    println!(
-                        "[{}] {} {} {}",
-                        task.id.unwrap(),
-                        status_icon,
-                        priority_icon,
-                        task.title
-                    );
+                    db.replace_busy_events(&events)?;
+                    println!("✅ 已导入 {} 个忙碌时段", events.len());
+                }
+                CalendarCommands::Clear => {
+                    db.replace_busy_events(&[])?;
+                    println!("✅ 已清除导入的忙碌时段");
                 }
             }
         }
-        Some(Commands::Complete { id }) => {
+        Some(Commands::Db { action: DbCommands::Compact }) => {
+            let db = Database::open(&db_path)?;
+            let report = db.compact()?;
+            println!("✅ 数据库已压缩：{}", report.describe());
+        }
+        Some(Commands::SelfManage { action: SelfCommands::CheckUpdate }) => check_update(),
+        Some(Commands::Db { action: DbCommands::Reindex { pause_ms } }) => {
+            let db = Database::open(&db_path)?;
+            let mut job = db::ReindexJob::new(&db)?;
+            while job.step(&db, db::REINDEX_BATCH)? {
+                let (done, total) = job.progress();
+                print!("\r重建索引中… {}/{}", done, total);
+                std::io::Write::flush(&mut std::io::stdout())?;
+                std::thread::sleep(std::time::Duration::from_millis(pause_ms));
+            }
+            println!();
+
+            // 提示符缓存中的待办数也是派生数据，一并刷新
+            let cache = prompt::cache_path(&db_path);
+            let mut segment = prompt::PromptSegment::load(&cache).unwrap_or_default();
+            segment.set_counts(&db.get_all_tasks()?, chrono::Utc::now());
+            segment.save(&cache)?;
+            println!("✅ 索引已重建，修正了 {} 个任务的番茄钟数", job.fixed);
+        }
+        Some(Commands::Db { action: DbCommands::Orphans { fix, task } }) => {
             let db = Database::open(&db_path)?;
-            let mut tasks = db.get_all_tasks()?;
+            let notes = db.get_orphaned_notes()?;
+            let sessions = db.get_orphaned_sessions()?;
+            if notes.is_empty() && sessions.is_empty() {
+                println!("✅ No orphaned notes or pomodoro sessions");
+                return Ok(());
+            }
+            for note in &notes {
+                println!("📝 note {:<5} → deleted task {:<5} {}", note.id.unwrap_or(0), note.task_id.unwrap_or(0), note.title);
+            }
+            for session in &sessions {
+                println!(
+                    "🍅 session {:<5} → deleted task {:<5} {} ({} min)",
+                    session.id.unwrap_or(0),
+                    session.task_id.unwrap_or(0),
+                    session.start_time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                    session.duration_minutes
+                );
+            }
+
+            let fix = match (fix, task) {
+                (Some(OrphanFixArg::Relink), Some(task)) => db::OrphanFix::Relink(task),
+                (Some(OrphanFixArg::Relink), None) => {
+                    println!("❌ --fix relink needs --task <id>");
+                    return Ok(());
+                }
+                (Some(OrphanFixArg::Detach), _) => db::OrphanFix::Detach,
+                (Some(OrphanFixArg::Delete), _) => db::OrphanFix::Delete,
+                (None, _) if !std::io::stdin().is_terminal() => return Ok(()),
+                (None, _) => match ask_orphan_fix()? {
+                    Some(fix) => fix,
+                    None => return Ok(()),
+                },
+            };
+            match db.fix_orphans(fix) {
+                Ok((notes, sessions)) => println!("✅ 已处理 {} 条便签、{} 条番茄钟记录", notes, sessions),
+                Err(error::Error::NotFound { id, .. }) => println!("❌ Task {} not found", id),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// tasks add 中标题以外的任务属性
+struct TaskOptions<'a> {
+    tags: &'a [String],
+    repeat: Option<&'a str>,
+    due: Option<&'a str>,
+    priority: Option<&'a str>,
+    remind: Option<&'a str>,
+    desc: Option<&'a str>,
+}
 
-            if let Some(task) = tasks.iter_mut().find(|t| t.id == Some(id)) {
-                task.status = models::TaskStatus::Completed;
-                task.completed_at = Some(chrono::Utc::now());
-                task.updated_at = chrono::Utc::now();
-                db.update_task(task)?;
-                println!("✅ Task {} marked as completed", id);
+/// 根据标题或模板创建任务（展开日期占位符并校验）
+fn new_task(
+    title: Option<String>,
+    template: Option<String>,
+    options: &TaskOptions,
+    config: &Config,
+) -> std::result::Result<Task, error::Error> {
+    let title = match template {
+        Some(name) => config
+            .template(&name)
+            .map(|t| t.to_string())
+            .ok_or_else(|| error::Error::Validation(format!("模板不存在: {}", name)))?,
+        None => title.unwrap_or_default(),
+    };
+
+    let mut task = Task::new(models::expand_placeholders(title.trim()));
+    config.apply_rules(&mut task);
+    for tag in options.tags {
+        task.add_tag(tag);
+    }
+    if let Some(repeat) = options.repeat {
+        let rule = models::Recurrence::parse(repeat)
+            .ok_or_else(|| error::Error::Validation(format!("无效的重复规则: {}", repeat)))?;
+        task.recurrence = Some(rule);
+    }
+    if let Some(due) = options.due {
+        let at = dateparse::parse(due, chrono::Local::now())
+            .ok_or_else(|| error::Error::Validation(format!("无法识别的截止时间: {}", due)))?;
+        task.due_date = Some(at);
+    }
+    if let Some(priority) = options.priority {
+        task.priority = models::Priority::parse(priority)
+            .ok_or_else(|| error::Error::Validation(format!("无效的优先级: {}（可选 high/medium/low）", priority)))?;
+    }
+    if let Some(remind) = options.remind {
+        // 30m / 2h / 1d 表示截止前多久，其他按时间解析
+        task.reminder_time = match (models::parse_reminder_offset(remind), task.due_date) {
+            (Some(offset), Some(due)) => Some(due - offset),
+            (Some(_), None) => {
+                return Err(error::Error::Validation(format!(
+                    "截止前提醒需要同时设置 --due: {}",
+                    remind
+                )))
+            }
+            (None, _) => Some(
+                dateparse::parse(remind, chrono::Local::now())
+                    .ok_or_else(|| error::Error::Validation(format!("无法识别的提醒时间: {}", remind)))?,
+            ),
+        };
+    }
+    if let Some(desc) = options.desc.map(str::trim).filter(|d| !d.is_empty()) {
+        task.description = Some(desc.to_string());
+    }
+    task.validate()?;
+    Ok(task)
+}
+
+/// tasks --remote list --tag：只保留带有指定标签的任务（允许写成 #work）
+fn filter_by_tag(tasks: Vec<Task>, tag: Option<&str>) -> Vec<Task> {
+    match tag.map(|t| t.trim().trim_start_matches('#')) {
+        Some(tag) => tasks.into_iter().filter(|t| t.tags.iter().any(|x| x == tag)).collect(),
+        None => tasks,
+    }
+}
+
+/// tasks list 的过滤参数（--project 需要查库，单独处理）
+struct ListFilters<'a> {
+    tag: Option<&'a str>,
+    status: Option<&'a str>,
+    priority: Option<&'a str>,
+    due_before: Option<&'a str>,
+    due_after: Option<&'a str>,
+    overdue: bool,
+    sort: SortArg,
+}
+
+impl ListFilters<'_> {
+    /// 校验并转换为数据库查询条件
+    fn to_query(&self) -> std::result::Result<db::TaskQuery, error::Error> {
+        let now = chrono::Local::now();
+        let parse_due = |s: &str| {
+            dateparse::parse(s, now).ok_or_else(|| error::Error::Validation(format!("无法识别的时间: {}", s)))
+        };
+        let status = self
+            .status
+            .map(|s| {
+                models::TaskStatus::parse(s).ok_or_else(|| {
+                    error::Error::Validation(format!("无效的状态: {}（可选 todo/in-progress/waiting/completed）", s))
+                })
+            })
+            .transpose()?;
+        let priority = self
+            .priority
+            .map(|p| {
+                models::Priority::parse(p)
+                    .ok_or_else(|| error::Error::Validation(format!("无效的优先级: {}（可选 high/medium/low）", p)))
+            })
+            .transpose()?;
+
+        Ok(db::TaskQuery {
+            status,
+            priority,
+            due_before: self.due_before.map(parse_due).transpose()?,
+            due_after: self.due_after.map(parse_due).transpose()?,
+            overdue_at: self.overdue.then(|| now.with_timezone(&chrono::Utc)),
+            project_id: None,
+            tag: self.tag.map(|t| t.trim().trim_start_matches('#').to_string()),
+            sort: match self.sort {
+                SortArg::Priority => db::TaskSort::Priority,
+                SortArg::Due => db::TaskSort::Due,
+                SortArg::Created => db::TaskSort::Created,
+                SortArg::Title => db::TaskSort::Title,
+            },
+        })
+    }
+}
+
+/// tasks self check-update：与 GitHub 上的最新发布比较版本
+fn check_update() {
+    match update::latest_release() {
+        Ok(release) if update::is_newer(&release.version, update::CURRENT_VERSION) => {
+            println!("⬆️  New version {} available (current {})", release.version, update::CURRENT_VERSION);
+            if !release.url.is_empty() {
+                println!("   {}", release.url);
+            }
+            if !release.notes.trim().is_empty() {
+                println!();
+                println!("{}", release.notes.trim());
+            }
+        }
+        Ok(_) => println!("✅ Up to date ({})", update::CURRENT_VERSION),
+        Err(e) => println!("❌ {}", e),
+    }
+}
+
+/// taskd 是否在运行：以本地套接字能否应答为准
+fn daemon_running(db_path: &std::path::Path) -> bool {
+    ipc::send(db_path, &ipc::Request::Ping).is_ok_and(|response| response.ok)
+}
+
+/// 等待 taskd 进入（或退出）运行状态，最多 5 秒
+fn wait_for_daemon(db_path: &std::path::Path, running: bool) -> bool {
+    for _ in 0..50 {
+        if daemon_running(db_path) == running {
+            return true;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    false
+}
+
+/// tasks daemon start：在后台启动与 tasks 同目录的 taskd，输出写入数据库旁的 tasks.log
+fn daemon_start(
+    db_path: &std::path::Path,
+    profile: Option<&str>,
+    listen: Option<&str>,
+    supervise: bool,
+) -> Result<()> {
+    use std::process::{Command, Stdio};
+
+    if daemon_running(db_path) {
+        println!("✅ taskd is already running{}", ipc::read_pid(db_path).map(|pid| format!(" (pid {})", pid)).unwrap_or_default());
+        return Ok(());
+    }
+    let program = service::taskd_binary();
+    let log_path = db_path.with_extension("log");
+    let log = std::fs::OpenOptions::new().create(true).append(true).open(&log_path)?;
+
+    let mut command = Command::new(&program);
+    command.arg("--db-path").arg(db_path);
+    if let Some(profile) = profile {
+        command.args(["--profile", profile]);
+    }
+    if let Some(addr) = listen {
+        command.args(["--listen", addr]);
+    }
+    if supervise {
+        command.arg("--supervise");
+    }
+    command.stdin(Stdio::null()).stdout(log.try_clone()?).stderr(log);
+    // 脱离当前终端：关闭终端时不会随之退出
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        command.creation_flags(DETACHED_PROCESS);
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => anyhow::bail!("could not start {}: {}", program.display(), e),
+    };
+    if wait_for_daemon(db_path, true) {
+        println!("✅ taskd started (pid {})", child.id());
+        println!("   Log: {}", log_path.display());
+    } else if let Ok(Some(status)) = child.try_wait() {
+        anyhow::bail!("taskd exited during startup ({}); see {}", status, log_path.display());
+    } else {
+        println!("⚠️  taskd started (pid {}) but is not answering yet; see {}", child.id(), log_path.display());
+    }
+    Ok(())
+}
+
+/// tasks daemon stop：通过本地套接字请求退出；套接字无应答时按 PID 文件发送终止信号。
+/// 返回 taskd 是否已停止（本来就没在运行也算）
+fn daemon_stop(db_path: &std::path::Path) -> Result<bool> {
+    match ipc::send(db_path, &ipc::Request::Shutdown) {
+        Ok(response) if response.ok => {
+            if wait_for_daemon(db_path, false) {
+                println!("✅ taskd stopped");
+                return Ok(true);
+            }
+            println!("❌ taskd did not exit in time");
+            return Ok(false);
+        }
+        Ok(response) => {
+            println!("❌ {}", response.message);
+            return Ok(false);
+        }
+        Err(_) => {}
+    }
+    let Some(pid) = ipc::read_pid(db_path) else {
+        println!("taskd is not running.");
+        return Ok(true);
+    };
+    // PID 文件残留（进程已不存在，或 PID 已被其他程序复用）时只清理文件，不发送信号
+    if !is_taskd_process(pid) {
+        println!("taskd is not running (removed stale PID file).");
+        ipc::cleanup(db_path);
+        return Ok(true);
+    }
+    #[cfg(unix)]
+    let stopped = std::process::Command::new("kill")
+        .args(["-TERM", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    #[cfg(windows)]
+    let stopped = std::process::Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/F"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if stopped {
+        ipc::cleanup(db_path);
+        println!("✅ taskd stopped (pid {})", pid);
+    } else {
+        println!("❌ Could not stop taskd (pid {})", pid);
+    }
+    Ok(stopped)
+}
+
+/// 该 PID 的进程是否存在且是 taskd（按进程的可执行文件名判断）
+fn is_taskd_process(pid: u32) -> bool {
+    #[cfg(unix)]
+    let output = std::process::Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "comm="])
+        .output();
+    // CSV 格式的第一列为映像名，如 "taskd.exe","1234",...
+    #[cfg(windows)]
+    let output = std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+        .output();
+    let Ok(output) = output else {
+        return false;
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let name = stdout.trim().split(',').next().unwrap_or("").trim_matches('"');
+    // ps 可能输出完整路径
+    let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    name == "taskd" || name.eq_ignore_ascii_case("taskd.exe")
+}
+
+/// tasks daemon install：由 systemd / launchd 在登录时启动 taskd；
+/// 先停止 tasks daemon start 启动的实例，避免两个 taskd 重复发送提醒
+fn daemon_install(db_path: &std::path::Path, profile: Option<&str>) -> Result<()> {
+    if daemon_running(db_path) && !daemon_stop(db_path)? {
+        return Ok(());
+    }
+    match service::install(db_path, profile) {
+        Ok((path, Ok(()))) => {
+            println!("✅ Installed {}", path.display());
+            println!("   taskd now starts at login; remove it with `tasks daemon uninstall`");
+        }
+        Ok((path, Err(e))) => {
+            println!("⚠️  Wrote {} but could not enable it: {}", path.display(), e);
+            println!("   Enable it with: {}", service::activate_hint(&path, profile));
+        }
+        Err(e) => println!("❌ {}", e),
+    }
+    Ok(())
+}
+
+/// tasks daemon status：PID 和套接字是否应答
+fn daemon_status(db_path: &std::path::Path) {
+    match (ipc::send(db_path, &ipc::Request::Ping), ipc::read_pid(db_path)) {
+        (Ok(response), _) if response.ok => println!("✅ {}", response.message),
+        (_, Some(pid)) => println!("⚠️  taskd is not answering (PID file says {}; stale after a crash?)", pid),
+        (_, None) => println!("taskd is not running (start it with `tasks daemon start`)"),
+    }
+    println!("   PID file: {}", ipc::pid_path(db_path).display());
+}
+
+/// 交互式询问如何处理孤立数据，直接回车表示不处理
+fn ask_orphan_fix() -> Result<Option<db::OrphanFix>> {
+    print!("Fix: [r <task id>] relink / [d] detach / [x] delete / [Enter] skip: ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    let parts: Vec<&str> = answer.split_whitespace().collect();
+    Ok(match parts.as_slice() {
+        ["r", id] | ["relink", id] => match id.parse() {
+            Ok(id) => Some(db::OrphanFix::Relink(id)),
+            Err(_) => {
+                println!("❌ Invalid task id: {}", id);
+                None
+            }
+        },
+        ["d"] | ["detach"] => Some(db::OrphanFix::Detach),
+        ["x"] | ["delete"] => Some(db::OrphanFix::Delete),
+        _ => None,
+    })
+}
+
+/// 读取同步口令：优先使用环境变量 TASKS_SYNC_PASSPHRASE，否则从标准输入读取
+fn read_sync_passphrase() -> Result<String> {
+    if let Ok(passphrase) = std::env::var("TASKS_SYNC_PASSPHRASE") {
+        return Ok(passphrase);
+    }
+
+    Ok(rpassword::prompt_password("Sync passphrase: ")?)
+}
+
+/// 远程模式：命令通过 taskd 的 HTTP 接口执行
+fn run_remote(url: &str, command: Option<Commands>, config: &Config) -> Result<()> {
+    let client = remote::RemoteClient::new(url, config.remote.token())?;
+
+    match command {
+        Some(Commands::Show) | None => ui::run_remote(client, config.clone())?,
+        Some(Commands::Add { project: Some(_), .. }) | Some(Commands::List { project: Some(_), .. }) => {
+            anyhow::bail!("--project is not available in remote mode yet");
+        }
+        Some(Commands::Add { title, template, tags, repeat, due, priority, remind, desc, .. }) => {
+            let options = TaskOptions {
+                tags: &tags,
+                repeat: repeat.as_deref(),
+                due: due.as_deref(),
+                priority: priority.as_deref(),
+                remind: remind.as_deref(),
+                desc: desc.as_deref(),
+            };
+            let task = new_task(title, template, &options, config)?;
+            let id = client.create_task(&task)?;
+            println!("✅ Task created with ID: {}", id);
+            if let Some(due) = task.due_date {
+                println!("📅 Due: {}", due.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+            }
+            if let Some(at) = task.reminder_time {
+                println!("⏰ Reminder: {}", at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+            }
+            if let Some(rule) = task.recurrence {
+                println!("🔁 Repeats: {}", rule.to_rule());
+            }
+        }
+        Some(Commands::List { status: Some(_), .. })
+        | Some(Commands::List { priority: Some(_), .. })
+        | Some(Commands::List { due_before: Some(_), .. })
+        | Some(Commands::List { due_after: Some(_), .. })
+        | Some(Commands::List { overdue: true, .. }) => {
+            anyhow::bail!("only --tag filtering is available in remote mode yet");
+        }
+        Some(Commands::List { tag, json, .. }) => {
+            let tasks = filter_by_tag(client.get_all_tasks()?, tag.as_deref());
+            if json {
+                println!("{}", serde_json::to_string_pretty(&tasks)?);
             } else {
-                println!("❌ Task {} not found", id);
+                print_tasks(&tasks, config);
             }
         }
+        Some(Commands::Complete { follow_up: Some(_), .. }) => {
+            anyhow::bail!("--follow-up is not available in remote mode yet");
+        }
+        Some(Commands::Complete { id, .. }) => match client.complete_task(id) {
+            Ok(()) => println!("✅ Task {} marked as completed", id),
+            Err(error::Error::NotFound { .. }) => println!("❌ Task {} not found", id),
+            Err(e) => return Err(e.into()),
+        },
+        Some(Commands::SelfManage { action: SelfCommands::CheckUpdate }) => check_update(),
+        Some(Commands::Sync { .. })
+        | Some(Commands::Log { .. })
+        | Some(Commands::Print { .. })
+        | Some(Commands::Export { .. })
+        | Some(Commands::ExportIcs { .. })
+        | Some(Commands::Import { .. })
+        | Some(Commands::Qr { .. })
+        | Some(Commands::PromptSegment { .. })
+        | Some(Commands::Estimate { .. })
+        | Some(Commands::Snooze { .. })
+        | Some(Commands::Comment { .. })
+        | Some(Commands::Calendar { .. })
+        | Some(Commands::Goal { .. })
+        | Some(Commands::Project { .. })
+        | Some(Commands::Profile { .. })
+        | Some(Commands::Dashboard { .. })
+        | Some(Commands::Pause { .. })
+        | Some(Commands::Resume)
+        | Some(Commands::Sound { .. })
+        | Some(Commands::Report { .. })
+        | Some(Commands::Daemon { .. })
+        | Some(Commands::Db { .. }) => {
+            anyhow::bail!("this command is not available in remote mode; run it on the server instead");
+        }
     }
 
     Ok(())
 }
+
+/// 打印任务列表（list 命令）
+fn print_tasks(tasks: &[Task], config: &Config) {
+    if tasks.is_empty() {
+        println!("No tasks found.");
+        return;
+    }
+
+    // 仅在输出到终端时着色，避免管道中混入转义序列
+    let colored = std::io::stdout().is_terminal();
+    let paint = |style: &config::IconStyle| {
+        if colored {
+            style.ansi_painted()
+        } else {
+            style.icon.clone()
+        }
+    };
+
+    for task in tasks {
+        let status_icon = paint(config.icons.status(task.status));
+        let priority_icon = paint(config.icons.priority(task.priority));
+        let tags: String = task.tags.iter().map(|t| format!(" #{}", t)).collect();
+        println!(
+            "[{}] {} {} {}{}",
+            task.id.unwrap(),
+            status_icon,
+            priority_icon,
+            task.title,
+            tags
+        );
+    }
+}
+
+/// 解析 YYYY-MM-DD 日期
+fn parse_date(s: &str) -> std::result::Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| format!("无效的日期: {}（格式 YYYY-MM-DD）", s))
+}
+
+/// 本地日期的零点（UTC）
+fn local_day_start(date: chrono::NaiveDate) -> chrono::DateTime<chrono::Utc> {
+    date.and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .unwrap_or_else(|| date.and_hms_opt(0, 0, 0).unwrap().and_utc().with_timezone(&chrono::Local))
+        .with_timezone(&chrono::Utc)
+}
+
+/// 按天打印活动记录（log 命令），番茄钟按天汇总
+fn print_activity(db: &Database, changes: &[models::Change]) {
+    if changes.is_empty() {
+        println!("No activity found.");
+        return;
+    }
+
+    let mut current_day = None;
+    let mut pomodoros = 0;
+    for change in changes {
+        let local = change.created_at.with_timezone(&chrono::Local);
+        let day = local.date_naive();
+        if current_day != Some(day) {
+            if current_day.is_some() {
+                print_pomodoro_summary(pomodoros);
+                println!();
+            }
+            println!("{}", day.format("%Y-%m-%d %a"));
+            current_day = Some(day);
+            pomodoros = 0;
+        }
+
+        if change.entity == "pomodoro" {
+            // 完成的番茄钟：直接以已完成状态插入，或由未完成更新为完成
+            let completed = match change.action {
+                models::ChangeAction::Insert => change.diff["completed"].as_bool(),
+                _ => change.diff["completed"]["new"].as_bool(),
+            };
+            if completed == Some(true) {
+                pomodoros += 1;
+            }
+            continue;
+        }
+        if let Some(text) = describe_change(db, change) {
+            println!("  {}  {}", local.format("%H:%M"), text);
+        }
+    }
+    print_pomodoro_summary(pomodoros);
+}
+
+fn print_pomodoro_summary(count: usize) {
+    if count > 0 {
+        println!("  🍅 {} pomodoro{} completed", count, if count == 1 { "" } else { "s" });
+    }
+}
+
+/// 将一条变更描述为一行活动记录
+fn describe_change(db: &Database, change: &models::Change) -> Option<String> {
+    use models::ChangeAction;
+
+    // 标题：插入/删除记录中带有完整数据，更新时取当前标题
+    let title = change.diff["title"]
+        .as_str()
+        .or_else(|| change.diff["title"]["new"].as_str())
+        .map(|t| t.to_string())
+        .or_else(|| match change.entity.as_str() {
+            "task" => db.get_task(change.entity_id).ok().map(|t| t.title),
+            "note" => db.get_note(change.entity_id).ok().map(|n| n.title),
+            _ => None,
+        })
+        .unwrap_or_else(|| format!("#{}", change.entity_id));
+
+    let text = match (change.entity.as_str(), change.action) {
+        ("task", ChangeAction::Insert) => format!("➕ created \"{}\"", title),
+        ("task", ChangeAction::Delete) => format!("🗑️ deleted \"{}\"", title),
+        ("task", ChangeAction::Update) => match change.diff["status"]["new"].as_str() {
+            Some("Completed") => format!("✅ completed \"{}\"", title),
+            Some("InProgress") => format!("🔄 started \"{}\"", title),
+            Some("Todo") => format!("⭕ reopened \"{}\"", title),
+            _ => {
+                let fields: Vec<&str> = change
+                    .diff
+                    .as_object()
+                    .map(|d| d.keys().map(|k| k.as_str()).collect())
+                    .unwrap_or_default();
+                format!("✏️ updated \"{}\" ({})", title, fields.join(", "))
+            }
+        },
+        ("note", ChangeAction::Insert) => format!("📓 created note \"{}\"", title),
+        ("note", ChangeAction::Update) => format!("📓 edited note \"{}\"", title),
+        ("note", ChangeAction::Delete) => format!("📓 deleted note \"{}\"", title),
+        ("comment", ChangeAction::Insert) => {
+            let task = change.diff["task_id"]
+                .as_i64()
+                .and_then(|id| db.get_task(id).ok())
+                .map(|t| t.title)
+                .unwrap_or_else(|| "?".to_string());
+            format!("💬 commented on \"{}\": {}", task, change.diff["body"].as_str().unwrap_or(""))
+        }
+        _ => return None,
+    };
+    Some(text)
+}
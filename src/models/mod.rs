@@ -1,6 +1,17 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Error, Result};
+
+/// 标题最大长度（字符数）
+pub const MAX_TITLE_LEN: usize = 200;
+/// 任务描述最大长度（字符数）
+pub const MAX_DESCRIPTION_LEN: usize = 10_000;
+/// 便签内容最大长度（字符数）
+pub const MAX_NOTE_CONTENT_LEN: usize = 100_000;
+/// 便签可选的颜色（终端的基本颜色名称）
+pub const NOTE_COLORS: [&str; 7] = ["red", "green", "yellow", "blue", "magenta", "cyan", "gray"];
+
 /// 任务优先级
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Priority {
@@ -9,12 +20,68 @@ pub enum Priority {
     High = 3,
 }
 
+impl Priority {
+    /// 解析优先级名称（high/medium/low，也接受 h/m/l）
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "high" | "h" => Some(Priority::High),
+            "medium" | "med" | "m" => Some(Priority::Medium),
+            "low" | "l" => Some(Priority::Low),
+            _ => None,
+        }
+    }
+
+    /// 导出时使用的名称，可被 parse 读回
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Medium => "medium",
+            Priority::Low => "low",
+        }
+    }
+}
+
 /// 任务状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TaskStatus {
     Todo,
     InProgress,
     Completed,
+    /// 已委派/等待他人，可设置跟进时间
+    Waiting,
+}
+
+impl TaskStatus {
+    /// 解析状态名称（todo / in-progress / waiting / completed，也接受 done）
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "todo" => Some(TaskStatus::Todo),
+            "in_progress" | "doing" => Some(TaskStatus::InProgress),
+            "waiting" => Some(TaskStatus::Waiting),
+            "completed" | "done" => Some(TaskStatus::Completed),
+            _ => None,
+        }
+    }
+
+    /// 界面中显示的名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            TaskStatus::Todo => "待办",
+            TaskStatus::InProgress => "进行中",
+            TaskStatus::Waiting => "等待中",
+            TaskStatus::Completed => "已完成",
+        }
+    }
+
+    /// 导出时使用的名称，可被 parse 读回
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Todo => "todo",
+            TaskStatus::InProgress => "in_progress",
+            TaskStatus::Waiting => "waiting",
+            TaskStatus::Completed => "completed",
+        }
+    }
 }
 
 /// 任务数据模型
@@ -31,6 +98,187 @@ pub struct Task {
     pub updated_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub pomodoro_count: i32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 进入等待状态的时间
+    #[serde(default)]
+    pub waiting_since: Option<DateTime<Utc>>,
+    /// 等待中任务的跟进时间，到时由守护进程提醒
+    #[serde(default)]
+    pub follow_up_at: Option<DateTime<Utc>>,
+    /// 预计耗时（分钟），与截止时间一起用于提醒最晚开始时间
+    #[serde(default)]
+    pub estimate_minutes: Option<i64>,
+    /// 重复规则，完成时自动创建下一次
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// 所属项目
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    /// 归档时间，归档的任务不出现在任务列表和提醒中，可恢复或彻底删除
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
+    /// 同步时跨设备标识任务（本地ID在各设备上不同），保存到数据库时生成
+    #[serde(default)]
+    pub uuid: Option<String>,
+}
+
+/// 项目（任务清单），归档后不再出现在项目列表和切换中，任务保留
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub id: Option<i64>,
+    pub name: String,
+    #[serde(default)]
+    pub archived: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 重复频率
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    /// 周一到周五（忽略间隔）
+    Weekdays,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// 任务重复规则，以 RRULE 子集（FREQ=WEEKLY;INTERVAL=2）的形式保存
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Recurrence {
+    pub freq: Frequency,
+    pub interval: u32,
+}
+
+impl Recurrence {
+    /// 切换顺序（DDL 对话框中按 r 循环）
+    pub const PRESETS: [Recurrence; 5] = [
+        Recurrence { freq: Frequency::Daily, interval: 1 },
+        Recurrence { freq: Frequency::Weekdays, interval: 1 },
+        Recurrence { freq: Frequency::Weekly, interval: 1 },
+        Recurrence { freq: Frequency::Monthly, interval: 1 },
+        Recurrence { freq: Frequency::Yearly, interval: 1 },
+    ];
+
+    /// 解析重复规则：daily / weekdays / weekly / monthly / yearly，
+    /// 间隔写法 3d / 2w / 6mo / 1y，或 RRULE 子集 FREQ=WEEKLY;INTERVAL=2
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_lowercase();
+        let rule = |freq, interval: u32| (interval > 0).then_some(Recurrence { freq, interval });
+        match s.as_str() {
+            "daily" => return rule(Frequency::Daily, 1),
+            "weekdays" | "workdays" => return rule(Frequency::Weekdays, 1),
+            "weekly" => return rule(Frequency::Weekly, 1),
+            "monthly" => return rule(Frequency::Monthly, 1),
+            "yearly" | "annually" => return rule(Frequency::Yearly, 1),
+            _ => {}
+        }
+
+        if s.starts_with("freq=") {
+            let mut freq = None;
+            let mut interval = 1;
+            let mut weekdays = false;
+            for part in s.split(';') {
+                match part.split_once('=')? {
+                    ("freq", "daily") => freq = Some(Frequency::Daily),
+                    ("freq", "weekly") => freq = Some(Frequency::Weekly),
+                    ("freq", "monthly") => freq = Some(Frequency::Monthly),
+                    ("freq", "yearly") => freq = Some(Frequency::Yearly),
+                    ("interval", n) => interval = n.parse().ok()?,
+                    ("byday", "mo,tu,we,th,fr") => weekdays = true,
+                    _ => return None,
+                }
+            }
+            return match (freq?, weekdays) {
+                (Frequency::Weekly, true) => rule(Frequency::Weekdays, 1),
+                (_, true) => None,
+                (freq, false) => rule(freq, interval),
+            };
+        }
+
+        let split = s.find(|c: char| !c.is_ascii_digit())?;
+        let interval = s[..split].parse().ok()?;
+        match &s[split..] {
+            "d" => rule(Frequency::Daily, interval),
+            "w" => rule(Frequency::Weekly, interval),
+            "mo" => rule(Frequency::Monthly, interval),
+            "y" => rule(Frequency::Yearly, interval),
+            _ => None,
+        }
+    }
+
+    /// 保存到数据库的 RRULE 形式
+    pub fn to_rule(&self) -> String {
+        let freq = match self.freq {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekdays => return "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR".to_string(),
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        if self.interval == 1 {
+            format!("FREQ={}", freq)
+        } else {
+            format!("FREQ={};INTERVAL={}", freq, self.interval)
+        }
+    }
+
+    /// 界面显示：每天、每 2 周、每个工作日……
+    pub fn describe(&self) -> String {
+        let unit = match self.freq {
+            Frequency::Weekdays => return "每个工作日".to_string(),
+            Frequency::Daily => "天",
+            Frequency::Weekly => "周",
+            Frequency::Monthly => "月",
+            Frequency::Yearly => "年",
+        };
+        if self.interval == 1 {
+            format!("每{}", unit)
+        } else {
+            format!("每 {} {}", self.interval, unit)
+        }
+    }
+
+    /// 下一次的时间（按本地时间推算，保持时分不变；月末日期在短月份取当月最后一天）
+    pub fn advance(&self, from: DateTime<Utc>) -> DateTime<Utc> {
+        use chrono::{Local, Months, TimeZone, Weekday};
+
+        let local = from.with_timezone(&Local).naive_local();
+        let interval = self.interval.max(1);
+        let next = match self.freq {
+            Frequency::Daily => Some(local + chrono::Duration::days(interval as i64)),
+            Frequency::Weekdays => {
+                let mut next = local + chrono::Duration::days(1);
+                while matches!(next.weekday(), Weekday::Sat | Weekday::Sun) {
+                    next += chrono::Duration::days(1);
+                }
+                Some(next)
+            }
+            Frequency::Weekly => Some(local + chrono::Duration::weeks(interval as i64)),
+            Frequency::Monthly => local.checked_add_months(Months::new(interval)),
+            Frequency::Yearly => local.checked_add_months(Months::new(interval * 12)),
+        };
+        next.and_then(|next| Local.from_local_datetime(&next).earliest())
+            .map(|next| next.with_timezone(&Utc))
+            // 本地时间不存在（夏令时切换）时退回按 UTC 计算
+            .unwrap_or(from + chrono::Duration::days(1))
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        Recurrence::parse(&s).ok_or_else(|| format!("invalid recurrence: {}", s))
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(rule: Recurrence) -> String {
+        rule.to_rule()
+    }
 }
 
 /// 便签数据模型
@@ -42,6 +290,73 @@ pub struct Note {
     pub task_id: Option<i64>, // 关联到任务
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub archived: bool,
+    /// 到期后由守护进程自动归档
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// 置顶的便签排在便签墙最前面
+    #[serde(default)]
+    pub pinned: bool,
+    /// 卡片颜色（NOTE_COLORS 中的名称），None 时使用主题颜色
+    #[serde(default)]
+    pub color: Option<String>,
+    /// 与任务共用 tags 表
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// 任务评论：简短的带时间戳备注（如"等待 Bob 回复"），在任务详情中按时间顺序显示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: Option<i64>,
+    pub task_id: i64,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Comment {
+    pub fn new(task_id: i64, body: String) -> Self {
+        Self {
+            id: None,
+            task_id,
+            body,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// 回收站中的任务或便签：删除时保存的快照，保留 TRASH_RETENTION_DAYS 天后由守护进程清除
+#[derive(Debug, Clone)]
+pub struct TrashItem {
+    pub id: i64,
+    /// "task" 或 "note"
+    pub entity: String,
+    pub entity_id: i64,
+    pub title: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl TrashItem {
+    pub fn is_task(&self) -> bool {
+        self.entity == "task"
+    }
+}
+
+/// 从日历导入的忙碌时段（只读，重新导入时整体替换）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusyEvent {
+    pub uid: String,
+    pub summary: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl BusyEvent {
+    /// 是否与 [start, end) 有重叠
+    pub fn overlaps(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        self.start < end && start < self.end
+    }
 }
 
 /// 番茄钟记录
@@ -55,6 +370,111 @@ pub struct PomodoroSession {
     pub completed: bool,
 }
 
+/// 任务计时记录（与番茄钟无关的开始/停止计时）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub id: Option<i64>,
+    pub task_id: i64,
+    pub started_at: DateTime<Utc>,
+    /// None 表示正在计时
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+impl TimeEntry {
+    /// 计时秒数，正在计时的算到 now
+    pub fn seconds(&self, now: DateTime<Utc>) -> i64 {
+        (self.ended_at.unwrap_or(now) - self.started_at).num_seconds().max(0)
+    }
+
+    /// 落在 [start, end) 之内的秒数（跨越统计区间边界的记录只计区间内的部分）
+    pub fn seconds_between(&self, start: DateTime<Utc>, end: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+        let from = self.started_at.max(start);
+        let to = self.ended_at.unwrap_or(now).min(end);
+        (to - from).num_seconds().max(0)
+    }
+}
+
+/// 变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeAction {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl ChangeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeAction::Insert => "insert",
+            ChangeAction::Update => "update",
+            ChangeAction::Delete => "delete",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "insert" => Some(ChangeAction::Insert),
+            "update" => Some(ChangeAction::Update),
+            "delete" => Some(ChangeAction::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// 每周目标：本周在带某个标签（项目）的任务上完成的番茄钟数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyGoal {
+    pub tag: String,
+    pub target: usize,
+    /// 本周已完成的番茄钟数（查询时统计）
+    pub done: usize,
+}
+
+impl WeeklyGoal {
+    pub fn hit(&self) -> bool {
+        self.done >= self.target
+    }
+
+    /// 文本进度条，如 ███░░ 3/5
+    pub fn bar(&self, width: usize) -> String {
+        let filled = (self.done * width / self.target.max(1)).min(width);
+        format!("{}{} {}/{}", "█".repeat(filled), "░".repeat(width - filled), self.done, self.target)
+    }
+}
+
+/// 本地时间所在周（周一 00:00 到下周一 00:00）对应的 UTC 区间
+pub fn local_week_bounds(date: chrono::NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    use chrono::{Datelike, TimeZone};
+
+    let monday = date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+    let start = chrono::Local
+        .from_local_datetime(&monday.and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| monday.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    (start, start + chrono::Duration::days(7))
+}
+
+/// 本地使用统计（命令/按键的使用次数，只保存在本地数据库，不会上传）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageStat {
+    pub name: String, // 命令以 : 开头，如 ":goto"；按键为 "key:x"
+    pub count: i64,
+    pub last_used: DateTime<Utc>,
+}
+
+/// 变更日志记录（审计、撤销、同步和活动记录的数据来源）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Change {
+    pub id: i64,
+    pub entity: String, // task / note / pomodoro
+    pub entity_id: i64,
+    pub action: ChangeAction,
+    /// 插入/删除时为完整记录，更新时为 {字段: {"old": .., "new": ..}}
+    pub diff: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
 impl Task {
     pub fn new(title: String) -> Self {
         let now = Utc::now();
@@ -70,7 +490,98 @@ impl Task {
             updated_at: now,
             completed_at: None,
             pomodoro_count: 0,
+            tags: Vec::new(),
+            waiting_since: None,
+            follow_up_at: None,
+            estimate_minutes: None,
+            recurrence: None,
+            project_id: None,
+            archived_at: None,
+            uuid: None,
+        }
+    }
+
+    /// 标记为等待中（已在等待时保留原来的开始时间）
+    pub fn set_waiting(&mut self, follow_up_at: Option<DateTime<Utc>>) {
+        if self.status != TaskStatus::Waiting {
+            self.waiting_since = Some(Utc::now());
+        }
+        self.status = TaskStatus::Waiting;
+        self.follow_up_at = follow_up_at;
+        self.completed_at = None;
+    }
+
+    /// 完成任务时安排的跟进任务：在 at 时截止并提醒，继承优先级和标签
+    pub fn follow_up_task(&self, title: Option<&str>, at: DateTime<Utc>) -> Task {
+        let title = match title {
+            Some(title) if !title.trim().is_empty() => title.trim().to_string(),
+            _ => format!("跟进: {}", self.title),
+        };
+        let mut task = Task::new(title);
+        task.description = Some(match self.id {
+            Some(id) => format!("跟进自任务 #{}：{}", id, self.title),
+            None => format!("跟进自任务：{}", self.title),
+        });
+        task.priority = self.priority;
+        task.tags = self.tags.clone();
+        task.project_id = self.project_id;
+        task.due_date = Some(at);
+        task.reminder_time = Some(at);
+        task
+    }
+
+    /// 重复任务的下一次：截止时间按规则顺延到 now 之后（没有截止时间时从 now 起算），
+    /// 提醒保持相同的提前量，继承标题、描述、优先级、标签和预计耗时
+    pub fn next_occurrence(&self, now: DateTime<Utc>) -> Option<Task> {
+        let rule = self.recurrence?;
+        let mut task = Task::new(self.title.clone());
+        task.description = self.description.clone();
+        task.priority = self.priority;
+        task.tags = self.tags.clone();
+        task.estimate_minutes = self.estimate_minutes;
+        task.recurrence = Some(rule);
+        task.project_id = self.project_id;
+
+        let due = self.due_date.unwrap_or(now);
+        let mut next = rule.advance(due);
+        while next <= now {
+            next = rule.advance(next);
+        }
+        task.due_date = Some(next);
+        task.reminder_time = self.reminder_time.map(|reminder| next - (due - reminder));
+        Some(task)
+    }
+
+    /// 按步长推迟 DDL，提醒保持相同的提前量
+    pub fn defer(&mut self, step: Defer, now: DateTime<Utc>) {
+        let due = step.apply(self.due_date, now);
+        if let Some(old) = self.due_date {
+            self.reminder_time = self.reminder_time.map(|reminder| due - (old - reminder));
         }
+        self.due_date = Some(due);
+        self.updated_at = now;
+    }
+
+    /// 最晚开始时间：截止时间 − 预计耗时 − 缓冲，缺少截止时间或预计耗时时为 None
+    pub fn latest_start(&self, buffer: chrono::Duration) -> Option<DateTime<Utc>> {
+        Some(self.due_date? - chrono::Duration::minutes(self.estimate_minutes?) - buffer)
+    }
+
+    /// 结束等待，回到待办
+    pub fn clear_waiting(&mut self) {
+        self.status = TaskStatus::Todo;
+        self.waiting_since = None;
+        self.follow_up_at = None;
+    }
+
+    /// 添加标签（去掉开头的 #，忽略空标签和重复标签）
+    pub fn add_tag(&mut self, tag: &str) {
+        insert_tag(&mut self.tags, tag);
+    }
+
+    /// 移除标签，返回任务原本是否带有该标签
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        delete_tag(&mut self.tags, tag)
     }
 
     pub fn is_overdue(&self) -> bool {
@@ -80,6 +591,42 @@ impl Task {
             false
         }
     }
+
+    /// 未完成且将在 `window` 内到期（已逾期的不算）
+    pub fn is_due_within(&self, window: chrono::Duration) -> bool {
+        match self.due_date {
+            Some(due) => {
+                let now = Utc::now();
+                due >= now && due - now <= window && self.status != TaskStatus::Completed
+            }
+            None => false,
+        }
+    }
+
+    /// 校验任务字段：标题非空且不超长、描述不超长、日期在合理范围内
+    pub fn validate(&self) -> Result<()> {
+        validate_title(&self.title)?;
+        if let Some(desc) = &self.description {
+            if desc.chars().count() > MAX_DESCRIPTION_LEN {
+                return Err(Error::Validation(format!(
+                    "描述过长（最多 {} 字符）",
+                    MAX_DESCRIPTION_LEN
+                )));
+            }
+        }
+        if let Some(due) = self.due_date {
+            validate_date("截止时间", due)?;
+        }
+        if let Some(reminder) = self.reminder_time {
+            validate_date("提醒时间", reminder)?;
+            if let Some(due) = self.due_date {
+                if reminder > due {
+                    return Err(Error::Validation("提醒时间不能晚于截止时间".to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Note {
@@ -92,6 +639,263 @@ impl Note {
             task_id: None,
             created_at: now,
             updated_at: now,
+            archived: false,
+            expires_at: None,
+            pinned: false,
+            color: None,
+            tags: Vec::new(),
+        }
+    }
+
+    /// 添加标签（规则与任务标签相同）
+    pub fn add_tag(&mut self, tag: &str) {
+        insert_tag(&mut self.tags, tag);
+    }
+
+    /// 移除标签，返回便签原本是否带有该标签
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        delete_tag(&mut self.tags, tag)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        !self.archived && self.expires_at.is_some_and(|t| t <= now)
+    }
+
+    /// 校验便签字段：标题非空且不超长、内容不超长
+    pub fn validate(&self) -> Result<()> {
+        validate_title(&self.title)?;
+        if self.content.chars().count() > MAX_NOTE_CONTENT_LEN {
+            return Err(Error::Validation(format!(
+                "便签内容过长（最多 {} 字符）",
+                MAX_NOTE_CONTENT_LEN
+            )));
+        }
+        if let Some(color) = self.color.as_deref().filter(|c| !NOTE_COLORS.contains(c)) {
+            return Err(Error::Validation(format!(
+                "无效的便签颜色: {}（可选 {}）",
+                color,
+                NOTE_COLORS.join("/")
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 添加标签并保持排序（去掉开头的 #，忽略空标签和重复标签）
+fn insert_tag(tags: &mut Vec<String>, tag: &str) {
+    let tag = tag.trim().trim_start_matches('#').trim();
+    if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+        tags.push(tag.to_string());
+        tags.sort();
+    }
+}
+
+/// 移除标签，返回原本是否带有该标签
+fn delete_tag(tags: &mut Vec<String>, tag: &str) -> bool {
+    let tag = tag.trim().trim_start_matches('#').trim();
+    let before = tags.len();
+    tags.retain(|t| t != tag);
+    tags.len() != before
+}
+
+/// 展开标题中的日期占位符（创建任务时调用）：
+/// `{date}` → 2025-10-18，`{week}` → 2025-W42，`{month}` → 2025-10，`{year}` → 2025
+pub fn expand_placeholders(text: &str) -> String {
+    let now = chrono::Local::now();
+    let week = now.iso_week();
+    text.replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{week}", &format!("{}-W{:02}", week.year(), week.week()))
+        .replace("{month}", &now.format("%Y-%m").to_string())
+        .replace("{year}", &now.format("%Y").to_string())
+}
+
+/// 解析跟进/提醒时间：日期（当天 9:00）、tomorrow 或相对时长（3d/12h/30m）
+pub fn parse_follow_up(s: &str) -> Option<DateTime<Utc>> {
+    use chrono::TimeZone;
+
+    let date = match s {
+        "tomorrow" => Some(chrono::Local::now().date_naive() + chrono::Duration::days(1)),
+        _ => chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok(),
+    };
+    if let Some(date) = date {
+        return chrono::Local
+            .from_local_datetime(&date.and_hms_opt(9, 0, 0)?)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc));
+    }
+
+    let (number, unit) = s.split_at(s.len().checked_sub(1)?);
+    let amount: i64 = number.parse().ok().filter(|n| *n > 0)?;
+    let duration = match unit {
+        "d" => chrono::Duration::days(amount),
+        "h" => chrono::Duration::hours(amount),
+        "m" => chrono::Duration::minutes(amount),
+        _ => return None,
+    };
+    Some(Utc::now() + duration)
+}
+
+/// 解析预计耗时：90m、2h、1h30m，纯数字按分钟计；返回分钟数
+pub fn parse_estimate(s: &str) -> Option<i64> {
+    let s = s.trim().to_lowercase();
+    if let Ok(minutes) = s.parse::<i64>() {
+        return (minutes > 0).then_some(minutes);
+    }
+    let (hours, rest) = match s.split_once('h') {
+        Some((hours, rest)) => (hours.parse::<i64>().ok()?, rest),
+        None => (0, s.as_str()),
+    };
+    let minutes = match rest.strip_suffix('m') {
+        Some(minutes) => minutes.parse::<i64>().ok()?,
+        None if rest.is_empty() => 0,
+        None => return None,
+    };
+    let total = hours * 60 + minutes;
+    (total > 0).then_some(total)
+}
+
+/// 解析"截止前多久"提醒：30m、2h、1h30m、1d
+pub fn parse_reminder_offset(s: &str) -> Option<chrono::Duration> {
+    let s = s.trim().to_lowercase();
+    if let Some(days) = s.strip_suffix('d') {
+        return days.parse::<i64>().ok().filter(|d| *d > 0).map(chrono::Duration::days);
+    }
+    // 必须带单位：与预计耗时不同，纯数字不按分钟解释
+    if s.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    parse_estimate(&s).map(chrono::Duration::minutes)
+}
+
+/// 推迟任务 DDL 的步长（> / < 键和 :defer）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Defer {
+    Hours(i64),
+    Days(i64),
+    Weeks(i64),
+    /// 下周一，保持原来的时刻（没有 DDL 时为 09:00）
+    NextMonday,
+}
+
+impl Defer {
+    /// 解析 1h、2d、1w、-1d（提前）、mon / monday / 周一
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_lowercase();
+        if matches!(s.as_str(), "mon" | "monday" | "周一" | "下周一") {
+            return Some(Defer::NextMonday);
+        }
+        let unit = s.chars().last()?;
+        let n = s[..s.len() - unit.len_utf8()].parse::<i64>().ok().filter(|n| *n != 0)?;
+        match unit {
+            'h' => Some(Defer::Hours(n)),
+            'd' => Some(Defer::Days(n)),
+            'w' => Some(Defer::Weeks(n)),
+            _ => None,
         }
     }
+
+    /// 新的截止时间。往后推迟时，已逾期的任务从今天（按小时推迟时从现在）算起，
+    /// 没有 DDL 的任务从现在算起
+    pub fn apply(self, due: Option<DateTime<Utc>>, now: DateTime<Utc>) -> DateTime<Utc> {
+        use chrono::{Local, TimeZone};
+
+        let local_at = |date: chrono::NaiveDate, time: chrono::NaiveTime| {
+            Local
+                .from_local_datetime(&date.and_time(time))
+                .earliest()
+                .map(|t| t.with_timezone(&Utc))
+                .unwrap_or_else(|| date.and_time(time).and_utc())
+        };
+        let today = now.with_timezone(&Local).date_naive();
+        let days = match self {
+            Defer::Hours(h) => {
+                let base = match due {
+                    Some(due) if h > 0 => due.max(now),
+                    Some(due) => due,
+                    None => now,
+                };
+                return base + chrono::Duration::hours(h);
+            }
+            Defer::Days(d) => d,
+            Defer::Weeks(w) => w * 7,
+            Defer::NextMonday => {
+                let monday = today + chrono::Duration::days(7 - today.weekday().num_days_from_monday() as i64);
+                let time = match due {
+                    Some(due) => due.with_timezone(&Local).time(),
+                    None => chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                };
+                return local_at(monday, time);
+            }
+        };
+        let base = match due {
+            Some(due) if due < now && days > 0 => local_at(today, due.with_timezone(&Local).time()),
+            Some(due) => due,
+            None => now,
+        };
+        // 按本地日期推迟，跨夏令时也保持同一时刻
+        let local = base.with_timezone(&Local);
+        local_at(local.date_naive() + chrono::Duration::days(days), local.time())
+    }
+
+    /// 状态栏中的说明
+    pub fn describe(self) -> String {
+        match self {
+            Defer::Hours(h) if h < 0 => format!("提前 {} 小时", -h),
+            Defer::Hours(h) => format!("推迟 {} 小时", h),
+            Defer::Days(d) if d < 0 => format!("提前 {} 天", -d),
+            Defer::Days(d) => format!("推迟 {} 天", d),
+            Defer::Weeks(w) if w < 0 => format!("提前 {} 周", -w),
+            Defer::Weeks(w) => format!("推迟 {} 周", w),
+            Defer::NextMonday => "推迟到下周一".to_string(),
+        }
+    }
+}
+
+/// 以 1h30m / 45m 的形式显示分钟数
+pub fn format_estimate(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
+/// 格式化计时时长，不足一分钟显示为 <1m
+pub fn format_tracked(seconds: i64) -> String {
+    if seconds < 60 {
+        "<1m".to_string()
+    } else {
+        format_estimate(seconds / 60)
+    }
+}
+
+/// 校验标题：去除首尾空白后非空，且不超过最大长度
+fn validate_title(title: &str) -> Result<()> {
+    let trimmed = title.trim();
+    if trimmed.is_empty() {
+        return Err(Error::Validation("标题不能为空".to_string()));
+    }
+    if trimmed.chars().count() > MAX_TITLE_LEN {
+        return Err(Error::Validation(format!(
+            "标题过长（最多 {} 字符）",
+            MAX_TITLE_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// 校验日期：年份需在 2000-2099 之间（与日期选择器的范围一致）
+fn validate_date(label: &str, dt: DateTime<Utc>) -> Result<()> {
+    if !(2000..=2099).contains(&dt.year()) {
+        return Err(Error::Validation(format!(
+            "{}超出合理范围: {}",
+            label,
+            dt.format("%Y-%m-%d")
+        )));
+    }
+    Ok(())
 }
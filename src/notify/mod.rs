@@ -1,23 +1,93 @@
 use anyhow::Result;
 use notify_rust::{Notification, Timeout};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::config::{NotificationsConfig, NotifyBackend};
+
+/// 实际使用的通知方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Backend {
+    /// 桌面通知（Linux 上经由 DBus）
+    Desktop,
+    /// POST JSON 到指定地址（通过 curl，支持 https）
+    Webhook(String),
+    /// 输出到 taskd 所在的终端（带响铃）
+    Terminal,
+}
+
+impl Backend {
+    /// 按配置选择通知方式；auto 时先检测桌面通知服务是否存在，避免每次提醒都报错
+    pub fn detect(config: &NotificationsConfig) -> Backend {
+        let webhook = config.webhook_url.clone().filter(|url| !url.trim().is_empty());
+        match (config.backend, webhook) {
+            (NotifyBackend::Desktop, _) => Backend::Desktop,
+            (NotifyBackend::Webhook, Some(url)) => Backend::Webhook(url),
+            (NotifyBackend::Webhook, None) => {
+                tracing::warn!("notifications.backend = \"webhook\" but webhook_url is not set, using terminal");
+                Backend::Terminal
+            }
+            (NotifyBackend::Terminal, _) => Backend::Terminal,
+            (NotifyBackend::Auto, _) if desktop_available() => Backend::Desktop,
+            (NotifyBackend::Auto, Some(url)) => Backend::Webhook(url),
+            (NotifyBackend::Auto, None) => Backend::Terminal,
+        }
+    }
+
+    /// 在 taskd status 中显示的名称
+    pub fn describe(&self) -> String {
+        match self {
+            Backend::Desktop => "desktop".to_string(),
+            Backend::Webhook(url) => format!("webhook ({})", url),
+            Backend::Terminal => "terminal".to_string(),
+        }
+    }
+}
+
+/// 是否有可用的桌面通知服务（Linux/BSD 上检查 DBus 通知服务，其他平台总是可用）
+#[cfg(all(unix, not(target_os = "macos")))]
+fn desktop_available() -> bool {
+    notify_rust::get_server_information().is_ok()
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn desktop_available() -> bool {
+    true
+}
 
 /// 通知管理器
-pub struct NotificationManager;
+pub struct NotificationManager {
+    backend: Backend,
+}
 
 impl NotificationManager {
     pub fn new() -> Self {
-        Self
+        Self::with_backend(Backend::Desktop)
+    }
+
+    pub fn with_backend(backend: Backend) -> Self {
+        Self { backend }
     }
 
     /// 发送任务提醒
     pub fn send_task_reminder(&self, title: &str, body: &str) -> Result<()> {
-        Notification::new()
-            .summary(&format!("📅 {}", title))
-            .body(body)
-            .icon("calendar")
-            .timeout(Timeout::Milliseconds(5000))
-            .show()?;
-        Ok(())
+        self.show(&format!("📅 {}", title), body, Some("calendar"), 5000)
+    }
+
+    /// 发送提醒汇总通知（多个提醒同时到期时代替逐条通知）
+    pub fn send_reminder_digest(&self, titles: &[&str], max_titles: usize) -> Result<()> {
+        let mut body = titles
+            .iter()
+            .take(max_titles)
+            .map(|title| format!("• {}", title))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if titles.len() > max_titles {
+            body.push_str(&format!("\n…还有 {} 项", titles.len() - max_titles));
+        }
+        body.push_str("\n打开 tasks 查看详情");
+
+        self.show(&format!("📅 {} 个提醒待处理", titles.len()), &body, Some("calendar"), 10000)
     }
 
     /// 发送番茄钟完成通知
@@ -28,22 +98,48 @@ impl NotificationManager {
             ("番茄钟完成", "干得好！休息一下吧。")
         };
 
-        Notification::new()
-            .summary(&format!("🍅 {}", summary))
-            .body(body)
-            .icon("emblem-default")
-            .timeout(Timeout::Milliseconds(5000))
-            .show()?;
-        Ok(())
+        self.show(&format!("🍅 {}", summary), body, Some("emblem-default"), 5000)
     }
 
     /// 发送普通通知
     pub fn send_notification(&self, title: &str, body: &str) -> Result<()> {
-        Notification::new()
-            .summary(title)
-            .body(body)
-            .timeout(Timeout::Milliseconds(3000))
-            .show()?;
+        self.show(title, body, None, 3000)
+    }
+
+    fn show(&self, summary: &str, body: &str, icon: Option<&str>, timeout_ms: u32) -> Result<()> {
+        match &self.backend {
+            Backend::Desktop => {
+                let mut notification = Notification::new();
+                notification
+                    .summary(summary)
+                    .body(body)
+                    .timeout(Timeout::Milliseconds(timeout_ms));
+                if let Some(icon) = icon {
+                    notification.icon(icon);
+                }
+                notification.show()?;
+            }
+            Backend::Webhook(url) => {
+                let payload = serde_json::json!({ "title": summary, "body": body }).to_string();
+                let status = Command::new("curl")
+                    .args(["-fsS", "-m", "10", "-H", "Content-Type: application/json", "-d", &payload, url])
+                    .stdout(Stdio::null())
+                    .status()?;
+                if !status.success() {
+                    anyhow::bail!("webhook request failed ({})", status);
+                }
+            }
+            Backend::Terminal => {
+                let mut stderr = std::io::stderr();
+                writeln!(
+                    stderr,
+                    "\x07[{}] {}\n    {}",
+                    chrono::Local::now().format("%H:%M"),
+                    summary,
+                    body.replace('\n', "\n    ")
+                )?;
+            }
+        }
         Ok(())
     }
 }
@@ -1,11 +1,14 @@
 use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 use tokio::time::{sleep, Duration as TokioDuration};
 
+use crate::clock::{self, Clock};
 use crate::models::PomodoroSession;
 
 /// 番茄钟状态
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PomodoroState {
     Idle,
     Working,
@@ -13,16 +16,38 @@ pub enum PomodoroState {
     Paused,
 }
 
+/// 进行中的番茄钟，保存在数据库中，TUI 退出（包括意外退出）后下次启动时询问是否继续
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PomodoroSnapshot {
+    /// 所处阶段：working / break
+    pub phase: PomodoroState,
+    /// 计时中的结束时间，暂停时为 None
+    pub ends_at: Option<DateTime<Utc>>,
+    /// 暂停时的剩余秒数
+    pub paused_remaining: Option<i32>,
+    pub task_id: Option<i64>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub long_break: bool,
+}
+
 /// 番茄钟计时器
 #[derive(Debug, Clone)]
 pub struct PomodoroTimer {
     pub state: PomodoroState,
     pub work_duration: i32,      // 工作时长（分钟）
     pub break_duration: i32,     // 休息时长（分钟）
+    pub long_break_duration: i32, // 长休息时长（分钟）
+    pub cycle_length: u32,       // 每完成几个番茄钟进行一次长休息，0 表示不使用长休息
+    pub completed_in_cycle: u32, // 本轮已完成的番茄钟数
+    pub long_break: bool,        // 当前休息是否为长休息
     pub remaining_seconds: i32,   // 剩余秒数
     pub current_task_id: Option<i64>,
     pub session_id: Option<i64>,
     pub start_time: Option<DateTime<Utc>>,
+    pub paused_from: Option<PomodoroState>, // 暂停前的状态（工作或休息），恢复时回到该状态
+    // 上次结算剩余时间的时刻，tick 时按实际经过的时间扣减
+    last_tick: Option<DateTime<Utc>>,
+    clock: Arc<dyn Clock>,
 }
 
 impl Default for PomodoroTimer {
@@ -31,10 +56,17 @@ impl Default for PomodoroTimer {
             state: PomodoroState::Idle,
             work_duration: 25,
             break_duration: 5,
+            long_break_duration: 15,
+            cycle_length: 4,
+            completed_in_cycle: 0,
+            long_break: false,
             remaining_seconds: 0,
             current_task_id: None,
             session_id: None,
             start_time: None,
+            paused_from: None,
+            last_tick: None,
+            clock: clock::system(),
         }
     }
 }
@@ -48,34 +80,83 @@ impl PomodoroTimer {
         }
     }
 
+    /// 使用指定时钟（测试中传入 SimulatedClock）
+    pub fn with_clock(work_duration: i32, break_duration: i32, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            ..Self::new(work_duration, break_duration)
+        }
+    }
+
     /// 开始工作计时
     pub fn start_work(&mut self, task_id: Option<i64>) {
+        let now = self.clock.now();
         self.state = PomodoroState::Working;
         self.remaining_seconds = self.work_duration * 60;
         self.current_task_id = task_id;
-        self.start_time = Some(Utc::now());
+        self.start_time = Some(now);
+        self.last_tick = Some(now);
+    }
+
+    /// 工作时段完成：计入本轮并开始休息（本轮满 cycle_length 个时为长休息）
+    pub fn finish_work(&mut self) {
+        self.completed_in_cycle += 1;
+        self.start_break();
+    }
+
+    /// 休息结束；长休息结束后开始新的一轮
+    pub fn finish_break(&mut self) {
+        if self.long_break {
+            self.completed_in_cycle = 0;
+            self.long_break = false;
+        }
+        self.stop();
+    }
+
+    /// 当前休息的时长（分钟）
+    fn current_break_duration(&self) -> i32 {
+        if self.long_break {
+            self.long_break_duration
+        } else {
+            self.break_duration
+        }
+    }
+
+    /// 本轮进度，如 🍅🍅🍅⚪；不使用长休息时为空
+    pub fn cycle_marks(&self) -> String {
+        if self.cycle_length == 0 {
+            return String::new();
+        }
+        let done = self.completed_in_cycle.min(self.cycle_length) as usize;
+        "🍅".repeat(done) + &"⚪".repeat(self.cycle_length as usize - done)
     }
 
     /// 开始休息
     pub fn start_break(&mut self) {
+        let now = self.clock.now();
         self.state = PomodoroState::Break;
-        self.remaining_seconds = self.break_duration * 60;
-        self.start_time = Some(Utc::now());
+        self.long_break = self.cycle_length > 0 && self.completed_in_cycle >= self.cycle_length;
+        self.remaining_seconds = self.current_break_duration() * 60;
+        self.start_time = Some(now);
+        self.last_tick = Some(now);
     }
 
     /// 暂停
     pub fn pause(&mut self) {
         if self.state == PomodoroState::Working || self.state == PomodoroState::Break {
+            // 先结算暂停前经过的时间
+            self.tick();
+            self.paused_from = Some(self.state);
             self.state = PomodoroState::Paused;
+            self.last_tick = None;
         }
     }
 
     /// 恢复
     pub fn resume(&mut self) {
         if self.state == PomodoroState::Paused {
-            // 需要记录之前的状态，现在简单处理：恢复到Working
-            // 理想情况下应该保存之前的状态
-            self.state = PomodoroState::Working;
+            self.state = self.paused_from.take().unwrap_or(PomodoroState::Working);
+            self.last_tick = Some(self.clock.now());
         }
     }
 
@@ -86,40 +167,85 @@ impl PomodoroTimer {
         self.current_task_id = None;
         self.session_id = None;
         self.start_time = None;
+        self.paused_from = None;
+        self.last_tick = None;
     }
 
-    /// 减少一秒
-    pub fn tick(&mut self) -> bool {
-        if self.state == PomodoroState::Working || self.state == PomodoroState::Break {
-            if self.remaining_seconds > 0 {
-                self.remaining_seconds -= 1;
-                true
-            } else {
-                false // 时间到
+    /// 进行中（包括暂停）的番茄钟快照，空闲时为 None；计时中 last_tick + 剩余时间不随 tick 变化，
+    /// 快照只在开始、暂停、继续、切换阶段时改变
+    pub fn snapshot(&self) -> Option<PomodoroSnapshot> {
+        let (phase, ends_at, paused_remaining) = match self.state {
+            PomodoroState::Working | PomodoroState::Break => (
+                self.state,
+                Some(self.last_tick? + Duration::seconds(self.remaining_seconds as i64)),
+                None,
+            ),
+            PomodoroState::Paused => (self.paused_from?, None, Some(self.remaining_seconds)),
+            PomodoroState::Idle => return None,
+        };
+        Some(PomodoroSnapshot {
+            phase,
+            ends_at,
+            paused_remaining,
+            task_id: self.current_task_id,
+            start_time: self.start_time,
+            long_break: self.long_break,
+        })
+    }
+
+    /// 按快照恢复；计时中的阶段按结束时间计算剩余时间，关闭期间已经结束的剩余 0 秒，
+    /// 下一次 tick 时按正常流程结束（记录番茄钟、开始休息）
+    pub fn restore(&mut self, snapshot: &PomodoroSnapshot) {
+        let now = self.clock.now();
+        self.current_task_id = snapshot.task_id;
+        self.start_time = snapshot.start_time;
+        self.long_break = snapshot.long_break;
+        self.session_id = None;
+        match snapshot.paused_remaining {
+            Some(remaining) => {
+                self.state = PomodoroState::Paused;
+                self.paused_from = Some(snapshot.phase);
+                self.remaining_seconds = remaining;
+                self.last_tick = None;
+            }
+            None => {
+                self.state = snapshot.phase;
+                self.paused_from = None;
+                self.remaining_seconds = snapshot.ends_at.map_or(0, |end| (end - now).num_seconds().max(0) as i32);
+                self.last_tick = Some(now);
             }
-        } else {
-            false
         }
     }
 
+    /// 按时钟经过的整秒数扣减剩余时间，返回 false 表示时间到（或未在计时）
+    pub fn tick(&mut self) -> bool {
+        if self.state != PomodoroState::Working && self.state != PomodoroState::Break {
+            return false;
+        }
+
+        let now = self.clock.now();
+        let last_tick = self.last_tick.unwrap_or(now);
+        let elapsed = (now - last_tick).num_seconds();
+        if elapsed > 0 {
+            self.remaining_seconds = (self.remaining_seconds - elapsed as i32).max(0);
+            // 只推进整秒，保留不足一秒的部分到下次结算
+            self.last_tick = Some(last_tick + Duration::seconds(elapsed));
+        }
+
+        self.remaining_seconds > 0
+    }
+
     /// 获取进度百分比
     pub fn progress(&self) -> f32 {
-        // 在 Paused 状态时，也应该显示当前的进度（基于之前的状态）
-        // 这里我们使用 remaining_seconds 来推断之前的状态时长
-        let total = if self.remaining_seconds > 0 {
-            // 通过 remaining_seconds 推断总时长
-            // 如果小于 work_duration，则是 work_duration；否则是 break_duration
-            if self.remaining_seconds <= self.work_duration * 60 {
-                self.work_duration * 60
-            } else {
-                self.break_duration * 60
-            }
-        } else {
-            match self.state {
-                PomodoroState::Working => self.work_duration * 60,
-                PomodoroState::Break => self.break_duration * 60,
-                _ => return 0.0,
-            }
+        // 暂停时按暂停前的阶段计算
+        let phase = match self.state {
+            PomodoroState::Paused => self.paused_from,
+            state => Some(state),
+        };
+        let total = match phase {
+            Some(PomodoroState::Working) => self.work_duration * 60,
+            Some(PomodoroState::Break) => self.current_break_duration() * 60,
+            _ => return 0.0,
         };
 
         if total == 0 {
@@ -138,3 +264,94 @@ impl PomodoroTimer {
         format!("{:02}:{:02}", minutes, seconds)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SimulatedClock;
+    use chrono::TimeZone;
+
+    fn timer() -> (PomodoroTimer, SimulatedClock) {
+        let clock = SimulatedClock::new(Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap());
+        (PomodoroTimer::with_clock(25, 5, Arc::new(clock.clone())), clock)
+    }
+
+    #[test]
+    fn resume_after_pausing_work_returns_to_working() {
+        let (mut timer, clock) = timer();
+        timer.start_work(Some(1));
+        clock.advance(Duration::minutes(10));
+        timer.pause();
+        assert_eq!(timer.state, PomodoroState::Paused);
+        assert_eq!(timer.remaining_seconds, 15 * 60);
+
+        // 暂停期间不计时
+        clock.advance(Duration::minutes(30));
+        assert!(!timer.tick());
+        timer.resume();
+        assert_eq!(timer.state, PomodoroState::Working);
+        assert_eq!(timer.remaining_seconds, 15 * 60);
+
+        clock.advance(Duration::minutes(5));
+        assert!(timer.tick());
+        assert_eq!(timer.remaining_seconds, 10 * 60);
+    }
+
+    #[test]
+    fn resume_after_pausing_break_returns_to_break() {
+        let (mut timer, clock) = timer();
+        timer.start_work(None);
+        timer.finish_work();
+        assert_eq!(timer.state, PomodoroState::Break);
+
+        clock.advance(Duration::minutes(2));
+        timer.pause();
+        assert_eq!(timer.remaining_seconds, 3 * 60);
+        // 暂停时按休息时长（5 分钟）计算进度，而不是工作时长
+        assert!((timer.progress() - 40.0).abs() < 0.01);
+
+        clock.advance(Duration::minutes(10));
+        timer.resume();
+        assert_eq!(timer.state, PomodoroState::Break);
+        assert_eq!(timer.remaining_seconds, 3 * 60);
+        assert!((timer.progress() - 40.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn ticks_with_simulated_time() {
+        let (mut timer, clock) = timer();
+        timer.start_work(None);
+        // 不足一秒的部分留到下次结算
+        clock.advance(Duration::milliseconds(1500));
+        assert!(timer.tick());
+        assert_eq!(timer.remaining_seconds, 25 * 60 - 1);
+        clock.advance(Duration::milliseconds(500));
+        assert!(timer.tick());
+        assert_eq!(timer.format_remaining(), "24:58");
+
+        clock.advance(Duration::minutes(30));
+        assert!(!timer.tick());
+        assert_eq!(timer.remaining_seconds, 0);
+    }
+
+    #[test]
+    fn restore_counts_time_spent_closed() {
+        let (mut timer, clock) = timer();
+        let start = clock.now();
+        timer.start_work(Some(7));
+        let snapshot = timer.snapshot().unwrap();
+
+        // TUI 关闭 10 分钟后重新打开
+        clock.set(start + Duration::minutes(10));
+        let mut restored = PomodoroTimer::with_clock(25, 5, Arc::new(clock.clone()));
+        restored.restore(&snapshot);
+        assert_eq!(restored.state, PomodoroState::Working);
+        assert_eq!(restored.current_task_id, Some(7));
+        assert_eq!(restored.remaining_seconds, 15 * 60);
+
+        // 关闭期间已经结束的阶段剩余 0 秒，下一次 tick 时结束
+        clock.set(start + Duration::hours(1));
+        restored.restore(&snapshot);
+        assert!(!restored.tick());
+    }
+}
@@ -0,0 +1,91 @@
+// shell 提示符片段（tasks prompt-segment）
+// TUI 和守护进程把今日待办数和番茄钟状态写入数据库旁的缓存文件，
+// 提示符中只读取这个小文件，不打开数据库，保证足够快
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::models::{Task, TaskStatus};
+
+/// 缓存文件中的番茄钟状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PomodoroMark {
+    /// work / break / paused
+    pub state: String,
+    /// 计时中时的结束时间（提示符据此计算剩余分钟，无需 TUI 每秒写入）
+    pub ends_at: Option<DateTime<Utc>>,
+    /// 暂停时的剩余秒数
+    pub remaining_secs: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptSegment {
+    pub due_today: usize,
+    pub overdue: usize,
+    pub pomodoro: Option<PomodoroMark>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 缓存文件路径：与数据库同目录同名（tasks.db → tasks.prompt），各 profile 互不影响
+pub fn cache_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("prompt")
+}
+
+impl PromptSegment {
+    /// 读取缓存，文件不存在或损坏时返回 None
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// 写入缓存（先写临时文件再改名，避免提示符读到半个文件）
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let tmp = path.with_extension("prompt.tmp");
+        std::fs::write(&tmp, serde_json::to_string(self)?)?;
+        std::fs::rename(tmp, path)
+    }
+
+    /// 根据任务列表更新今日截止和逾期数
+    pub fn set_counts(&mut self, tasks: &[Task], now: DateTime<Utc>) {
+        let today = now.with_timezone(&Local).date_naive();
+        let open = tasks.iter().filter(|t| t.status != TaskStatus::Completed);
+        self.due_today = open
+            .clone()
+            .filter(|t| t.due_date.is_some_and(|d| d >= now && d.with_timezone(&Local).date_naive() == today))
+            .count();
+        self.overdue = open.filter(|t| t.due_date.is_some_and(|d| d < now)).count();
+        self.updated_at = Some(now);
+    }
+
+    /// 生成提示符文本，没有需要显示的内容时为空字符串
+    pub fn render(&self, now: DateTime<Utc>, ascii: bool) -> String {
+        let mut parts = Vec::new();
+        if self.due_today > 0 {
+            parts.push(format!("{}{}", if ascii { "D" } else { "📋" }, self.due_today));
+        }
+        if self.overdue > 0 {
+            parts.push(format!("{}{}", if ascii { "!" } else { "⚠" }, self.overdue));
+        }
+        if let Some(pomodoro) = &self.pomodoro {
+            let remaining = match pomodoro.ends_at {
+                Some(end) => (end - now).num_seconds(),
+                None => pomodoro.remaining_secs,
+            };
+            // 结束时间已过说明 TUI 已退出或未及时更新，不再显示
+            if remaining > 0 {
+                let minutes = (remaining + 59) / 60;
+                let icon = match (pomodoro.state.as_str(), ascii) {
+                    ("break", true) => "B",
+                    ("break", false) => "☕",
+                    ("paused", true) => "P",
+                    ("paused", false) => "⏸",
+                    (_, true) => "W",
+                    (_, false) => "🍅",
+                };
+                parts.push(format!("{}{}m", icon, minutes));
+            }
+        }
+        parts.join(" ")
+    }
+}
@@ -0,0 +1,39 @@
+// 任务二维码分享（tasks qr / :qr）：把任务编码为紧凑 JSON，用 Unicode 方块在终端中绘制
+
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
+
+use crate::error::{Error, Result};
+use crate::models::Task;
+
+/// 二维码中携带的任务字段（只保留手机上有用的部分，保持二维码尽量小）
+pub fn task_payload(task: &Task) -> String {
+    let mut payload = serde_json::json!({
+        "title": task.title,
+        "priority": task.priority,
+    });
+    if let Some(description) = task.description.as_deref().filter(|d| !d.is_empty()) {
+        payload["description"] = serde_json::json!(description);
+    }
+    if let Some(due) = task.due_date {
+        payload["due"] = serde_json::json!(due.to_rfc3339());
+    }
+    if !task.tags.is_empty() {
+        payload["tags"] = serde_json::json!(task.tags);
+    }
+    payload.to_string()
+}
+
+/// 渲染二维码，每个字符表示上下两个模块
+///
+/// 颜色取反（暗色模块用空白、亮色用 █），在深色背景的终端中也能被手机扫描
+pub fn render(payload: &str) -> Result<String> {
+    let code = QrCode::new(payload.as_bytes())
+        .map_err(|e| Error::Validation(format!("无法生成二维码: {}", e)))?;
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .quiet_zone(true)
+        .build())
+}
@@ -0,0 +1,204 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::models::Task;
+
+/// 远程客户端：通过守护进程的 HTTP 接口（taskd --listen）读写任务
+/// http:// 直接使用 TcpStream，https:// 通过 curl 发送（与检查更新、webhook 一致）
+pub struct RemoteClient {
+    scheme: Scheme,
+    host: String,
+    base_path: String,
+    token: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    Http,
+    Https,
+}
+
+impl RemoteClient {
+    /// 解析服务器地址，如 `http://host:7878` 或 `https://tasks.example.com`；
+    /// `token` 为服务器要求的访问令牌（config.toml 的 `[remote] token`）
+    pub fn new(url: &str, token: Option<&str>) -> Result<Self> {
+        let (scheme, rest) = if let Some(rest) = url.strip_prefix("http://") {
+            (Scheme::Http, rest)
+        } else if let Some(rest) = url.strip_prefix("https://") {
+            (Scheme::Https, rest)
+        } else {
+            (Scheme::Http, url)
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(idx) => (&rest[..idx], rest[idx..].trim_end_matches('/')),
+            None => (rest, ""),
+        };
+        if authority.is_empty() {
+            return Err(Error::Remote(format!("无效的服务器地址: {}", url)));
+        }
+
+        // https 交给 curl，不需要补默认端口
+        let host = if authority.contains(':') || scheme == Scheme::Https {
+            authority.to_string()
+        } else {
+            format!("{}:80", authority)
+        };
+
+        Ok(Self {
+            scheme,
+            host,
+            base_path: path.to_string(),
+            token: token.map(|t| t.to_string()),
+        })
+    }
+
+    /// 服务器地址（用于界面中显示）
+    pub fn url(&self) -> String {
+        let scheme = match self.scheme {
+            Scheme::Http => "http",
+            Scheme::Https => "https",
+        };
+        format!("{}://{}{}", scheme, self.host, self.base_path)
+    }
+
+    /// 获取所有任务
+    pub fn get_all_tasks(&self) -> Result<Vec<Task>> {
+        let body = self.request("GET", "/tasks", None)?;
+        parse_json(&body)
+    }
+
+    /// 创建任务，返回新任务ID
+    pub fn create_task(&self, task: &Task) -> Result<i64> {
+        let payload = serde_json::to_string(task).map_err(|e| Error::Remote(e.to_string()))?;
+        let body = self.request("POST", "/tasks", Some(&payload))?;
+        let value: serde_json::Value = parse_json(&body)?;
+        value["id"]
+            .as_i64()
+            .ok_or_else(|| Error::Remote("服务器响应缺少 id".to_string()))
+    }
+
+    /// 将任务标记为已完成
+    pub fn complete_task(&self, id: i64) -> Result<()> {
+        match self.request("POST", &format!("/tasks/{}/complete", id), None) {
+            Ok(_) => Ok(()),
+            Err(Error::Remote(msg)) if msg.starts_with("404") => {
+                Err(Error::NotFound { entity: "task", id })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 发送一次请求，返回响应体；非 2xx 状态转换为错误
+    fn request(&self, method: &str, path: &str, body: Option<&str>) -> Result<String> {
+        let body = body.unwrap_or("");
+        let (status, body) = match self.scheme {
+            Scheme::Http => self.send_http(method, path, body)?,
+            Scheme::Https => self.send_curl(method, path, body)?,
+        };
+
+        if (200..300).contains(&status) {
+            Ok(body)
+        } else if status == 401 {
+            Err(Error::Remote(
+                "401 服务器要求访问令牌，请在 config.toml 的 [remote] 中设置与服务器相同的 token".to_string(),
+            ))
+        } else {
+            // 服务器错误体格式：{"error": "..."}
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|v| v["error"].as_str().map(|s| s.to_string()))
+                .unwrap_or(body);
+            Err(Error::Remote(format!("{} {}", status, message)))
+        }
+    }
+
+    /// 通过 TcpStream 发送 HTTP/1.1 请求（Connection: close），返回状态码和响应体
+    fn send_http(&self, method: &str, path: &str, body: &str) -> Result<(u16, String)> {
+        let mut stream = TcpStream::connect(&self.host)?;
+        stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+
+        let authorization = self
+            .token
+            .as_ref()
+            .map(|token| format!("Authorization: Bearer {}\r\n", token))
+            .unwrap_or_default();
+        let request = format!(
+            "{} {}{} HTTP/1.1\r\nHost: {}\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            self.base_path,
+            path,
+            self.host,
+            authorization,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes())?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| Error::Remote("无效的 HTTP 响应".to_string()))?;
+        let status = head
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse::<u16>().ok())
+            .ok_or_else(|| Error::Remote("无效的 HTTP 状态行".to_string()))?;
+        Ok((status, body.to_string()))
+    }
+
+    /// 通过 curl 发送 HTTPS 请求，返回状态码和响应体。
+    /// 令牌和请求体经标准输入以 curl 配置文件（-K -）传入，不出现在进程参数中
+    fn send_curl(&self, method: &str, path: &str, body: &str) -> Result<(u16, String)> {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut config = format!(
+            "url = {}\nrequest = {}\nheader = \"Content-Type: application/json\"\n",
+            quote(&format!("https://{}{}{}", self.host, self.base_path, path)),
+            quote(method)
+        );
+        if let Some(token) = &self.token {
+            config.push_str(&format!("header = {}\n", quote(&format!("Authorization: Bearer {}", token))));
+        }
+        if method != "GET" {
+            config.push_str(&format!("data-raw = {}\n", quote(body)));
+        }
+
+        let mut child = Command::new("curl")
+            .args(["-sS", "-m", "10", "-w", "\n%{http_code}", "-K", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::Remote(format!("无法运行 curl: {}", e)))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(config.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(Error::Remote(format!(
+                "请求失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        // -w 在响应体之后追加一行状态码
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (body, status) = stdout
+            .rsplit_once('\n')
+            .ok_or_else(|| Error::Remote("无效的 HTTP 响应".to_string()))?;
+        let status = status
+            .trim()
+            .parse::<u16>()
+            .map_err(|_| Error::Remote("无效的 HTTP 状态行".to_string()))?;
+        Ok((status, body.to_string()))
+    }
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(body: &str) -> Result<T> {
+    serde_json::from_str(body).map_err(|e| Error::Remote(format!("响应解析失败: {}", e)))
+}
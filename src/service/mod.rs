@@ -0,0 +1,219 @@
+// 登录时自动运行 taskd：tasks daemon install 生成 systemd 用户服务（Linux）或 launchd 代理（macOS），
+// 服务以 --db-path 指向当前数据库，每个 profile 各自安装；tasks daemon uninstall 停用并删除
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use directories::BaseDirs;
+
+use crate::error::{Error, Result};
+
+/// 服务名称：默认 profile 为 taskd，其他 profile 为 taskd-<profile>
+fn service_name(profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("taskd-{}", profile),
+        None => "taskd".to_string(),
+    }
+}
+
+/// launchd 标签，与数据目录的 ProjectDirs 限定名一致
+#[cfg(target_os = "macos")]
+fn launchd_label(profile: Option<&str>) -> String {
+    format!("com.terminator-task.{}", service_name(profile))
+}
+
+fn base_dirs() -> Result<BaseDirs> {
+    BaseDirs::new().ok_or_else(|| Error::Validation("无法确定用户主目录".to_string()))
+}
+
+/// 与 tasks 一起安装的 taskd；找不到时使用 PATH 中的 taskd
+pub fn taskd_binary() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .map(|exe| exe.with_file_name(format!("taskd{}", std::env::consts::EXE_SUFFIX)))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from("taskd"))
+}
+
+/// 服务文件的位置
+pub fn service_path(profile: Option<&str>) -> Result<PathBuf> {
+    let dirs = base_dirs()?;
+    #[cfg(target_os = "macos")]
+    {
+        Ok(dirs.home_dir().join("Library/LaunchAgents").join(format!("{}.plist", launchd_label(profile))))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        Ok(dirs.config_dir().join("systemd/user").join(format!("{}.service", service_name(profile))))
+    }
+}
+
+/// 服务文件内容
+fn render(taskd: &Path, db_path: &Path, profile: Option<&str>) -> String {
+    #[cfg(target_os = "macos")]
+    {
+        render_launchd(taskd, db_path, profile)
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        render_systemd(taskd, db_path, profile)
+    }
+}
+
+/// taskd 的命令行参数
+fn taskd_args(db_path: &Path, profile: Option<&str>) -> Vec<String> {
+    let mut args = vec!["--db-path".to_string(), db_path.display().to_string()];
+    if let Some(profile) = profile {
+        args.push("--profile".to_string());
+        args.push(profile.to_string());
+    }
+    args
+}
+
+#[cfg(not(target_os = "macos"))]
+fn render_systemd(taskd: &Path, db_path: &Path, profile: Option<&str>) -> String {
+    // systemd 中 % 是格式说明符；含空格的参数加引号
+    let quote = |arg: &str| {
+        let arg = arg.replace('%', "%%");
+        if arg.contains(char::is_whitespace) {
+            format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+        } else {
+            arg
+        }
+    };
+    let command: Vec<String> = std::iter::once(taskd.display().to_string())
+        .chain(taskd_args(db_path, profile))
+        .map(|arg| quote(&arg))
+        .collect();
+    format!(
+        "[Unit]\n\
+         Description=Terminator task manager reminders ({})\n\
+         \n\
+         [Service]\n\
+         ExecStart={}\n\
+         Restart=on-failure\n\
+         RestartSec=10\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        service_name(profile),
+        command.join(" ")
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn render_launchd(taskd: &Path, db_path: &Path, profile: Option<&str>) -> String {
+    let escape = |s: &str| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let arguments: String = std::iter::once(taskd.display().to_string())
+        .chain(taskd_args(db_path, profile))
+        .map(|arg| format!("        <string>{}</string>\n", escape(&arg)))
+        .collect();
+    let log = escape(&db_path.with_extension("log").display().to_string());
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n\
+         \x20   <key>Label</key>\n\
+         \x20   <string>{}</string>\n\
+         \x20   <key>ProgramArguments</key>\n\
+         \x20   <array>\n\
+         {}\
+         \x20   </array>\n\
+         \x20   <key>RunAtLoad</key>\n\
+         \x20   <true/>\n\
+         \x20   <key>KeepAlive</key>\n\
+         \x20   <dict>\n\
+         \x20       <key>SuccessfulExit</key>\n\
+         \x20       <false/>\n\
+         \x20   </dict>\n\
+         \x20   <key>StandardOutPath</key>\n\
+         \x20   <string>{}</string>\n\
+         \x20   <key>StandardErrorPath</key>\n\
+         \x20   <string>{}</string>\n\
+         </dict>\n\
+         </plist>\n",
+        launchd_label(profile),
+        arguments,
+        log,
+        log
+    )
+}
+
+/// 运行 systemctl / launchctl，失败时返回其输出
+fn run(program: &str, args: &[&str]) -> std::result::Result<(), String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("无法运行 {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} {}: {}", program, args.join(" "), String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// 写入服务文件并启用；返回服务文件路径和启用结果（失败时文件已写入，可按提示手动启用）
+pub fn install(db_path: &Path, profile: Option<&str>) -> Result<(PathBuf, std::result::Result<(), String>)> {
+    if !cfg!(any(target_os = "linux", target_os = "macos")) {
+        return Err(Error::Validation("只支持 Linux（systemd）和 macOS（launchd）".to_string()));
+    }
+    let path = service_path(profile)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, render(&taskd_binary(), db_path, profile))?;
+    Ok((path.clone(), activate(&path, profile)))
+}
+
+#[cfg(target_os = "macos")]
+fn activate(path: &Path, _profile: Option<&str>) -> std::result::Result<(), String> {
+    let path = path.display().to_string();
+    // 重新安装时先卸载旧的定义
+    let _ = run("launchctl", &["unload", &path]);
+    run("launchctl", &["load", "-w", &path])
+}
+
+#[cfg(not(target_os = "macos"))]
+fn activate(_path: &Path, profile: Option<&str>) -> std::result::Result<(), String> {
+    let unit = format!("{}.service", service_name(profile));
+    run("systemctl", &["--user", "daemon-reload"])?;
+    run("systemctl", &["--user", "enable", &unit])?;
+    // 已在运行时重启，使新的服务文件生效
+    run("systemctl", &["--user", "restart", &unit])
+}
+
+/// 手动启用的命令（启用失败时提示）
+pub fn activate_hint(path: &Path, profile: Option<&str>) -> String {
+    if cfg!(target_os = "macos") {
+        format!("launchctl load -w {}", path.display())
+    } else {
+        format!("systemctl --user daemon-reload && systemctl --user enable --now {}.service", service_name(profile))
+    }
+}
+
+/// 停用并删除服务文件；没有安装时返回 None
+pub fn uninstall(profile: Option<&str>) -> Result<Option<PathBuf>> {
+    let path = service_path(profile)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    deactivate(&path, profile);
+    std::fs::remove_file(&path)?;
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = run("systemctl", &["--user", "daemon-reload"]);
+    }
+    Ok(Some(path))
+}
+
+/// 停止并停用服务（服务可能已被手动停用，忽略错误）
+#[cfg(target_os = "macos")]
+fn deactivate(path: &Path, _profile: Option<&str>) {
+    let _ = run("launchctl", &["unload", "-w", &path.display().to_string()]);
+}
+
+#[cfg(not(target_os = "macos"))]
+fn deactivate(_path: &Path, profile: Option<&str>) {
+    let _ = run("systemctl", &["--user", "disable", "--now", &format!("{}.service", service_name(profile))]);
+}
@@ -0,0 +1,136 @@
+// 可打印的每日清单（tasks print）：纯文本，带复选框、按小时的时间块和笔记区
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike, Utc, Weekday};
+
+use crate::models::{BusyEvent, Priority, Task, TaskStatus};
+
+/// 纸面宽度（字符数）
+const WIDTH: usize = 60;
+/// 时间块覆盖的小时（含首尾）
+const FIRST_HOUR: u32 = 8;
+const LAST_HOUR: u32 = 19;
+/// 笔记区行数
+const NOTE_LINES: usize = 6;
+
+/// 生成某天的清单
+///
+/// 列出当天截止、已逾期和进行中的未完成任务；`day_start` / `day_end` 为当天本地零点对应的 UTC 时间
+pub fn daily_sheet(
+    date: NaiveDate,
+    day_start: DateTime<Utc>,
+    day_end: DateTime<Utc>,
+    tasks: &[Task],
+    busy: &[BusyEvent],
+) -> String {
+    let mut out = Vec::new();
+    let rule = "═".repeat(WIDTH);
+    out.push(rule.clone());
+    out.push(format!("  每日清单 · {} {}", date.format("%Y-%m-%d"), weekday_name(date.weekday())));
+    out.push(rule);
+
+    let open: Vec<&Task> = tasks.iter().filter(|t| t.status != TaskStatus::Completed).collect();
+    let mut today: Vec<&Task> = open
+        .iter()
+        .copied()
+        .filter(|t| t.due_date.is_some_and(|d| d >= day_start && d < day_end))
+        .collect();
+    today.sort_by_key(|t| (t.due_date, std::cmp::Reverse(t.priority as i32)));
+    let mut overdue: Vec<&Task> = open
+        .iter()
+        .copied()
+        .filter(|t| t.due_date.is_some_and(|d| d < day_start))
+        .collect();
+    overdue.sort_by_key(|t| t.due_date);
+    let in_progress: Vec<&Task> = open
+        .iter()
+        .copied()
+        .filter(|t| t.status == TaskStatus::InProgress && t.due_date.is_none_or(|d| d >= day_end))
+        .collect();
+
+    section(&mut out, "今日任务", &today, true);
+    section(&mut out, "已逾期", &overdue, false);
+    section(&mut out, "进行中", &in_progress, false);
+
+    // 时间块：每小时一行，列出该小时内的日程和截止的任务
+    out.push(String::new());
+    out.push("时间块".to_string());
+    for hour in FIRST_HOUR..=LAST_HOUR {
+        let mut items: Vec<String> = busy
+            .iter()
+            .filter(|e| overlaps_hour(e.start, e.end, date, hour))
+            .map(|e| {
+                format!(
+                    "■ {} ({}-{})",
+                    e.summary,
+                    e.start.with_timezone(&Local).format("%H:%M"),
+                    e.end.with_timezone(&Local).format("%H:%M")
+                )
+            })
+            .collect();
+        items.extend(today.iter().filter_map(|t| {
+            let due = t.due_date?.with_timezone(&Local);
+            (due.hour() == hour).then(|| format!("⚑ {} 截止", t.title))
+        }));
+        let line = format!("  {:02}:00 │ {}", hour, items.join("  "));
+        out.push(line);
+    }
+
+    out.push(String::new());
+    out.push("笔记".to_string());
+    for _ in 0..NOTE_LINES {
+        out.push(format!("  {}", "_".repeat(WIDTH - 2)));
+    }
+    out.push(String::new());
+    out.join("\n")
+}
+
+fn section(out: &mut Vec<String>, title: &str, tasks: &[&Task], show_time: bool) {
+    if tasks.is_empty() && !show_time {
+        return;
+    }
+    out.push(String::new());
+    out.push(format!("{}（{}）", title, tasks.len()));
+    if tasks.is_empty() {
+        out.push("  （无）".to_string());
+    }
+    for task in tasks {
+        let when = match (task.due_date, show_time) {
+            (Some(due), true) => due.with_timezone(&Local).format("%H:%M").to_string(),
+            (Some(due), false) => due.with_timezone(&Local).format("%m-%d").to_string(),
+            (None, _) => "     ".to_string(),
+        };
+        out.push(format!("  [ ] {} {} {}", priority_mark(task.priority), when, task.title));
+    }
+}
+
+/// 打印时不依赖 emoji/颜色的优先级标记
+fn priority_mark(priority: Priority) -> &'static str {
+    match priority {
+        Priority::High => "!!!",
+        Priority::Medium => " !!",
+        Priority::Low => "  !",
+    }
+}
+
+fn overlaps_hour(start: DateTime<Utc>, end: DateTime<Utc>, date: NaiveDate, hour: u32) -> bool {
+    let hour_start = match date
+        .and_hms_opt(hour, 0, 0)
+        .and_then(|t| t.and_local_timezone(Local).earliest())
+    {
+        Some(t) => t.with_timezone(&Utc),
+        None => return false,
+    };
+    start < hour_start + chrono::Duration::hours(1) && hour_start < end
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "星期一",
+        Weekday::Tue => "星期二",
+        Weekday::Wed => "星期三",
+        Weekday::Thu => "星期四",
+        Weekday::Fri => "星期五",
+        Weekday::Sat => "星期六",
+        Weekday::Sun => "星期日",
+    }
+}
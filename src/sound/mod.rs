@@ -0,0 +1,134 @@
+// 提示音：番茄钟结束、提醒发送时播放。启用 sound feature 时用 rodio 播放自定义音频文件或内置提示音，
+// 未启用或没有可用的音频设备时改用终端响铃。设置保存在数据库的 config 表中，TUI、CLI 和 taskd 共用
+
+use anyhow::Result;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::db::Storage;
+
+/// 最大音量
+pub const MAX_VOLUME: u8 = 100;
+const DEFAULT_VOLUME: u8 = 70;
+
+/// 播放提示音的场合（内置提示音的音高和次数不同）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alert {
+    Pomodoro,
+    Reminder,
+}
+
+/// 提示音设置（config 表中的 sound_enabled / sound_volume / sound_file）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoundSettings {
+    pub enabled: bool,
+    /// 0-100
+    pub volume: u8,
+    /// 自定义音频文件（wav/ogg/mp3），None 时使用内置提示音
+    pub file: Option<PathBuf>,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: DEFAULT_VOLUME,
+            file: None,
+        }
+    }
+}
+
+impl SoundSettings {
+    pub fn load(db: &dyn Storage) -> Result<Self> {
+        let defaults = Self::default();
+        Ok(Self {
+            enabled: db.get_config("sound_enabled")?.map_or(defaults.enabled, |v| v == "on"),
+            volume: db
+                .get_config("sound_volume")?
+                .and_then(|v| v.parse::<u8>().ok())
+                .map_or(defaults.volume, |v| v.min(MAX_VOLUME)),
+            file: db.get_config("sound_file")?.filter(|v| !v.is_empty()).map(PathBuf::from),
+        })
+    }
+
+    pub fn save(&self, db: &dyn Storage) -> Result<()> {
+        db.set_config("sound_enabled", if self.enabled { "on" } else { "off" })?;
+        db.set_config("sound_volume", &self.volume.to_string())?;
+        match &self.file {
+            Some(file) => db.set_config("sound_file", &file.to_string_lossy())?,
+            None => db.delete_config("sound_file")?,
+        }
+        Ok(())
+    }
+
+    /// 显示给用户的当前设置
+    pub fn describe(&self) -> String {
+        format!(
+            "sound={} volume={} file={}",
+            if self.enabled { "on" } else { "off" },
+            self.volume,
+            self.file
+                .as_ref()
+                .map(|f| f.display().to_string())
+                .unwrap_or_else(|| "(built-in)".to_string())
+        )
+    }
+}
+
+/// 在后台线程中播放提示音（提示音关闭时什么也不做）
+pub fn play_in_background(settings: SoundSettings, alert: Alert) {
+    if !settings.enabled || settings.volume == 0 {
+        return;
+    }
+    std::thread::spawn(move || {
+        if let Err(e) = play(&settings, alert) {
+            tracing::warn!("Failed to play alert sound: {}", e);
+        }
+    });
+}
+
+/// 播放提示音直到结束；无法播放音频时改用终端响铃
+pub fn play(settings: &SoundSettings, alert: Alert) -> Result<()> {
+    if let Err(e) = play_audio(settings, alert) {
+        tracing::debug!("Cannot play audio, using the terminal bell: {}", e);
+        let mut stderr = std::io::stderr();
+        stderr.write_all(b"\x07")?;
+        stderr.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sound")]
+fn play_audio(settings: &SoundSettings, alert: Alert) -> Result<()> {
+    use rodio::source::{SineWave, Source, Zero};
+    use rodio::{Decoder, OutputStream, Sink};
+    use std::time::Duration;
+
+    let (_stream, handle) = OutputStream::try_default()?;
+    let sink = Sink::try_new(&handle)?;
+    sink.set_volume(f32::from(settings.volume) / f32::from(MAX_VOLUME));
+    match &settings.file {
+        Some(path) => {
+            let file = std::io::BufReader::new(std::fs::File::open(path)?);
+            sink.append(Decoder::new(file)?);
+        }
+        None => {
+            // 内置提示音：番茄钟两声低音，提醒三声高音
+            let (frequency, beeps) = match alert {
+                Alert::Pomodoro => (660.0, 2),
+                Alert::Reminder => (880.0, 3),
+            };
+            for _ in 0..beeps {
+                sink.append(SineWave::new(frequency).take_duration(Duration::from_millis(150)).amplify(0.5));
+                sink.append(Zero::<f32>::new(1, 48_000).take_duration(Duration::from_millis(100)));
+            }
+        }
+    }
+    sink.sleep_until_end();
+    Ok(())
+}
+
+#[cfg(not(feature = "sound"))]
+fn play_audio(_settings: &SoundSettings, _alert: Alert) -> Result<()> {
+    anyhow::bail!("built without the sound feature")
+}
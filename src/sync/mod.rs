@@ -0,0 +1,321 @@
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::models::Task;
+
+/// 盐文件名（所有设备共用，首次同步时生成）
+const SALT_FILE: &str = "salt";
+/// ChaCha20Poly1305 nonce 长度
+const NONCE_LEN: usize = 12;
+
+/// 同步操作：写入变更日志的一行（加密后）
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncOp {
+    /// 产生该操作的设备
+    device: String,
+    /// 操作时间，用于最后写入者胜出（LWW）合并
+    timestamp: DateTime<Utc>,
+    /// 跨设备的任务标识（任务的 UUID），本地ID在各设备上不同
+    key: String,
+    #[serde(flatten)]
+    action: SyncAction,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum SyncAction {
+    UpsertTask {
+        // Task 比其他变体大得多，装箱后 SyncAction 不必按它的大小分配
+        task: Box<Task>,
+        /// 项目按名称同步（project_id 只在本设备有效）
+        #[serde(default)]
+        project: Option<String>,
+    },
+    DeleteTask,
+}
+
+/// 一次同步的结果
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    /// 写入本设备日志的操作数
+    pub pushed: usize,
+    /// 从其他设备日志中合并的操作数
+    pub applied: usize,
+}
+
+/// 基于文件夹的端到端加密同步
+///
+/// 同步目录可以是任何会被同步的文件夹（Dropbox/Syncthing/WebDAV 挂载等），
+/// 每台设备只追加写自己的 `<device>.log`，读取其他设备的日志并按时间戳合并，
+/// 因此不需要服务器，也不会产生文件冲突。日志的每一行都用口令派生的密钥加密。
+pub struct SyncEngine<'a> {
+    db: &'a Database,
+    dir: PathBuf,
+    cipher: ChaCha20Poly1305,
+    device_id: String,
+}
+
+impl<'a> SyncEngine<'a> {
+    pub fn new(db: &'a Database, dir: &Path, passphrase: &str) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(Error::Sync("同步口令不能为空".to_string()));
+        }
+        std::fs::create_dir_all(dir)?;
+
+        let salt = load_or_create_salt(dir)?;
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| Error::Sync(format!("密钥派生失败: {}", e)))?;
+
+        let device_id = match db.get_config("sync_device_id")? {
+            Some(id) => id,
+            None => {
+                let mut bytes = [0u8; 8];
+                OsRng.fill_bytes(&mut bytes);
+                let id = hex::encode(bytes);
+                db.set_config("sync_device_id", &id)?;
+                id
+            }
+        };
+
+        Ok(Self {
+            db,
+            dir: dir.to_path_buf(),
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+            device_id,
+        })
+    }
+
+    /// 执行一次同步：先推送本地变更，再合并其他设备的变更
+    pub fn run(&self) -> Result<SyncReport> {
+        let started_at = Utc::now();
+        let known_keys = self.known_keys()?;
+
+        let pushed = self.push(&known_keys)?;
+        let applied = self.pull(&known_keys)?;
+
+        // 记录本次同步后的状态，下次只推送之后的变更
+        let current_keys: Vec<String> = self
+            .db
+            .get_all_tasks_with_archived()?
+            .iter()
+            .map(task_key)
+            .collect();
+        let known = serde_json::to_string(&current_keys).map_err(|e| Error::Sync(e.to_string()))?;
+        self.db.set_config("sync_known_tasks", &known)?;
+        self.db.set_config("sync_last_push", &started_at.to_rfc3339())?;
+
+        Ok(SyncReport { pushed, applied })
+    }
+
+    /// 将上次同步后修改过的任务和已删除的任务追加到本设备日志
+    fn push(&self, known_keys: &HashSet<String>) -> Result<usize> {
+        let last_push = self
+            .db
+            .get_config("sync_last_push")?
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+
+        let tasks = self.db.get_all_tasks_with_archived()?;
+        let projects = self.db.get_projects(true)?;
+        let mut ops = Vec::new();
+
+        for task in &tasks {
+            if last_push.is_none_or(|t| task.updated_at > t) {
+                ops.push(SyncOp {
+                    device: self.device_id.clone(),
+                    timestamp: task.updated_at,
+                    key: task_key(task),
+                    action: SyncAction::UpsertTask {
+                        task: Box::new(Task { id: None, project_id: None, ..task.clone() }),
+                        project: task
+                            .project_id
+                            .and_then(|id| projects.iter().find(|p| p.id == Some(id)))
+                            .map(|p| p.name.clone()),
+                    },
+                });
+            }
+        }
+
+        // 上次同步时存在、现在已不存在的任务视为本地删除；时间取变更日志中的删除时间，
+        // 而不是推送的时间，否则其他设备在删除之后的修改会被这次迟到的删除覆盖
+        let current: HashSet<String> = tasks.iter().map(task_key).collect();
+        for key in known_keys.difference(&current) {
+            ops.push(SyncOp {
+                device: self.device_id.clone(),
+                timestamp: self.db.task_deleted_at(key)?.unwrap_or_else(Utc::now),
+                key: key.clone(),
+                action: SyncAction::DeleteTask,
+            });
+        }
+
+        if ops.is_empty() {
+            return Ok(0);
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.log_path(&self.device_id))?;
+        for op in &ops {
+            writeln!(file, "{}", self.encrypt(op)?)?;
+        }
+        Ok(ops.len())
+    }
+
+    /// 读取其他设备日志中尚未处理的行并合并
+    fn pull(&self, known_keys: &HashSet<String>) -> Result<usize> {
+        let mut applied = 0;
+
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("log") {
+                continue;
+            }
+            let device = match path.file_stem().and_then(|s| s.to_str()) {
+                Some(device) if device != self.device_id => device.to_string(),
+                _ => continue,
+            };
+
+            let offset_key = format!("sync_offset_{}", device);
+            let offset: usize = self
+                .db
+                .get_config(&offset_key)?
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0);
+
+            let content = std::fs::read_to_string(&path)?;
+            let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+            for (index, line) in lines.iter().enumerate().skip(offset) {
+                let op = match self.decrypt(line) {
+                    Ok(op) => op,
+                    // 最后一行可能是对方还没写完或还没同步完整的，下次同步再读
+                    Err(_) if index + 1 == lines.len() => break,
+                    Err(e) => return Err(e),
+                };
+                // 每合并一行就保存进度，中途失败时下次不会重复合并已处理的行
+                let changed = self.db.in_transaction(|db| {
+                    let changed = self.apply(op, known_keys)?;
+                    db.set_config(&offset_key, &(index + 1).to_string())?;
+                    Ok(changed)
+                })?;
+                if changed {
+                    applied += 1;
+                }
+            }
+        }
+
+        Ok(applied)
+    }
+
+    /// 按最后写入者胜出合并一条操作，返回本地数据是否发生变化
+    fn apply(&self, op: SyncOp, known_keys: &HashSet<String>) -> Result<bool> {
+        let local = self.db.find_task_by_uuid(&op.key)?;
+
+        match (op.action, local) {
+            (SyncAction::UpsertTask { task, project }, Some(local)) => {
+                if op.timestamp <= local.updated_at {
+                    return Ok(false);
+                }
+                let project_id = self.project_id(project.as_deref())?;
+                self.db.update_task(&Task { id: local.id, project_id, uuid: local.uuid, ..*task })?;
+                Ok(true)
+            }
+            (SyncAction::UpsertTask { task, project }, None) => {
+                // 本地已删除（删除操作已推送给其他设备），不再重新创建
+                if known_keys.contains(&op.key) {
+                    return Ok(false);
+                }
+                let project_id = self.project_id(project.as_deref())?;
+                self.db.in_transaction(|db| {
+                    let id = db.create_task(&Task { project_id, ..*task })?;
+                    db.set_task_uuid(id, &op.key)
+                })?;
+                Ok(true)
+            }
+            (SyncAction::DeleteTask, Some(local)) => {
+                if op.timestamp < local.updated_at {
+                    return Ok(false);
+                }
+                self.db.delete_task(local.id.unwrap_or(0))?;
+                Ok(true)
+            }
+            (SyncAction::DeleteTask, None) => Ok(false),
+        }
+    }
+
+    /// 按名称找到本地项目，没有时创建
+    fn project_id(&self, name: Option<&str>) -> Result<Option<i64>> {
+        name.map(|name| self.db.ensure_project(name)).transpose()
+    }
+
+    fn known_keys(&self) -> Result<HashSet<String>> {
+        Ok(self
+            .db
+            .get_config("sync_known_tasks")?
+            .and_then(|s| serde_json::from_str::<Vec<String>>(&s).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .collect())
+    }
+
+    fn log_path(&self, device: &str) -> PathBuf {
+        self.dir.join(format!("{}.log", device))
+    }
+
+    /// 加密一条操作：hex(nonce || 密文)
+    fn encrypt(&self, op: &SyncOp) -> Result<String> {
+        let plaintext = serde_json::to_vec(op).map_err(|e| Error::Sync(e.to_string()))?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| Error::Sync("加密失败".to_string()))?;
+
+        let mut bytes = nonce.to_vec();
+        bytes.extend_from_slice(&ciphertext);
+        Ok(hex::encode(bytes))
+    }
+
+    fn decrypt(&self, line: &str) -> Result<SyncOp> {
+        let bytes = hex::decode(line.trim()).map_err(|_| Error::Sync("同步日志已损坏".to_string()))?;
+        if bytes.len() <= NONCE_LEN {
+            return Err(Error::Sync("同步日志已损坏".to_string()));
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| Error::Sync("解密失败：口令错误或同步日志已损坏".to_string()))?;
+        serde_json::from_slice(&plaintext).map_err(|e| Error::Sync(e.to_string()))
+    }
+}
+
+/// 任务的跨设备标识（迁移前创建的任务为创建时间，与旧版本的同步日志一致）
+fn task_key(task: &Task) -> String {
+    task.uuid.clone().unwrap_or_else(|| task.created_at.to_rfc3339())
+}
+
+fn load_or_create_salt(dir: &Path) -> Result<Vec<u8>> {
+    let path = dir.join(SALT_FILE);
+    if path.exists() {
+        let content = std::fs::read_to_string(&path)?;
+        return hex::decode(content.trim()).map_err(|_| Error::Sync("盐文件已损坏".to_string()));
+    }
+
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    std::fs::write(&path, hex::encode(salt))?;
+    Ok(salt.to_vec())
+}
@@ -0,0 +1,167 @@
+// 今日视图：启动时默认显示在任务标签页，按时间列出逾期任务、今天到期的任务和今天的提醒，
+// 用于规划当天；:today 在今日视图和完整任务列表之间切换（:set agenda=off 可改为默认显示任务列表）
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use super::icon_span;
+use super::theme::Theme;
+use crate::config::IconConfig;
+use crate::models::{Task, TaskStatus};
+
+/// 今日视图的分组
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Overdue,
+    DueToday,
+    Reminder,
+}
+
+impl Section {
+    pub fn title(self) -> &'static str {
+        match self {
+            Section::Overdue => "逾期",
+            Section::DueToday => "今天到期",
+            Section::Reminder => "今日提醒",
+        }
+    }
+}
+
+/// 今日视图中的一行：index 为任务在 App::tasks 中的位置，at 为排序用的时间
+#[derive(Debug, Clone, Copy)]
+pub struct Entry {
+    pub index: usize,
+    pub section: Section,
+    pub at: DateTime<Utc>,
+}
+
+/// 本地时间明天 00:00
+fn end_of_today(now: DateTime<Utc>) -> DateTime<Utc> {
+    let tomorrow = now.with_timezone(&Local).date_naive() + Duration::days(1);
+    Local
+        .from_local_datetime(&tomorrow.and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or(now + Duration::days(1))
+}
+
+/// 按 逾期 / 今天到期 / 今日提醒 分组、组内按时间排序的条目；已在前两组中的任务不再列入提醒
+pub fn entries(tasks: &[Task], now: DateTime<Utc>) -> Vec<Entry> {
+    let end = end_of_today(now);
+    let start = end - Duration::days(1);
+    let mut entries: Vec<Entry> = Vec::new();
+    for (index, task) in tasks.iter().enumerate() {
+        if task.status == TaskStatus::Completed {
+            continue;
+        }
+        match task.due_date {
+            Some(due) if due < now => entries.push(Entry { index, section: Section::Overdue, at: due }),
+            Some(due) if due < end => entries.push(Entry { index, section: Section::DueToday, at: due }),
+            _ => {
+                if let Some(at) = task.reminder_time.filter(|at| *at >= start && *at < end) {
+                    entries.push(Entry { index, section: Section::Reminder, at });
+                }
+            }
+        }
+    }
+    let rank = |s: Section| match s {
+        Section::Overdue => 0,
+        Section::DueToday => 1,
+        Section::Reminder => 2,
+    };
+    entries.sort_by_key(|e| (rank(e.section), e.at));
+    entries
+}
+
+/// 渲染今日视图，selected 为选中的条目
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    tasks: &[Task],
+    selected: Option<usize>,
+    icons: &IconConfig,
+    theme: &Theme,
+) {
+    let now = Utc::now();
+    let entries = entries(tasks, now);
+    let count = |section: Section| entries.iter().filter(|e| e.section == section).count();
+    let title = format!(
+        " 📅 今天 {}  逾期 {} · 到期 {} · 提醒 {} ",
+        now.with_timezone(&Local).format("%m-%d"),
+        count(Section::Overdue),
+        count(Section::DueToday),
+        count(Section::Reminder),
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border))
+        .title(Span::styled(title, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)));
+
+    if entries.is_empty() {
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled("🎉 今天没有待处理的任务", Style::default().fg(theme.success))),
+            Line::from(""),
+            Line::from(Span::styled(":today 查看全部任务", Style::default().fg(theme.muted))),
+        ])
+        .alignment(Alignment::Center)
+        .block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    // 分组标题也占一行，选中位置要换算成列表中的行号
+    let mut items = Vec::new();
+    let mut selected_row = None;
+    let mut section = None;
+    for (i, entry) in entries.iter().enumerate() {
+        if section != Some(entry.section) {
+            section = Some(entry.section);
+            let color = if entry.section == Section::Overdue { theme.danger } else { theme.muted };
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!("── {} ({}) ──", entry.section.title(), count(entry.section)),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ))));
+        }
+        if selected == Some(i) {
+            selected_row = Some(items.len());
+        }
+        items.push(ListItem::new(entry_line(&tasks[entry.index], entry, icons, theme)));
+    }
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(theme.selection).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default();
+    state.select(selected_row);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// 时间（逾期的显示日期）、状态和优先级图标、标题，提醒带 ⏰
+fn entry_line(task: &Task, entry: &Entry, icons: &IconConfig, theme: &Theme) -> Line<'static> {
+    let local = entry.at.with_timezone(&Local);
+    let (time, color) = match entry.section {
+        Section::Overdue => (local.format("%m-%d %H:%M").to_string(), theme.danger),
+        Section::DueToday => (local.format("%H:%M").to_string(), theme.warning),
+        Section::Reminder => (format!("⏰ {}", local.format("%H:%M")), theme.info),
+    };
+    let title_style = if task.status == TaskStatus::InProgress {
+        Style::default().fg(theme.info).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    Line::from(vec![
+        Span::styled(format!("{:>11} ", time), Style::default().fg(color)),
+        icon_span(icons.status(task.status)),
+        Span::raw(" "),
+        icon_span(icons.priority(task.priority)),
+        Span::styled(format!(" {}", task.title), title_style),
+    ])
+}
@@ -0,0 +1,133 @@
+// 看板视图（第 5 个标签页）：按状态分为 待办 / 进行中 / 已完成 三列（等待中的任务放在进行中一列），
+// h/l 在列之间移动，H/L 把选中的任务移到左/右一列（被依赖阻塞的任务不能移到已完成）
+
+use chrono::{Datelike, Local, Utc};
+use std::collections::HashSet;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+use super::icon_span;
+use super::theme::Theme;
+use crate::config::IconConfig;
+use crate::models::{Priority, Task, TaskStatus};
+
+/// 各列的标题和移入该列后的状态
+pub const COLUMNS: [(&str, TaskStatus); 3] = [
+    ("待办", TaskStatus::Todo),
+    ("进行中", TaskStatus::InProgress),
+    ("已完成", TaskStatus::Completed),
+];
+
+/// 任务所在的列
+pub fn column_of(status: TaskStatus) -> usize {
+    match status {
+        TaskStatus::Todo => 0,
+        TaskStatus::InProgress | TaskStatus::Waiting => 1,
+        TaskStatus::Completed => 2,
+    }
+}
+
+/// 某一列的任务：未完成的按优先级、截止时间排序，已完成的最近完成的在前
+pub fn column_tasks(tasks: &[Task], column: usize) -> Vec<&Task> {
+    let mut tasks: Vec<&Task> = tasks.iter().filter(|t| column_of(t.status) == column).collect();
+    if COLUMNS[column].1 == TaskStatus::Completed {
+        tasks.sort_by(|a, b| b.completed_at.cmp(&a.completed_at));
+    } else {
+        let rank = |p: Priority| match p {
+            Priority::High => 0,
+            Priority::Medium => 1,
+            Priority::Low => 2,
+        };
+        tasks.sort_by_key(|t| (rank(t.priority), t.due_date.is_none(), t.due_date));
+    }
+    tasks
+}
+
+/// 渲染看板：selection 为（当前列, 各列选中的行），blocked 为被依赖阻塞的任务
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    tasks: &[Task],
+    blocked: &HashSet<i64>,
+    (column, selected): (usize, &[usize; 3]),
+    icons: &IconConfig,
+    theme: &Theme,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 3), Constraint::Ratio(1, 3), Constraint::Ratio(1, 3)])
+        .split(area);
+
+    for (i, (title, _)) in COLUMNS.iter().enumerate() {
+        let column_tasks = column_tasks(tasks, i);
+        let active = i == column;
+        let items: Vec<ListItem> = column_tasks
+            .iter()
+            .map(|task| {
+                let is_blocked = task.id.is_some_and(|id| blocked.contains(&id));
+                ListItem::new(task_line(task, is_blocked, icons, theme))
+            })
+            .collect();
+
+        let (border, title_style) = if active {
+            (theme.accent, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+        } else {
+            (theme.border, Style::default().fg(theme.secondary))
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border))
+            .title(Span::styled(format!(" {} ({}) ", title, column_tasks.len()), title_style));
+
+        // 其他列也保留选中的行（滚动位置不变），只是不高亮
+        let mut state = ListState::default();
+        if !column_tasks.is_empty() {
+            state.select(Some(selected[i].min(column_tasks.len() - 1)));
+        }
+        let list = if active {
+            List::new(items)
+                .highlight_style(Style::default().bg(theme.selection).add_modifier(Modifier::BOLD))
+                .highlight_symbol("▶ ")
+        } else {
+            List::new(items).highlight_symbol("  ")
+        };
+        let list = list.block(block);
+        f.render_stateful_widget(list, chunks[i], &mut state);
+    }
+}
+
+/// 卡片：优先级、标题和截止日期（逾期标红），等待中的任务带状态图标，被阻塞的任务变暗并显示 🔒
+fn task_line(task: &Task, blocked: bool, icons: &IconConfig, theme: &Theme) -> Line<'static> {
+    let blocked = blocked && task.status != TaskStatus::Completed;
+    let mut spans = Vec::new();
+    if task.status == TaskStatus::Waiting {
+        spans.push(icon_span(icons.status(task.status)));
+        spans.push(Span::raw(" "));
+    }
+    spans.push(icon_span(icons.priority(task.priority)));
+    if blocked {
+        spans.push(Span::styled(" 🔒", Style::default().fg(theme.muted)));
+    }
+    let title_style = if task.status == TaskStatus::Completed {
+        Style::default().fg(theme.muted).add_modifier(Modifier::CROSSED_OUT)
+    } else if blocked {
+        Style::default().fg(theme.muted)
+    } else if task.status == TaskStatus::InProgress {
+        Style::default().fg(theme.info).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    spans.push(Span::styled(format!(" {}", task.title), title_style));
+    if let Some(due) = task.due_date.filter(|_| task.status != TaskStatus::Completed) {
+        let local = due.with_timezone(&Local);
+        let format = if local.year() == Local::now().year() { "%m-%d" } else { "%Y-%m-%d" };
+        let color = if due < Utc::now() { theme.danger } else { theme.muted };
+        spans.push(Span::styled(format!(" {}", local.format(format)), Style::default().fg(color)));
+    }
+    Line::from(spans)
+}
@@ -0,0 +1,217 @@
+// 日历视图（第 4 个标签页）：月历标出有截止任务的日期，右侧列出选中日期的任务
+
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, TimeZone, Utc};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use super::icon_span;
+use super::theme::Theme;
+use crate::config::{CapacityConfig, IconConfig};
+use crate::models::{Task, TaskStatus};
+
+const WEEKDAYS: [&str; 7] = ["一", "二", "三", "四", "五", "六", "日"];
+
+/// 本地时间某月（1 日 00:00 到下月 1 日 00:00）对应的 UTC 区间
+pub fn month_bounds(date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let first = date.with_day(1).unwrap_or(date);
+    let next = first.checked_add_months(Months::new(1)).unwrap_or(first);
+    (local_midnight(first), local_midnight(next))
+}
+
+fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+/// 前后移动若干个月，日期超出该月天数时取月末（1 月 31 日 → 2 月 28 日）
+pub fn shift_month(date: NaiveDate, months: i32) -> NaiveDate {
+    let shifted = if months >= 0 {
+        date.checked_add_months(Months::new(months as u32))
+    } else {
+        date.checked_sub_months(Months::new(months.unsigned_abs()))
+    };
+    shifted.unwrap_or(date)
+}
+
+/// 任务截止的本地日期
+fn due_day(task: &Task) -> Option<NaiveDate> {
+    task.due_date.map(|d| d.with_timezone(&Local).date_naive())
+}
+
+/// 某天截止的未完成任务超出每日容量时返回容量
+fn over_capacity(open: usize, capacity: &CapacityConfig) -> Option<usize> {
+    capacity.daily.filter(|daily| open > *daily)
+}
+
+/// 渲染日历：tasks 为当月截止的任务（get_tasks_between 的结果）
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    selected: NaiveDate,
+    tasks: &[Task],
+    icons: &IconConfig,
+    capacity: &CapacityConfig,
+    theme: &Theme,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(32), Constraint::Min(0)])
+        .split(area);
+
+    render_month(f, chunks[0], selected, tasks, capacity, theme);
+    render_day(f, chunks[1], selected, tasks, icons, capacity, theme);
+}
+
+fn render_month(f: &mut Frame, area: Rect, selected: NaiveDate, tasks: &[Task], capacity: &CapacityConfig, theme: &Theme) {
+    let today = Local::now().date_naive();
+    let now = Utc::now();
+    let first = selected.with_day(1).unwrap_or(selected);
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(
+            WEEKDAYS
+                .iter()
+                .enumerate()
+                .map(|(i, d)| {
+                    let color = if i >= 5 { theme.danger } else { theme.secondary };
+                    Span::styled(format!(" {}  ", d), Style::default().fg(color))
+                })
+                .collect::<Vec<_>>(),
+        ),
+    ];
+
+    let mut day = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+    loop {
+        let mut spans = Vec::new();
+        for _ in 0..7 {
+            if day.month() != first.month() {
+                spans.push(Span::raw("    "));
+            } else {
+                let due: Vec<&Task> = tasks.iter().filter(|t| due_day(t) == Some(day)).collect();
+                let open = due.iter().filter(|t| t.status != TaskStatus::Completed).count();
+                let overdue = due
+                    .iter()
+                    .any(|t| t.status != TaskStatus::Completed && t.due_date.is_some_and(|d| d < now));
+
+                // 有未完成任务标 •（逾期为红色），超出每日容量标 !，全部完成标 ✓
+                let (mark, color) = if over_capacity(open, capacity).is_some() {
+                    ("!", if overdue { theme.danger } else { theme.warning })
+                } else if overdue {
+                    ("•", theme.danger)
+                } else if open > 0 {
+                    ("•", theme.accent)
+                } else if !due.is_empty() {
+                    ("✓", theme.success)
+                } else {
+                    (" ", theme.text)
+                };
+                let mut style = Style::default().fg(if due.is_empty() { theme.text } else { color });
+                if day == today {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                if day == selected {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                spans.push(Span::styled(format!("{:>2}", day.day()), style));
+                spans.push(Span::styled(mark, Style::default().fg(color)));
+                spans.push(Span::raw(" "));
+            }
+            day += Duration::days(1);
+        }
+        lines.push(Line::from(spans));
+        if day.month() != first.month() || day.year() != first.year() {
+            break;
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "hjkl 选择日期 · [ ] 切换月份",
+        Style::default().fg(theme.muted),
+    )));
+    lines.push(Line::from(Span::styled(
+        "Enter 查看该日任务 · t 今天",
+        Style::default().fg(theme.muted),
+    )));
+    if let Some(daily) = capacity.daily {
+        lines.push(Line::from(Span::styled(
+            format!("! 超出每日容量（{} 个）", daily),
+            Style::default().fg(theme.warning),
+        )));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border))
+        .title(format!(" 📅 {}年{}月 ", first.year(), first.month()));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_day(
+    f: &mut Frame,
+    area: Rect,
+    selected: NaiveDate,
+    tasks: &[Task],
+    icons: &IconConfig,
+    capacity: &CapacityConfig,
+    theme: &Theme,
+) {
+    let due: Vec<&Task> = tasks.iter().filter(|t| due_day(t) == Some(selected)).collect();
+    let open = due.iter().filter(|t| t.status != TaskStatus::Completed).count();
+
+    let lines: Vec<Line> = if due.is_empty() {
+        vec![
+            Line::from(""),
+            Line::from(Span::styled("这一天没有截止的任务", Style::default().fg(theme.muted))),
+        ]
+    } else {
+        due.iter()
+            .map(|task| {
+                let time = task
+                    .due_date
+                    .map(|d| d.with_timezone(&Local).format("%H:%M").to_string())
+                    .unwrap_or_default();
+                let title_style = if task.status == TaskStatus::Completed {
+                    Style::default().fg(theme.muted).add_modifier(Modifier::CROSSED_OUT)
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    icon_span(icons.status(task.status)),
+                    Span::raw(" "),
+                    icon_span(icons.priority(task.priority)),
+                    Span::styled(format!(" {} ", time), Style::default().fg(theme.border)),
+                    Span::styled(task.title.clone(), title_style),
+                ])
+            })
+            .collect()
+    };
+
+    let mut title = vec![Span::raw(format!(
+        " {} 周{}（{} 项） ",
+        selected.format("%Y-%m-%d"),
+        WEEKDAYS[selected.weekday().num_days_from_monday() as usize],
+        due.len()
+    ))];
+    if let Some(daily) = over_capacity(open, capacity) {
+        title.push(Span::styled(
+            format!("⚠ 未完成 {} 个，超出每日容量 {} ", open, daily),
+            Style::default().fg(theme.warning),
+        ));
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border))
+        .title(Line::from(title));
+    f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: false }), area);
+}
@@ -0,0 +1,182 @@
+// 复制到剪贴板（:yank）：把便签转换成 Markdown / HTML / 纯文本后复制，
+// 方便粘贴到 wiki 或邮件中
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::models::Note;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoteFormat {
+    Markdown,
+    Html,
+    Plain,
+}
+
+impl NoteFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "md" | "markdown" => Some(Self::Markdown),
+            "html" => Some(Self::Html),
+            "plain" | "txt" | "text" => Some(Self::Plain),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Markdown => "Markdown",
+            Self::Html => "HTML",
+            Self::Plain => "纯文本",
+        }
+    }
+}
+
+/// 便签内容中的一行（只识别标题、复选框和列表，其余按普通段落处理）
+enum Block<'a> {
+    Heading(usize, &'a str),
+    Checkbox(bool, &'a str),
+    Item(&'a str),
+    Text(&'a str),
+    Blank,
+}
+
+fn parse_line(line: &str) -> Block<'_> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return Block::Blank;
+    }
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+        return Block::Heading(level, trimmed[level..].trim());
+    }
+    for (prefix, done) in [("- [ ] ", false), ("- [x] ", true), ("- [X] ", true)] {
+        if let Some(rest) = trimmed.strip_prefix(prefix) {
+            return Block::Checkbox(done, rest);
+        }
+    }
+    match trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+        Some(rest) => Block::Item(rest),
+        None => Block::Text(trimmed),
+    }
+}
+
+/// 按指定格式导出便签（标题作为一级标题）
+pub fn render_note(note: &Note, format: NoteFormat) -> String {
+    match format {
+        NoteFormat::Markdown => format!("# {}\n\n{}\n", note.title, note.content.trim_end()),
+        NoteFormat::Plain => render_plain(note),
+        NoteFormat::Html => render_html(note),
+    }
+}
+
+fn render_plain(note: &Note) -> String {
+    let mut out = vec![note.title.clone(), "=".repeat(note.title.chars().count().max(3)), String::new()];
+    for line in note.content.lines() {
+        out.push(match parse_line(line) {
+            Block::Heading(_, text) => text.to_string(),
+            Block::Checkbox(done, text) => format!("{} {}", if done { "☑" } else { "☐" }, text),
+            Block::Item(text) => format!("• {}", text),
+            Block::Text(text) => text.to_string(),
+            Block::Blank => String::new(),
+        });
+    }
+    out.join("\n").trim_end().to_string() + "\n"
+}
+
+fn render_html(note: &Note) -> String {
+    let mut out = vec![format!("<h1>{}</h1>", escape_html(&note.title))];
+    let mut in_list = false;
+    for line in note.content.lines() {
+        let block = parse_line(line);
+        let is_item = matches!(block, Block::Checkbox(..) | Block::Item(_));
+        if is_item && !in_list {
+            out.push("<ul>".to_string());
+        } else if !is_item && in_list {
+            out.push("</ul>".to_string());
+        }
+        in_list = is_item;
+        match block {
+            Block::Heading(level, text) => {
+                // 便签标题已占用 h1，内容中的标题顺延一级
+                let level = (level + 1).min(6);
+                out.push(format!("<h{0}>{1}</h{0}>", level, escape_html(text)));
+            }
+            Block::Checkbox(done, text) => out.push(format!(
+                "<li><input type=\"checkbox\" disabled{}> {}</li>",
+                if done { " checked" } else { "" },
+                escape_html(text)
+            )),
+            Block::Item(text) => out.push(format!("<li>{}</li>", escape_html(text))),
+            Block::Text(text) => out.push(format!("<p>{}</p>", escape_html(text))),
+            Block::Blank => {}
+        }
+    }
+    if in_list {
+        out.push("</ul>".to_string());
+    }
+    out.join("\n") + "\n"
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 复制文本到系统剪贴板，返回使用的方式
+///
+/// 依次尝试 wl-copy、xclip、xsel、pbcopy、clip.exe，都不可用时通过 OSC 52 转义序列交给终端处理
+/// （SSH 会话中也能用，但需要终端支持）
+pub fn copy(text: &str) -> std::io::Result<&'static str> {
+    const TOOLS: [(&str, &[&str]); 5] = [
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+        ("pbcopy", &[]),
+        ("clip.exe", &[]),
+    ];
+    for (tool, args) in TOOLS {
+        let child = Command::new(tool)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(_) => continue,
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(tool);
+        }
+    }
+
+    let encoded = base64(text.as_bytes());
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", encoded)?;
+    stdout.flush()?;
+    Ok("OSC 52")
+}
+
+/// OSC 52 需要的标准 base64 编码
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
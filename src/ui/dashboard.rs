@@ -0,0 +1,348 @@
+// 看板模式（tasks dashboard）：只读的全屏视图，轮流显示今日任务、统计和番茄钟，
+// 适合放在副屏或信息屏上。不响应编辑操作，只能按 q / Esc 退出
+
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame, Terminal,
+};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::db::Database;
+use crate::models::{self, Priority, Task, TaskStatus, WeeklyGoal};
+use crate::prompt::PromptSegment;
+
+/// 重新读取数据库的间隔
+const REFRESH_SECS: u64 = 30;
+/// 今日页最多列出的任务数
+const MAX_TASKS: usize = 12;
+
+const PAGES: [&str; 3] = ["今日", "统计", "番茄钟"];
+
+/// 大号数字字体（每个字符 5 行）
+const BIG_DIGITS: [[&str; 5]; 10] = [
+    ["███", "█ █", "█ █", "█ █", "███"],
+    ["  █", "  █", "  █", "  █", "  █"],
+    ["███", "  █", "███", "█  ", "███"],
+    ["███", "  █", "███", "  █", "███"],
+    ["█ █", "█ █", "███", "  █", "  █"],
+    ["███", "█  ", "███", "  █", "███"],
+    ["███", "█  ", "███", "█ █", "███"],
+    ["███", "  █", "  █", "  █", "  █"],
+    ["███", "█ █", "███", "█ █", "███"],
+    ["███", "█ █", "███", "  █", "███"],
+];
+const BIG_COLON: [&str; 5] = [" ", "█", " ", "█", " "];
+
+struct Snapshot {
+    tasks: Vec<Task>,
+    pomodoros_today: usize,
+    focus_minutes: usize,
+    goals: Vec<WeeklyGoal>,
+    loaded_at: DateTime<Utc>,
+}
+
+impl Snapshot {
+    fn load(db_path: &Path) -> Result<Self> {
+        let db = Database::open(db_path)?;
+        let tasks = db.get_all_tasks()?;
+        let (pomodoros_today, focus_minutes) = db.get_today_pomodoro_stats()?;
+        let (start, end) = models::local_week_bounds(Local::now().date_naive());
+        let goals = db.get_weekly_goals(start, end)?;
+        Ok(Self {
+            tasks,
+            pomodoros_today,
+            focus_minutes,
+            goals,
+            loaded_at: Utc::now(),
+        })
+    }
+}
+
+/// 启动看板，`cycle` 为每页停留的时间
+pub fn run(db_path: String, cycle: Duration) -> Result<()> {
+    let db_path = PathBuf::from(db_path);
+    // 先读一次数据，数据库打不开时直接报错，不进入全屏
+    let snapshot = Snapshot::load(&db_path)?;
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+    terminal.hide_cursor()?;
+
+    let res = event_loop(&mut terminal, &db_path, cycle, snapshot);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    res
+}
+
+fn event_loop<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    db_path: &Path,
+    cycle: Duration,
+    mut snapshot: Snapshot,
+) -> Result<()> {
+    let prompt_cache = crate::prompt::cache_path(db_path);
+    let mut page = 0;
+    let mut page_started = Instant::now();
+    let mut refreshed = Instant::now();
+    loop {
+        if refreshed.elapsed() >= Duration::from_secs(REFRESH_SECS) {
+            // 刷新失败（如数据库正被写入）时保留上次的数据
+            if let Ok(fresh) = Snapshot::load(db_path) {
+                snapshot = fresh;
+            }
+            refreshed = Instant::now();
+        }
+        if page_started.elapsed() >= cycle {
+            page = (page + 1) % PAGES.len();
+            page_started = Instant::now();
+        }
+
+        // 番茄钟状态由 TUI 写入提示符缓存文件，看板只读取
+        let pomodoro = PromptSegment::load(&prompt_cache).and_then(|s| s.pomodoro);
+        terminal.draw(|f| render(f, page, &snapshot, pomodoro.as_ref()))?;
+
+        if event::poll(Duration::from_millis(500))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn render(f: &mut Frame, page: usize, snapshot: &Snapshot, pomodoro: Option<&crate::prompt::PomodoroMark>) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)])
+        .split(f.area());
+
+    // 顶部：页名和当前时间
+    let now = Local::now();
+    let tabs: Vec<Span> = PAGES
+        .iter()
+        .enumerate()
+        .flat_map(|(i, name)| {
+            let style = if i == page {
+                Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            [Span::styled(format!(" {} ", name), style), Span::raw(" ")]
+        })
+        .collect();
+    let mut header = tabs;
+    header.push(Span::styled(
+        now.format("  %Y-%m-%d %H:%M").to_string(),
+        Style::default().fg(Color::Gray),
+    ));
+    f.render_widget(Paragraph::new(Line::from(header)), chunks[0]);
+
+    match page {
+        0 => render_today(f, snapshot, chunks[1]),
+        1 => render_stats(f, snapshot, chunks[1]),
+        _ => render_pomodoro(f, pomodoro, chunks[1]),
+    }
+
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            format!(
+                "只读看板 · 数据更新于 {} · q 退出",
+                snapshot.loaded_at.with_timezone(&Local).format("%H:%M:%S")
+            ),
+            Style::default().fg(Color::DarkGray),
+        )),
+        chunks[2],
+    );
+}
+
+fn render_today(f: &mut Frame, snapshot: &Snapshot, area: Rect) {
+    let now = Utc::now();
+    let today = now.with_timezone(&Local).date_naive();
+    let mut tasks: Vec<&Task> = snapshot
+        .tasks
+        .iter()
+        .filter(|t| t.status != TaskStatus::Completed)
+        .filter(|t| {
+            t.status == TaskStatus::InProgress
+                || t.due_date.is_some_and(|d| d.with_timezone(&Local).date_naive() <= today)
+        })
+        .collect();
+    tasks.sort_by_key(|t| (t.due_date.is_none(), t.due_date, std::cmp::Reverse(t.priority as i32)));
+
+    let mut lines = vec![Line::from("")];
+    if tasks.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "今天没有待办 🎉",
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+        )));
+    }
+    for task in tasks.iter().take(MAX_TASKS) {
+        let overdue = task.due_date.is_some_and(|d| d < now);
+        let when = task
+            .due_date
+            .map(|d| d.with_timezone(&Local).format("%H:%M").to_string())
+            .unwrap_or_else(|| "  -  ".to_string());
+        let color = match (overdue, task.priority) {
+            (true, _) => Color::Red,
+            (_, Priority::High) => Color::Yellow,
+            _ => Color::White,
+        };
+        let mark = if task.status == TaskStatus::InProgress { "▶" } else { "○" };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} {}  ", mark, when), Style::default().fg(Color::Gray)),
+            Span::styled(task.title.clone(), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::from(""));
+    }
+    if tasks.len() > MAX_TASKS {
+        lines.push(Line::from(Span::styled(
+            format!("  …… 还有 {} 项", tasks.len() - MAX_TASKS),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" 📋 今日（{}） ", tasks.len()));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_stats(f: &mut Frame, snapshot: &Snapshot, area: Rect) {
+    let now = Utc::now();
+    let today = now.with_timezone(&Local).date_naive();
+    let completed_today = snapshot
+        .tasks
+        .iter()
+        .filter(|t| t.completed_at.is_some_and(|d| d.with_timezone(&Local).date_naive() == today))
+        .count();
+    let open = snapshot.tasks.iter().filter(|t| t.status != TaskStatus::Completed).count();
+    let overdue = snapshot
+        .tasks
+        .iter()
+        .filter(|t| t.status != TaskStatus::Completed && t.due_date.is_some_and(|d| d < now))
+        .count();
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(9), Constraint::Min(0)])
+        .split(area);
+    let cells = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 4); 4])
+        .split(rows[0]);
+
+    big_number(f, cells[0], "✅ 今日完成", completed_today, Color::Green);
+    big_number(f, cells[1], "🍅 番茄钟", snapshot.pomodoros_today, Color::Red);
+    big_number(f, cells[2], "📋 未完成", open, Color::Cyan);
+    big_number(f, cells[3], "⚠ 已逾期", overdue, if overdue > 0 { Color::Red } else { Color::DarkGray });
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(format!("  专注时长: {} 分钟", snapshot.focus_minutes)),
+        Line::from(""),
+    ];
+    if !snapshot.goals.is_empty() {
+        lines.push(Line::from(Span::styled("  🎯 本周目标", Style::default().add_modifier(Modifier::BOLD))));
+        for goal in &snapshot.goals {
+            let color = if goal.hit() { Color::Green } else { Color::Yellow };
+            lines.push(Line::from(Span::styled(
+                format!("  #{:<16} {}", goal.tag, goal.bar(30)),
+                Style::default().fg(color),
+            )));
+        }
+    }
+    f.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" 📊 统计 ")),
+        rows[1],
+    );
+}
+
+fn render_pomodoro(f: &mut Frame, pomodoro: Option<&crate::prompt::PomodoroMark>, area: Rect) {
+    let now = Utc::now();
+    let running = pomodoro.and_then(|p| {
+        let remaining = match p.ends_at {
+            Some(end) => (end - now).num_seconds(),
+            None => p.remaining_secs,
+        };
+        (remaining > 0).then(|| (p.state.as_str(), remaining))
+    });
+
+    let (label, color, remaining) = match running {
+        Some(("break", remaining)) => ("☕ 休息中", Color::Green, remaining),
+        Some(("paused", remaining)) => ("⏸ 暂停", Color::Gray, remaining),
+        Some((_, remaining)) => ("🔥 工作中", Color::Red, remaining),
+        None => ("空闲", Color::DarkGray, 0),
+    };
+
+    let mut lines = vec![Line::from(""), Line::from("")];
+    lines.extend(
+        big_text(&format!("{:02}:{:02}", remaining / 60, remaining % 60))
+            .into_iter()
+            .map(|row| Line::from(Span::styled(row, Style::default().fg(color).add_modifier(Modifier::BOLD)))),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(label, Style::default().fg(color).add_modifier(Modifier::BOLD))));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta))
+        .title(" ⏱️ 番茄钟 ");
+    f.render_widget(Paragraph::new(lines).block(block).alignment(Alignment::Center), area);
+}
+
+/// 带标题的大号数字格子
+fn big_number(f: &mut Frame, area: Rect, title: &str, value: usize, color: Color) {
+    let mut lines = vec![Line::from("")];
+    lines.extend(
+        big_text(&value.to_string())
+            .into_iter()
+            .map(|row| Line::from(Span::styled(row, Style::default().fg(color).add_modifier(Modifier::BOLD)))),
+    );
+    let block = Block::default().borders(Borders::ALL).title(format!(" {} ", title));
+    f.render_widget(Paragraph::new(lines).block(block).alignment(Alignment::Center), area);
+}
+
+/// 把数字和冒号排成 5 行的大号字，其他字符忽略
+fn big_text(text: &str) -> Vec<String> {
+    let mut rows = vec![String::new(); 5];
+    for c in text.chars() {
+        let glyph = match c {
+            ':' => BIG_COLON,
+            _ => match c.to_digit(10) {
+                Some(d) => BIG_DIGITS[d as usize],
+                None => continue,
+            },
+        };
+        for (row, part) in rows.iter_mut().zip(glyph.iter()) {
+            if !row.is_empty() {
+                row.push(' ');
+            }
+            row.push_str(part);
+        }
+    }
+    rows
+}
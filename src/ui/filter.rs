@@ -0,0 +1,90 @@
+// 任务过滤引擎
+// 所有过滤条件同时生效（AND），在 reload_data 时应用到任务列表
+
+use crate::models::{Priority, Project, Task, TaskStatus};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskFilter {
+    pub status: Option<TaskStatus>,
+    pub priority: Option<Priority>,
+    pub tag: Option<String>,
+    /// 标题包含的文本（不区分大小写）
+    pub text: Option<String>,
+    pub project: Option<Project>,
+}
+
+impl TaskFilter {
+    /// 是否设置了任何过滤条件
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        if let Some(status) = self.status {
+            if task.status != status {
+                return false;
+            }
+        }
+        if let Some(priority) = self.priority {
+            if task.priority != priority {
+                return false;
+            }
+        }
+        if let Some(tag) = &self.tag {
+            if !task.tags.iter().any(|t| t == tag) {
+                return false;
+            }
+        }
+        if let Some(text) = &self.text {
+            if !task.title.to_lowercase().contains(&text.to_lowercase()) {
+                return false;
+            }
+        }
+        if let Some(project) = &self.project {
+            if task.project_id != project.id {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// 状态栏上显示的过滤描述，如 "待办 #dev"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(status) = self.status {
+            parts.push(
+                match status {
+                    TaskStatus::Todo => "待办",
+                    TaskStatus::InProgress => "进行中",
+                    TaskStatus::Completed => "已完成",
+                    TaskStatus::Waiting => "等待中",
+                }
+                .to_string(),
+            );
+        }
+        if let Some(priority) = self.priority {
+            parts.push(
+                match priority {
+                    Priority::High => "高优先级",
+                    Priority::Medium => "中优先级",
+                    Priority::Low => "低优先级",
+                }
+                .to_string(),
+            );
+        }
+        if let Some(tag) = &self.tag {
+            parts.push(format!("#{}", tag));
+        }
+        if let Some(text) = &self.text {
+            parts.push(format!("\"{}\"", text));
+        }
+        if let Some(project) = &self.project {
+            parts.push(format!("📁{}", project.name));
+        }
+        parts.join(" ")
+    }
+}
@@ -0,0 +1,54 @@
+// 任务列表按截止日期分组（已逾期/今天/明天/本周/以后/无截止日期）
+
+use chrono::{DateTime, Datelike, Duration, Local, Utc};
+
+use crate::models::Task;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DateGroup {
+    Overdue,
+    Today,
+    Tomorrow,
+    ThisWeek,
+    Later,
+    NoDate,
+}
+
+impl DateGroup {
+    /// 根据截止时间计算任务所属分组（按本地日期）
+    pub fn of(task: &Task, now: DateTime<Utc>) -> Self {
+        let due = match task.due_date {
+            Some(due) => due,
+            None => return DateGroup::NoDate,
+        };
+        if due < now {
+            return DateGroup::Overdue;
+        }
+
+        let today = now.with_timezone(&Local).date_naive();
+        let due_day = due.with_timezone(&Local).date_naive();
+        // 本周截止到周日（ISO 周以周一开始）
+        let week_end = today + Duration::days(6 - today.weekday().num_days_from_monday() as i64);
+
+        if due_day == today {
+            DateGroup::Today
+        } else if due_day == today + Duration::days(1) {
+            DateGroup::Tomorrow
+        } else if due_day <= week_end {
+            DateGroup::ThisWeek
+        } else {
+            DateGroup::Later
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateGroup::Overdue => "⚠️ 已逾期",
+            DateGroup::Today => "📅 今天",
+            DateGroup::Tomorrow => "🌅 明天",
+            DateGroup::ThisWeek => "🗓️ 本周",
+            DateGroup::Later => "🔭 以后",
+            DateGroup::NoDate => "📌 无截止日期",
+        }
+    }
+}
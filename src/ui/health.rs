@@ -0,0 +1,103 @@
+// 启动健康检查：发现问题时在界面顶部显示警告横幅，并给出建议的命令
+
+use chrono::{DateTime, Duration, Utc};
+
+use std::path::Path;
+
+use crate::db::{self, Database, Storage};
+
+/// 守护进程心跳超过该时长（且超过两个检查间隔）未更新视为未运行
+const DAEMON_STALE_MINUTES: i64 = 5;
+
+#[derive(Debug, Clone)]
+pub struct HealthWarning {
+    pub message: String,
+    pub suggestion: String,
+}
+
+impl HealthWarning {
+    fn new(message: impl Into<String>, suggestion: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            suggestion: suggestion.into(),
+        }
+    }
+}
+
+/// 运行所有检查，返回发现的问题（单项检查出错时跳过该项）
+///
+/// daemon_check 为守护进程写心跳的间隔（config.toml 的 [intervals] daemon_check_secs）
+pub fn run_checks(db: &Database, db_path: &Path, daemon_check: std::time::Duration, now: DateTime<Utc>) -> Vec<HealthWarning> {
+    let mut warnings = Vec::new();
+
+    // 数据库是否可写
+    if db.set_config("health_checked_at", &now.to_rfc3339()).is_err() {
+        warnings.push(HealthWarning::new(
+            "数据库不可写，修改将无法保存",
+            "检查数据库文件权限，或用 --db-path 指定其他位置",
+        ));
+    }
+
+    // 休假模式：提醒已暂停
+    if let Ok(Some(until)) = db.get_pause_until() {
+        if until > now {
+            warnings.push(HealthWarning::new(
+                format!(
+                    "🏖 休假模式中，直到 {}，提醒已暂停",
+                    until.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                ),
+                "tasks resume 提前结束",
+            ));
+        }
+    }
+
+    // 守护进程是否在运行（通过心跳文件判断）；检查间隔调大后心跳也相应变慢
+    let stale_after = Duration::minutes(DAEMON_STALE_MINUTES)
+        .max(Duration::from_std(daemon_check * 2).unwrap_or(Duration::MAX));
+    match crate::ipc::read_heartbeat(db_path) {
+        Some(t) if now - t <= stale_after => {}
+        Some(_) => warnings.push(HealthWarning::new(
+            "守护进程似乎已停止，截止提醒不会发送",
+            "重新运行 taskd",
+        )),
+        None => warnings.push(HealthWarning::new(
+            "守护进程未运行，截止提醒不会发送",
+            "在后台运行 taskd",
+        )),
+    }
+
+    // 系统时钟是否正常：不应早于数据库中最近的修改时间
+    if let Ok(Some(latest)) = db.latest_updated_at() {
+        if latest - now > Duration::minutes(5) {
+            warnings.push(HealthWarning::new(
+                format!(
+                    "系统时间早于最近的修改时间（{}），截止提醒和排序可能出错",
+                    latest.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                ),
+                "校准系统时钟",
+            ));
+        }
+    }
+
+    // 数据库是否过大
+    if let Ok(size) = db.size_bytes() {
+        if size > db::COMPACT_SUGGEST_BYTES {
+            warnings.push(HealthWarning::new(
+                format!("数据库已有 {}", db::format_size(size)),
+                ":compact 压缩数据库",
+            ));
+        }
+    }
+
+    // 关联到已删除任务的便签
+    if let Ok(count) = db.count_orphaned_notes() {
+        if count > 0 {
+            warnings.push(HealthWarning::new(
+                format!("{} 个便签关联的任务已不存在", count),
+                ":health fix 解除这些便签的任务关联",
+            ));
+        }
+    }
+
+    warnings
+}
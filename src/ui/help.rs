@@ -0,0 +1,303 @@
+// 帮助系统：按主题分页的快捷键/命令注册表
+// 帮助对话框的内容全部由 ENTRIES 生成，新增快捷键或命令时只需在这里登记一行
+
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+use super::keymap;
+use super::theme::Theme;
+use crate::config::KeymapPreset;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpTopic {
+    Tasks,
+    Notes,
+    Pomodoro,
+    Filters,
+    Commands,
+}
+
+impl HelpTopic {
+    pub const ALL: [HelpTopic; 5] = [
+        HelpTopic::Tasks,
+        HelpTopic::Notes,
+        HelpTopic::Pomodoro,
+        HelpTopic::Filters,
+        HelpTopic::Commands,
+    ];
+
+    /// 解析 `:help <topic>` 的主题名（支持前缀，如 `:help fil`）
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.to_lowercase();
+        if s.is_empty() {
+            return None;
+        }
+        Self::ALL.into_iter().find(|t| t.name().starts_with(&s))
+    }
+
+    /// 标签页默认打开的主题
+    pub fn for_tab(tab: usize) -> Self {
+        match tab {
+            1 => HelpTopic::Notes,
+            2 => HelpTopic::Pomodoro,
+            _ => HelpTopic::Tasks,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HelpTopic::Tasks => "tasks",
+            HelpTopic::Notes => "notes",
+            HelpTopic::Pomodoro => "pomodoro",
+            HelpTopic::Filters => "filters",
+            HelpTopic::Commands => "commands",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            HelpTopic::Tasks => "任务管理",
+            HelpTopic::Notes => "便签墙",
+            HelpTopic::Pomodoro => "番茄钟",
+            HelpTopic::Filters => "过滤与分组",
+            HelpTopic::Commands => "命令模式",
+        }
+    }
+
+    /// 帮助对话框标题
+    pub fn dialog_title(&self) -> &'static str {
+        match self {
+            HelpTopic::Tasks => "任务管理 - 快捷键帮助",
+            HelpTopic::Notes => "便签墙 - 快捷键帮助",
+            HelpTopic::Pomodoro => "番茄钟 - 快捷键帮助",
+            HelpTopic::Filters => "过滤与分组 - 快捷键帮助",
+            HelpTopic::Commands => "命令模式 - 帮助",
+        }
+    }
+
+    /// 下一个/上一个主题（帮助中用 Tab / Shift+Tab 切换）
+    pub fn cycle(&self, forward: bool) -> Self {
+        let len = Self::ALL.len();
+        let idx = Self::ALL.iter().position(|t| t == self).unwrap_or(0);
+        let next = if forward { (idx + 1) % len } else { (idx + len - 1) % len };
+        Self::ALL[next]
+    }
+}
+
+/// 注册表中的一条快捷键或命令
+pub struct HelpEntry {
+    pub topic: HelpTopic,
+    pub section: &'static str,
+    pub keys: &'static str,
+    pub desc: &'static str,
+}
+
+const fn entry(topic: HelpTopic, section: &'static str, keys: &'static str, desc: &'static str) -> HelpEntry {
+    HelpEntry { topic, section, keys, desc }
+}
+
+use HelpTopic::{Commands, Filters, Notes, Pomodoro, Tasks};
+
+pub const ENTRIES: &[HelpEntry] = &[
+    // 任务
+    entry(Tasks, "导航", "j/k / ↓/↑", "上下移动"),
+    entry(Tasks, "导航", "h/l / Shift+Tab", "切换标签"),
+    entry(Tasks, "导航", "Tab / :split", "打开/关闭右侧详情窗格"),
+    entry(Tasks, "导航", "gg / G", "首行/末行"),
+    entry(Tasks, "导航", "5j / 10G", "数字前缀跳转"),
+    entry(Tasks, "任务操作", "n / a / o", "新建任务"),
+    entry(Tasks, "任务操作", "e", "编辑任务"),
+    entry(Tasks, "任务操作", "Enter", "查看详情(完整标题)"),
+    entry(Tasks, "任务操作", "dd", "归档任务(双击d，可撤销)"),
+    entry(Tasks, "任务操作", "Space / x", "切换状态：待办→进行中→已完成"),
+    entry(Tasks, "任务操作", "p", "切换优先级"),
+    entry(Tasks, "任务操作", "u / Ctrl-r", "撤销/重做(删除、归档、完成、优先级、编辑、DDL)"),
+    entry(Tasks, "任务操作", "t", "设置DDL时间（对话框中 r 切换重复，x 不设DDL）"),
+    entry(Tasks, "任务操作", "> / <", "DDL推迟/提前一天(7> 推迟 7 天，提醒一起移动)"),
+    entry(Tasks, "任务操作", "r", "设置提醒时间"),
+    entry(Tasks, "任务操作", "T", "开始/停止计时（:track start/stop）"),
+    entry(Tasks, "任务操作", "za", "展开/折叠已完成任务"),
+    entry(Tasks, "任务操作", "s / gs", "为当前任务开始番茄钟"),
+    entry(Tasks, "批量操作", "V", "可视模式：j/k/G 选择一段任务"),
+    entry(Tasks, "批量操作", "x / d", "完成/归档选中的任务"),
+    entry(Tasks, "批量操作", "H / M / L", "设置选中任务的优先级"),
+    entry(Tasks, "批量操作", "# / :tag", "修改选中任务的标签（:p 3 设置优先级）"),
+    entry(Tasks, "任务详情", "c", "添加评论"),
+    entry(Tasks, "任务详情", "e", "编辑任务"),
+    entry(Tasks, "日历(4)", "h/l / j/k", "前后一天/一周"),
+    entry(Tasks, "日历(4)", "[ / ]", "上个月/下个月"),
+    entry(Tasks, "日历(4)", "gg / t", "回到今天"),
+    entry(Tasks, "日历(4)", "Enter", "在任务列表中查看该日任务"),
+    entry(Tasks, "看板(5)", "h/l / j/k", "切换列/选择任务"),
+    entry(Tasks, "看板(5)", "H / L", "把任务移到左/右一列（改变状态，u 撤销）"),
+    // 便签
+    entry(Notes, "导航", "j/k / ↓/↑", "上下移动"),
+    entry(Notes, "导航", "h/l / Tab", "切换标签"),
+    entry(Notes, "导航", "gg / G", "首行/末行"),
+    entry(Notes, "便签操作", "n / a / o", "新建便签"),
+    entry(Notes, "便签操作", "e", "编辑便签"),
+    entry(Notes, "便签操作", "dd", "删除便签到回收站(双击d，u 撤销)"),
+    entry(Notes, "便签操作", "< / >", "前移/后移便签(也可鼠标拖拽)"),
+    entry(Notes, "便签操作", "A", "归档/取消归档便签"),
+    entry(Notes, "便签操作", "P", "置顶/取消置顶便签"),
+    entry(Notes, "便签操作", "Enter", "查看便签"),
+    entry(Notes, "查看便签", "z", "放大/还原查看窗口"),
+    entry(Notes, "查看便签", "w", "切换自动换行"),
+    entry(Notes, "查看便签", "h/l", "不换行时横向滚动"),
+    entry(Notes, "编辑便签", "↑/↓ 或 k/j", "选择编辑字段(标题/内容)"),
+    entry(Notes, "编辑便签", "i", "进入编辑模式"),
+    entry(Notes, "编辑便签", "Enter", "保存当前字段"),
+    entry(Notes, "编辑便签", "Esc", "取消编辑"),
+    // 番茄钟
+    entry(Pomodoro, "导航", "h/l / Tab", "切换标签"),
+    entry(Pomodoro, "导航", "1-5", "快速跳转"),
+    entry(Pomodoro, "番茄钟控制", "s", "开始（先选择关联的任务）/暂停"),
+    entry(Pomodoro, "番茄钟控制", "S / c", "停止/取消"),
+    entry(Pomodoro, "时长调整（仅空闲时）", "+ / -", "调整工作时长 (±5分钟)"),
+    entry(Pomodoro, "时长调整（仅空闲时）", "[ / ]", "调整休息时长 (±1分钟)"),
+    entry(Pomodoro, "提醒", ":set notify=off", "时段结束时不发送桌面通知"),
+    entry(Pomodoro, "提醒", ":set bell=on", "时段结束时终端响铃"),
+    entry(Pomodoro, "提醒", ":set sound=on volume=40", "播放提示音（tasks sound file 自定义音频）"),
+    entry(Pomodoro, "今日日程", "tasks calendar import", "导入 .ics 忙碌时段，开始计时时提示冲突"),
+    // 过滤与分组
+    entry(Filters, "快速过滤", "zt", "只看待办"),
+    entry(Filters, "快速过滤", "zi", "只看进行中"),
+    entry(Filters, "快速过滤", "zw", "只看等待中"),
+    entry(Filters, "快速过滤", "zc", "只看已完成"),
+    entry(Filters, "快速过滤", "zz", "清除过滤"),
+    entry(Filters, "快速过滤", ":filter #标签", "只看带该标签的任务(:filter 清除)"),
+    entry(Filters, "快速过滤", "zp", "在项目之间依次切换过滤"),
+    entry(Filters, "快速过滤", ":project 名称", "只看该项目的任务(:project 清除并列出项目)"),
+    entry(Filters, "分组", "za", "展开/折叠已完成任务"),
+    entry(Filters, "分组", ":set group=on/off", "按截止日期分组"),
+    entry(Filters, "分组", ":goto 日期", "在日历中选中该日"),
+    // 命令
+    entry(Commands, "通用", ":q / :wq", "退出"),
+    entry(Commands, "通用", ":5", "跳转第5行"),
+    entry(Commands, "通用", ":new 内容", "直接创建任务/便签"),
+    entry(Commands, "通用", ":new 买菜 !h #家务 @明天", "快速设置优先级、标签和DDL(有 @ 时直接创建)"),
+    entry(Commands, "通用", ":d / :delete", "删除当前项(任务为归档)"),
+    entry(Commands, "通用", ":delete!", "删除当前任务(移到回收站)"),
+    entry(Commands, "通用", ":trash", "回收站：删除的任务和便签保留 30 天，Enter / u 恢复"),
+    entry(Commands, "通用", ":restore [ID]", "恢复归档的任务或回收站中的项目(不带参数时恢复最近删除的)"),
+    entry(Commands, "通用", ":help 主题", "打开帮助(tasks/notes/pomodoro/filters/commands)"),
+    entry(Commands, "通用", ":set", "查看/修改界面设置(:set 名称=值 或 :set 名称 值)"),
+    entry(Commands, "通用", ":set?", "列出全部设置及说明"),
+    entry(Commands, "通用", ":messages", "查看最近的错误(:messages clear 清空)"),
+    entry(Commands, "通用", ":set auto_sort=off", "修改任务后保持当前顺序(:sort 手动排序)"),
+    entry(Commands, "通用", ":set confirm_delete=off", "删除到回收站时不再确认"),
+    entry(Commands, "通用", ":set tick_rate=50", "界面刷新间隔(毫秒，default 使用配置文件)"),
+    entry(Commands, "通用", ":set keymap=emacs", "切换键位方案(vim/emacs/arrows)"),
+    entry(Commands, "通用", ":set require_ddl=off", "新建任务时不强制设置DDL"),
+    entry(Commands, "通用", ":set agenda=off", "启动时显示完整任务列表而非今日视图"),
+    entry(Commands, "通用", ":theme solarized", "切换配色主题(dark/light/solarized)"),
+    entry(Commands, "通用", ":profile 名称", "切换 profile"),
+    entry(Commands, "通用", ":workspace", "从列表中选择并切换 profile"),
+    entry(Commands, "通用", ":health", "重新运行启动检查(:health fix 修复孤立便签)"),
+    entry(Commands, "通用", ":compact", "压缩数据库(VACUUM + ANALYZE)"),
+    entry(Commands, "通用", ":reindex", "后台重建索引和番茄钟计数(大量导入后使用)"),
+    entry(Commands, "通用", ":timing on", "记录慢查询，超过阈值的操作在状态栏显示(:timing 200 设阈值，:timing log 查看)"),
+    entry(Commands, "通用", ":usage", "查看本地使用统计(:usage reset 清空)"),
+    entry(Commands, "通用", ":changelog", "查看更新日志"),
+    entry(Commands, "任务", ":p [1/2/3]", "设置优先级 (1=Low, 2=Med, 3=High)"),
+    entry(Commands, "任务", ":t / :ddl", "设置DDL"),
+    entry(Commands, "任务", ":ddl tomorrow 5pm", "直接设置DDL(next friday 18:00 / in 3 days / 明天 18:00)"),
+    entry(Commands, "任务", ":remind 30m", "截止前 30 分钟提醒，也可 1h/1d、日期或 off；不带参数打开选择器"),
+    entry(Commands, "任务", ":defer 1d", "推迟DDL(也可 1h/1w/mon 下周一，-1d 提前)"),
+    entry(Commands, "任务", ":snooze 10m", "把提醒推迟到 10 分钟后(也可 1h/1d)"),
+    entry(Commands, "任务", ":repeat weekly", "设置重复，完成后自动创建下一次(:repeat off 取消)"),
+    entry(Commands, "任务", ":sort by priority", "按 due/priority/created/title [asc|desc] 排序(:sort default 恢复默认)"),
+    entry(Commands, "任务", ":tpl 名称", "从模板创建任务"),
+    entry(Commands, "任务", ":goto 日期", "在日历中选中该日"),
+    entry(Commands, "任务", ":triage", "逐个确认未安排任务的优先级和DDL建议"),
+    entry(Commands, "任务", ":done [2d 标题]", "完成任务并安排跟进任务"),
+    entry(Commands, "任务", ":split", "打开/关闭任务详情窗格(描述、便签、番茄钟记录)"),
+    entry(Commands, "任务", ":today", "今日视图(逾期/今天到期/今日提醒)与完整列表之间切换"),
+    entry(Commands, "任务", ":archive", "查看归档任务/返回任务列表"),
+    entry(Commands, "任务", ":restore [ID]", "恢复归档的任务"),
+    entry(Commands, "任务", ":purge [天数]", "彻底删除归档任务(归档视图中)"),
+    entry(Commands, "任务", ":status doing", "设置状态(todo/doing/waiting/done)"),
+    entry(Commands, "任务", ":blocked-by 3", "依赖 #3(其完成前不能完成)，-3 移除，:blocks 反向"),
+    entry(Commands, "任务", ":wait [3d]", "标记为等待中，可设跟进时间(:wait off 结束)"),
+    entry(Commands, "任务", ":comment 内容", "给任务添加评论(:cm)"),
+    entry(Commands, "任务", ":tag work -home", "添加/移除标签，不带参数时显示当前标签"),
+    entry(Commands, "任务", ":assign 名称", "归入项目(不存在时创建)，:assign - 移出项目"),
+    entry(Commands, "任务", ":track start/stop", "给选中的任务计时，tasks report time 查看汇总"),
+    entry(Commands, "任务", ":est 1h30m", "设置预计耗时，临近最晚开始时间时提醒(:est off 清除)"),
+    entry(Commands, "任务", ":qr", "以二维码显示当前任务，便于发送到手机"),
+    entry(Commands, "便签", ":move 位置", "移动便签(up/down/first/last/序号)"),
+    entry(Commands, "便签", ":archive", "归档/取消归档便签"),
+    entry(Commands, "便签", ":archived", "查看归档便签"),
+    entry(Commands, "便签", ":tag idea -old", "添加/移除便签标签(与任务共用标签)"),
+    entry(Commands, "便签", ":filter #标签", "便签墙只显示带该标签的便签(:filter 清除)"),
+    entry(Commands, "便签", ":color 颜色", "设置便签颜色(red/green/yellow/blue/magenta/cyan/gray, off 恢复)"),
+    entry(Commands, "便签", ":expire 3d", "到期自动归档(日期/3d/12h/off)"),
+    entry(Commands, "便签", ":set wrap=off", "查看便签时不换行"),
+    entry(Commands, "便签", ":yank md", "复制当前便签到剪贴板(md/html/plain，查看便签时也可用)"),
+    entry(Commands, "番茄钟", ":s / :start", "开始/暂停"),
+    entry(Commands, "番茄钟", ":c / :cancel", "停止/取消"),
+    entry(Commands, "番茄钟", ":pomo w=25 b=5", "设置时长并保存"),
+    entry(Commands, "番茄钟", ":pomo long=15 every=4", "每 4 个番茄钟一次长休息(every=0 关闭)"),
+];
+
+/// 注册表中的所有命令名（含别名，如 ":q"、":wq"），用于统计从未用过的命令
+pub fn command_names() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = ENTRIES
+        .iter()
+        .filter(|e| e.topic == HelpTopic::Commands)
+        .flat_map(|e| e.keys.split(" / "))
+        .filter_map(|alt| alt.split_whitespace().next())
+        .collect();
+    names.dedup();
+    names
+}
+
+/// 生成帮助页面内容
+///
+/// `query` 非空时在所有主题中搜索（不区分大小写），只列出匹配的条目；
+/// 非 vim 键位方案的按键别名附加在任务主题末尾
+pub fn page_lines(topic: HelpTopic, query: &str, preset: KeymapPreset, theme: &Theme) -> Vec<Line<'static>> {
+    let query = query.to_lowercase();
+    let preset_entries = keymap::help_entries(preset);
+    let registry = ENTRIES.iter().chain(preset_entries.iter());
+    let entries: Vec<&HelpEntry> = if query.is_empty() {
+        registry.filter(|e| e.topic == topic).collect()
+    } else {
+        registry
+            .filter(|e| e.keys.to_lowercase().contains(&query) || e.desc.to_lowercase().contains(&query))
+            .collect()
+    };
+
+    let mut lines = vec![Line::from("")];
+    if entries.is_empty() {
+        lines.push(Line::from(Span::styled("  没有匹配的条目", Style::default().fg(theme.muted))));
+        return lines;
+    }
+
+    let mut current: Option<(HelpTopic, &str)> = None;
+    for e in entries {
+        if current != Some((e.topic, e.section)) {
+            if current.is_some() {
+                lines.push(Line::from(""));
+            }
+            // 搜索结果跨主题，标题中带上主题名
+            let header = if query.is_empty() {
+                format!("━━━ {} ━━━", e.section)
+            } else {
+                format!("━━━ {} · {} ━━━", e.topic.title(), e.section)
+            };
+            lines.push(Line::from(Span::styled(
+                header,
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+            )));
+            current = Some((e.topic, e.section));
+        }
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<14}", e.keys), Style::default().fg(theme.border)),
+            Span::raw(e.desc),
+        ]));
+    }
+    lines
+}
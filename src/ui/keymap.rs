@@ -0,0 +1,139 @@
+// 键位方案：把 emacs / 方向键方案的按键翻译成等价的 vim 按键序列
+// vim 键位始终可用，方案只是额外的别名；新增别名时在 BINDINGS 中登记一行，帮助页面会自动列出
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::help::{HelpEntry, HelpTopic};
+use crate::config::KeymapPreset;
+
+/// 一条按键别名：按下 (modifiers, code) 等同于依次按下 action 中的按键
+pub struct Binding {
+    pub preset: KeymapPreset,
+    pub modifiers: KeyModifiers,
+    pub code: KeyCode,
+    pub keys: &'static str,
+    pub action: &'static [KeyCode],
+    pub desc: &'static str,
+}
+
+const fn bind(
+    preset: KeymapPreset,
+    modifiers: KeyModifiers,
+    code: KeyCode,
+    keys: &'static str,
+    action: &'static [KeyCode],
+    desc: &'static str,
+) -> Binding {
+    Binding { preset, modifiers, code, keys, action, desc }
+}
+
+use KeymapPreset::{Arrows, Emacs, Vim};
+
+const CTRL: KeyModifiers = KeyModifiers::CONTROL;
+const ALT: KeyModifiers = KeyModifiers::ALT;
+const NONE: KeyModifiers = KeyModifiers::NONE;
+
+pub const BINDINGS: &[Binding] = &[
+    // emacs
+    bind(Emacs, CTRL, KeyCode::Char('n'), "C-n", &[KeyCode::Down], "下移"),
+    bind(Emacs, CTRL, KeyCode::Char('p'), "C-p", &[KeyCode::Up], "上移"),
+    bind(Emacs, CTRL, KeyCode::Char('f'), "C-f", &[KeyCode::Tab], "下一个标签"),
+    bind(Emacs, CTRL, KeyCode::Char('b'), "C-b", &[KeyCode::BackTab], "上一个标签"),
+    bind(Emacs, ALT, KeyCode::Char('<'), "M-<", &[KeyCode::Char('g'), KeyCode::Char('g')], "首行"),
+    bind(Emacs, ALT, KeyCode::Char('>'), "M->", &[KeyCode::Char('G')], "末行"),
+    bind(Emacs, CTRL, KeyCode::Char('o'), "C-o", &[KeyCode::Char('n')], "新建"),
+    bind(Emacs, CTRL, KeyCode::Char('e'), "C-e", &[KeyCode::Char('e')], "编辑"),
+    bind(Emacs, CTRL, KeyCode::Char('k'), "C-k", &[KeyCode::Char('d'), KeyCode::Char('d')], "删除/归档"),
+    bind(Emacs, CTRL, KeyCode::Char('t'), "C-t", &[KeyCode::Char(' ')], "切换完成状态"),
+    bind(Emacs, CTRL, KeyCode::Char('g'), "C-g", &[KeyCode::Esc], "取消/关闭"),
+    bind(Emacs, ALT, KeyCode::Char('x'), "M-x", &[KeyCode::Char(':')], "命令模式"),
+    // 方向键与功能键
+    bind(Arrows, NONE, KeyCode::Home, "Home", &[KeyCode::Char('g'), KeyCode::Char('g')], "首行"),
+    bind(Arrows, NONE, KeyCode::End, "End", &[KeyCode::Char('G')], "末行"),
+    bind(Arrows, NONE, KeyCode::Insert, "Insert", &[KeyCode::Char('n')], "新建"),
+    bind(Arrows, NONE, KeyCode::F(2), "F2", &[KeyCode::Char('e')], "编辑"),
+    bind(Arrows, NONE, KeyCode::Delete, "Delete", &[KeyCode::Char('d'), KeyCode::Char('d')], "删除/归档"),
+    bind(Arrows, NONE, KeyCode::F(5), "F5", &[KeyCode::Char(' ')], "切换完成状态"),
+    bind(Arrows, NONE, KeyCode::F(1), "F1", &[KeyCode::Char('?')], "帮助"),
+    bind(Arrows, NONE, KeyCode::F(10), "F10", &[KeyCode::Char(':')], "命令模式"),
+];
+
+/// 简单模式的按键：不区分模式，Enter 编辑、Delete 删除，没有 `:` 命令行
+pub const SIMPLE_BINDINGS: &[Binding] = &[
+    bind(Vim, NONE, KeyCode::Enter, "Enter", &[KeyCode::Char('e')], "编辑"),
+    bind(Vim, NONE, KeyCode::Delete, "Delete", &[KeyCode::Char('d'), KeyCode::Char('d')], "删除/归档"),
+    bind(Vim, NONE, KeyCode::Insert, "Insert", &[KeyCode::Char('n')], "新建"),
+    bind(Vim, CTRL, KeyCode::Char('n'), "Ctrl+N", &[KeyCode::Char('n')], "新建"),
+    bind(Vim, NONE, KeyCode::F(3), "F3", &[KeyCode::Enter], "查看详情"),
+    bind(Vim, NONE, KeyCode::F(4), "F4", &[KeyCode::Char('t')], "设置DDL"),
+    bind(Vim, NONE, KeyCode::F(1), "F1", &[KeyCode::Char('?')], "帮助"),
+    bind(Vim, NONE, KeyCode::Home, "Home", &[KeyCode::Char('g'), KeyCode::Char('g')], "首行"),
+    bind(Vim, NONE, KeyCode::End, "End", &[KeyCode::Char('G')], "末行"),
+];
+
+/// 简单模式下按原样处理的按键（方向键、Tab、空格和番茄钟按键），其余按键忽略
+fn simple_passthrough(code: KeyCode) -> bool {
+    matches!(
+        code,
+        KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::Left
+            | KeyCode::Right
+            | KeyCode::Tab
+            | KeyCode::BackTab
+            | KeyCode::Esc
+            | KeyCode::Char(' ')
+            | KeyCode::Char('?')
+            | KeyCode::Char('s')
+            | KeyCode::Char('S')
+            | KeyCode::Char('+')
+            | KeyCode::Char('-')
+            | KeyCode::Char('[')
+            | KeyCode::Char(']')
+    )
+}
+
+/// 简单模式下主界面的按键处理：返回要执行的按键序列（可能为空，表示忽略该按键）
+pub fn translate_simple(key: KeyEvent) -> Vec<KeyCode> {
+    let modifiers = key.modifiers - KeyModifiers::SHIFT;
+    match SIMPLE_BINDINGS.iter().find(|b| b.modifiers == modifiers && b.code == key.code) {
+        Some(b) => b.action.to_vec(),
+        None if simple_passthrough(key.code) => vec![key.code],
+        None => Vec::new(),
+    }
+}
+
+/// 简单模式底部常驻的操作提示
+pub fn simple_hints(tab: usize) -> &'static str {
+    match tab {
+        0 => "↑↓ 选择 · Enter 编辑 · F3 详情 · Insert 新建 · Delete 归档 · 空格 完成 · F4 DDL · Tab 切换 · F1 帮助 · Ctrl+Q 退出",
+        1 => "↑↓←→ 选择 · Enter 编辑 · Insert 新建 · Delete 删除 · Tab 切换 · F1 帮助 · Ctrl+Q 退出",
+        3 => "↑↓←→ 选择日期 · [/] 切换月份 · F3 在任务列表中查看 · Tab 切换 · F1 帮助 · Ctrl+Q 退出",
+        4 => "↑↓ 选择 · ←→ 切换列 · [/] 把任务移到左/右一列 · Tab 切换 · F1 帮助 · Ctrl+Q 退出",
+        _ => "s 开始/暂停 · S 取消 · +/- 工作时长 · [/] 休息时长 · Tab 切换 · F1 帮助 · Ctrl+Q 退出",
+    }
+}
+
+/// 查找按键在当前方案下对应的 vim 按键序列，未登记的按键返回 None（按原样处理）
+pub fn translate(preset: KeymapPreset, key: KeyEvent) -> Option<&'static [KeyCode]> {
+    // Alt 组合键在部分终端中会带上 Shift（如 M-<），比较时忽略 Shift
+    let modifiers = key.modifiers - KeyModifiers::SHIFT;
+    BINDINGS
+        .iter()
+        .find(|b| b.preset == preset && b.modifiers == modifiers && b.code == key.code)
+        .map(|b| b.action)
+}
+
+/// 当前方案的按键别名，作为帮助注册表的补充条目显示在任务主题中
+pub fn help_entries(preset: KeymapPreset) -> Vec<HelpEntry> {
+    BINDINGS
+        .iter()
+        .filter(|b| b.preset == preset)
+        .map(|b| HelpEntry {
+            topic: HelpTopic::Tasks,
+            section: "当前键位方案",
+            keys: b.keys,
+            desc: b.desc,
+        })
+        .collect()
+}
@@ -0,0 +1,230 @@
+// 便签的 Markdown 显示（查看便签对话框）：识别标题、粗体/斜体/行内代码、列表、复选框、引用和代码块，
+// 开启换行时按内容换行：列表和引用的续行与正文对齐，代码块只按字符切分、不解析行内格式
+
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+use super::theme::Theme;
+
+/// 正文左侧的缩进
+const INDENT: &str = "  ";
+
+/// 换行时每行至少保留的字符数（嵌套很深的列表也不至于每行只有几个字）
+const MIN_TEXT_WIDTH: usize = 10;
+
+/// 带样式的一段文字
+type Segment = (String, Style);
+
+/// 一行源文本的类型
+enum Block<'a> {
+    Blank,
+    Rule,
+    Heading(usize, &'a str),
+    /// 缩进、是否完成、文字
+    Checkbox(usize, bool, &'a str),
+    /// 缩进、符号（• 或 "1."）、文字
+    Item(usize, String, &'a str),
+    Quote(&'a str),
+    Text(&'a str),
+}
+
+fn parse_line(line: &str) -> Block<'_> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() {
+        return Block::Blank;
+    }
+    let indent = line.len() - trimmed.len();
+    // 分隔线：三个以上的 - * _（中间可以有空格）
+    let marks: Vec<char> = trimmed.chars().filter(|c| !c.is_whitespace()).collect();
+    if marks.len() >= 3 && matches!(marks[0], '-' | '*' | '_') && marks.iter().all(|c| *c == marks[0]) {
+        return Block::Rule;
+    }
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+        return Block::Heading(level, trimmed[level..].trim());
+    }
+    for (prefix, done) in [("- [ ] ", false), ("- [x] ", true), ("- [X] ", true), ("* [ ] ", false), ("* [x] ", true)] {
+        if let Some(rest) = trimmed.strip_prefix(prefix) {
+            return Block::Checkbox(indent, done, rest);
+        }
+    }
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(rest) = trimmed.strip_prefix(marker) {
+            return Block::Item(indent, "•".to_string(), rest);
+        }
+    }
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && trimmed[digits..].starts_with(". ") {
+        return Block::Item(indent, trimmed[..digits + 1].to_string(), &trimmed[digits + 2..]);
+    }
+    if let Some(rest) = trimmed.strip_prefix('>') {
+        return Block::Quote(rest.trim_start());
+    }
+    Block::Text(trimmed)
+}
+
+/// 把便签内容渲染为带样式的行；`wrap_width` 为每行最多显示的字符数（含缩进），None 表示不换行
+pub fn render(content: &str, wrap_width: Option<usize>, theme: &Theme) -> Vec<Line<'static>> {
+    let muted = Style::default().fg(theme.muted);
+    let code = Style::default().fg(theme.highlight);
+    let mut lines = Vec::new();
+    let mut in_code = false;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            let prefix = format!("{}│ ", INDENT);
+            push_wrapped(&mut lines, &prefix, &prefix, muted, vec![(line.to_string(), code)], wrap_width, false);
+            continue;
+        }
+
+        match parse_line(line) {
+            Block::Blank => lines.push(Line::from("")),
+            Block::Rule => {
+                let width = wrap_width.unwrap_or(40).min(40);
+                lines.push(Line::from(Span::styled(format!("{}{}", INDENT, "─".repeat(width)), muted)));
+            }
+            Block::Heading(level, text) => {
+                let mut style = Style::default().fg(theme.accent).add_modifier(Modifier::BOLD);
+                if level == 1 {
+                    style = style.add_modifier(Modifier::UNDERLINED);
+                }
+                push_wrapped(&mut lines, INDENT, INDENT, muted, inline(text, style, theme), wrap_width, true);
+            }
+            Block::Checkbox(indent, done, text) => {
+                let (mark, style) = if done {
+                    ("☑ ", muted.add_modifier(Modifier::CROSSED_OUT))
+                } else {
+                    ("☐ ", Style::default())
+                };
+                let first = format!("{}{}{}", INDENT, " ".repeat(indent), mark);
+                let rest = " ".repeat(first.chars().count());
+                let mark_style = if done { Style::default().fg(theme.success) } else { Style::default() };
+                push_wrapped(&mut lines, &first, &rest, mark_style, inline(text, style, theme), wrap_width, true);
+            }
+            Block::Item(indent, marker, text) => {
+                let first = format!("{}{}{} ", INDENT, " ".repeat(indent), marker);
+                let rest = " ".repeat(first.chars().count());
+                let marker_style = Style::default().fg(theme.accent);
+                push_wrapped(&mut lines, &first, &rest, marker_style, inline(text, Style::default(), theme), wrap_width, true);
+            }
+            Block::Quote(text) => {
+                let prefix = format!("{}│ ", INDENT);
+                let style = muted.add_modifier(Modifier::ITALIC);
+                push_wrapped(&mut lines, &prefix, &prefix, muted, inline(text, style, theme), wrap_width, true);
+            }
+            Block::Text(text) => {
+                push_wrapped(&mut lines, INDENT, INDENT, muted, inline(text, Style::default(), theme), wrap_width, true);
+            }
+        }
+    }
+    lines
+}
+
+/// 解析行内格式：`代码`、**粗体**、*斜体*，没有闭合的标记按原样显示
+fn inline(text: &str, base: Style, theme: &Theme) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let styled = match c {
+            '`' => rest[1..]
+                .find('`')
+                .map(|end| (&rest[1..1 + end], 2 + end, Style::default().fg(theme.highlight))),
+            '*' if rest.starts_with("**") => rest[2..]
+                .find("**")
+                .filter(|end| *end > 0)
+                .map(|end| (&rest[2..2 + end], 4 + end, base.add_modifier(Modifier::BOLD))),
+            // 单个 * 后面紧跟空格时不是斜体（如 "a * b"）
+            '*' if !rest[1..].starts_with(' ') => rest[1..]
+                .find('*')
+                .filter(|end| *end > 0)
+                .map(|end| (&rest[1..1 + end], 2 + end, base.add_modifier(Modifier::ITALIC))),
+            _ => None,
+        };
+        match styled {
+            Some((content, consumed, style)) => {
+                if !plain.is_empty() {
+                    segments.push((std::mem::take(&mut plain), base));
+                }
+                segments.push((content.to_string(), style));
+                rest = &rest[consumed..];
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        segments.push((plain, base));
+    }
+    segments
+}
+
+/// 按宽度换行后加入 lines：第一行使用 `first` 前缀，续行使用 `rest` 前缀
+///
+/// `at_spaces` 时优先在空格处断行（英文单词不被截断），否则按字符切分
+fn push_wrapped(
+    lines: &mut Vec<Line<'static>>,
+    first: &str,
+    rest: &str,
+    prefix_style: Style,
+    segments: Vec<Segment>,
+    wrap_width: Option<usize>,
+    at_spaces: bool,
+) {
+    let chars: Vec<(char, Style)> = segments
+        .iter()
+        .flat_map(|(text, style)| text.chars().map(move |c| (c, *style)))
+        .collect();
+    let rows = match wrap_width {
+        Some(width) => wrap(&chars, width.saturating_sub(first.chars().count()).max(MIN_TEXT_WIDTH), at_spaces),
+        None => vec![chars.as_slice()],
+    };
+
+    for (i, row) in rows.into_iter().enumerate() {
+        let prefix = if i == 0 { first } else { rest };
+        // 相邻的同样式字符合并为一个 Span
+        let mut text: Vec<Span<'static>> = Vec::new();
+        for (c, style) in row {
+            match text.last_mut() {
+                Some(last) if last.style == *style => last.content.to_mut().push(*c),
+                _ => text.push(Span::styled(c.to_string(), *style)),
+            }
+        }
+        let mut spans = vec![Span::styled(prefix.to_string(), prefix_style)];
+        spans.extend(text);
+        lines.push(Line::from(spans));
+    }
+}
+
+/// 把字符切成每行不超过 width 个
+fn wrap(chars: &[(char, Style)], width: usize, at_spaces: bool) -> Vec<&[(char, Style)]> {
+    let mut rows = Vec::new();
+    let mut start = 0;
+    while chars.len() - start > width {
+        let mut end = start + width;
+        if at_spaces {
+            // 空格太靠前时直接按字符切分，避免留下大段空白（中文通常没有空格）
+            if let Some(space) = chars[start..end].iter().rposition(|(c, _)| *c == ' ').filter(|p| *p > width / 2) {
+                end = start + space;
+            }
+        }
+        rows.push(&chars[start..end]);
+        start = end;
+        if at_spaces {
+            while chars.get(start).is_some_and(|(c, _)| *c == ' ') {
+                start += 1;
+            }
+        }
+    }
+    rows.push(&chars[start..]);
+    rows
+}
@@ -0,0 +1,55 @@
+// 错误记录：界面中操作失败（数据库写入失败等）时在状态栏提示，同时记在这里，
+// 状态栏消息几秒后消失，:messages 可以查看最近的错误和完整的错误信息
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local};
+
+/// 最多保留的条数
+pub const MAX_MESSAGES: usize = 100;
+
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub at: DateTime<Local>,
+    /// 出错的操作，如 "保存番茄钟配置"
+    pub context: String,
+    pub error: String,
+    /// 连续出现的次数（同一操作反复失败时只记一条）
+    pub count: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct MessageLog {
+    messages: VecDeque<Message>,
+    /// 上次打开 :messages 之后新增的条数
+    pub unread: usize,
+}
+
+impl MessageLog {
+    pub fn push(&mut self, context: &str, error: String) {
+        self.unread += 1;
+        if let Some(last) = self.messages.back_mut().filter(|m| m.context == context && m.error == error) {
+            last.count += 1;
+            last.at = Local::now();
+            return;
+        }
+        if self.messages.len() == MAX_MESSAGES {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(Message { at: Local::now(), context: context.to_string(), error, count: 1 });
+    }
+
+    /// 最近的在前
+    pub fn recent(&self) -> impl Iterator<Item = &Message> {
+        self.messages.iter().rev()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+        self.unread = 0;
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use chrono::{Datelike, TimeZone, Timelike, Utc};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, MouseButton, MouseEvent, MouseEventKind},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -13,25 +13,67 @@ use ratatui::{
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap},
     Frame, Terminal,
 };
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::{self, Write};
 use std::fs;
+use std::path::Path;
 use std::process::Command;
 
-use crate::db::Database;
-use crate::models::{Note, PomodoroSession, Priority, Task, TaskStatus};
-use crate::pomodoro::PomodoroTimer;
+use crate::config::{Config, IconStyle, KeymapPreset, UiMode};
+use crate::db::{Database, Storage};
+use crate::error::Error;
+use crate::models::{BusyEvent, Comment, Note, WeeklyGoal, PomodoroSession, Priority, Project, Recurrence, Task, TaskStatus, TimeEntry, TrashItem, UsageStat};
+use crate::pomodoro::{PomodoroSnapshot, PomodoroTimer};
 
 mod task_list;
 mod note_list;
 mod pomodoro_view;
+mod filter;
+mod group;
+mod help;
+mod keymap;
+mod recovery;
+mod health;
+mod triage;
+mod dashboard;
+mod remote;
+mod clipboard;
+mod undo;
+mod calendar_view;
+mod board_view;
+mod agenda;
+mod quick_add;
+mod markdown;
+mod theme;
+mod sort;
+mod settings;
+mod messages;
 
 pub use task_list::TaskListWidget;
 pub use note_list::NoteListWidget;
 pub use pomodoro_view::PomodoroWidget;
+pub use filter::TaskFilter;
+pub use dashboard::run as run_dashboard;
+pub use remote::run as run_remote;
+use group::DateGroup;
+use health::HealthWarning;
+use help::HelpTopic;
+use sort::TaskSort;
+use settings::Settings;
+use messages::MessageLog;
+use theme::Theme;
+
+/// 标签页数：任务、便签、番茄钟、日历、看板
+const TAB_COUNT: usize = 5;
+
+/// 检查数据库是否被其他进程（CLI、taskd、另一个 TUI）修改的间隔
+const CHANGE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
 
 /// 应用状态
 pub struct App {
     pub db_path: String,
+    // 整个运行期间共用的数据库连接（切换 profile 时重新打开）
+    pub db: Database,
     pub tasks: Vec<Task>,
     pub notes: Vec<Note>,
     pub pomodoro: PomodoroTimer,
@@ -47,7 +89,7 @@ pub struct App {
     pub show_dialog: DialogType,
     pub status_message: Option<String>,
     pub note_edit_field: usize, // 0=标题, 1=内容
-    pub pending_task_title: Option<String>, // 待创建任务的标题（用于强制设置DDL）
+    pub pending_task: Option<Task>, // 待创建的任务（用于强制设置DDL）
     // 日期时间选择器状态
     pub datetime_picker_field: usize, // 0=年, 1=月, 2=日, 3=时, 4=分
     pub datetime_input_buffer: String, // 当前字段的输入缓冲区（用于键盘直接输入）
@@ -56,6 +98,7 @@ pub struct App {
     pub datetime_day: u32,
     pub datetime_hour: u32,
     pub datetime_minute: u32,
+    pub datetime_recurrence: Option<Recurrence>, // 重复规则（r 切换）
     // 番茄钟统计
     pub pomodoro_completed_today: usize,
     pub pomodoro_total_minutes: usize,
@@ -64,15 +107,128 @@ pub struct App {
     pub number_prefix: String,
     // 番茄钟计时控制
     pub last_tick_time: std::time::Instant,
+    // 上次检查外部修改的时间和当时的 data_version（None 表示还没有基准，如刚切换 profile）
+    pub last_change_check: std::time::Instant,
+    pub data_version: Option<i64>,
     // 提示消息时间戳（用于自动消失）
     pub status_message_time: Option<std::time::Instant>,
+    // 操作失败的记录（:messages 查看）
+    pub messages: MessageLog,
     // 滚动偏移量
     pub help_scroll_offset: usize,
+    // 帮助主题（:help <topic>）与帮助内搜索（/ 输入）
+    pub help_topic: HelpTopic,
+    pub help_search: String,
+    pub help_searching: bool,
+    // 尚未写入数据库的使用次数（退出或 :usage 时写入），以及 :usage 显示的统计
+    pub pending_usage: BTreeMap<String, u64>,
+    pub usage_stats: Vec<UsageStat>,
+    // 本周目标进度（番茄钟页显示）
+    pub weekly_goals: Vec<WeeklyGoal>,
+    // 今天从日历导入的忙碌时段（番茄钟页显示，开始计时时检查冲突）
+    pub busy_today: Vec<BusyEvent>,
+    // 日历标签页选中的日期和该月截止的任务
+    pub calendar_date: chrono::NaiveDate,
+    pub calendar_tasks: Vec<Task>,
+    // 看板标签页：未归档的任务（含已完成）、当前列和各列选中的行
+    pub board_tasks: Vec<Task>,
+    pub board_column: usize,
+    pub board_selected: [usize; 3],
+    // 各日期截止的未完成任务数（打开DDL选择器时加载，不含正在设置的任务）
+    pub due_counts: BTreeMap<chrono::NaiveDate, usize>,
+    // 当前番茄钟绑定的任务标题（gs 启动时设置），计时期间显示在状态栏
+    pub pomodoro_task_title: Option<String>,
+    // 已保存到数据库的进行中番茄钟（与当前状态不同时重新保存）
+    pub saved_pomodoro: Option<PomodoroSnapshot>,
+    // 启动时发现的上次未完成的番茄钟，等待选择继续或放弃
+    pub pending_pomodoro: Option<PomodoroSnapshot>,
+    // 番茄钟任务选择框中选中的行（0 为不关联任务）
+    pub pomodoro_pick: usize,
+    // 回收站（:trash）中的项目和选中的行
+    pub trash: Vec<TrashItem>,
+    pub trash_pick: usize,
+    // :workspace 切换框中的 profile 和选中的行
+    pub workspaces: Vec<String>,
+    pub workspace_pick: usize,
+    // 上次写入提示符缓存时的番茄钟状态，变化时重新写入
+    pub prompt_pomodoro_state: crate::pomodoro::PomodoroState,
+    // :qr 显示的二维码（已渲染为文本）
+    pub qr_code: String,
+    // :changelog 对话框的滚动位置
+    pub changelog_scroll: usize,
+    // :reindex 的后台任务，主循环中每次处理一批
+    pub reindex_job: Option<crate::db::ReindexJob>,
+    // :triage 待确认的建议（第一个为当前建议）和本轮已接受的数量
+    pub triage_queue: Vec<triage::Proposal>,
+    pub triage_accepted: usize,
+    // 撤销/重做记录（u / Ctrl-r）
+    pub history: undo::History,
+    // 任务详情中显示的评论（打开详情或添加评论时加载）
+    pub task_comments: Vec<Comment>,
+    // 启动健康检查发现的问题（顶部横幅显示，Esc 关闭）
+    pub health_warnings: Vec<HealthWarning>,
     pub pomodoro_scroll_offset: usize,
     pub note_scroll_offset: usize,
     pub view_note_scroll_offset: usize, // ViewNote对话框滚动
     // 完整重绘标志（vim 退出后需要重绘）
     pub needs_full_redraw: bool,
+    // 用户配置（图标等）
+    pub config: Config,
+    // 当前 profile（None 为默认 profile）
+    pub profile: Option<String>,
+    // 任务列表过滤条件
+    pub task_filter: TaskFilter,
+    // 所有项目（含已归档，用于显示任务所属项目）
+    pub projects: Vec<Project>,
+    // 已完成任务分组是否展开（默认折叠），折叠时被隐藏的已完成任务数
+    pub show_completed: bool,
+    pub hidden_completed: usize,
+    // 任务列表的显示状态（行包含分组标题，与 task_list_state 的任务索引不同）
+    pub task_view_state: ListState,
+    // 自定义排序（:sort by priority desc，保存在数据库中），None 为默认规则
+    pub task_sort: Option<TaskSort>,
+    // 上次渲染的便签卡片位置（用于鼠标命中检测）
+    pub note_card_areas: Vec<(usize, Rect)>,
+    // 正在拖拽的便签索引
+    pub drag_note: Option<usize>,
+    // 便签墙显示归档便签（:archived 切换）
+    pub show_archived_notes: bool,
+    // 便签墙只显示带该标签的便签（:filter #idea）
+    pub note_tag_filter: Option<String>,
+    // 便签墙顶部的标签栏：当前视图中的标签及便签数（过滤前）
+    pub note_tag_counts: Vec<(String, usize)>,
+    // 任务列表显示归档的任务（:archive 切换）
+    pub show_archived_tasks: bool,
+    // 任务标签页显示今日视图（:today 切换，启动时是否默认显示由 :set agenda=on/off 保存在数据库中）
+    pub show_agenda: bool,
+    // 查看便签时长行关闭自动换行（:set wrap=off）时的横向滚动位置
+    pub view_note_hscroll: usize,
+    // 查看便签时放大到接近全屏（z 切换），两项都保存在数据库中
+    pub note_zoom: bool,
+    // 键位方案（config.toml 的 keymap，可被 :set keymap= 覆盖）
+    pub keymap: KeymapPreset,
+    // 配色主题（config.toml 的 [theme]，可被 :theme 覆盖）
+    pub theme: Theme,
+    pub theme_name: String,
+    // 可视模式（V）的起点任务索引，与当前选中的任务之间为批量操作的范围
+    pub visual_anchor: Option<usize>,
+    // 任务列表右侧的详情窗格（Tab / :split 切换，保存在数据库中）
+    pub show_task_pane: bool,
+    // 详情窗格当前显示的任务，及其关联的便签和番茄钟记录（选中的任务变化时重新加载）
+    pub task_pane_id: Option<i64>,
+    pub task_pane_notes: Vec<Note>,
+    pub task_pane_pomodoros: Vec<PomodoroSession>,
+    pub task_pane_time_entries: Vec<TimeEntry>,
+    pub task_pane_dependencies: Vec<Task>,
+    // 还有未完成依赖的任务（列表中变暗并显示 🔒，不能完成）
+    pub blocked_task_ids: HashSet<i64>,
+    // 计时（T / :track）：各任务已结束计时的累计秒数，以及正在进行的计时
+    pub tracked_seconds: HashMap<i64, i64>,
+    pub time_tracking: Option<TimeEntry>,
+    // :set 修改的界面设置（分组、换行、番茄钟通知、是否必须设置DDL等，保存在数据库中）
+    pub settings: Settings,
+    // 通知方式，第一次发送时按 config.toml 的 [notifications] 检测
+    pub notify_backend: Option<crate::notify::Backend>,
 }
 
 /// 输入模式
@@ -81,6 +237,7 @@ pub enum InputMode {
     Normal,
     Insert,      // 插入模式 (类似vim的i)
     Command,     // 命令模式 (类似vim的:)
+    Visual,      // 可视模式 (类似vim的V)，批量操作任务
 }
 
 /// 对话框类型
@@ -93,12 +250,33 @@ pub enum DialogType {
     CreateNote,
     EditNote,
     ViewNote,
+    ViewTask,
     Help,
     SetDeadline,
+    /// 设置提醒时间（与 SetDeadline 共用日期时间选择器）
+    SetReminder,
+    Usage,
+    Triage,
+    Qr,
+    SlowLog,
+    Changelog,
+    // :set? 列出全部设置
+    Settings,
+    // :messages 最近的错误记录
+    Messages,
+    /// 开始番茄钟前选择关联的任务
+    PickPomodoroTask,
+    /// 回收站中删除的任务和便签
+    Trash,
+    /// 切换 profile（:workspace）
+    PickWorkspace,
+    /// 启动时询问是否继续上次未完成的番茄钟
+    ResumePomodoro,
 }
 
-impl Default for App {
-    fn default() -> Self {
+impl App {
+    /// 各项状态的初始值
+    fn with_database(db: Database) -> Self {
         let mut task_list_state = ListState::default();
         task_list_state.select(Some(0));
 
@@ -109,6 +287,7 @@ impl Default for App {
 
         Self {
             db_path: String::new(),
+            db,
             tasks: Vec::new(),
             notes: Vec::new(),
             pomodoro: PomodoroTimer::default(),
@@ -124,7 +303,7 @@ impl Default for App {
             show_dialog: DialogType::None,
             status_message: None,
             note_edit_field: 0,
-            pending_task_title: None,
+            pending_task: None,
             datetime_picker_field: 0,
             datetime_input_buffer: String::new(),
             datetime_year: now.year(),
@@ -132,52 +311,490 @@ impl Default for App {
             datetime_day: now.day(),
             datetime_hour: now.hour(),
             datetime_minute: now.minute(),
+            datetime_recurrence: None,
             pomodoro_completed_today: 0,
             pomodoro_total_minutes: 0,
             last_key: None,
             number_prefix: String::new(),
             last_tick_time: std::time::Instant::now(),
+            last_change_check: std::time::Instant::now(),
+            data_version: None,
             status_message_time: None,
+            messages: MessageLog::default(),
             help_scroll_offset: 0,
+            help_topic: HelpTopic::Tasks,
+            help_search: String::new(),
+            help_searching: false,
+            pending_usage: BTreeMap::new(),
+            usage_stats: Vec::new(),
+            health_warnings: Vec::new(),
+            task_comments: Vec::new(),
+            qr_code: String::new(),
+            reindex_job: None,
+            changelog_scroll: 0,
+            prompt_pomodoro_state: crate::pomodoro::PomodoroState::Idle,
+            pomodoro_task_title: None,
+            saved_pomodoro: None,
+            pending_pomodoro: None,
+            pomodoro_pick: 0,
+            trash: Vec::new(),
+            trash_pick: 0,
+            workspaces: Vec::new(),
+            workspace_pick: 0,
+            triage_queue: Vec::new(),
+            triage_accepted: 0,
+            history: undo::History::default(),
+            due_counts: BTreeMap::new(),
+            busy_today: Vec::new(),
+            calendar_date: chrono::Local::now().date_naive(),
+            board_tasks: Vec::new(),
+            board_column: 0,
+            board_selected: [0; 3],
+            calendar_tasks: Vec::new(),
+            weekly_goals: Vec::new(),
             pomodoro_scroll_offset: 0,
             note_scroll_offset: 0,
             view_note_scroll_offset: 0,
             needs_full_redraw: false,
+            config: Config::default(),
+            profile: None,
+            task_filter: TaskFilter::default(),
+            projects: Vec::new(),
+            show_completed: false,
+            hidden_completed: 0,
+            task_view_state: ListState::default(),
+            task_sort: None,
+            note_card_areas: Vec::new(),
+            drag_note: None,
+            show_archived_notes: false,
+            note_tag_filter: None,
+            note_tag_counts: Vec::new(),
+            show_archived_tasks: false,
+            show_agenda: true,
+            view_note_hscroll: 0,
+            note_zoom: false,
+            keymap: KeymapPreset::Vim,
+            theme: Theme::default(),
+            theme_name: "dark".to_string(),
+            visual_anchor: None,
+            show_task_pane: false,
+            task_pane_id: None,
+            task_pane_notes: Vec::new(),
+            task_pane_pomodoros: Vec::new(),
+            task_pane_time_entries: Vec::new(),
+            task_pane_dependencies: Vec::new(),
+            blocked_task_ids: HashSet::new(),
+            tracked_seconds: HashMap::new(),
+            time_tracking: None,
+            settings: Settings::default(),
+            notify_backend: None,
         }
     }
 }
 
 impl App {
-    pub fn new(db_path: String) -> Result<Self> {
+    pub fn new(db_path: String, config: Config, profile: Option<String>) -> Result<Self> {
+        let db = Database::open(&db_path)?;
         let mut app = Self {
-            db_path: db_path.clone(),
-            ..Default::default()
+            db_path,
+            config,
+            profile,
+            ..Self::with_database(db)
         };
+        app.load_settings()?;
         app.reload_data()?;
+        app.run_health_checks()?;
+        app.pending_pomodoro = app.db.get_pomodoro_state()?;
+        if app.pending_pomodoro.is_some() {
+            app.saved_pomodoro = app.pending_pomodoro.clone();
+            app.show_dialog = DialogType::ResumePomodoro;
+        }
         Ok(app)
     }
 
+    /// 打开任务详情，同时加载评论
+    fn open_task_detail(&mut self) -> Result<()> {
+        let task_id = match self.selected_task().and_then(|t| t.id) {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+        let db = &self.db;
+        self.task_comments = db.get_comments(task_id)?;
+        self.show_dialog = DialogType::ViewTask;
+        Ok(())
+    }
+
+    /// 给选中的任务添加评论
+    fn add_comment(&mut self, body: &str) -> Result<()> {
+        let task_id = match self.selected_task().and_then(|t| t.id) {
+            Some(id) => id,
+            None => {
+                self.set_status_message("没有选中的任务".to_string());
+                return Ok(());
+            }
+        };
+        let db = &self.db;
+        db.add_comment(&Comment::new(task_id, body.to_string()))?;
+        self.task_comments = db.get_comments(task_id)?;
+        self.set_status_message(format!("💬 已添加评论（共 {} 条）", self.task_comments.len()));
+        Ok(())
+    }
+
+    /// 运行健康检查，结果显示在顶部横幅
+    fn run_health_checks(&mut self) -> Result<()> {
+        let db = &self.db;
+        self.health_warnings = health::run_checks(
+            &db,
+            std::path::Path::new(&self.db_path),
+            self.config.intervals.daemon_check(),
+            Utc::now(),
+        );
+        Ok(())
+    }
+
+    /// 从数据库加载界面设置
+    fn load_settings(&mut self) -> Result<()> {
+        let db = &self.db;
+        self.settings = Settings::load(db)?;
+        self.show_agenda = self.settings.agenda;
+        self.note_zoom = db.get_config("ui_note_zoom")?.is_some_and(|v| v == "on");
+        self.show_task_pane = db.get_config("ui_task_pane")?.is_some_and(|v| v == "on");
+        self.task_sort = db
+            .get_config("ui_sort")?
+            .and_then(|v| TaskSort::parse(&v.split_whitespace().collect::<Vec<_>>()));
+        self.keymap = match db.get_config("ui_keymap")?.as_deref().and_then(KeymapPreset::parse) {
+            Some(preset) => preset,
+            None => self.config.keymap,
+        };
+        let name = match db.get_config("ui_theme")? {
+            Some(name) => name,
+            None => self.config.theme.preset.clone().unwrap_or_else(|| "dark".to_string()),
+        };
+        self.load_theme(&name);
+        Ok(())
+    }
+
+    /// 切换到内置主题并应用 config.toml 中覆盖的颜色，名称无效时返回 false
+    fn load_theme(&mut self, name: &str) -> bool {
+        match Theme::preset(name) {
+            Some(theme) => {
+                self.theme = theme.with_overrides(&self.config.theme);
+                self.theme_name = name.to_lowercase();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// :theme <名称>：切换主题并保存到数据库
+    pub fn set_theme(&mut self, name: &str) -> Result<()> {
+        if !self.load_theme(name) {
+            self.set_status_message(format!("未知主题: {}（可选 {}）", name, Theme::NAMES.join("/")));
+            return Ok(());
+        }
+        let db = &self.db;
+        db.set_config("ui_theme", &self.theme_name)?;
+        self.set_status_message(format!("主题: {}", self.theme_name));
+        Ok(())
+    }
+
+    /// 修改界面设置（:set key=value），成功时保存到数据库并返回提示
+    fn apply_setting(&mut self, key: &str, value: &str) -> Result<String> {
+        if key == "keymap" {
+            let preset = match KeymapPreset::parse(value) {
+                Some(preset) => preset,
+                None => return Ok(format!("无效的键位方案: {}（可选 vim/emacs/arrows）", value)),
+            };
+            self.keymap = preset;
+            let db = &self.db;
+            db.set_config("ui_keymap", preset.name())?;
+            return Ok(format!("keymap={}", preset.name()));
+        }
+        if key == "volume" {
+            let mut sound = crate::sound::SoundSettings::load(&self.db)?;
+            match value.parse::<u8>() {
+                Ok(volume) if volume <= crate::sound::MAX_VOLUME => sound.volume = volume,
+                _ => return Ok(format!("无效的音量: {}（0-{}）", value, crate::sound::MAX_VOLUME)),
+            }
+            sound.save(&self.db)?;
+            return Ok(sound.describe());
+        }
+
+        if key == "sound" {
+            let mut sound = crate::sound::SoundSettings::load(&self.db)?;
+            match value {
+                "on" | "true" | "1" => sound.enabled = true,
+                "off" | "false" | "0" => sound.enabled = false,
+                _ => return Ok(format!("无效的值: {}（可选 on/off）", value)),
+            }
+            sound.save(&self.db)?;
+            return Ok(sound.describe());
+        }
+
+        if let Err(message) = self.settings.set(&self.db, key, value)? {
+            return Ok(message);
+        }
+        match key {
+            "group" => self.sort_tasks(),
+            "wrap" => self.view_note_hscroll = 0,
+            "agenda" => {
+                self.show_agenda = self.settings.agenda;
+                self.sync_agenda_selection();
+            }
+            "auto_sort" if self.settings.auto_sort => self.sort_tasks(),
+            _ => {}
+        }
+        Ok(format!("{}={}", key, self.settings.describe(key)))
+    }
+
+    /// 番茄钟工作/休息时段结束：按设置发送桌面通知、播放提示音并响铃
+    fn notify_pomodoro_end(&mut self, is_break: bool) {
+        if self.settings.bell {
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+        }
+        // 提示音设置可能被 tasks sound 修改，每次从数据库读取
+        if let Ok(sound) = crate::sound::SoundSettings::load(&self.db) {
+            crate::sound::play_in_background(sound, crate::sound::Alert::Pomodoro);
+        }
+        if !self.settings.notify {
+            return;
+        }
+        let backend = self
+            .notify_backend
+            .get_or_insert_with(|| crate::notify::Backend::detect(&self.config.notifications))
+            .clone();
+        // 终端方式会把通知写到 TUI 所在的终端上，TUI 中不使用（可改用 :set bell=on），在状态栏注明
+        if backend == crate::notify::Backend::Terminal {
+            let message = format!(
+                "{}（通知方式为 {}，TUI 中不发送通知，可 :set bell=on）",
+                self.status_message.take().unwrap_or_default(),
+                backend.describe()
+            );
+            self.set_status_message(message);
+            return;
+        }
+        // webhook 可能要等几秒，不阻塞界面
+        std::thread::spawn(move || {
+            let _ = crate::notify::NotificationManager::with_backend(backend).send_pomodoro_complete(is_break);
+        });
+    }
+
+    /// 切换便签查看器的放大模式，并保存偏好
+    fn toggle_note_zoom(&mut self) -> Result<()> {
+        self.note_zoom = !self.note_zoom;
+        let db = &self.db;
+        db.set_config("ui_note_zoom", value_str(self.note_zoom))?;
+        Ok(())
+    }
+
+    /// 切换任务列表右侧的详情窗格，并保存偏好
+    fn toggle_task_pane(&mut self) -> Result<()> {
+        self.show_task_pane = !self.show_task_pane;
+        self.task_pane_id = None;
+        let db = &self.db;
+        db.set_config("ui_task_pane", value_str(self.show_task_pane))?;
+        self.set_status_message(if self.show_task_pane { "详情窗格已打开" } else { "详情窗格已关闭" }.to_string());
+        Ok(())
+    }
+
+    /// 选中的任务变化（或数据重新加载）后，加载详情窗格中的便签和番茄钟记录
+    fn refresh_task_pane(&mut self) -> Result<()> {
+        if !self.show_task_pane || self.current_tab != 0 {
+            return Ok(());
+        }
+        let task_id = self.selected_task().and_then(|t| t.id);
+        if task_id == self.task_pane_id {
+            return Ok(());
+        }
+        self.task_pane_id = task_id;
+        match task_id {
+            Some(id) => {
+                self.task_pane_notes = self.db.get_task_notes(id)?;
+                self.task_pane_pomodoros = self.db.get_task_pomodoros(id)?;
+                self.task_pane_time_entries = self.db.get_task_time_entries(id)?;
+                self.task_pane_dependencies = self.db.get_dependencies(id)?;
+            }
+            None => {
+                self.task_pane_notes.clear();
+                self.task_pane_pomodoros.clear();
+                self.task_pane_time_entries.clear();
+                self.task_pane_dependencies.clear();
+            }
+        }
+        Ok(())
+    }
+
+    /// 重新加载计时：已结束计时的累计时长和正在进行的计时
+    fn load_time_tracking(&mut self) -> Result<()> {
+        self.tracked_seconds = self.db.get_tracked_seconds()?;
+        self.time_tracking = self.db.running_time_entry()?;
+        Ok(())
+    }
+
+    /// 任务的累计计时秒数（含正在进行的计时）
+    fn tracked_for(&self, task_id: i64, now: chrono::DateTime<Utc>) -> i64 {
+        let running = self
+            .time_tracking
+            .as_ref()
+            .filter(|entry| entry.task_id == task_id)
+            .map_or(0, |entry| entry.seconds(now));
+        self.tracked_seconds.get(&task_id).copied().unwrap_or(0) + running
+    }
+
+    /// 开始给选中的任务计时（正在给其他任务计时时切换到选中的任务）
+    fn start_time_tracking(&mut self) -> Result<()> {
+        let (id, title) = match self.selected_task() {
+            Some(Task { id: Some(id), title, .. }) => (*id, title.clone()),
+            _ => {
+                self.set_status_message("没有选中的任务".to_string());
+                return Ok(());
+            }
+        };
+        if self.time_tracking.as_ref().is_some_and(|entry| entry.task_id == id) {
+            self.set_status_message(format!("⏱ 已在计时: {}", title));
+            return Ok(());
+        }
+        self.db.start_time_entry(id, Utc::now())?;
+        self.load_time_tracking()?;
+        self.task_pane_id = None;
+        self.set_status_message(format!("⏱ 开始计时: {}", title));
+        Ok(())
+    }
+
+    /// 停止正在进行的计时
+    fn stop_time_tracking(&mut self) -> Result<()> {
+        let now = Utc::now();
+        let Some(entry) = self.db.stop_time_entry(now)? else {
+            self.set_status_message("没有正在进行的计时".to_string());
+            return Ok(());
+        };
+        self.load_time_tracking()?;
+        self.task_pane_id = None;
+        let title = self.db.get_task(entry.task_id).map(|t| t.title).unwrap_or_default();
+        self.set_status_message(format!(
+            "⏱ 停止计时: {}（本次 {}，累计 {}）",
+            title,
+            crate::models::format_tracked(entry.seconds(now)),
+            crate::models::format_tracked(self.tracked_for(entry.task_id, now))
+        ));
+        Ok(())
+    }
+
+    /// T：选中的任务正在计时则停止，否则开始给它计时
+    fn toggle_time_tracking(&mut self) -> Result<()> {
+        let selected = self.selected_task().and_then(|t| t.id);
+        if selected.is_some() && self.time_tracking.as_ref().map(|entry| entry.task_id) == selected {
+            self.stop_time_tracking()
+        } else {
+            self.start_time_tracking()
+        }
+    }
+
+    /// 查看便签时每行最多显示的字符数，None 表示不换行
+    fn note_wrap_width(&self) -> Option<usize> {
+        match (self.settings.note_wrap, self.note_zoom) {
+            (false, _) => None,
+            (true, false) => Some(50),
+            (true, true) => Some(110),
+        }
+    }
+
     /// 设置状态消息（会自动记录时间戳，3秒后自动消失）
     pub fn set_status_message(&mut self, message: String) {
         self.status_message = Some(message);
         self.status_message_time = Some(std::time::Instant::now());
     }
 
+    /// 在状态栏显示操作失败并记录到 :messages
+    pub fn report_error(&mut self, context: &str, error: impl std::fmt::Display) {
+        let error = error.to_string();
+        self.set_status_message(format!("❌ {}失败: {}（:messages 查看）", context, error));
+        self.messages.push(context, error);
+    }
+
+    /// 结果出错时报告错误（见 report_error），成功时返回值
+    pub fn report<T, E: std::fmt::Display>(&mut self, context: &str, result: std::result::Result<T, E>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.report_error(context, e);
+                None
+            }
+        }
+    }
+
     /// 清除状态消息
     pub fn clear_status_message(&mut self) {
         self.status_message = None;
         self.status_message_time = None;
     }
 
+    /// 检查校验结果，失败时在状态栏显示原因并返回 false
+    fn check_valid(&mut self, result: crate::error::Result<()>) -> bool {
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                self.set_status_message(format!("⚠️ {}", e));
+                false
+            }
+        }
+    }
+
+    /// 任务被未完成的依赖阻塞时在状态栏说明原因并返回 false
+    fn check_unblocked(&mut self, task: &Task) -> Result<bool> {
+        match task.id.filter(|id| self.blocked_task_ids.contains(id)) {
+            Some(id) => {
+                let result = self.db.check_dependencies(id);
+                Ok(self.check_valid(result))
+            }
+            None => Ok(true),
+        }
+    }
+
     /// 从数据库重新加载数据
     pub fn reload_data(&mut self) -> Result<()> {
         // 在重新加载数据之前，先保存当前选中任务的ID
         // 这很重要，因为重新加载后tasks数组会变化，但task_list_state的索引还是旧的
         let selected_task_id = self.selected_task().and_then(|t| t.id);
-
-        let db = Database::open(&self.db_path)?;
-        self.tasks = db.get_all_tasks()?;
+        // 关闭自动排序时按原来的顺序排列
+        let previous_order: HashMap<i64, usize> =
+            self.tasks.iter().enumerate().filter_map(|(i, t)| t.id.map(|id| (id, i))).collect();
+
+        let db = &self.db;
+        self.projects = db.get_projects(true)?;
+        let tasks = db.get_all_tasks()?;
+        self.write_prompt_segment(Some(&tasks), self.pomodoro_mark());
+        self.set_board_tasks(&tasks);
+        let db = &self.db;
+        self.tasks = if self.show_archived_tasks { db.get_archived_tasks()? } else { tasks };
+        self.tasks.retain(|t| self.task_filter.matches(t));
+
+        // 已完成任务默认折叠到列表底部的分组中（只看已完成时、查看归档时除外）
+        self.hidden_completed = 0;
+        if !self.show_completed
+            && !self.show_archived_tasks
+            && self.task_filter.status != Some(TaskStatus::Completed)
+        {
+            let before = self.tasks.len();
+            self.tasks.retain(|t| t.status != TaskStatus::Completed);
+            self.hidden_completed = before - self.tasks.len();
+        }
         self.notes = db.get_all_notes()?;
+        self.notes.retain(|n| n.archived == self.show_archived_notes);
+        let mut tag_counts: BTreeMap<String, usize> = BTreeMap::new();
+        for tag in self.notes.iter().flat_map(|n| &n.tags) {
+            *tag_counts.entry(tag.clone()).or_default() += 1;
+        }
+        self.note_tag_counts = tag_counts.into_iter().collect();
+        if let Some(tag) = &self.note_tag_filter {
+            self.notes.retain(|n| n.has_tag(tag));
+        }
+        // 详情窗格在下一次绘制前重新加载
+        self.task_pane_id = None;
+        self.tracked_seconds = db.get_tracked_seconds()?;
+        self.time_tracking = db.running_time_entry()?;
+        self.blocked_task_ids = db.get_blocked_task_ids()?;
 
         // 加载番茄钟统计
         let (completed, minutes) = db.get_today_pomodoro_stats()?;
@@ -188,6 +805,29 @@ impl App {
         let (work, break_time) = db.get_pomodoro_config()?;
         self.pomodoro.work_duration = work;
         self.pomodoro.break_duration = break_time;
+        let (long_break, cycle_length, position) = db.get_pomodoro_cycle()?;
+        self.pomodoro.long_break_duration = long_break;
+        self.pomodoro.cycle_length = cycle_length;
+        self.pomodoro.completed_in_cycle = position;
+
+        // 加载今天的忙碌时段（本地时间 00:00 ~ 次日 00:00）
+        let today = chrono::Local::now().date_naive();
+        let day_start = chrono::Local
+            .from_local_datetime(&today.and_hms_opt(0, 0, 0).unwrap())
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_else(Utc::now);
+        self.busy_today = db.get_busy_events_between(day_start, day_start + chrono::Duration::days(1))?;
+        let (week_start, week_end) = crate::models::local_week_bounds(today);
+        self.weekly_goals = db.get_weekly_goals(week_start, week_end)?;
+        let (month_start, month_end) = calendar_view::month_bounds(self.calendar_date);
+        self.calendar_tasks = db.get_tasks_between(month_start, month_end)?;
+
+        // 关闭自动排序（:set auto_sort=off）时保持原来的顺序，新任务排在最后
+        let keep_order = !self.settings.auto_sort && !previous_order.is_empty();
+        if keep_order {
+            self.tasks.sort_by_key(|t| t.id.and_then(|id| previous_order.get(&id).copied()).unwrap_or(usize::MAX));
+        }
 
         // 在排序前，先根据保存的task id恢复选中状态
         // 这样sort_tasks就能正确保存和恢复选中位置
@@ -198,19 +838,43 @@ impl App {
         }
 
         // 自动排序任务（会进一步保持选中状态）
-        self.sort_tasks();
+        if !keep_order {
+            self.sort_tasks();
+        }
 
-        // 更新选择状态
-        if !self.tasks.is_empty() && self.task_list_state.selected().is_none() {
+        // 更新选择状态（过滤后列表可能变短）
+        if self.tasks.is_empty() {
+            self.task_list_state.select(None);
+        } else if self.task_list_state.selected().is_none_or(|i| i >= self.tasks.len()) {
             self.task_list_state.select(Some(0));
         }
-        if !self.notes.is_empty() && self.note_list_state.selected().is_none() {
+        self.sync_agenda_selection();
+        if self.notes.is_empty() {
+            self.note_list_state.select(None);
+        } else if self.note_list_state.selected().is_none_or(|i| i >= self.notes.len()) {
             self.note_list_state.select(Some(0));
         }
 
         Ok(())
     }
 
+    /// 其他进程修改了数据库时自动重新加载（本进程的写入不改变 data_version，不会触发）；
+    /// 输入、可视模式或对话框打开时等回到普通界面再加载，避免正在操作的任务在列表中移动
+    pub fn reload_if_changed(&mut self) -> Result<()> {
+        if self.last_change_check.elapsed() < CHANGE_CHECK_INTERVAL
+            || self.input_mode != InputMode::Normal
+            || self.show_dialog != DialogType::None
+        {
+            return Ok(());
+        }
+        self.last_change_check = std::time::Instant::now();
+        let version = self.db.data_version()?;
+        if self.data_version.replace(version).is_some_and(|previous| previous != version) {
+            self.reload_data()?;
+        }
+        Ok(())
+    }
+
     /// 任务自动排序（保持选中状态）
     /// 排序规则：
     /// 1. 未完成的任务优先（按状态：InProgress > Todo > Completed）
@@ -221,35 +885,53 @@ impl App {
         let selected_task_id = self.selected_task().and_then(|t| t.id);
 
         // 执行排序
-        self.tasks.sort_by(|a, b| {
-            use std::cmp::Ordering;
-
-            // 1. 首先按状态排序
-            let status_order = |status: &TaskStatus| match status {
-                TaskStatus::InProgress => 0,
-                TaskStatus::Todo => 1,
-                TaskStatus::Completed => 2,
-            };
+        let group_by_date = self.settings.group_by_date;
+        let now = Utc::now();
+        if let Some(sort) = self.task_sort {
+            self.tasks.sort_by(|a, b| sort.compare(a, b));
+        } else {
+            self.tasks.sort_by(|a, b| {
+                use std::cmp::Ordering;
 
-            let status_cmp = status_order(&a.status).cmp(&status_order(&b.status));
-            if status_cmp != Ordering::Equal {
-                return status_cmp;
-            }
+                // 0. 按日期分组时，未完成任务先按分组排序，保证同组任务连续
+                if group_by_date {
+                    let group = |t: &Task| {
+                        (t.status == TaskStatus::Completed, DateGroup::of(t, now))
+                    };
+                    let group_cmp = group(a).cmp(&group(b));
+                    if group_cmp != Ordering::Equal {
+                        return group_cmp;
+                    }
+                }
 
-            // 2. 同状态下，按优先级排序（逆序，因为High=3, Medium=2, Low=1）
-            let priority_cmp = (b.priority as i32).cmp(&(a.priority as i32));
-            if priority_cmp != Ordering::Equal {
-                return priority_cmp;
-            }
+                // 1. 首先按状态排序
+                let status_order = |status: &TaskStatus| match status {
+                    TaskStatus::InProgress => 0,
+                    TaskStatus::Todo => 1,
+                    TaskStatus::Waiting => 2,
+                    TaskStatus::Completed => 3,
+                };
 
-            // 3. 同优先级下，按DDL排序
-            match (&a.due_date, &b.due_date) {
-                (Some(a_due), Some(b_due)) => a_due.cmp(b_due), // 都有DDL，早的优先
-                (Some(_), None) => Ordering::Less,               // a有DDL，a优先
-                (None, Some(_)) => Ordering::Greater,            // b有DDL，b优先
-                (None, None) => Ordering::Equal,                 // 都没有DDL，相等
-            }
-        });
+                let status_cmp = status_order(&a.status).cmp(&status_order(&b.status));
+                if status_cmp != Ordering::Equal {
+                    return status_cmp;
+                }
+
+                // 2. 同状态下，按优先级排序（逆序，因为High=3, Medium=2, Low=1）
+                let priority_cmp = (b.priority as i32).cmp(&(a.priority as i32));
+                if priority_cmp != Ordering::Equal {
+                    return priority_cmp;
+                }
+
+                // 3. 同优先级下，按DDL排序
+                match (&a.due_date, &b.due_date) {
+                    (Some(a_due), Some(b_due)) => a_due.cmp(b_due), // 都有DDL，早的优先
+                    (Some(_), None) => Ordering::Less,               // a有DDL，a优先
+                    (None, Some(_)) => Ordering::Greater,            // b有DDL，b优先
+                    (None, None) => Ordering::Equal,                 // 都没有DDL，相等
+                }
+            });
+        }
 
         // 恢复选中状态：找到之前选中任务的新位置
         if let Some(task_id) = selected_task_id {
@@ -259,27 +941,122 @@ impl App {
         }
     }
 
+    /// 修改任务后重新排序（:set auto_sort=off 时保持当前顺序）
+    fn auto_sort_tasks(&mut self) {
+        if self.settings.auto_sort {
+            self.sort_tasks();
+        }
+    }
+
+    /// :sort by ...：设置自定义排序并保存，None 恢复默认规则
+    pub fn set_task_sort(&mut self, sort: Option<TaskSort>) -> Result<()> {
+        let db = &self.db;
+        match sort {
+            Some(sort) => db.set_config("ui_sort", &sort.name())?,
+            None => db.delete_config("ui_sort")?,
+        }
+        self.task_sort = sort;
+        self.sort_tasks();
+        self.set_status_message(match sort {
+            Some(sort) => format!("按 {} 排序（不再按日期分组，:sort default 恢复默认）", sort.name()),
+            None => "已恢复默认排序（状态→优先级→DDL）".to_string(),
+        });
+        Ok(())
+    }
+
     /// 切换标签页
     pub fn next_tab(&mut self) {
-        self.current_tab = (self.current_tab + 1) % 3;
+        self.current_tab = (self.current_tab + 1) % TAB_COUNT;
     }
 
     pub fn previous_tab(&mut self) {
         if self.current_tab > 0 {
             self.current_tab -= 1;
         } else {
-            self.current_tab = 2;
+            self.current_tab = TAB_COUNT - 1;
         }
     }
 
     pub fn goto_tab(&mut self, tab: usize) {
-        if tab < 3 {
+        if tab < TAB_COUNT {
             self.current_tab = tab;
         }
     }
 
+    /// 更新看板中的任务（应用任务过滤），各列的选中行不超出范围
+    fn set_board_tasks(&mut self, tasks: &[Task]) {
+        self.board_tasks = tasks.iter().filter(|t| self.task_filter.matches(t)).cloned().collect();
+        for (column, selected) in self.board_selected.iter_mut().enumerate() {
+            let len = board_view::column_tasks(&self.board_tasks, column).len();
+            *selected = (*selected).min(len.saturating_sub(1));
+        }
+    }
+
+    /// 看板中选中的任务
+    fn board_selected_task(&self) -> Option<&Task> {
+        board_view::column_tasks(&self.board_tasks, self.board_column)
+            .get(self.board_selected[self.board_column])
+            .copied()
+    }
+
+    /// 看板中上下移动选中的任务
+    pub fn board_move_selection(&mut self, delta: i64) {
+        let len = board_view::column_tasks(&self.board_tasks, self.board_column).len();
+        let selected = &mut self.board_selected[self.board_column];
+        *selected = (*selected as i64 + delta).clamp(0, len.saturating_sub(1) as i64) as usize;
+    }
+
+    /// 看板中左右切换列
+    pub fn board_move_column(&mut self, delta: i64) {
+        self.board_column = (self.board_column as i64 + delta).clamp(0, board_view::COLUMNS.len() as i64 - 1) as usize;
+    }
+
+    /// H/L：把选中的任务移到左/右一列并立即保存（u 撤销），选中移动后的任务
+    pub fn board_move_task(&mut self, delta: i64) -> Result<()> {
+        let target = (self.board_column as i64 + delta).clamp(0, board_view::COLUMNS.len() as i64 - 1) as usize;
+        let mut task = match self.board_selected_task() {
+            Some(task) if target != self.board_column => task.clone(),
+            _ => return Ok(()),
+        };
+        let before = task.clone();
+        let mut changes = Vec::new();
+        match board_view::COLUMNS[target].1 {
+            TaskStatus::Completed => {
+                if !self.check_unblocked(&task)? {
+                    return Ok(());
+                }
+                if let Some(next_id) = self.db.complete_task(&mut task)? {
+                    changes.push(undo::Change::create(self.db.get_task(next_id)?));
+                }
+            }
+            status => {
+                if task.status == TaskStatus::Waiting {
+                    task.clear_waiting();
+                }
+                task.status = status;
+                task.completed_at = None;
+                task.updated_at = Utc::now();
+                self.db.update_task(&task)?;
+            }
+        }
+        changes.insert(0, undo::Change::update(before, task.clone()));
+        self.history.record("移动任务", changes);
+        self.reload_data()?;
+
+        self.board_column = target;
+        if let Some(index) = board_view::column_tasks(&self.board_tasks, target).iter().position(|t| t.id == task.id) {
+            self.board_selected[target] = index;
+        }
+        self.set_status_message(format!("已移到「{}」: {}", board_view::COLUMNS[target].0, task.title));
+        Ok(())
+    }
+
     /// 任务列表导航
     pub fn next_task(&mut self) {
+        if self.agenda_active() {
+            self.move_agenda_selection(1);
+            return;
+        }
         if self.tasks.is_empty() {
             return;
         }
@@ -297,6 +1074,10 @@ impl App {
     }
 
     pub fn previous_task(&mut self) {
+        if self.agenda_active() {
+            self.move_agenda_selection(-1);
+            return;
+        }
         if self.tasks.is_empty() {
             return;
         }
@@ -382,17 +1163,77 @@ impl App {
 
     /// vim风格：跳到第一个
     pub fn goto_first_task(&mut self) {
-        if !self.tasks.is_empty() {
+        if self.agenda_active() {
+            let first = self.agenda_entries().first().map(|e| e.index);
+            self.task_list_state.select(first);
+        } else if !self.tasks.is_empty() {
             self.task_list_state.select(Some(0));
         }
     }
 
     pub fn goto_last_task(&mut self) {
-        if !self.tasks.is_empty() {
+        if self.agenda_active() {
+            let last = self.agenda_entries().last().map(|e| e.index);
+            self.task_list_state.select(last);
+        } else if !self.tasks.is_empty() {
             self.task_list_state.select(Some(self.tasks.len() - 1));
         }
     }
 
+    /// 任务标签页当前显示今日视图（查看归档任务时显示完整列表）
+    pub fn agenda_active(&self) -> bool {
+        self.show_agenda && !self.show_archived_tasks
+    }
+
+    fn agenda_entries(&self) -> Vec<agenda::Entry> {
+        agenda::entries(&self.tasks, Utc::now())
+    }
+
+    /// 今日视图中选中的条目
+    fn agenda_position(&self) -> Option<usize> {
+        let selected = self.task_list_state.selected()?;
+        self.agenda_entries().iter().position(|e| e.index == selected)
+    }
+
+    /// 在今日视图的条目之间移动（首尾循环）
+    fn move_agenda_selection(&mut self, delta: isize) {
+        let entries = self.agenda_entries();
+        if entries.is_empty() {
+            return;
+        }
+        let len = entries.len() as isize;
+        let next = match self.agenda_position() {
+            Some(i) => (i as isize + delta).rem_euclid(len) as usize,
+            None => 0,
+        };
+        self.task_list_state.select(Some(entries[next].index));
+    }
+
+    /// 今日视图中选中的任务不在视图里时改选第一条（没有条目时不选中），回到任务列表时保证有选中
+    fn sync_agenda_selection(&mut self) {
+        if self.agenda_active() {
+            if self.agenda_position().is_none() {
+                let first = self.agenda_entries().first().map(|e| e.index);
+                self.task_list_state.select(first);
+            }
+        } else if self.task_list_state.selected().is_none() && !self.tasks.is_empty() {
+            self.task_list_state.select(Some(0));
+        }
+    }
+
+    /// :today 在今日视图和完整任务列表之间切换
+    pub fn toggle_agenda(&mut self) -> Result<()> {
+        self.show_agenda = !self.show_agenda;
+        if self.show_agenda && self.show_archived_tasks {
+            self.show_archived_tasks = false;
+            self.reload_data()?;
+        }
+        self.sync_agenda_selection();
+        let message = if self.show_agenda { "今日视图（:today 查看全部任务）" } else { "全部任务（:today 返回今日视图）" };
+        self.set_status_message(message.to_string());
+        Ok(())
+    }
+
     pub fn goto_first_note(&mut self) {
         if !self.notes.is_empty() {
             self.note_list_state.select(Some(0));
@@ -418,6 +1259,12 @@ impl App {
             .and_then(|i| self.tasks.get_mut(i))
     }
 
+    /// 选中的任务（可修改）和数据库连接，修改任务后直接写回
+    fn selected_task_and_db(&mut self) -> (Option<&mut Task>, &Database) {
+        let task = self.task_list_state.selected().and_then(|i| self.tasks.get_mut(i));
+        (task, &self.db)
+    }
+
     /// 获取当前选中的便签
     pub fn selected_note(&self) -> Option<&Note> {
         self.note_list_state
@@ -425,50 +1272,994 @@ impl App {
             .and_then(|i| self.notes.get(i))
     }
 
+    /// :timing 开启时，操作耗时超过阈值则在状态栏显示耗时和其中最慢的查询
+    fn report_timing(&mut self, (started, started_at): (std::time::Instant, chrono::DateTime<Utc>)) {
+        let elapsed = started.elapsed();
+        if !crate::db::timing::enabled() || elapsed < crate::db::timing::threshold() {
+            return;
+        }
+        let mut report = format!("⏱ 操作耗时 {}ms", elapsed.as_millis());
+        if let Some(query) = crate::db::timing::slowest_since(started_at) {
+            let sql: String = query.sql.chars().take(40).collect();
+            report.push_str(&format!("，最慢查询 {}ms: {}", query.elapsed.as_millis(), sql));
+        }
+        let message = match self.status_message.take() {
+            Some(message) => format!("{} | {}", message, report),
+            None => report,
+        };
+        self.set_status_message(message);
+    }
+
+    /// 执行 :reindex 的下一批，在状态栏显示进度，完成后重新加载数据
+    fn step_reindex(&mut self) {
+        let mut job = match self.reindex_job.take() {
+            Some(job) => job,
+            None => return,
+        };
+        let result = job.step(&self.db, crate::db::REINDEX_BATCH);
+        match result {
+            Err(e) => self.set_status_message(format!("重建索引失败: {}", e)),
+            Ok(_) if job.is_finished() => {
+                let reloaded = self.reload_data();
+                self.report("重新加载", reloaded);
+                self.set_status_message(format!("✅ 索引已重建，修正了 {} 个任务的番茄钟数", job.fixed));
+            }
+            Ok(_) => {
+                let (done, total) = job.progress();
+                self.set_status_message(format!("重建索引中… {}/{}", done, total));
+                self.reindex_job = Some(job);
+            }
+        }
+    }
+
+    /// 把当前便签转换为指定格式后复制到剪贴板
+    pub fn yank_note(&mut self, format: clipboard::NoteFormat) {
+        let text = match self.selected_note() {
+            Some(note) => clipboard::render_note(note, format),
+            None => {
+                self.set_status_message("没有选中的便签".to_string());
+                return;
+            }
+        };
+        let message = match clipboard::copy(&text) {
+            Ok(via) => format!("📋 已复制为 {}（{}）", format.name(), via),
+            Err(e) => format!("复制失败: {}", e),
+        };
+        self.set_status_message(message);
+    }
+
     /// 切换任务完成状态
+    /// Space：待办 → 进行中 → 已完成 → 待办（等待中的任务直接完成）
     pub fn toggle_task_status(&mut self) -> Result<()> {
-        let db_path = self.db_path.clone();
+        let next = match self.selected_task().map(|t| t.status) {
+            Some(TaskStatus::Todo) => TaskStatus::InProgress,
+            Some(TaskStatus::Completed) => TaskStatus::Todo,
+            Some(TaskStatus::InProgress | TaskStatus::Waiting) => TaskStatus::Completed,
+            None => return Ok(()),
+        };
+        self.set_task_status(next)
+    }
 
-        if let Some(task) = self.selected_task_mut() {
-            task.status = match task.status {
-                TaskStatus::Todo => TaskStatus::Completed,
-                TaskStatus::Completed => TaskStatus::Todo,
-                TaskStatus::InProgress => TaskStatus::Completed,
+    /// 修改选中任务的状态（Space / :status），可撤销；完成时按重复规则创建下一次
+    pub fn set_task_status(&mut self, status: TaskStatus) -> Result<()> {
+        if status == TaskStatus::Completed {
+            let Some(task) = self.selected_task().cloned() else {
+                return Ok(());
             };
-            task.updated_at = Utc::now();
-            if task.status == TaskStatus::Completed {
-                task.completed_at = Some(Utc::now());
+            if !self.check_unblocked(&task)? {
+                return Ok(());
+            }
+        }
+        if let (Some(task), db) = self.selected_task_and_db() {
+            let before = task.clone();
+            if task.status == status {
+                self.set_status_message(format!("任务已经是「{}」", status.label()));
+                return Ok(());
+            }
+            if status != TaskStatus::Completed {
+                match status {
+                    TaskStatus::Waiting => task.set_waiting(None),
+                    _ => {
+                        if task.status == TaskStatus::Waiting {
+                            task.clear_waiting();
+                        }
+                        task.status = status;
+                        task.completed_at = None;
+                    }
+                }
+                task.updated_at = Utc::now();
+                db.update_task(task)?;
+                let change = undo::Change::update(before.clone(), task.clone());
+                let label = match status {
+                    TaskStatus::Todo if before.status == TaskStatus::Completed => "恢复未完成",
+                    TaskStatus::InProgress => "开始任务",
+                    _ => "修改状态",
+                };
+                self.history.record(label, vec![change]);
+                let message = match status {
+                    TaskStatus::InProgress => "🔄 进行中（再按 Space 完成）".to_string(),
+                    status => format!("任务状态: {}", status.label()),
+                };
+                self.set_status_message(message);
             } else {
-                task.completed_at = None;
+                let next_id = db.complete_task(task)?;
+                let mut changes = vec![undo::Change::update(before, task.clone())];
+                match next_id {
+                    Some(next_id) => {
+                        changes.push(undo::Change::create(db.get_task(next_id)?));
+                        // 新生成的下一次需要出现在列表中
+                        self.reload_data()?;
+                        self.set_status_message(format!("任务已完成，已创建下一次 #{}", next_id));
+                    }
+                    None => self.set_status_message("任务已完成（:done 2d 可安排跟进，u 撤销）".to_string()),
+                }
+                self.history.record("完成任务", changes);
             }
-
-            let db = Database::open(&db_path)?;
-            db.update_task(task)?;
-            self.set_status_message("任务状态已更新".to_string());
         }
 
+        // 完成或恢复任务会改变依赖它的任务是否被阻塞，看板中的列也随之变化
+        self.blocked_task_ids = self.db.get_blocked_task_ids()?;
+        let tasks = self.db.get_all_tasks()?;
+        self.set_board_tasks(&tasks);
         // 立即重新排序
-        self.sort_tasks();
+        self.auto_sort_tasks();
         Ok(())
     }
 
-    /// 创建新任务
-    pub fn create_task(&mut self) -> Result<()> {
-        if self.input_buffer.is_empty() {
-            return Ok(());
+    /// 进入可视模式（V），从当前任务开始选择范围
+    pub fn enter_visual_mode(&mut self) {
+        if let Some(index) = self.task_list_state.selected().filter(|i| *i < self.tasks.len()) {
+            self.visual_anchor = Some(index);
+            self.input_mode = InputMode::Visual;
+        }
+    }
+
+    /// 退出可视模式，回到普通模式
+    pub fn exit_visual_mode(&mut self) {
+        self.visual_anchor = None;
+        if self.input_mode == InputMode::Visual {
+            self.input_mode = InputMode::Normal;
         }
+    }
 
-        let db = Database::open(&self.db_path)?;
-        let task = Task::new(self.input_buffer.clone());
-        let id = db.create_task(&task)?;
+    /// 可视模式选中的任务索引范围（不在可视模式时为 None）
+    pub fn visual_range(&self) -> Option<std::ops::RangeInclusive<usize>> {
+        let anchor = self.visual_anchor?;
+        let cursor = self.task_list_state.selected()?;
+        let last = self.tasks.len().checked_sub(1)?;
+        Some(anchor.min(cursor).min(last)..=anchor.max(cursor).min(last))
+    }
 
-        self.input_buffer.clear();
-        self.cursor_position = 0;
+    fn visual_selection(&self) -> Vec<Task> {
+        self.visual_range()
+            .map(|range| self.tasks[range].to_vec())
+            .unwrap_or_default()
+    }
+
+    /// 记录批量操作并退出可视模式
+    fn finish_batch(&mut self, label: &str, count: usize, changes: Vec<undo::Change>) -> Result<()> {
+        self.history.record(label, changes);
+        self.exit_visual_mode();
+        self.reload_data()?;
+        self.set_status_message(format!("{}: {} 个任务（u 撤销）", label, count));
+        Ok(())
+    }
+
+    /// 批量修改选中范围内的任务，所有写入在同一个事务中完成，撤销时作为一步
+    fn batch_update(&mut self, label: &str, edit: impl Fn(&mut Task)) -> Result<()> {
+        let tasks = self.visual_selection();
+        let now = Utc::now();
+        let db = &self.db;
+        let changes = db.in_transaction(|db| {
+            let mut changes = Vec::new();
+            for before in tasks {
+                let mut after = before.clone();
+                edit(&mut after);
+                after.updated_at = now;
+                db.update_task(&after)?;
+                changes.push(undo::Change::update(before, after));
+            }
+            Ok(changes)
+        })?;
+        let count = changes.len();
+        self.finish_batch(label, count, changes)
+    }
+
+    /// 可视模式：完成选中的任务（重复任务同时创建下一次）
+    pub fn batch_complete(&mut self) -> Result<()> {
+        let tasks: Vec<Task> = self
+            .visual_selection()
+            .into_iter()
+            .filter(|t| t.status != TaskStatus::Completed)
+            .collect();
+        let (tasks, blocked): (Vec<Task>, Vec<Task>) = tasks
+            .into_iter()
+            .partition(|t| !t.id.is_some_and(|id| self.blocked_task_ids.contains(&id)));
+        let count = tasks.len();
+        let db = &self.db;
+        let changes = db.in_transaction(|db| {
+            let mut changes = Vec::new();
+            for mut task in tasks {
+                let before = task.clone();
+                let next_id = db.complete_task(&mut task)?;
+                changes.push(undo::Change::update(before, task));
+                if let Some(next_id) = next_id {
+                    changes.push(undo::Change::create(db.get_task(next_id)?));
+                }
+            }
+            Ok(changes)
+        })?;
+        self.finish_batch("批量完成", count, changes)?;
+        if !blocked.is_empty() {
+            self.set_status_message(format!("批量完成: {} 个任务，🔒 跳过 {} 个被依赖阻塞的任务（u 撤销）", count, blocked.len()));
+        }
+        Ok(())
+    }
+
+    /// 可视模式：归档选中的任务
+    pub fn batch_archive(&mut self) -> Result<()> {
+        let now = Utc::now();
+        self.batch_update("批量归档", |task| task.archived_at = Some(now))
+    }
+
+    /// 可视模式：设置选中任务的优先级
+    pub fn batch_set_priority(&mut self, priority: Priority) -> Result<()> {
+        self.batch_update("批量设置优先级", |task| task.priority = priority)
+    }
+
+    /// 可视模式：修改选中任务的标签（参数同 :tag，work 添加，-work 移除）
+    pub fn batch_edit_tags(&mut self, args: &[&str]) -> Result<()> {
+        if args.is_empty() {
+            self.set_status_message("用法: :tag work -home（对选中的任务添加/移除标签）".to_string());
+            return Ok(());
+        }
+        self.batch_update("批量修改标签", |task| {
+            for arg in args {
+                match arg.strip_prefix('-') {
+                    Some(tag) => {
+                        task.remove_tag(tag);
+                    }
+                    None => task.add_tag(arg.trim_start_matches('+')),
+                }
+            }
+        })
+    }
+
+    /// 创建新任务
+    pub fn create_task(&mut self) -> Result<()> {
+        if self.input_buffer.is_empty() {
+            return Ok(());
+        }
+
+        let mut task = Task::new(crate::models::expand_placeholders(self.input_buffer.trim()));
+        self.config.apply_rules(&mut task);
+        if !self.check_valid(task.validate()) {
+            return Ok(());
+        }
+        let db = &self.db;
+        let id = db.create_task_in_project(&task, self.config.rule_project(&task.title))?;
+
+        self.input_buffer.clear();
+        self.cursor_position = 0;
+        self.show_dialog = DialogType::None;
+        self.input_mode = InputMode::Normal;
+        self.reload_data()?;
+        self.set_status_message(format!("任务 #{} 已创建", id));
+
+        Ok(())
+    }
+
+    /// 快速过滤（z 前缀）：zt 待办、zi 进行中、zw 等待中、zc 已完成、zp 切换项目、zz 清除
+    /// 返回按键是否被识别
+    pub fn quick_filter(&mut self, c: char) -> Result<bool> {
+        if c == 'p' {
+            self.cycle_project_filter()?;
+            return Ok(true);
+        }
+        let status = match c {
+            't' => Some(TaskStatus::Todo),
+            'i' => Some(TaskStatus::InProgress),
+            'w' => Some(TaskStatus::Waiting),
+            'c' => Some(TaskStatus::Completed),
+            'z' => None,
+            _ => return Ok(false),
+        };
+
+        match status {
+            Some(status) => self.task_filter.status = Some(status),
+            None => self.task_filter.clear(),
+        }
+        self.reload_data()?;
+        if self.task_filter.is_active() {
+            self.set_status_message(format!("过滤: {}（{} 项，zz 清除）", self.task_filter.describe(), self.tasks.len()));
+        } else {
+            self.set_status_message("已清除过滤".to_string());
+        }
+        Ok(true)
+    }
+
+    /// 完成选中任务，并可安排一个在 follow_up 时提醒的跟进任务（:done）
+    pub fn complete_with_follow_up(
+        &mut self,
+        follow_up: Option<chrono::DateTime<Utc>>,
+        title: Option<&str>,
+    ) -> Result<()> {
+        let task = match self.selected_task() {
+            Some(task) => task.clone(),
+            None => return Ok(()),
+        };
+        if !self.check_unblocked(&task)? {
+            return Ok(());
+        }
+        let db = &self.db;
+
+        let mut next_id = None;
+        let mut changes = Vec::new();
+        if task.status != TaskStatus::Completed {
+            let mut done = task.clone();
+            next_id = db.complete_task(&mut done)?;
+            changes.push(undo::Change::update(task.clone(), done));
+            if let Some(next_id) = next_id {
+                changes.push(undo::Change::create(db.get_task(next_id)?));
+            }
+        }
+
+        let message = match follow_up {
+            Some(at) => {
+                let follow = task.follow_up_task(title, at);
+                if !self.check_valid(follow.validate()) {
+                    return Ok(());
+                }
+                let db = &self.db;
+                let id = db.create_task(&follow)?;
+                changes.push(undo::Change::create(db.get_task(id)?));
+                if let Some(task_id) = task.id {
+                    db.add_comment(&Comment::new(task_id, format!("已安排跟进任务 #{}", id)))?;
+                }
+                format!(
+                    "✅ 已完成，跟进任务 #{} 将于 {} 提醒",
+                    id,
+                    at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                )
+            }
+            None => "✅ 任务已完成".to_string(),
+        };
+        let message = match next_id {
+            Some(next_id) => format!("{}，已创建下一次 #{}", message, next_id),
+            None => message,
+        };
+        self.history.record("完成任务", changes);
+        self.reload_data()?;
+        self.set_status_message(message);
+        Ok(())
+    }
+
+    /// 开始分诊：为未安排的任务生成建议（高优先级建议在前）
+    pub fn start_triage(&mut self) -> Result<()> {
+        let db = &self.db;
+        let now = Utc::now();
+        let mut queue: Vec<triage::Proposal> = db
+            .get_all_tasks()?
+            .iter()
+            .filter_map(|task| triage::propose(task, now))
+            .collect();
+        queue.sort_by_key(|p| std::cmp::Reverse(p.priority as i32));
+
+        if queue.is_empty() {
+            self.set_status_message("✅ 没有需要分诊的任务".to_string());
+            return Ok(());
+        }
+        self.triage_queue = queue;
+        self.triage_accepted = 0;
+        self.show_dialog = DialogType::Triage;
+        Ok(())
+    }
+
+    /// 处理当前建议：accept 为 false 时跳过，priority 为用户修改后的优先级
+    pub fn triage_next(&mut self, accept: bool, priority: Option<Priority>) -> Result<()> {
+        if self.triage_queue.is_empty() {
+            return Ok(());
+        }
+        let proposal = self.triage_queue.remove(0);
+        if accept {
+            let db = &self.db;
+            let mut task = db.get_task(proposal.task_id)?;
+            task.priority = priority.unwrap_or(proposal.priority);
+            task.due_date = proposal.due_date;
+            task.updated_at = Utc::now();
+            db.update_task(&task)?;
+            self.triage_accepted += 1;
+        }
+
+        if self.triage_queue.is_empty() {
+            self.finish_triage()?;
+        }
+        Ok(())
+    }
+
+    /// 结束分诊（剩余建议不再处理）
+    pub fn finish_triage(&mut self) -> Result<()> {
+        self.triage_queue.clear();
+        self.show_dialog = DialogType::None;
+        self.reload_data()?;
+        self.set_status_message(format!("分诊结束：已更新 {} 个任务", self.triage_accepted));
+        Ok(())
+    }
+
+    /// 将选中任务标记为等待中（follow_up 为跟进时间），或结束等待（:wait off）
+    pub fn set_task_waiting(&mut self, waiting: bool, follow_up: Option<chrono::DateTime<Utc>>) -> Result<()> {
+        let (task, db) = self.selected_task_and_db();
+        let task = match task {
+            Some(task) => task,
+            None => return Ok(()),
+        };
+        if waiting {
+            task.set_waiting(follow_up);
+        } else {
+            task.clear_waiting();
+        }
+        task.updated_at = Utc::now();
+        db.update_task(task)?;
+
+        let message = match (waiting, follow_up) {
+            (true, Some(t)) => format!(
+                "⏳ 等待中，{} 提醒跟进",
+                t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            ),
+            (true, None) => "⏳ 已标记为等待中".to_string(),
+            (false, _) => "已结束等待".to_string(),
+        };
+        self.set_status_message(message);
+        self.auto_sort_tasks();
+        Ok(())
+    }
+
+    /// 修改选中任务的依赖：:blocked-by 3 -5 让选中任务依赖 #3、不再依赖 #5；
+    /// blocks 为 true 时（:blocks）方向相反，让 #3 依赖选中的任务。不带参数时显示当前依赖
+    pub fn edit_task_dependencies(&mut self, args: &[&str], blocks: bool) -> Result<()> {
+        let task_id = match self.selected_task().and_then(|t| t.id) {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+        for arg in args {
+            let (remove, id) = match arg.strip_prefix('-') {
+                Some(id) => (true, id),
+                None => (false, *arg),
+            };
+            let other = match id.trim_start_matches('#').parse::<i64>() {
+                Ok(other) => other,
+                Err(_) => {
+                    let usage = if blocks { "用法: :blocks <任务ID> [-任务ID]" } else { "用法: :blocked-by <任务ID> [-任务ID]" };
+                    self.set_status_message(usage.to_string());
+                    return Ok(());
+                }
+            };
+            let (task, depends_on) = if blocks { (other, task_id) } else { (task_id, other) };
+            if remove {
+                self.db.remove_dependency(task, depends_on)?;
+            } else {
+                let result = self.db.add_dependency(task, depends_on);
+                if !self.check_valid(result) {
+                    self.reload_data()?;
+                    return Ok(());
+                }
+            }
+        }
+        if !args.is_empty() {
+            self.reload_data()?;
+        }
+
+        let ids = |tasks: Vec<Task>| {
+            if tasks.is_empty() {
+                "无".to_string()
+            } else {
+                tasks.iter().map(|t| format!("#{}", t.id.unwrap_or(0))).collect::<Vec<_>>().join(" ")
+            }
+        };
+        let message = format!(
+            "#{} 依赖: {} | 被依赖: {}",
+            task_id,
+            ids(self.db.get_dependencies(task_id)?),
+            ids(self.db.get_dependents(task_id)?)
+        );
+        self.set_status_message(message);
+        Ok(())
+    }
+
+    /// 设置当前任务的预计耗时（分钟），None 表示清除
+    pub fn set_task_estimate(&mut self, minutes: Option<i64>) -> Result<()> {
+        let buffer = chrono::Duration::minutes(self.config.start_nag.buffer_minutes.max(0));
+        let (task, db) = self.selected_task_and_db();
+        let task = match task {
+            Some(task) => task,
+            None => return Ok(()),
+        };
+        task.estimate_minutes = minutes;
+        task.updated_at = Utc::now();
+        db.update_task(task)?;
+
+        let message = match (minutes, task.latest_start(buffer)) {
+            (Some(m), Some(start)) => format!(
+                "预计耗时 {}，最晚 {} 开始",
+                crate::models::format_estimate(m),
+                start.with_timezone(&chrono::Local).format("%m-%d %H:%M")
+            ),
+            (Some(m), None) => format!("预计耗时 {}（设置DDL后会提醒最晚开始时间）", crate::models::format_estimate(m)),
+            (None, _) => "已清除预计耗时".to_string(),
+        };
+        self.set_status_message(message);
+        Ok(())
+    }
+
+    /// 修改选中任务的标签（:tag）：work 添加，-work 移除，不带参数时显示当前标签
+    pub fn edit_task_tags(&mut self, args: &[&str]) -> Result<()> {
+        let (task, db) = self.selected_task_and_db();
+        let task = match task {
+            Some(task) => task,
+            None => return Ok(()),
+        };
+        if args.is_empty() {
+            let message = if task.tags.is_empty() {
+                "没有标签（:tag work 添加，:tag -work 移除）".to_string()
+            } else {
+                format!("标签: {}", task.tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" "))
+            };
+            self.set_status_message(message);
+            return Ok(());
+        }
+
+        for arg in args {
+            match arg.strip_prefix('-') {
+                Some(tag) => {
+                    task.remove_tag(tag);
+                }
+                None => task.add_tag(arg.trim_start_matches('+')),
+            }
+        }
+        task.updated_at = Utc::now();
+        db.update_task(task)?;
+        let message = if task.tags.is_empty() {
+            "已清除全部标签".to_string()
+        } else {
+            format!("标签: {}", task.tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" "))
+        };
+        self.reload_data()?;
+        self.set_status_message(message);
+        Ok(())
+    }
+
+    /// 按项目过滤任务列表（:project 名称），None 时清除项目过滤
+    pub fn filter_by_project(&mut self, name: Option<&str>) -> Result<()> {
+        let project = match name {
+            Some(name) => match self.projects.iter().find(|p| p.name == name.trim()) {
+                Some(project) => Some(project.clone()),
+                None => {
+                    self.set_status_message(format!("项目不存在: {}（:assign {} 可创建并归入）", name, name));
+                    return Ok(());
+                }
+            },
+            None => None,
+        };
+        self.task_filter.project = project;
+        self.show_filter_status()
+    }
+
+    /// zp：在未归档的项目之间依次切换过滤，最后一个之后回到不过滤
+    pub fn cycle_project_filter(&mut self) -> Result<()> {
+        let active: Vec<&Project> = self.projects.iter().filter(|p| !p.archived).collect();
+        if active.is_empty() {
+            self.set_status_message("还没有项目（:assign 名称 或 tasks project add 名称）".to_string());
+            return Ok(());
+        }
+        let current = self.task_filter.project.as_ref().and_then(|p| p.id);
+        let next = match active.iter().position(|p| p.id == current) {
+            Some(i) => active.get(i + 1),
+            None => active.first(),
+        };
+        self.task_filter.project = next.map(|p| (*p).clone());
+        self.show_filter_status()
+    }
+
+    /// 把选中任务归入项目（项目不存在时创建），None 时移出项目
+    pub fn assign_project(&mut self, name: Option<&str>) -> Result<()> {
+        let db = &self.db;
+        let (project_id, created) = match name {
+            Some(name) => match db.find_project(name)? {
+                Some(project) => (project.id, false),
+                None => match db.create_project(name) {
+                    Ok(id) => (Some(id), true),
+                    Err(e) => {
+                        self.set_status_message(e.to_string());
+                        return Ok(());
+                    }
+                },
+            },
+            None => (None, false),
+        };
+        let (task, db) = self.selected_task_and_db();
+        let task = match task {
+            Some(task) => task,
+            None => return Ok(()),
+        };
+        task.project_id = project_id;
+        task.updated_at = Utc::now();
+        db.update_task(task)?;
+
+        self.reload_data()?;
+        self.set_status_message(match (name, created) {
+            (Some(name), true) => format!("已创建项目 📁{} 并归入", name.trim()),
+            (Some(name), false) => format!("已归入项目 📁{}", name.trim()),
+            (None, _) => "已移出项目".to_string(),
+        });
+        Ok(())
+    }
+
+    /// 重新加载并在状态栏显示当前过滤条件
+    fn show_filter_status(&mut self) -> Result<()> {
+        self.reload_data()?;
+        if self.task_filter.is_active() {
+            self.set_status_message(format!("过滤: {}（{} 项，zz 清除）", self.task_filter.describe(), self.tasks.len()));
+        } else {
+            self.set_status_message("已清除过滤".to_string());
+        }
+        Ok(())
+    }
+
+    /// 按标签过滤任务列表（:filter #work），不带参数时清除标签过滤
+    pub fn filter_by_tag(&mut self, tag: Option<&str>) -> Result<()> {
+        self.task_filter.tag = tag
+            .map(|t| t.trim().trim_start_matches('#').to_string())
+            .filter(|t| !t.is_empty());
+        self.show_filter_status()
+    }
+
+    /// 设置或清除选中任务的重复规则（:repeat）
+    pub fn set_task_recurrence(&mut self, recurrence: Option<Recurrence>) -> Result<()> {
+        let (task, db) = self.selected_task_and_db();
+        let task = match task {
+            Some(task) => task,
+            None => return Ok(()),
+        };
+        task.recurrence = recurrence;
+        task.updated_at = Utc::now();
+        db.update_task(task)?;
+
+        let message = match (recurrence, task.due_date) {
+            (Some(rule), Some(_)) => format!("🔁 {}重复，完成后自动创建下一次", rule.describe()),
+            (Some(rule), None) => format!("🔁 {}重复（没有DDL，下一次从完成时间起算）", rule.describe()),
+            (None, _) => "已取消重复".to_string(),
+        };
+        self.set_status_message(message);
+        Ok(())
+    }
+
+    /// 跳转到截止日期在指定日期当天或之后的第一个任务（:goto）
+    pub fn goto_date(&mut self, date: chrono::NaiveDate) {
+        let target = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| t.due_date.map(|due| (i, due.with_timezone(&chrono::Local).date_naive())))
+            .filter(|(_, day)| *day >= date)
+            .min_by_key(|(i, day)| (*day, *i));
+
+        match target {
+            Some((index, day)) => {
+                self.task_list_state.select(Some(index));
+                self.set_status_message(format!("跳转到 {} 的任务", day.format("%Y-%m-%d")));
+            }
+            None => self.set_status_message(format!("{} 之后没有截止的任务", date.format("%Y-%m-%d"))),
+        }
+    }
+
+    /// 日历中移动选中日期（h/l 一天，j/k 一周），跨月时重新加载该月的任务
+    pub fn calendar_move_days(&mut self, days: i64) -> Result<()> {
+        let date = self.calendar_date + chrono::Duration::days(days);
+        self.calendar_select(date)
+    }
+
+    /// 日历中切换月份（[ / ]）
+    pub fn calendar_move_months(&mut self, months: i32) -> Result<()> {
+        let date = calendar_view::shift_month(self.calendar_date, months);
+        self.calendar_select(date)
+    }
+
+    fn calendar_select(&mut self, date: chrono::NaiveDate) -> Result<()> {
+        let month_changed = (date.year(), date.month()) != (self.calendar_date.year(), self.calendar_date.month());
+        self.calendar_date = date;
+        if month_changed {
+            self.reload_data()?;
+        }
+        Ok(())
+    }
+
+    /// 日历中按 Enter：切换到任务列表并跳转到选中日期
+    pub fn calendar_open_day(&mut self) {
+        self.current_tab = 0;
+        self.goto_date(self.calendar_date);
+    }
+
+    /// 将便签从 from 移动到 to（手动排序，保存到数据库）
+    pub fn move_note(&mut self, from: usize, to: usize) -> Result<()> {
+        if from >= self.notes.len() || to >= self.notes.len() || from == to {
+            return Ok(());
+        }
+
+        let note = self.notes.remove(from);
+        self.notes.insert(to, note);
+        let ids: Vec<i64> = self.notes.iter().filter_map(|n| n.id).collect();
+
+        let db = &self.db;
+        db.reorder_notes(&ids)?;
+        self.reload_data()?;
+        self.note_list_state.select(Some(to));
+        self.set_status_message(format!("便签已移动到第 {} 位", to + 1));
+        Ok(())
+    }
+
+    /// 归档/取消归档当前便签
+    pub fn toggle_note_archived(&mut self) -> Result<()> {
+        let mut note = match self.selected_note() {
+            Some(note) => note.clone(),
+            None => return Ok(()),
+        };
+
+        note.archived = !note.archived;
+        if !note.archived {
+            // 取消归档时清除已过的到期时间，避免立即被再次归档
+            if note.expires_at.is_some_and(|t| t <= Utc::now()) {
+                note.expires_at = None;
+            }
+        }
+        note.updated_at = Utc::now();
+        let db = &self.db;
+        db.update_note(&note)?;
+        self.reload_data()?;
+        self.set_status_message(if note.archived {
+            format!("便签 \"{}\" 已归档（:archived 查看）", note.title)
+        } else {
+            format!("便签 \"{}\" 已取消归档", note.title)
+        });
+        Ok(())
+    }
+
+    /// 切换便签墙/归档便签视图
+    pub fn toggle_archived_view(&mut self) -> Result<()> {
+        self.show_archived_notes = !self.show_archived_notes;
+        self.current_tab = 1;
+        self.note_list_state.select(Some(0));
+        self.note_scroll_offset = 0;
+        self.reload_data()?;
+        self.set_status_message(if self.show_archived_notes {
+            "归档便签（:archived 返回便签墙）".to_string()
+        } else {
+            "便签墙".to_string()
+        });
+        Ok(())
+    }
+
+    /// 按标签过滤便签墙（:filter #idea），不带参数时清除过滤
+    pub fn filter_notes_by_tag(&mut self, tag: Option<&str>) -> Result<()> {
+        self.note_tag_filter = tag
+            .map(|t| t.trim().trim_start_matches('#').to_string())
+            .filter(|t| !t.is_empty());
+        self.note_list_state.select(Some(0));
+        self.note_scroll_offset = 0;
+        self.reload_data()?;
+        self.set_status_message(match &self.note_tag_filter {
+            Some(tag) => format!("便签过滤: #{}（{} 条，:filter 清除）", tag, self.notes.len()),
+            None => "已清除便签过滤".to_string(),
+        });
+        Ok(())
+    }
+
+    /// 修改当前便签的标签（:tag）：idea 添加，-idea 移除，不带参数时显示当前标签
+    pub fn edit_note_tags(&mut self, args: &[&str]) -> Result<()> {
+        let mut note = match self.selected_note() {
+            Some(note) => note.clone(),
+            None => return Ok(()),
+        };
+        if args.is_empty() {
+            let message = if note.tags.is_empty() {
+                "没有标签（:tag idea 添加，:tag -idea 移除）".to_string()
+            } else {
+                format!("标签: {}", note.tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" "))
+            };
+            self.set_status_message(message);
+            return Ok(());
+        }
+
+        for arg in args {
+            match arg.strip_prefix('-') {
+                Some(tag) => {
+                    note.remove_tag(tag);
+                }
+                None => note.add_tag(arg.trim_start_matches('+')),
+            }
+        }
+        note.updated_at = Utc::now();
+        let db = &self.db;
+        db.update_note(&note)?;
+        self.reload_data()?;
+        self.select_note_by_id(note.id);
+        self.set_status_message(if note.tags.is_empty() {
+            "已清除全部标签".to_string()
+        } else {
+            format!("标签: {}", note.tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" "))
+        });
+        Ok(())
+    }
+
+    /// 设置当前便签的到期时间（到期后由守护进程自动归档）
+    pub fn set_note_expiry(&mut self, expires_at: Option<chrono::DateTime<Utc>>) -> Result<()> {
+        let mut note = match self.selected_note() {
+            Some(note) => note.clone(),
+            None => return Ok(()),
+        };
+
+        note.expires_at = expires_at;
+        note.updated_at = Utc::now();
+        let db = &self.db;
+        db.update_note(&note)?;
+        self.reload_data()?;
+        self.set_status_message(match expires_at {
+            Some(t) => format!(
+                "便签将于 {} 自动归档",
+                t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            ),
+            None => "已取消便签到期时间".to_string(),
+        });
+        Ok(())
+    }
+
+    /// 置顶/取消置顶当前便签（置顶的便签排在便签墙最前面）
+    pub fn toggle_note_pinned(&mut self) -> Result<()> {
+        let mut note = match self.selected_note() {
+            Some(note) => note.clone(),
+            None => return Ok(()),
+        };
+
+        note.pinned = !note.pinned;
+        note.updated_at = Utc::now();
+        let db = &self.db;
+        db.update_note(&note)?;
+        self.reload_data()?;
+        self.select_note_by_id(note.id);
+        self.set_status_message(if note.pinned {
+            format!("📌 便签 \"{}\" 已置顶", note.title)
+        } else {
+            format!("便签 \"{}\" 已取消置顶", note.title)
+        });
+        Ok(())
+    }
+
+    /// 设置当前便签的颜色（None 恢复默认）
+    pub fn set_note_color(&mut self, color: Option<String>) -> Result<()> {
+        let mut note = match self.selected_note() {
+            Some(note) => note.clone(),
+            None => return Ok(()),
+        };
+
+        note.color = color;
+        if !self.check_valid(note.validate()) {
+            return Ok(());
+        }
+        note.updated_at = Utc::now();
+        let db = &self.db;
+        db.update_note(&note)?;
+        self.reload_data()?;
+        self.select_note_by_id(note.id);
+        self.set_status_message(match &note.color {
+            Some(color) => format!("便签颜色已设为 {}", color),
+            None => "已恢复便签默认颜色".to_string(),
+        });
+        Ok(())
+    }
+
+    /// 重新加载后按 id 选中便签（置顶会改变便签的位置）
+    fn select_note_by_id(&mut self, id: Option<i64>) {
+        if let Some(index) = self.notes.iter().position(|n| n.id == id) {
+            self.note_list_state.select(Some(index));
+        }
+    }
+
+    /// 展开/折叠已完成任务分组（za）
+    pub fn toggle_completed_section(&mut self) -> Result<()> {
+        self.show_completed = !self.show_completed;
+        self.reload_data()?;
+        self.set_status_message(if self.show_completed {
+            "已展开已完成任务".to_string()
+        } else {
+            "已折叠已完成任务".to_string()
+        });
+        Ok(())
+    }
+
+    /// 按输入的标题构造新任务：解析快速输入记号、展开占位符，并应用自动标签规则（输入的记号优先）
+    fn new_task_from_input(&self, input: &str) -> Task {
+        let quick = quick_add::QuickAdd::parse(input, chrono::Local::now());
+        let mut task = Task::new(crate::models::expand_placeholders(&quick.title));
+        self.config.apply_rules(&mut task);
+        quick.apply(&mut task);
+        task.due_date = quick.due;
+        task
+    }
+
+    /// 开始创建新任务：解析快速输入记号（!high #标签 @明天 18:00）、展开占位符并校验标题，
+    /// 然后打开DDL选择器（新建任务时强制设定DDL）；输入中已有 @DDL 或 :set require_ddl=off 时直接创建
+    /// 返回标题是否有效
+    pub fn begin_new_task(&mut self, input: &str) -> Result<bool> {
+        let task = self.new_task_from_input(input);
+        if !self.check_valid(task.validate()) {
+            return Ok(false);
+        }
+        if task.due_date.is_some() || !self.settings.require_ddl {
+            self.create_new_task(task)?;
+            return Ok(true);
+        }
+        self.pending_task = Some(task);
+        if let Err(e) = self.load_due_counts(None) {
+            self.report_error("加载任务容量", e);
+        }
+
+        // 初始化datetime picker为当前时间
+        let now = chrono::Local::now();
+        self.datetime_year = now.year();
+        self.datetime_month = now.month();
+        self.datetime_day = now.day();
+        self.datetime_hour = now.hour();
+        self.datetime_minute = now.minute();
+        self.datetime_recurrence = None;
+        self.datetime_picker_field = 0;
+        self.show_dialog = DialogType::SetDeadline;
+        Ok(true)
+    }
+
+    /// 直接保存新任务（不经过DDL选择器），有DDL时附带容量提醒
+    fn create_new_task(&mut self, task: Task) -> Result<()> {
+        let capacity_warning = match task.due_date {
+            Some(due) => {
+                self.load_due_counts(None)?;
+                let (day_count, week_count) = self.planned_load(due.with_timezone(&chrono::Local).date_naive());
+                self.config.capacity.warning(day_count, week_count)
+            }
+            None => None,
+        };
+        let id = self.db.create_task_in_project(&task, self.config.rule_project(&task.title))?;
         self.show_dialog = DialogType::None;
-        self.input_mode = InputMode::Normal;
         self.reload_data()?;
-        self.set_status_message(format!("任务 #{} 已创建", id));
+        let mut message = match task.due_date {
+            Some(due) => format!("任务 #{} 已创建，DDL: {}", id, due.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")),
+            None => format!("任务 #{} 已创建（无DDL）", id),
+        };
+        if let Some(warning) = capacity_warning {
+            message = format!("{} | ⚠️ {}", message, warning);
+        }
+        self.set_status_message(message);
+        crate::ipc::notify_reload_in_background(std::path::Path::new(&self.db_path));
+        Ok(())
+    }
 
+    /// DDL选择器中按 x：新建任务时创建不带DDL的任务，否则清除选中任务的DDL（u 撤销）
+    pub fn apply_no_deadline(&mut self) -> Result<()> {
+        let recurrence = self.datetime_recurrence;
+        if let Some(mut task) = self.pending_task.take() {
+            task.recurrence = recurrence;
+            return self.create_new_task(task);
+        }
+        if let (Some(task), db) = self.selected_task_and_db() {
+            let before = task.clone();
+            task.due_date = None;
+            task.recurrence = recurrence;
+            task.updated_at = Utc::now();
+            db.update_task(task)?;
+            let change = undo::Change::update(before, task.clone());
+            self.history.record("清除DDL", vec![change]);
+            self.set_status_message("已清除DDL（u 撤销）".to_string());
+            crate::ipc::notify_reload_in_background(std::path::Path::new(&self.db_path));
+            self.auto_sort_tasks();
+        }
+        self.show_dialog = DialogType::None;
         Ok(())
     }
 
@@ -487,9 +2278,12 @@ impl App {
                     .to_string();
 
                 if !title.is_empty() && title != "新任务标题" {
-                    let db = Database::open(&self.db_path)?;
-                    let task = Task::new(title);
-                    let id = db.create_task(&task)?;
+                    let task = self.new_task_from_input(&title);
+                    if !self.check_valid(task.validate()) {
+                        return Ok(());
+                    }
+                    let db = &self.db;
+                    let id = db.create_task_in_project(&task, self.config.rule_project(&task.title))?;
                     self.reload_data()?;
                     self.set_status_message(format!("任务 #{} 已创建", id));
                 } else {
@@ -500,7 +2294,7 @@ impl App {
                 self.set_status_message("任务创建已取消".to_string());
             }
             Err(e) => {
-                self.set_status_message(format!("创建失败: {}", e));
+                self.report_error("创建", e);
             }
         }
         Ok(())
@@ -526,9 +2320,13 @@ impl App {
                         let mut updated_task = task.clone();
                         updated_task.title = new_title;
                         updated_task.updated_at = Utc::now();
+                        if !self.check_valid(updated_task.validate()) {
+                            return Ok(());
+                        }
 
-                        let db = Database::open(&self.db_path)?;
+                        let db = &self.db;
                         db.update_task(&updated_task)?;
+                        self.history.record("编辑任务", vec![undo::Change::update(task.clone(), updated_task)]);
 
                         self.reload_data()?;
                         self.set_status_message(format!("任务 #{} 已更新", task.id.unwrap_or(0)));
@@ -538,7 +2336,7 @@ impl App {
                     self.set_status_message("任务编辑已取消".to_string());
                 }
                 Err(e) => {
-                    self.set_status_message(format!("编辑失败: {}", e));
+                    self.report_error("编辑", e);
                 }
             }
         }
@@ -560,8 +2358,11 @@ impl App {
                     let content = parts[1].trim().to_string();
 
                     if !title.is_empty() && title != "标题" && !content.is_empty() && content != "内容" {
-                        let db = Database::open(&self.db_path)?;
                         let note = Note::new(title, content);
+                        if !self.check_valid(note.validate()) {
+                            return Ok(());
+                        }
+                        let db = &self.db;
                         let id = db.create_note(&note)?;
                         self.reload_data()?;
                         self.set_status_message(format!("便签 #{} 已创建", id));
@@ -578,7 +2379,7 @@ impl App {
                 self.set_status_message("便签创建已取消".to_string());
             }
             Err(e) => {
-                self.set_status_message(format!("创建失败: {}", e));
+                self.report_error("创建", e);
             }
         }
         Ok(())
@@ -591,11 +2392,16 @@ impl App {
         }
 
         if let Some(mut task) = self.selected_task().cloned() {
-            task.title = self.input_buffer.clone();
+            let before = task.clone();
+            task.title = self.input_buffer.trim().to_string();
             task.updated_at = chrono::Utc::now();
+            if !self.check_valid(task.validate()) {
+                return Ok(());
+            }
 
-            let db = Database::open(&self.db_path)?;
+            let db = &self.db;
             db.update_task(&task)?;
+            self.history.record("编辑任务", vec![undo::Change::update(before, task.clone())]);
 
             self.input_buffer.clear();
             self.cursor_position = 0;
@@ -608,14 +2414,151 @@ impl App {
         Ok(())
     }
 
-    /// 删除任务
+    /// 归档任务（dd）：不再出现在任务列表和提醒中，可用 :restore 恢复
+    pub fn archive_task(&mut self) -> Result<()> {
+        let before = match self.selected_task() {
+            Some(task) => task.clone(),
+            None => return Ok(()),
+        };
+        let id = before.id.unwrap_or(0);
+        let now = Utc::now();
+        let after = Task { archived_at: Some(now), updated_at: now, ..before.clone() };
+        let db = &self.db;
+        db.update_task(&after)?;
+        self.history.record("归档任务", vec![undo::Change::update(before, after)]);
+        self.reload_data()?;
+        self.set_status_message(format!("任务 #{} 已归档（u 撤销，:restore {} 恢复，:archive 查看归档）", id, id));
+        Ok(())
+    }
+
+    /// 恢复归档的任务；id 为空时恢复归档视图中选中的任务
+    pub fn restore_archived_task(&mut self, id: Option<i64>) -> Result<()> {
+        let id = match id.or_else(|| self.show_archived_tasks.then(|| self.selected_task().and_then(|t| t.id)).flatten()) {
+            Some(id) => id,
+            None => {
+                self.set_status_message("用法: :restore <任务ID>（归档视图中可省略）".to_string());
+                return Ok(());
+            }
+        };
+        let db = &self.db;
+        let before = match db.get_task(id) {
+            Ok(task) => task,
+            // 已删除的任务从回收站恢复
+            Err(Error::NotFound { .. }) => return self.restore_trashed(|item| item.is_task() && item.entity_id == id),
+            Err(e) => return Err(e.into()),
+        };
+        if before.archived_at.is_none() {
+            self.set_status_message(format!("任务 #{} 没有归档", id));
+            return Ok(());
+        }
+        let after = Task { archived_at: None, updated_at: Utc::now(), ..before.clone() };
+        db.update_task(&after)?;
+        self.history.record("恢复任务", vec![undo::Change::update(before, after)]);
+        self.reload_data()?;
+        self.set_status_message(format!("任务 #{} 已恢复", id));
+        Ok(())
+    }
+
+    /// 打开回收站（:trash）
+    pub fn open_trash(&mut self) -> Result<()> {
+        let db = &self.db;
+        self.trash = db.get_trash()?;
+        self.trash_pick = 0;
+        self.show_dialog = DialogType::Trash;
+        Ok(())
+    }
+
+    /// 在回收站中上下移动
+    pub fn move_trash_pick(&mut self, delta: isize) {
+        let max = self.trash.len().saturating_sub(1);
+        self.trash_pick = self.trash_pick.saturating_add_signed(delta).min(max);
+    }
+
+    /// 恢复回收站中第一个满足条件的项目（最近删除的在前）
+    pub fn restore_trashed(&mut self, matches: impl Fn(&TrashItem) -> bool) -> Result<()> {
+        let db = &self.db;
+        let item = match db.get_trash()?.into_iter().find(|item| matches(item)) {
+            Some(item) => item,
+            None => {
+                self.set_status_message("回收站中没有要恢复的项目".to_string());
+                return Ok(());
+            }
+        };
+        let message = match db.restore_from_trash(item.id) {
+            Ok(item) if item.is_task() => format!("任务 #{} \"{}\" 已从回收站恢复", item.entity_id, item.title),
+            Ok(item) => format!("便签 #{} \"{}\" 已从回收站恢复", item.entity_id, item.title),
+            Err(e) => format!("无法恢复: {}", e),
+        };
+        self.reload_data()?;
+        if self.show_dialog == DialogType::Trash {
+            let db = &self.db;
+            self.trash = db.get_trash()?;
+            self.move_trash_pick(0);
+        }
+        self.set_status_message(message);
+        Ok(())
+    }
+
+    /// 恢复回收站中选中的项目
+    pub fn restore_trash_pick(&mut self) -> Result<()> {
+        match self.trash.get(self.trash_pick).map(|item| item.id) {
+            Some(id) => self.restore_trashed(|item| item.id == id),
+            None => Ok(()),
+        }
+    }
+
+    /// 彻底删除归档的任务；days 不为空时只删除归档超过该天数的
+    pub fn purge_archived_tasks(&mut self, days: Option<i64>) -> Result<()> {
+        let before = days.map(|d| Utc::now() - chrono::Duration::days(d));
+        let db = &self.db;
+        let purged = db.purge_archived_tasks(before)?;
+        self.reload_data()?;
+        self.set_status_message(format!("已彻底删除 {} 个归档任务", purged));
+        Ok(())
+    }
+
+    /// 切换任务列表/归档任务视图
+    pub fn toggle_task_archive_view(&mut self) -> Result<()> {
+        self.show_archived_tasks = !self.show_archived_tasks;
+        self.current_tab = 0;
+        self.task_list_state.select(Some(0));
+        self.reload_data()?;
+        self.set_status_message(if self.show_archived_tasks {
+            "归档任务（:restore 恢复，:delete! 移到回收站，:purge 清空，:archive 返回）".to_string()
+        } else {
+            "任务列表".to_string()
+        });
+        Ok(())
+    }
+
+    /// 删除到回收站前确认；:set confirm_delete=off 时直接删除（可从回收站恢复，u 撤销）
+    fn confirm_delete(&mut self) -> Result<()> {
+        if self.settings.confirm_delete {
+            self.show_dialog = DialogType::DeleteConfirm;
+        } else if self.current_tab == 0 {
+            self.delete_task()?;
+        } else {
+            self.delete_note()?;
+        }
+        Ok(())
+    }
+
+    /// 删除任务（移到回收站）
     pub fn delete_task(&mut self) -> Result<()> {
-        if let Some(task) = self.selected_task() {
+        if let Some(task) = self.selected_task().cloned() {
             if let Some(id) = task.id {
-                let db = Database::open(&self.db_path)?;
-                db.delete_task(id)?;
+                match self.db.trash_task(id) {
+                    Ok(snapshot) => {
+                        self.history.record("删除任务", vec![undo::Change::Delete(Box::new(snapshot))]);
+                        self.set_status_message(format!("任务 #{} 已移到回收站（u 撤销，:trash 查看）", id))
+                    }
+                    // 可能已被 CLI 或其他进程删除，刷新列表即可
+                    Err(Error::NotFound { .. }) => {
+                        self.set_status_message(format!("任务 #{} 不存在，列表已刷新", id))
+                    }
+                    Err(e) => return Err(e.into()),
+                }
                 self.reload_data()?;
-                self.set_status_message(format!("任务 #{} 已删除", id));
             }
         }
         self.show_dialog = DialogType::None;
@@ -628,8 +2571,11 @@ impl App {
             return Ok(());
         }
 
-        let db = Database::open(&self.db_path)?;
-        let note = Note::new(self.input_title.clone(), self.input_buffer.clone());
+        let note = Note::new(self.input_title.trim().to_string(), self.input_buffer.clone());
+        if !self.check_valid(note.validate()) {
+            return Ok(());
+        }
+        let db = &self.db;
         let id = db.create_note(&note)?;
 
         self.input_buffer.clear();
@@ -664,8 +2610,11 @@ impl App {
                         updated_note.title = new_title;
                         updated_note.content = new_content;
                         updated_note.updated_at = Utc::now();
+                        if !self.check_valid(updated_note.validate()) {
+                            return Ok(());
+                        }
 
-                        let db = Database::open(&self.db_path)?;
+                        let db = &self.db;
                         db.update_note(&updated_note)?;
 
                         self.reload_data()?;
@@ -676,7 +2625,7 @@ impl App {
                     self.set_status_message("便签编辑已取消".to_string());
                 }
                 Err(e) => {
-                    self.set_status_message(format!("编辑失败: {}", e));
+                    self.report_error("编辑", e);
                 }
             }
         }
@@ -686,11 +2635,14 @@ impl App {
     /// 保存编辑后的便签
     pub fn save_edit_note(&mut self) -> Result<()> {
         if let Some(mut note) = self.selected_note().cloned() {
-            note.title = self.input_title.clone();
+            note.title = self.input_title.trim().to_string();
             note.content = self.input_content.clone();
             note.updated_at = chrono::Utc::now();
+            if !self.check_valid(note.validate()) {
+                return Ok(());
+            }
 
-            let db = Database::open(&self.db_path)?;
+            let db = &self.db;
             db.update_note(&note)?;
 
             self.input_buffer.clear();
@@ -707,19 +2659,92 @@ impl App {
         Ok(())
     }
 
-    /// 删除便签
+    /// 删除便签（移到回收站）
     pub fn delete_note(&mut self) -> Result<()> {
-        if let Some(note) = self.selected_note() {
+        if let Some(note) = self.selected_note().cloned() {
             if let Some(id) = note.id {
-                let db = Database::open(&self.db_path)?;
-                db.delete_note(id)?;
+                let db = &self.db;
+                match db.trash_note(id) {
+                    Ok(()) => {
+                        self.history.record("删除便签", vec![undo::Change::DeleteNote(Box::new(note))]);
+                        self.set_status_message(format!("便签 #{} 已移到回收站（u 撤销，:trash 查看）", id))
+                    }
+                    Err(Error::NotFound { .. }) => {
+                        self.set_status_message(format!("便签 #{} 不存在，列表已刷新", id))
+                    }
+                    Err(e) => return Err(e.into()),
+                }
                 self.reload_data()?;
-                self.set_status_message(format!("便签 #{} 已删除", id));
             }
         }
+        self.show_dialog = DialogType::None;
+        Ok(())
+    }
+
+    /// 切换到另一个 profile（使用其独立的数据库和配置）
+    pub fn switch_profile(&mut self, name: &str) -> Result<()> {
+        if self.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
+            self.set_status_message("番茄钟运行中，无法切换 profile！先用:c取消".to_string());
+            return Ok(());
+        }
+
+        // 只切换到已有的 profile，避免打错名称时新建一个空数据库
+        if name != "default" && !crate::config::list_profiles()?.iter().any(|p| p == name) {
+            self.set_status_message(format!(
+                "profile 不存在: {}（用 tasks profile create {} 创建）",
+                name, name
+            ));
+            return Ok(());
+        }
+
+        let profile = if name == "default" {
+            None
+        } else {
+            Some(name.to_string())
+        };
+        let db_path = crate::config::db_path(profile.as_deref())?;
+        self.db = Database::open(&db_path)?;
+        self.data_version = None;
+        self.config = Config::load(profile.as_deref())?;
+        self.db_path = db_path.to_string_lossy().to_string();
+        self.profile = profile;
+        self.load_settings()?;
+        // 撤销记录属于原来的数据库
+        self.history = undo::History::default();
+
+        self.task_list_state.select(Some(0));
+        self.note_list_state.select(Some(0));
+        self.reload_data()?;
+        self.set_status_message(format!("已切换到 profile: {}", name));
+        Ok(())
+    }
+
+    /// 打开 profile 切换框（:workspace），默认选中当前 profile
+    pub fn open_workspace_picker(&mut self) -> Result<()> {
+        self.workspaces = vec!["default".to_string()];
+        self.workspaces.extend(crate::config::list_profiles()?);
+        let current = self.profile.as_deref().unwrap_or("default");
+        self.workspace_pick = self.workspaces.iter().position(|p| p == current).unwrap_or(0);
+        self.show_dialog = DialogType::PickWorkspace;
         Ok(())
     }
 
+    /// 在 profile 切换框中上下移动
+    pub fn move_workspace_pick(&mut self, delta: isize) {
+        let max = self.workspaces.len().saturating_sub(1);
+        self.workspace_pick = self.workspace_pick.saturating_add_signed(delta).min(max);
+    }
+
+    /// 切换到选中的 profile
+    pub fn confirm_workspace_pick(&mut self) {
+        self.show_dialog = DialogType::None;
+        if let Some(name) = self.workspaces.get(self.workspace_pick).cloned() {
+            if let Err(e) = self.switch_profile(&name) {
+                self.report_error("切换", e);
+            }
+        }
+    }
+
     /// 使用系统 vim 编辑文本
     pub fn edit_with_vim(&mut self, initial_content: &str) -> Result<Option<String>> {
         // 生成唯一的临时文件名
@@ -734,8 +2759,12 @@ impl App {
         execute!(io::stdout(), LeaveAlternateScreen, crossterm::cursor::Show)?;
         io::stdout().flush()?;
 
-        // 调用 vim
-        let status = Command::new("vim")
+        // 调用 vim（简单模式下优先使用 $EDITOR，未设置时使用 nano）
+        let editor = match self.config.ui_mode {
+            UiMode::Vim => "vim".to_string(),
+            UiMode::Simple => std::env::var("EDITOR").unwrap_or_else(|_| "nano".to_string()),
+        };
+        let status = Command::new(editor)
             .arg(&temp_file)
             .status()?;
 
@@ -777,48 +2806,188 @@ impl App {
         Ok(Some(edited_content))
     }
 
+    /// 撤销上一步任务修改（u）
+    pub fn undo(&mut self) -> Result<()> {
+        let db = &self.db;
+        let message = match self.history.undo(&db) {
+            Ok(Some(label)) => format!("已撤销: {}（Ctrl-r 重做）", label),
+            Ok(None) => "没有可撤销的操作".to_string(),
+            // 任务可能已被 CLI 或其他进程修改/删除，这一步已做的修改全部回滚
+            Err(e) => format!("无法撤销: {}", e),
+        };
+        self.reload_data()?;
+        self.set_status_message(message);
+        Ok(())
+    }
+
+    /// 重做上一步撤销的修改（Ctrl-r）
+    pub fn redo(&mut self) -> Result<()> {
+        let db = &self.db;
+        let message = match self.history.redo(&db) {
+            Ok(Some(label)) => format!("已重做: {}", label),
+            Ok(None) => "没有可重做的操作".to_string(),
+            Err(e) => format!("无法重做: {}", e),
+        };
+        self.reload_data()?;
+        self.set_status_message(message);
+        Ok(())
+    }
+
     /// 循环切换任务优先级
     pub fn cycle_priority(&mut self) -> Result<()> {
-        let db_path = self.db_path.clone();
-
-        if let Some(task) = self.selected_task_mut() {
+        if let (Some(task), db) = self.selected_task_and_db() {
+            let before = task.clone();
             task.priority = match task.priority {
                 Priority::Low => Priority::Medium,
                 Priority::Medium => Priority::High,
                 Priority::High => Priority::Low,
             };
             task.updated_at = Utc::now();
-
-            let db = Database::open(&db_path)?;
             db.update_task(task)?;
+            let change = undo::Change::update(before, task.clone());
+            self.history.record("修改优先级", vec![change]);
             self.set_status_message("优先级已更新".to_string());
         }
 
         // 立即重新排序
-        self.sort_tasks();
+        self.auto_sort_tasks();
         Ok(())
     }
 
     /// 初始化日期时间选择器 (设置为当前选中任务的deadline，或当前时间)
     pub fn init_datetime_picker(&mut self) {
-        if let Some(task) = self.selected_task() {
-            if let Some(due_date) = task.due_date {
-                let local = due_date.with_timezone(&chrono::Local);
-                self.datetime_year = local.year();
-                self.datetime_month = local.month();
-                self.datetime_day = local.day();
-                self.datetime_hour = local.hour();
-                self.datetime_minute = local.minute();
-            } else {
-                let now = chrono::Local::now();
-                self.datetime_year = now.year();
-                self.datetime_month = now.month();
-                self.datetime_day = now.day();
-                self.datetime_hour = now.hour();
-                self.datetime_minute = now.minute();
-            }
+        let task_id = self.selected_task().and_then(|t| t.id);
+        if let Err(e) = self.load_due_counts(task_id) {
+            self.report_error("加载任务容量", e);
+        }
+        if let Some(due_date) = self.selected_task().map(|t| t.due_date) {
+            self.set_datetime_fields(due_date.map_or_else(chrono::Local::now, |d| d.with_timezone(&chrono::Local)));
         }
+        self.datetime_recurrence = self.selected_task().and_then(|t| t.recurrence);
+        self.datetime_picker_field = 0;
+    }
+
+    /// 把日期时间选择器的各字段设为指定时间
+    fn set_datetime_fields(&mut self, local: chrono::DateTime<chrono::Local>) {
+        self.datetime_year = local.year();
+        self.datetime_month = local.month();
+        self.datetime_day = local.day();
+        self.datetime_hour = local.hour();
+        self.datetime_minute = local.minute();
+    }
+
+    /// 打开提醒时间选择器：已有提醒时显示原时间，否则默认截止前 30 分钟（没有DDL时为当前时间）
+    pub fn init_reminder_picker(&mut self) {
+        let task = match self.selected_task() {
+            Some(task) => task,
+            None => return,
+        };
+        let initial = task
+            .reminder_time
+            .or_else(|| task.due_date.map(|due| due - chrono::Duration::minutes(30)))
+            .unwrap_or_else(Utc::now)
+            .with_timezone(&chrono::Local);
+        self.datetime_year = initial.year();
+        self.datetime_month = initial.month();
+        self.datetime_day = initial.day();
+        self.datetime_hour = initial.hour();
+        self.datetime_minute = initial.minute();
         self.datetime_picker_field = 0;
+        self.datetime_input_buffer.clear();
+        self.show_dialog = DialogType::SetReminder;
+    }
+
+    /// 保存提醒时间选择器中的时间
+    pub fn apply_reminder(&mut self) -> Result<()> {
+        let local_dt = chrono::Local
+            .with_ymd_and_hms(
+                self.datetime_year,
+                self.datetime_month,
+                self.datetime_day,
+                self.datetime_hour,
+                self.datetime_minute,
+                0,
+            )
+            .single();
+        self.show_dialog = DialogType::None;
+        match local_dt {
+            Some(local_dt) => self.set_task_reminder(Some(local_dt.with_timezone(&Utc))),
+            None => {
+                self.set_status_message("无效的日期时间".to_string());
+                Ok(())
+            }
+        }
+    }
+
+    /// 设置或清除选中任务的提醒时间（r / :remind）
+    pub fn set_task_reminder(&mut self, reminder: Option<chrono::DateTime<Utc>>) -> Result<()> {
+        let db_path = self.db_path.clone();
+        let (task, db) = self.selected_task_and_db();
+        let task = match task {
+            Some(task) => task,
+            None => return Ok(()),
+        };
+        task.reminder_time = reminder;
+        task.updated_at = Utc::now();
+        db.update_task(task)?;
+        // 提醒可能就在一两分钟内，让守护进程立即重新检查
+        crate::ipc::notify_reload_in_background(std::path::Path::new(&db_path));
+
+        let message = match (reminder, task.due_date) {
+            (None, _) => "已清除提醒".to_string(),
+            (Some(at), _) if at <= Utc::now() => "⚠️ 提醒时间已过，不会再发送提醒".to_string(),
+            (Some(at), due) => {
+                let mut message = format!("⏰ 将于 {} 提醒", at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+                if let Some(due) = due.filter(|due| *due > at) {
+                    message.push_str(&format!("（截止前 {}）", crate::models::format_estimate((due - at).num_minutes())));
+                }
+                message
+            }
+        };
+        self.set_status_message(message);
+        Ok(())
+    }
+
+    /// 按步长推迟/提前选中任务的DDL（> / < 键、:defer），不打开选择器
+    pub fn defer_task(&mut self, step: crate::models::Defer) -> Result<()> {
+        let db_path = self.db_path.clone();
+        let (task, db) = self.selected_task_and_db();
+        let task = match task {
+            Some(task) => task,
+            None => return Ok(()),
+        };
+        if task.status == TaskStatus::Completed {
+            self.set_status_message("已完成的任务不能推迟".to_string());
+            return Ok(());
+        }
+        let before = task.clone();
+        task.defer(step, Utc::now());
+        db.update_task(task)?;
+        let due = task.due_date.unwrap_or_else(Utc::now);
+        let change = undo::Change::update(before, task.clone());
+        self.history.record("推迟DDL", vec![change]);
+        // 提醒随DDL一起移动，让守护进程重新检查
+        crate::ipc::notify_reload_in_background(std::path::Path::new(&db_path));
+        self.set_status_message(format!(
+            "{}，DDL: {}",
+            step.describe(),
+            due.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+        ));
+        self.reload_data()
+    }
+
+    /// 日期时间选择器：在 不重复 → 每天 → 每个工作日 → 每周 → 每月 → 每年 之间切换
+    pub fn datetime_picker_cycle_recurrence(&mut self) {
+        let presets = Recurrence::PRESETS;
+        self.datetime_recurrence = match self.datetime_recurrence {
+            None => Some(presets[0]),
+            Some(current) => match presets.iter().position(|p| *p == current) {
+                Some(i) if i + 1 < presets.len() => Some(presets[i + 1]),
+                Some(_) => None,
+                // 自定义间隔（如每 2 周）切换后回到第一个预设
+                None => Some(presets[0]),
+            },
+        };
     }
 
     /// 日期时间选择器：移动到下一个字段
@@ -999,33 +3168,42 @@ impl App {
         let day = self.datetime_day;
         let hour = self.datetime_hour;
         let minute = self.datetime_minute;
+        let recurrence = self.datetime_recurrence;
 
         // 创建本地时间
         let local_dt = chrono::Local
             .with_ymd_and_hms(year, month, day, hour, minute, 0)
             .single();
 
+        let mut capacity_warning = None;
         if let Some(local_dt) = local_dt {
             let due_date = Some(local_dt.with_timezone(&Utc));
+            let (day_count, week_count) = self.planned_load(local_dt.date_naive());
+            capacity_warning = self.config.capacity.warning(day_count, week_count);
 
             // 检查是否是为新任务设置DDL
-            if let Some(title) = self.pending_task_title.take() {
+            if let Some(mut task) = self.pending_task.take() {
                 // 创建新任务并设置DDL
-                let db = Database::open(&db_path)?;
-                let mut task = Task::new(title);
                 task.due_date = due_date;
-                let id = db.create_task(&task)?;
+                task.recurrence = recurrence;
+                if !self.check_valid(task.validate()) {
+                    self.show_dialog = DialogType::None;
+                    return Ok(());
+                }
+                let id = self.db.create_task_in_project(&task, self.config.rule_project(&task.title))?;
                 self.set_status_message(format!(
                     "任务 #{} 已创建，DDL: {}-{:02}-{:02} {:02}:{:02}",
                     id, year, month, day, hour, minute
                 ));
-            } else if let Some(task) = self.selected_task_mut() {
+            } else if let (Some(task), db) = self.selected_task_and_db() {
                 // 为现有任务设置DDL
+                let before = task.clone();
                 task.due_date = due_date;
+                task.recurrence = recurrence;
                 task.updated_at = Utc::now();
-
-                let db = Database::open(&db_path)?;
                 db.update_task(task)?;
+                let change = undo::Change::update(before, task.clone());
+                self.history.record("设置DDL", vec![change]);
                 self.set_status_message(format!(
                     "DDL已设置: {}-{:02}-{:02} {:02}:{:02}",
                     year, month, day, hour, minute
@@ -1033,69 +3211,352 @@ impl App {
             }
         } else {
             self.set_status_message("无效的日期时间".to_string());
-            // 如果日期无效，清除pending_task_title避免状态混乱
-            self.pending_task_title = None;
+            // 如果日期无效，清除pending_task避免状态混乱
+            self.pending_task = None;
         }
 
+        if let Some(warning) = capacity_warning {
+            if let Some(message) = self.status_message.take() {
+                self.set_status_message(format!("{} | ⚠️ {}", message, warning));
+            }
+        }
+
+        crate::ipc::notify_reload_in_background(std::path::Path::new(&db_path));
+
         // 立即重新排序
-        self.sort_tasks();
+        self.auto_sort_tasks();
         self.show_dialog = DialogType::None;
         Ok(())
     }
 
+    /// 统计各日期截止的未完成任务数（exclude 为正在设置DDL的任务）
+    fn load_due_counts(&mut self, exclude: Option<i64>) -> Result<()> {
+        let db = &self.db;
+        self.due_counts.clear();
+        for task in db.get_all_tasks()? {
+            if task.status == TaskStatus::Completed || (exclude.is_some() && task.id == exclude) {
+                continue;
+            }
+            if let Some(due) = task.due_date {
+                *self.due_counts.entry(due.with_timezone(&chrono::Local).date_naive()).or_insert(0) += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// 把任务安排到 date 后当天和当周（周一至周日）的任务数
+    fn planned_load(&self, date: chrono::NaiveDate) -> (usize, usize) {
+        let day = self.due_counts.get(&date).copied().unwrap_or(0) + 1;
+        let week_start = date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+        let week = self
+            .due_counts
+            .range(week_start..week_start + chrono::Duration::days(7))
+            .map(|(_, count)| count)
+            .sum::<usize>()
+            + 1;
+        (day, week)
+    }
+
     /// 计算 ViewNote 对话框的内容行数（包括长行分割）
     fn calculate_view_note_lines(&self) -> usize {
         if let Some(note) = self.selected_note() {
             let mut total_lines = 5; // 标题(1) + 空行(1) + 分隔线(1) + 空行(2)
 
-            // 计算内容行数，考虑长行分割（使用字符数而非字节数）
-            for line in note.content.lines() {
-                let char_count = line.chars().count();
-                if char_count > 50 {
-                    // 长行分割：每50个字符一行
-                    total_lines += (char_count + 49) / 50; // 向上取整
-                } else {
-                    total_lines += 1;
-                }
+            // 内容行数与显示时一致（Markdown 渲染并换行后的行数）
+            total_lines += markdown::render(&note.content, self.note_wrap_width(), &self.theme).len();
+
+            // 加上尾部信息行（分隔线、时间戳、快捷键等）
+            total_lines += 9; // 分隔线(1) + 空行(1) + 时间戳(2) + 空行(1) + 快捷键说明(2) + 空行(2)
+
+            total_lines
+        } else {
+            0
+        }
+    }
+
+    /// 计算 ViewNote 对话框的最大滚动偏移量
+    pub fn get_view_note_max_scroll(&self) -> usize {
+        let total_lines = self.calculate_view_note_lines();
+        // 动态计算窗口高度，确保即使在小终端也能滚动
+        // 对话框使用 85% 高度（放大时 95%），假设典型终端 25-50 行
+        // 实际可显示行数为：85% * height - 4(边框和边距)
+        // 对于安全起见，使用保守估计 30 行（即使小终端也能显示部分内容）
+        let estimate = if self.note_zoom { 36 } else { 30 };
+        let window_height = estimate.min(total_lines.saturating_sub(5)); // 至少显示 5 行内容
+        total_lines.saturating_sub(window_height)
+    }
+
+    /// 计算帮助对话框的最大滚动偏移量
+    pub fn get_help_max_scroll(&self) -> usize {
+        // 页面内容 + 底部分隔线、操作提示（2行）
+        let help_lines = help::page_lines(self.help_topic, &self.help_search, self.keymap, &self.theme).len() + 2;
+        let window_height: usize = 20; // 对话框可显示的行数
+        help_lines.saturating_sub(window_height)
+    }
+
+    /// 记录一次命令/按键使用（仅保存在本地）
+    fn record_usage(&mut self, name: String) {
+        *self.pending_usage.entry(name).or_insert(0) += 1;
+    }
+
+    /// 将累计的使用次数写入数据库
+    fn flush_usage(&mut self) -> Result<()> {
+        if self.pending_usage.is_empty() {
+            return Ok(());
+        }
+        let counts: Vec<(String, u64)> = std::mem::take(&mut self.pending_usage).into_iter().collect();
+        let db = &self.db;
+        db.add_usage(&counts, Utc::now())?;
+        Ok(())
+    }
+
+    /// 当前番茄钟状态（写入提示符缓存），空闲时为 None
+    fn pomodoro_mark(&self) -> Option<crate::prompt::PomodoroMark> {
+        use crate::pomodoro::PomodoroState;
+        let remaining = self.pomodoro.remaining_seconds as i64;
+        let (state, ends_at) = match self.pomodoro.state {
+            PomodoroState::Idle => return None,
+            PomodoroState::Working => ("work", Some(Utc::now() + chrono::Duration::seconds(remaining))),
+            PomodoroState::Break => ("break", Some(Utc::now() + chrono::Duration::seconds(remaining))),
+            PomodoroState::Paused => ("paused", None),
+        };
+        Some(crate::prompt::PomodoroMark {
+            state: state.to_string(),
+            ends_at,
+            remaining_secs: remaining,
+        })
+    }
+
+    /// 更新 shell 提示符缓存（tasks prompt-segment 读取），tasks 为 None 时保留原有计数
+    ///
+    /// 提示符缓存只是辅助信息，写入失败不影响界面
+    fn write_prompt_segment(&self, tasks: Option<&[Task]>, pomodoro: Option<crate::prompt::PomodoroMark>) {
+        let path = crate::prompt::cache_path(Path::new(&self.db_path));
+        let mut segment = crate::prompt::PromptSegment::load(&path).unwrap_or_default();
+        if let Some(tasks) = tasks {
+            segment.set_counts(tasks, Utc::now());
+        }
+        segment.pomodoro = pomodoro;
+        let _ = segment.save(&path);
+    }
+
+    /// 以二维码显示当前任务
+    fn show_task_qr(&mut self) {
+        let task = match self.selected_task() {
+            Some(task) => task,
+            None => {
+                self.set_status_message("没有选中的任务".to_string());
+                return;
+            }
+        };
+        match crate::qr::render(&crate::qr::task_payload(task)) {
+            Ok(code) => {
+                self.qr_code = code;
+                self.show_dialog = DialogType::Qr;
+            }
+            Err(e) => self.set_status_message(format!("{}", e)),
+        }
+    }
+
+    /// 打开使用统计对话框
+    fn show_usage(&mut self) -> Result<()> {
+        self.flush_usage()?;
+        let db = &self.db;
+        self.usage_stats = db.get_usage()?;
+        self.show_dialog = DialogType::Usage;
+        Ok(())
+    }
+
+    /// 打开帮助，topic 为空时显示当前标签页对应的主题
+    fn open_help(&mut self, topic: Option<HelpTopic>) {
+        self.help_topic = topic.unwrap_or_else(|| HelpTopic::for_tab(self.current_tab));
+        self.help_search.clear();
+        self.help_searching = false;
+        self.help_scroll_offset = 0;
+        self.show_dialog = DialogType::Help;
+    }
+
+    /// 开始工作计时，若本次番茄钟与今天的忙碌时段重叠则在提示中说明
+    ///
+    /// `task` 为绑定的任务（ID 和标题），完成的番茄钟会记录到该任务
+    pub fn start_pomodoro_work(&mut self, task: Option<(i64, String)>) {
+        let now = Utc::now();
+        let end = now + chrono::Duration::minutes(self.pomodoro.work_duration as i64);
+        let conflict = self.busy_today.iter().find(|e| e.overlaps(now, end)).cloned();
+        let (task_id, title) = match task {
+            Some((id, title)) => (Some(id), Some(title)),
+            None => (None, None),
+        };
+        self.pomodoro.start_work(task_id);
+        let started = match &title {
+            Some(title) => format!("番茄钟开始：{}", title),
+            None => "番茄钟开始！".to_string(),
+        };
+        self.pomodoro_task_title = title;
+        match conflict {
+            Some(event) => self.set_status_message(format!(
+                "{} ⚠ 与日程冲突：{} {}",
+                started,
+                event.start.with_timezone(&chrono::Local).format("%H:%M"),
+                event.summary
+            )),
+            None => self.set_status_message(started),
+        }
+    }
+
+    /// 番茄钟开始、暂停、继续或切换阶段后保存到数据库，TUI 退出后下次启动可以继续；
+    /// 询问是否继续上次的番茄钟时不覆盖
+    fn save_pomodoro_state(&mut self) {
+        if self.pending_pomodoro.is_some() {
+            return;
+        }
+        let snapshot = self.pomodoro.snapshot();
+        if snapshot == self.saved_pomodoro {
+            return;
+        }
+        let saved = crate::db::retry_busy(|| self.db.save_pomodoro_state(snapshot.as_ref()));
+        // 失败时也记下，避免每轮循环重复报错
+        self.saved_pomodoro = snapshot;
+        self.report("保存番茄钟状态", saved);
+    }
+
+    /// 继续上次未完成的番茄钟（关闭期间已经结束的阶段会立即按正常流程结束）
+    pub fn resume_saved_pomodoro(&mut self) {
+        self.show_dialog = DialogType::None;
+        let Some(snapshot) = self.pending_pomodoro.take() else {
+            return;
+        };
+        self.pomodoro.restore(&snapshot);
+        self.pomodoro_task_title = snapshot
+            .task_id
+            .and_then(|id| self.tasks.iter().find(|t| t.id == Some(id)))
+            .map(|t| t.title.clone());
+        self.last_tick_time = std::time::Instant::now();
+        self.set_status_message(format!("🍅 继续上次的番茄钟，剩余 {}", self.pomodoro.format_remaining()));
+    }
+
+    /// 放弃上次未完成的番茄钟
+    pub fn discard_saved_pomodoro(&mut self) {
+        self.show_dialog = DialogType::None;
+        if self.pending_pomodoro.take().is_none() {
+            return;
+        }
+        self.saved_pomodoro = None;
+        let saved = crate::db::retry_busy(|| self.db.save_pomodoro_state(None));
+        if self.report("保存番茄钟状态", saved).is_some() {
+            self.set_status_message("已放弃上次的番茄钟".to_string());
+        }
+    }
+
+    /// 保存本轮进度，重启后继续当前一轮
+    fn save_pomodoro_cycle_position(&mut self) {
+        let position = self.pomodoro.completed_in_cycle;
+        let saved = crate::db::retry_busy(|| self.db.save_pomodoro_cycle_position(position));
+        self.report("保存番茄钟进度", saved);
+    }
+
+    /// 保存完成的番茄钟并给绑定的任务计数（被其他进程锁定时重试），失败时在状态栏提示
+    fn save_pomodoro_session(&mut self, session: &PomodoroSession) {
+        let db = &self.db;
+        let saved = crate::db::retry_busy(|| db.create_pomodoro(session)).and_then(|_| match session.task_id {
+            Some(task_id) => crate::db::retry_busy(|| db.add_task_pomodoro(task_id)),
+            None => Ok(()),
+        });
+        if self.report("保存番茄钟记录", saved).is_some() {
+            if let Some(task) = self.tasks.iter_mut().find(|t| t.id.is_some() && t.id == session.task_id) {
+                task.pomodoro_count += 1;
+            }
+        }
+    }
+
+    /// 保存番茄钟时长（被其他进程锁定时重试），成功时显示 message，失败时在状态栏提示
+    fn save_pomodoro_config(&mut self, message: String) {
+        let (work, break_time) = (self.pomodoro.work_duration, self.pomodoro.break_duration);
+        let saved = crate::db::retry_busy(|| self.db.save_pomodoro_config(work, break_time));
+        if self.report("保存番茄钟配置", saved).is_some() {
+            self.set_status_message(message);
+        }
+    }
+
+    /// 保存长休息设置，同 save_pomodoro_config
+    fn save_pomodoro_cycle(&mut self, message: String) {
+        let (long_break, cycle_length) = (self.pomodoro.long_break_duration, self.pomodoro.cycle_length);
+        let saved = crate::db::retry_busy(|| self.db.save_pomodoro_cycle(long_break, cycle_length));
+        if self.report("保存番茄钟配置", saved).is_some() {
+            self.set_status_message(message);
+        }
+    }
+
+    /// gs：切换到番茄钟标签页并开始绑定当前任务的番茄钟
+    pub fn start_pomodoro_for_selected_task(&mut self) {
+        if self.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
+            self.set_status_message("番茄钟正在进行中，先按 S 取消".to_string());
+            return;
+        }
+        let task = match self.selected_task() {
+            Some(task) => task,
+            None => {
+                self.set_status_message("没有选中的任务".to_string());
+                return;
             }
+        };
+        let task = task.id.map(|id| (id, task.title.clone()));
+        self.current_tab = 2;
+        self.start_pomodoro_work(task);
+    }
 
-            // 加上尾部信息行（分隔线、时间戳、快捷键等）
-            total_lines += 8; // 分隔线(1) + 空行(1) + 时间戳(1) + 空行(1) + 快捷键说明(2) + 空行(2)
+    /// 可以关联番茄钟的任务：任务列表中未完成的任务
+    pub fn pomodoro_candidates(&self) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|t| t.id.is_some() && t.status != TaskStatus::Completed)
+            .collect()
+    }
 
-            total_lines
-        } else {
-            0
+    /// 番茄钟标签页按 s 开始时先选择关联的任务，默认选中任务列表中当前的任务
+    pub fn open_pomodoro_picker(&mut self) {
+        let candidates = self.pomodoro_candidates();
+        if candidates.is_empty() {
+            self.start_pomodoro_work(None);
+            return;
         }
+        let selected_id = self.selected_task().and_then(|t| t.id);
+        self.pomodoro_pick = candidates
+            .iter()
+            .position(|t| t.id == selected_id)
+            .map_or(0, |i| i + 1);
+        self.show_dialog = DialogType::PickPomodoroTask;
     }
 
-    /// 计算 ViewNote 对话框的最大滚动偏移量
-    pub fn get_view_note_max_scroll(&self) -> usize {
-        let total_lines = self.calculate_view_note_lines();
-        // 动态计算窗口高度，确保即使在小终端也能滚动
-        // 对话框使用 85% 高度，假设典型终端 25-50 行
-        // 实际可显示行数为：85% * height - 4(边框和边距)
-        // 对于安全起见，使用保守估计 30 行（即使小终端也能显示部分内容）
-        let window_height = 30.min(total_lines.saturating_sub(5)); // 至少显示 5 行内容
-        total_lines.saturating_sub(window_height)
+    /// 在任务选择框中上下移动
+    pub fn move_pomodoro_pick(&mut self, delta: isize) {
+        let max = self.pomodoro_candidates().len();
+        self.pomodoro_pick = self.pomodoro_pick.saturating_add_signed(delta).min(max);
     }
 
-    /// 计算帮助对话框的最大滚动偏移量
-    pub fn get_help_max_scroll(&self) -> usize {
-        // 每个标签页的帮助内容行数（实际统计）
-        let help_lines: usize = match self.current_tab {
-            0 => 36,  // 任务管理帮助（导航4行+任务操作6行+命令模式7行+分隔线+提示）
-            1 => 30,  // 便签墙帮助
-            2 => 25,  // 番茄钟帮助
-            _ => 20,
+    /// 确认选择并开始番茄钟
+    pub fn confirm_pomodoro_pick(&mut self) {
+        let task = match self.pomodoro_pick {
+            0 => None,
+            i => self
+                .pomodoro_candidates()
+                .get(i - 1)
+                .and_then(|t| t.id.map(|id| (id, t.title.clone()))),
         };
-        let window_height: usize = 20; // 对话框可显示的行数
-        help_lines.saturating_sub(window_height)
+        self.show_dialog = DialogType::None;
+        self.start_pomodoro_work(task);
     }
 
     /// 计算番茄钟界面右侧信息面板的最大滚动偏移量
     pub fn get_pomodoro_info_max_scroll(&self) -> usize {
         // 计算右侧信息面板的内容行数
         let mut content_lines: usize = 12; // 基础行数：标题、统计、配置、快捷键等
+        if !self.busy_today.is_empty() {
+            content_lines += self.busy_today.len() + 2; // 今日日程标题、事件和空行
+        }
+        if !self.weekly_goals.is_empty() {
+            content_lines += self.weekly_goals.len() + 2; // 本周目标标题、目标和空行
+        }
 
         // 根据计时器状态添加额外行数
         if self.pomodoro.state == crate::pomodoro::PomodoroState::Idle {
@@ -1110,7 +3571,9 @@ impl App {
 }
 
 /// 运行TUI应用
-pub fn run_app(db_path: String) -> Result<()> {
+///
+/// `explicit_path` 表示数据库路径由 --db-path 指定，文件不存在时进入恢复界面而不是新建
+pub fn run_app(db_path: String, config: Config, profile: Option<String>, explicit_path: bool) -> Result<()> {
     // 设置终端
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -1118,11 +3581,31 @@ pub fn run_app(db_path: String) -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    // 数据库无法打开时先进入恢复界面
+    let (db_path, recovered) = match recovery::probe(Path::new(&db_path), explicit_path) {
+        None => (db_path, None),
+        Some(problem) => match recovery::run(&mut terminal, Path::new(&db_path), problem)? {
+            Some((path, note)) => (path.to_string_lossy().to_string(), Some(note)),
+            None => {
+                disable_raw_mode()?;
+                execute!(terminal.backend_mut(), LeaveAlternateScreen, event::DisableMouseCapture)?;
+                terminal.show_cursor()?;
+                return Ok(());
+            }
+        },
+    };
+
     // 创建应用状态
-    let mut app = App::new(db_path)?;
+    let mut app = App::new(db_path, config, profile)?;
+    if let Some(note) = recovered {
+        app.set_status_message(note);
+    }
 
     // 主循环
     let res = run_ui_loop(&mut terminal, &mut app);
+    let _ = app.flush_usage();
+    // 番茄钟随 TUI 退出而结束，提示符中不再显示
+    app.write_prompt_segment(None, None);
 
     // 恢复终端
     disable_raw_mode()?;
@@ -1149,13 +3632,28 @@ fn run_ui_loop<B: ratatui::backend::Backend>(
             std::thread::sleep(std::time::Duration::from_millis(50));
         }
 
+        app.refresh_task_pane()?;
         terminal.draw(|f| ui(f, app))?;
 
-        // 使用较短的 poll 间隔以提高响应性，但用时间戳控制 tick 频率
-        if event::poll(std::time::Duration::from_millis(100))? {
+        // 使用较短的 poll 间隔以提高响应性，但用时间戳控制 tick 频率（间隔可在 [intervals] 中配置，:set tick_rate= 覆盖）
+        let poll = app.settings.tick_rate.map_or_else(|| app.config.intervals.poll(), std::time::Duration::from_millis);
+        if event::poll(poll)? {
             match event::read()? {
                 Event::Key(key) if key.kind == KeyEventKind::Press => {
-                    handle_key_event(app, key.code)?;
+                    let started = (std::time::Instant::now(), Utc::now());
+                    // 操作失败（如数据库写入失败）时在状态栏提示，不退出界面
+                    if let Err(e) = dispatch_key(app, key) {
+                        app.report_error("操作", e);
+                    }
+                    app.report_timing(started);
+                }
+                // 便签墙支持鼠标选择和拖拽排序（其他界面的鼠标响应暂时禁用）
+                Event::Mouse(mouse)
+                    if app.current_tab == 1 && app.show_dialog == DialogType::None =>
+                {
+                    if let Err(e) = handle_note_mouse(app, mouse) {
+                        app.report_error("操作", e);
+                    }
                 }
                 // 暂时禁用鼠标响应，后续再完善
                 // Event::Mouse(mouse) => {
@@ -1167,10 +3665,21 @@ fn run_ui_loop<B: ratatui::backend::Backend>(
             }
         }
 
-        // 检查并清除过期的状态消息（3秒后自动消失）
+        // :reindex 每轮循环处理一批，不阻塞界面
+        if app.reindex_job.is_some() {
+            app.step_reindex();
+        }
+
+        // CLI 添加任务、taskd 推迟提醒等外部修改后自动刷新
+        if let Err(e) = app.reload_if_changed() {
+            app.report_error("刷新数据", e);
+        }
+        app.save_pomodoro_state();
+
+        // 检查并清除过期的状态消息（默认3秒后自动消失）
         if let Some(msg_time) = app.status_message_time {
             let now = std::time::Instant::now();
-            if now.duration_since(msg_time) >= std::time::Duration::from_secs(3) {
+            if now.duration_since(msg_time) >= app.config.intervals.status_timeout() {
                 app.clear_status_message();
             }
         }
@@ -1182,8 +3691,8 @@ fn run_ui_loop<B: ratatui::backend::Backend>(
             let now = std::time::Instant::now();
             let elapsed = now.duration_since(app.last_tick_time);
 
-            // 只有距离上次 tick 超过 1 秒才执行
-            if elapsed >= std::time::Duration::from_secs(1) {
+            // 只有距离上次 tick 超过刷新间隔（默认 1 秒）才执行
+            if elapsed >= app.config.intervals.tick() {
                 app.last_tick_time = now;
 
                 if !app.pomodoro.tick() {
@@ -1191,26 +3700,39 @@ fn run_ui_loop<B: ratatui::backend::Backend>(
                 match app.pomodoro.state {
                     crate::pomodoro::PomodoroState::Working => {
                         // 工作时段完成，保存到数据库
-                        if let (Some(start_time), Ok(db)) = (app.pomodoro.start_time, Database::open(&app.db_path)) {
-                            let session = PomodoroSession {
-                                id: None,
-                                task_id: app.pomodoro.current_task_id,
-                                start_time,
-                                end_time: Some(Utc::now()),
-                                duration_minutes: app.pomodoro.work_duration,
-                                completed: true,
-                            };
-                            let _ = db.create_pomodoro(&session);
-                        }
+                        let session = app.pomodoro.start_time.map(|start_time| PomodoroSession {
+                            id: None,
+                            task_id: app.pomodoro.current_task_id,
+                            start_time,
+                            end_time: Some(Utc::now()),
+                            duration_minutes: app.pomodoro.work_duration,
+                            completed: true,
+                        });
 
                         app.pomodoro_completed_today += 1;
                         app.pomodoro_total_minutes += app.pomodoro.work_duration as usize;
-                        app.pomodoro.start_break();
-                        app.set_status_message("🎉 工作时段完成！开始休息！".to_string());
+                        app.pomodoro.finish_work();
+                        if app.pomodoro.long_break {
+                            app.set_status_message(format!(
+                                "🎉 完成一轮 {}！开始 {} 分钟长休息！",
+                                app.pomodoro.cycle_marks(),
+                                app.pomodoro.long_break_duration
+                            ));
+                        } else {
+                            app.set_status_message("🎉 工作时段完成！开始休息！".to_string());
+                        }
+                        // 保存失败时错误提示覆盖上面的消息
+                        if let Some(session) = session {
+                            app.save_pomodoro_session(&session);
+                        }
+                        app.save_pomodoro_cycle_position();
+                        app.notify_pomodoro_end(false);
                     }
                     crate::pomodoro::PomodoroState::Break => {
-                        app.pomodoro.stop();
+                        app.pomodoro.finish_break();
                         app.set_status_message("番茄钟完成！".to_string());
+                        app.save_pomodoro_cycle_position();
+                        app.notify_pomodoro_end(true);
                     }
                     _ => {}
                 }
@@ -1218,6 +3740,12 @@ fn run_ui_loop<B: ratatui::backend::Backend>(
             }
         }
 
+        // 番茄钟状态变化时更新提示符缓存
+        if app.pomodoro.state != app.prompt_pomodoro_state {
+            app.prompt_pomodoro_state = app.pomodoro.state;
+            app.write_prompt_segment(None, app.pomodoro_mark());
+        }
+
         if app.should_quit {
             break;
         }
@@ -1260,6 +3788,7 @@ fn execute_command(app: &mut App) -> Result<()> {
     // 解析命令和参数
     let parts: Vec<&str> = cmd.split_whitespace().collect();
     let command = parts.first().unwrap_or(&"");
+    app.record_usage(format!(":{}", command));
 
     match *command {
         // 退出命令
@@ -1271,9 +3800,16 @@ fn execute_command(app: &mut App) -> Result<()> {
             app.should_quit = true;
         }
 
-        // 删除命令
+        // 删除命令：任务列表中为归档，:delete! 移到回收站
         "d" | "delete" => {
-            app.show_dialog = DialogType::DeleteConfirm;
+            if app.current_tab == 0 && !app.show_archived_tasks {
+                app.archive_task()?;
+            } else {
+                app.confirm_delete()?;
+            }
+        }
+        "d!" | "delete!" => {
+            app.confirm_delete()?;
         }
 
         // 新建命令
@@ -1282,21 +3818,14 @@ fn execute_command(app: &mut App) -> Result<()> {
             if !title.is_empty() {
                 match app.current_tab {
                     0 => {
-                        // 新建任务时强制设定DDL
-                        app.pending_task_title = Some(title.clone());
-                        // 初始化datetime picker为当前时间
-                        let now = chrono::Local::now();
-                        app.datetime_year = now.year();
-                        app.datetime_month = now.month();
-                        app.datetime_day = now.day();
-                        app.datetime_hour = now.hour();
-                        app.datetime_minute = now.minute();
-                        app.datetime_picker_field = 0;
-                        app.show_dialog = DialogType::SetDeadline;
+                        app.begin_new_task(&title)?;
                     }
                     1 => {
-                        let db = Database::open(&app.db_path)?;
                         let note = Note::new("新便签".to_string(), title.clone());
+                        if !app.check_valid(note.validate()) {
+                            return Ok(());
+                        }
+                        let db = &app.db;
                         let id = db.create_note(&note)?;
                         app.reload_data()?;
                         app.set_status_message(format!("便签 #{} 已创建", id));
@@ -1308,12 +3837,12 @@ fn execute_command(app: &mut App) -> Result<()> {
                 match app.current_tab {
                     0 => {
                         if let Err(e) = app.create_task_with_vim() {
-                            app.set_status_message(format!("创建失败: {}", e));
+                            app.report_error("创建", e);
                         }
                     }
                     1 => {
                         if let Err(e) = app.create_note_with_vim() {
-                            app.set_status_message(format!("创建失败: {}", e));
+                            app.report_error("创建", e);
                         }
                     }
                     _ => {}
@@ -1327,7 +3856,7 @@ fn execute_command(app: &mut App) -> Result<()> {
                 0 => {
                     if !app.tasks.is_empty() {
                         if let Err(e) = app.init_edit_task_with_vim() {
-                            app.set_status_message(format!("编辑失败: {}", e));
+                            app.report_error("编辑", e);
                         }
                     } else {
                         app.set_status_message("没有可编辑的任务".to_string());
@@ -1336,7 +3865,7 @@ fn execute_command(app: &mut App) -> Result<()> {
                 1 => {
                     if !app.notes.is_empty() {
                         if let Err(e) = app.init_edit_note() {
-                            app.set_status_message(format!("编辑失败: {}", e));
+                            app.report_error("编辑", e);
                         }
                     } else {
                         app.set_status_message("没有可编辑的便签".to_string());
@@ -1352,7 +3881,7 @@ fn execute_command(app: &mut App) -> Result<()> {
                 0 => {
                     if !app.tasks.is_empty() {
                         if let Err(e) = app.init_edit_task_with_vim() {
-                            app.set_status_message(format!("编辑失败: {}", e));
+                            app.report_error("编辑", e);
                         }
                     } else {
                         app.set_status_message("没有可编辑的任务".to_string());
@@ -1361,7 +3890,7 @@ fn execute_command(app: &mut App) -> Result<()> {
                 1 => {
                     if !app.notes.is_empty() {
                         if let Err(e) = app.init_edit_note() {
-                            app.set_status_message(format!("编辑失败: {}", e));
+                            app.report_error("编辑", e);
                         }
                     } else {
                         app.set_status_message("没有可编辑的便签".to_string());
@@ -1376,12 +3905,12 @@ fn execute_command(app: &mut App) -> Result<()> {
             match app.current_tab {
                 0 => {
                     if let Err(e) = app.create_task_with_vim() {
-                        app.set_status_message(format!("创建失败: {}", e));
+                        app.report_error("创建", e);
                     }
                 }
                 1 => {
                     if let Err(e) = app.create_note_with_vim() {
-                        app.set_status_message(format!("创建失败: {}", e));
+                        app.report_error("创建", e);
                     }
                 }
                 _ => {}
@@ -1398,13 +3927,7 @@ fn execute_command(app: &mut App) -> Result<()> {
                                 if let Ok(minutes) = value.parse::<i32>() {
                                     if minutes >= 1 && minutes <= 120 {
                                         app.pomodoro.work_duration = minutes;
-                                        if let Ok(db) = Database::open(&app.db_path) {
-                                            let _ = db.save_pomodoro_config(
-                                                app.pomodoro.work_duration,
-                                                app.pomodoro.break_duration
-                                            );
-                                        }
-                                        app.set_status_message(format!("工作时长设置为 {} 分钟", minutes));
+                                        app.save_pomodoro_config(format!("工作时长设置为 {} 分钟", minutes));
                                     }
                                 }
                             }
@@ -1412,13 +3935,29 @@ fn execute_command(app: &mut App) -> Result<()> {
                                 if let Ok(minutes) = value.parse::<i32>() {
                                     if minutes >= 1 && minutes <= 60 {
                                         app.pomodoro.break_duration = minutes;
-                                        if let Ok(db) = Database::open(&app.db_path) {
-                                            let _ = db.save_pomodoro_config(
-                                                app.pomodoro.work_duration,
-                                                app.pomodoro.break_duration
-                                            );
-                                        }
-                                        app.set_status_message(format!("休息时长设置为 {} 分钟", minutes));
+                                        app.save_pomodoro_config(format!("休息时长设置为 {} 分钟", minutes));
+                                    }
+                                }
+                            }
+                            // 长休息时长
+                            "long" | "l" => {
+                                if let Ok(minutes) = value.parse::<i32>() {
+                                    if (5..=60).contains(&minutes) {
+                                        app.pomodoro.long_break_duration = minutes;
+                                        app.save_pomodoro_cycle(format!("长休息时长设置为 {} 分钟", minutes));
+                                    }
+                                }
+                            }
+                            // 每几个番茄钟一次长休息，0 关闭
+                            "every" | "cycle" => {
+                                if let Ok(count) = value.parse::<u32>() {
+                                    if count <= 12 {
+                                        app.pomodoro.cycle_length = count;
+                                        app.save_pomodoro_cycle(if count == 0 {
+                                            "已关闭长休息".to_string()
+                                        } else {
+                                            format!("每 {} 个番茄钟进行一次长休息", count)
+                                        });
                                     }
                                 }
                             }
@@ -1428,9 +3967,11 @@ fn execute_command(app: &mut App) -> Result<()> {
                 }
             } else {
                 app.set_status_message(format!(
-                    "番茄钟配置: 工作{}分钟 休息{}分钟 | 用法: :pomo work=25 break=5",
+                    "番茄钟配置: 工作{}分钟 休息{}分钟 每{}个长休息{}分钟 | 用法: :pomo work=25 break=5 long=15 every=4",
                     app.pomodoro.work_duration,
-                    app.pomodoro.break_duration
+                    app.pomodoro.break_duration,
+                    app.pomodoro.cycle_length,
+                    app.pomodoro.long_break_duration
                 ));
             }
         }
@@ -1440,6 +3981,17 @@ fn execute_command(app: &mut App) -> Result<()> {
             if app.current_tab == 0 {
                 if app.tasks.is_empty() {
                     app.set_status_message("没有任务可设置优先级".to_string());
+                } else if parts.len() > 1 && app.visual_anchor.is_some() {
+                    // 可视模式中的 :p 3：批量设置
+                    match Priority::parse(parts[1]).or(match parts[1] {
+                        "1" => Some(Priority::Low),
+                        "2" => Some(Priority::Medium),
+                        "3" => Some(Priority::High),
+                        _ => None,
+                    }) {
+                        Some(priority) => app.batch_set_priority(priority)?,
+                        None => app.set_status_message("用法: :p [1/low | 2/medium | 3/high]".to_string()),
+                    }
                 } else if parts.len() > 1 {
                     // 带参数：设置指定优先级
                     if let Some(mut task) = app.selected_task().cloned() {
@@ -1459,7 +4011,7 @@ fn execute_command(app: &mut App) -> Result<()> {
                                 return Ok(());
                             }
                         }
-                        let db = Database::open(&app.db_path)?;
+                        let db = &app.db;
                         db.update_task(&task)?;
                         app.reload_data()?;
                         app.set_status_message(format!("优先级: {:?} → {:?}", old_priority, task.priority));
@@ -1488,7 +4040,22 @@ fn execute_command(app: &mut App) -> Result<()> {
         "t" | "ddl" | "deadline" | "due" => {
             if app.current_tab == 0 && !app.tasks.is_empty() {
                 app.init_datetime_picker();
-                app.show_dialog = DialogType::SetDeadline;
+                if parts.len() > 1 {
+                    // :ddl next friday 18:00 直接设置，不打开选择器
+                    let text = parts[1..].join(" ");
+                    match crate::dateparse::parse(&text, chrono::Local::now()) {
+                        Some(at) => {
+                            app.set_datetime_fields(at.with_timezone(&chrono::Local));
+                            app.apply_deadline()?;
+                        }
+                        None => app.set_status_message(format!(
+                            "无法识别的时间: {} | 例如 :ddl tomorrow 5pm / next friday 18:00 / in 3 days / 明天 18:00",
+                            text
+                        )),
+                    }
+                } else {
+                    app.show_dialog = DialogType::SetDeadline;
+                }
             } else {
                 app.set_status_message("没有可设置DDL的任务 | 提示：按t键设置DDL".to_string());
             }
@@ -1501,8 +4068,7 @@ fn execute_command(app: &mut App) -> Result<()> {
             } else {
                 match app.pomodoro.state {
                     crate::pomodoro::PomodoroState::Idle => {
-                        app.pomodoro.start_work(None);
-                        app.set_status_message("番茄钟开始！".to_string());
+                        app.open_pomodoro_picker();
                     }
                     crate::pomodoro::PomodoroState::Working
                     | crate::pomodoro::PomodoroState::Break => {
@@ -1517,102 +4083,668 @@ fn execute_command(app: &mut App) -> Result<()> {
             }
         }
 
-        // 番茄钟取消命令
-        "c" | "cancel" | "stop" => {
-            if app.current_tab != 2 {
-                app.set_status_message("请先切换到番茄钟标签页 (Tab 3)".to_string());
+        // 番茄钟取消命令
+        "c" | "cancel" | "stop" => {
+            if app.current_tab != 2 {
+                app.set_status_message("请先切换到番茄钟标签页 (Tab 3)".to_string());
+            } else {
+                if app.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
+                    app.pomodoro.stop();
+                    app.set_status_message("番茄钟已取消".to_string());
+                } else {
+                    app.set_status_message("番茄钟未运行".to_string());
+                }
+            }
+        }
+
+        // 调整工作时长命令
+        "work+" | "w+" => {
+            if app.current_tab != 2 {
+                app.set_status_message("请先切换到番茄钟标签页".to_string());
+            } else if app.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
+                app.set_status_message("番茄钟运行中，无法调整！先用:c取消".to_string());
+            } else {
+                app.pomodoro.work_duration += 5;
+                if app.pomodoro.work_duration > 120 {
+                    app.pomodoro.work_duration = 120;
+                }
+                app.save_pomodoro_config(format!("工作时长: {}分钟", app.pomodoro.work_duration));
+            }
+        }
+        "work-" | "w-" => {
+            if app.current_tab != 2 {
+                app.set_status_message("请先切换到番茄钟标签页".to_string());
+            } else if app.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
+                app.set_status_message("番茄钟运行中，无法调整！先用:c取消".to_string());
+            } else {
+                if app.pomodoro.work_duration > 5 {
+                    app.pomodoro.work_duration -= 5;
+                    app.save_pomodoro_config(format!("工作时长: {}分钟", app.pomodoro.work_duration));
+                } else {
+                    app.set_status_message("工作时长最小为5分钟".to_string());
+                }
+            }
+        }
+
+        // 调整休息时长命令
+        "break+" | "b+" => {
+            if app.current_tab != 2 {
+                app.set_status_message("请先切换到番茄钟标签页".to_string());
+            } else if app.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
+                app.set_status_message("番茄钟运行中，无法调整！先用:c取消".to_string());
+            } else {
+                app.pomodoro.break_duration += 1;
+                if app.pomodoro.break_duration > 60 {
+                    app.pomodoro.break_duration = 60;
+                }
+                app.save_pomodoro_config(format!("休息时长: {}分钟", app.pomodoro.break_duration));
+            }
+        }
+        "break-" | "b-" => {
+            if app.current_tab != 2 {
+                app.set_status_message("请先切换到番茄钟标签页".to_string());
+            } else if app.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
+                app.set_status_message("番茄钟运行中，无法调整！先用:c取消".to_string());
+            } else {
+                if app.pomodoro.break_duration > 1 {
+                    app.pomodoro.break_duration -= 1;
+                    app.save_pomodoro_config(format!("休息时长: {}分钟", app.pomodoro.break_duration));
+                } else {
+                    app.set_status_message("休息时长最小为1分钟".to_string());
+                }
+            }
+        }
+
+        // 模板命令：从 config.toml 的 [templates] 创建任务
+        "template" | "tpl" => {
+            match parts.get(1) {
+                Some(name) => match app.config.template(name).map(|t| t.to_string()) {
+                    Some(title) => {
+                        app.current_tab = 0;
+                        app.begin_new_task(&title)?;
+                    }
+                    None => app.set_status_message(format!("模板不存在: {}", name)),
+                },
+                None => {
+                    if app.config.templates.is_empty() {
+                        app.set_status_message("未配置模板，请在 config.toml 的 [templates] 中添加".to_string());
+                    } else {
+                        let names: Vec<&str> = app.config.templates.keys().map(|k| k.as_str()).collect();
+                        app.set_status_message(format!("可用模板: {} | 用法: :template <名称>", names.join(", ")));
+                    }
+                }
+            }
+        }
+
+        // 移动便签命令: :move up/down/first/last/N
+        "move" | "mv" => {
+            if app.current_tab != 1 || app.notes.is_empty() {
+                app.set_status_message("请在便签墙中选择要移动的便签".to_string());
+            } else {
+                let from = app.note_list_state.selected().unwrap_or(0);
+                let last = app.notes.len() - 1;
+                let to = match parts.get(1).copied() {
+                    Some("up") | Some("left") => Some(from.saturating_sub(1)),
+                    Some("down") | Some("right") => Some((from + 1).min(last)),
+                    Some("first") => Some(0),
+                    Some("last") => Some(last),
+                    Some(n) => n.parse::<usize>().ok().filter(|n| *n >= 1).map(|n| (n - 1).min(last)),
+                    None => None,
+                };
+                match to {
+                    Some(to) => app.move_note(from, to)?,
+                    None => app.set_status_message("用法: :move up/down/first/last/序号".to_string()),
+                }
+            }
+        }
+
+        // 其他标签页中输入时切换到任务列表并打开窗格
+        "split" => {
+            if app.current_tab != 0 {
+                app.goto_tab(0);
+                app.show_task_pane = false;
+            }
+            app.toggle_task_pane()?;
+        }
+
+        "today" | "agenda" => {
+            app.goto_tab(0);
+            app.toggle_agenda()?;
+        }
+
+        // 归档命令
+        "archive" => {
+            if app.current_tab == 0 {
+                app.toggle_task_archive_view()?;
+            } else if app.current_tab == 1 && !app.notes.is_empty() {
+                app.toggle_note_archived()?;
+            } else {
+                app.set_status_message("请在便签墙中选择要归档的便签".to_string());
+            }
+        }
+        "archived" => {
+            if app.current_tab == 0 {
+                app.toggle_task_archive_view()?;
+            } else {
+                app.toggle_archived_view()?;
+            }
+        }
+        "restore" => {
+            let id = parts.get(1).map(|s| s.trim_start_matches('#').parse::<i64>());
+            match id {
+                Some(Err(_)) => app.set_status_message("用法: :restore <任务ID>".to_string()),
+                // 便签墙中按便签 ID 从回收站恢复
+                Some(Ok(id)) if app.current_tab == 1 => {
+                    app.restore_trashed(|item| !item.is_task() && item.entity_id == id)?
+                }
+                Some(Ok(id)) => app.restore_archived_task(Some(id))?,
+                None if app.current_tab == 0 && app.show_archived_tasks => app.restore_archived_task(None)?,
+                // 不带参数时恢复最近删除的项目
+                None => app.restore_trashed(|_| true)?,
+            }
+        }
+        "trash" => app.open_trash()?,
+        "purge" => {
+            if !app.show_archived_tasks {
+                app.set_status_message("请先用 :archive 打开归档任务".to_string());
+            } else {
+                match parts.get(1).map(|s| s.trim_end_matches('d').parse::<i64>()) {
+                    Some(Err(_)) => app.set_status_message("用法: :purge [天数]（只删除归档超过该天数的任务）".to_string()),
+                    Some(Ok(days)) => app.purge_archived_tasks(Some(days))?,
+                    None => app.purge_archived_tasks(None)?,
+                }
+            }
+        }
+
+        // 便签到期命令: :expire 2025-10-20 / 3d / 12h / off
+        "expire" => {
+            if app.current_tab != 1 || app.notes.is_empty() {
+                app.set_status_message("请在便签墙中选择便签".to_string());
+            } else {
+                match parts.get(1).and_then(|s| parse_expiry(s)) {
+                    Some(expires_at) => app.set_note_expiry(expires_at)?,
+                    None => app.set_status_message("用法: :expire 2025-10-20 / 3d / 12h / off".to_string()),
+                }
+            }
+        }
+
+        // 便签颜色命令: :color yellow / off
+        "color" => {
+            if app.current_tab != 1 || app.notes.is_empty() {
+                app.set_status_message("请在便签墙中选择便签".to_string());
+            } else {
+                match parts.get(1).map(|s| s.to_lowercase()) {
+                    Some(color) if color == "off" => app.set_note_color(None)?,
+                    Some(color) => app.set_note_color(Some(color))?,
+                    None => app.set_status_message(format!(
+                        "用法: :color {} / off",
+                        crate::models::NOTE_COLORS.join("/")
+                    )),
+                }
+            }
+        }
+
+        // 跳转到日期命令: :goto 2025-03-14 / today / tomorrow
+        "goto" => {
+            let today = chrono::Local::now().date_naive();
+            let date = match parts.get(1).copied() {
+                Some("today") | None => Some(today),
+                Some("tomorrow") => Some(today + chrono::Duration::days(1)),
+                Some(s) => chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok(),
+            };
+            match date {
+                Some(date) => {
+                    // 在日历中选中该日，Enter 再到任务列表中查看
+                    app.current_tab = 3;
+                    app.calendar_select(date)?;
+                    app.set_status_message(format!("日历: {}（Enter 查看该日任务）", date.format("%Y-%m-%d")));
+                }
+                None => app.set_status_message("用法: :goto 2025-03-14 / today / tomorrow".to_string()),
+            }
+        }
+
+        // 界面设置命令
+        "set" => {
+            let pair = match parts[..] {
+                [_, key, value] if !key.contains('=') && !value.contains('=') => Some((key, value)),
+                _ => None,
+            };
+            if let Some((key, value)) = pair {
+                // :set notify on 与 :set notify=on 相同
+                let message = app.apply_setting(key, value)?;
+                app.set_status_message(message);
+            } else if parts.len() > 1 {
+                for arg in &parts[1..] {
+                    let message = match arg.split_once('=') {
+                        Some((key, value)) => app.apply_setting(key, value)?,
+                        None => format!("用法: :set {}=on/off", arg),
+                    };
+                    app.set_status_message(message);
+                }
+            } else {
+                let sound = crate::sound::SoundSettings::load(&app.db)?;
+                let switches: Vec<String> = settings::SWITCHES
+                    .iter()
+                    .map(|(name, ..)| format!("{}={}", name, app.settings.describe(name)))
+                    .collect();
+                app.set_status_message(format!(
+                    "设置: {} tick_rate={} {} keymap={} | :set? 查看说明",
+                    switches.join(" "),
+                    app.settings.describe("tick_rate"),
+                    sound.describe(),
+                    app.keymap.name()
+                ));
+            }
+        }
+        "set?" => app.show_dialog = DialogType::Settings,
+
+        // 最近的错误记录
+        "messages" | "mes" => {
+            if parts.get(1) == Some(&"clear") {
+                app.messages.clear();
+                app.set_status_message("已清空错误记录".to_string());
+            } else {
+                app.messages.unread = 0;
+                app.show_dialog = DialogType::Messages;
+            }
+        }
+
+        // 配色主题
+        "theme" => match parts.get(1) {
+            Some(name) => app.set_theme(name)?,
+            None => app.set_status_message(format!(
+                "当前主题: {} | 用法: :theme {}",
+                app.theme_name,
+                Theme::NAMES.join("/")
+            )),
+        },
+
+        // 切换 profile 命令（:workspace 不带参数时打开切换框）
+        "workspace" | "ws" if parts.len() == 1 => app.open_workspace_picker()?,
+        "profile" | "workspace" | "ws" => {
+            if let Some(name) = parts.get(1) {
+                if let Err(e) = app.switch_profile(name) {
+                    app.report_error("切换", e);
+                }
+            } else {
+                let current = app.profile.clone().unwrap_or_else(|| "default".to_string());
+                let mut available = vec!["default".to_string()];
+                available.extend(crate::config::list_profiles().unwrap_or_default());
+                app.set_status_message(format!(
+                    "当前 profile: {} | 可用: {} | 用法: :profile <名称>",
+                    current,
+                    available.join(", ")
+                ));
+            }
+        }
+
+        // 健康检查: :health 重新检查，:health fix 修复孤立便签
+        "health" => {
+            if parts.get(1) == Some(&"fix") {
+                let db = &app.db;
+                let count = db.clear_orphaned_notes()?;
+                app.reload_data()?;
+                app.set_status_message(format!("已解除 {} 个便签的任务关联", count));
+            }
+            app.run_health_checks()?;
+            if app.health_warnings.is_empty() {
+                app.set_status_message("✅ 健康检查通过".to_string());
+            }
+        }
+
+        // 分诊助手
+        "triage" => {
+            app.start_triage()?;
+        }
+
+        // 完成并安排跟进: :done [2d [跟进任务标题]]
+        "done" | "followup" => {
+            if app.current_tab != 0 {
+                app.set_status_message("只有任务可以完成".to_string());
+            } else {
+                match parts.get(1) {
+                    None => app.complete_with_follow_up(None, None)?,
+                    Some(when) => match crate::models::parse_follow_up(when) {
+                        Some(at) => {
+                            let title = parts[2..].join(" ");
+                            app.complete_with_follow_up(Some(at), Some(title.as_str()))?;
+                        }
+                        None => app.set_status_message("用法: :done [2025-10-20 / tomorrow / 2d / 12h [跟进任务标题]]".to_string()),
+                    },
+                }
+            }
+        }
+
+        // 等待/委派: :wait [跟进时间 2025-10-20 / 3d / off]
+        "wait" | "waiting" => {
+            if app.current_tab != 0 {
+                app.set_status_message("只有任务可以标记为等待中".to_string());
+            } else {
+                match parts.get(1).copied() {
+                    None => app.set_task_waiting(true, None)?,
+                    Some("off") | Some("done") => app.set_task_waiting(false, None)?,
+                    Some(arg) => match crate::models::parse_follow_up(arg) {
+                        Some(time) => app.set_task_waiting(true, Some(time))?,
+                        None => app.set_status_message("用法: :wait [2025-10-20 / 3d / 12h / off]".to_string()),
+                    },
+                }
+            }
+        }
+
+        // 依赖: :blocked-by 3 选中任务依赖 #3（#3 完成前不能完成），:blocks 3 让 #3 依赖选中任务，-3 移除
+        "blocked-by" | "blockedby" | "blocks" => {
+            if app.current_tab != 0 {
+                app.set_status_message("只有任务可以设置依赖".to_string());
+            } else {
+                app.edit_task_dependencies(&parts[1..], *command == "blocks")?;
+            }
+        }
+
+        // 状态: :status todo / doing / waiting / done
+        "status" => {
+            if app.current_tab != 0 {
+                app.set_status_message("只有任务可以修改状态".to_string());
+            } else {
+                match parts.get(1).and_then(|s| TaskStatus::parse(s)) {
+                    Some(status) => app.set_task_status(status)?,
+                    None => app.set_status_message("用法: :status todo/doing/waiting/done".to_string()),
+                }
+            }
+        }
+
+        // 计时: :track start / :track stop，不带参数时显示正在进行的计时
+        "track" => match parts.get(1).copied() {
+            Some("start") if app.current_tab != 0 => app.set_status_message("只有任务可以计时".to_string()),
+            Some("start") => app.start_time_tracking()?,
+            Some("stop") => app.stop_time_tracking()?,
+            _ => {
+                let message = match &app.time_tracking {
+                    Some(entry) => format!(
+                        "⏱ 正在计时: {} 已 {} | 用法: :track start/stop",
+                        app.db.get_task(entry.task_id).map(|t| t.title).unwrap_or_default(),
+                        crate::models::format_tracked(entry.seconds(Utc::now()))
+                    ),
+                    None => "没有正在进行的计时 | 用法: :track start/stop（或按 T）".to_string(),
+                };
+                app.set_status_message(message);
+            }
+        },
+
+        // 预计耗时: :est 1h30m / :est off
+        "est" | "estimate" => {
+            if app.current_tab != 0 {
+                app.set_status_message("只有任务可以设置预计耗时".to_string());
+            } else {
+                match parts.get(1).copied() {
+                    Some("off") => app.set_task_estimate(None)?,
+                    Some(arg) => match crate::models::parse_estimate(arg) {
+                        Some(minutes) => app.set_task_estimate(Some(minutes))?,
+                        None => app.set_status_message("用法: :est 90m / 2h / 1h30m / off".to_string()),
+                    },
+                    None => app.set_status_message("用法: :est 90m / 2h / 1h30m / off".to_string()),
+                }
+            }
+        }
+
+        // 提醒: :remind 打开选择器，:remind 30m 截止前 30 分钟，:remind 2025-10-20 当天 9:00，:remind off 清除
+        "remind" | "reminder" => {
+            const USAGE: &str = "用法: :remind 30m / 1h / 1d（截止前）、2025-10-20 / tomorrow（当天 9:00）或 off";
+            let task = match (app.current_tab, app.selected_task()) {
+                (0, Some(task)) => Some(task.clone()),
+                _ => None,
+            };
+            match (task, parts.get(1).copied()) {
+                (None, _) => app.set_status_message("只有任务可以设置提醒".to_string()),
+                (Some(_), None) => app.init_reminder_picker(),
+                (Some(_), Some("off")) => app.set_task_reminder(None)?,
+                (Some(task), Some(arg)) => match crate::models::parse_reminder_offset(arg) {
+                    Some(offset) => match task.due_date {
+                        Some(due) => app.set_task_reminder(Some(due - offset))?,
+                        None => app.set_status_message("任务没有DDL，无法设置截止前提醒（可用 :remind 打开选择器）".to_string()),
+                    },
+                    None => match arg {
+                        "tomorrow" => app.set_task_reminder(crate::models::parse_follow_up(arg))?,
+                        _ if chrono::NaiveDate::parse_from_str(arg, "%Y-%m-%d").is_ok() => {
+                            app.set_task_reminder(crate::models::parse_follow_up(arg))?
+                        }
+                        _ => app.set_status_message(USAGE.to_string()),
+                    },
+                },
+            }
+        }
+
+        // 推迟提醒: :snooze 10m / 1h / 1d，从现在起算
+        "defer" | "postpone" => {
+            const USAGE: &str = "用法: :defer 1h / 1d / 1w / mon（下周一），-1d 提前";
+            match (app.current_tab, app.selected_task(), parts.get(1).copied()) {
+                (0, Some(_), arg) => match arg.map_or(Some(crate::models::Defer::Days(1)), crate::models::Defer::parse) {
+                    Some(step) => app.defer_task(step)?,
+                    None => app.set_status_message(USAGE.to_string()),
+                },
+                _ => app.set_status_message("请先在任务列表中选择要推迟的任务".to_string()),
+            }
+        }
+        "snooze" => {
+            const USAGE: &str = "用法: :snooze 10m / 1h / 1d（从现在起推迟提醒）";
+            match (app.current_tab, app.selected_task(), parts.get(1).copied()) {
+                (0, Some(_), arg) => match arg.map_or(Some(chrono::Duration::minutes(10)), crate::models::parse_reminder_offset) {
+                    Some(offset) => app.set_task_reminder(Some(Utc::now() + offset))?,
+                    None => app.set_status_message(USAGE.to_string()),
+                },
+                _ => app.set_status_message("只有任务可以推迟提醒".to_string()),
+            }
+        }
+
+        // 重复任务: :repeat weekly / 2w / FREQ=MONTHLY，:repeat off 取消
+        "repeat" => {
+            const USAGE: &str = "用法: :repeat daily / weekdays / weekly / monthly / yearly / 2w / off";
+            if app.current_tab != 0 {
+                app.set_status_message("只有任务可以设置重复".to_string());
+            } else {
+                match parts.get(1).copied() {
+                    Some("off") => app.set_task_recurrence(None)?,
+                    Some(arg) => match Recurrence::parse(arg) {
+                        Some(rule) => app.set_task_recurrence(Some(rule))?,
+                        None => app.set_status_message(USAGE.to_string()),
+                    },
+                    None => app.set_status_message(USAGE.to_string()),
+                }
+            }
+        }
+
+        // 任务/便签标签: :tag work home 添加，:tag -home 移除
+        "tag" => {
+            if app.current_tab == 1 {
+                app.edit_note_tags(&parts[1..])?;
+            } else if app.current_tab != 0 {
+                app.set_status_message("只有任务和便签可以设置标签".to_string());
+            } else if app.visual_anchor.is_some() {
+                app.batch_edit_tags(&parts[1..])?;
+            } else {
+                app.edit_task_tags(&parts[1..])?;
+            }
+        }
+
+        // 撤销/重做任务修改（同 u / Ctrl-r）
+        "undo" => {
+            app.current_tab = 0;
+            app.undo()?;
+        }
+        "redo" => {
+            app.current_tab = 0;
+            app.redo()?;
+        }
+
+        // 项目: :project 名称 只看该项目，:project 清除并列出项目
+        "project" | "proj" => {
+            app.current_tab = 0;
+            let name = cmd.splitn(2, char::is_whitespace).nth(1).map(str::trim).filter(|n| !n.is_empty());
+            match name {
+                Some(name) => app.filter_by_project(Some(name))?,
+                None => {
+                    app.filter_by_project(None)?;
+                    let names: Vec<String> = app
+                        .projects
+                        .iter()
+                        .filter(|p| !p.archived)
+                        .map(|p| format!("📁{}", p.name))
+                        .collect();
+                    if !names.is_empty() {
+                        app.set_status_message(format!("项目: {}（:project 名称 过滤，zp 切换）", names.join(" ")));
+                    }
+                }
+            }
+        }
+
+        // 归入项目: :assign 名称（不存在时创建），:assign - 移出项目
+        "assign" => {
+            let name = cmd.splitn(2, char::is_whitespace).nth(1).map(str::trim).filter(|n| !n.is_empty());
+            if app.current_tab != 0 {
+                app.set_status_message("只有任务可以归入项目".to_string());
             } else {
-                if app.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
-                    app.pomodoro.stop();
-                    app.set_status_message("番茄钟已取消".to_string());
-                } else {
-                    app.set_status_message("番茄钟未运行".to_string());
+                match name {
+                    Some("-") => app.assign_project(None)?,
+                    Some(name) => app.assign_project(Some(name))?,
+                    None => app.set_status_message("用法: :assign 项目名 / :assign -".to_string()),
                 }
             }
         }
 
-        // 调整工作时长命令
-        "work+" | "w+" => {
-            if app.current_tab != 2 {
-                app.set_status_message("请先切换到番茄钟标签页".to_string());
-            } else if app.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
-                app.set_status_message("番茄钟运行中，无法调整！先用:c取消".to_string());
+        // 按标签过滤: :filter #work，:filter 清除（在便签墙中过滤便签）
+        "filter" => {
+            if app.current_tab == 1 {
+                app.filter_notes_by_tag(parts.get(1).copied())?;
             } else {
-                app.pomodoro.work_duration += 5;
-                if app.pomodoro.work_duration > 120 {
-                    app.pomodoro.work_duration = 120;
-                }
-                if let Ok(db) = Database::open(&app.db_path) {
-                    let _ = db.save_pomodoro_config(app.pomodoro.work_duration, app.pomodoro.break_duration);
-                }
-                app.set_status_message(format!("工作时长: {}分钟", app.pomodoro.work_duration));
+                app.current_tab = 0;
+                app.filter_by_tag(parts.get(1).copied())?;
             }
         }
-        "work-" | "w-" => {
-            if app.current_tab != 2 {
-                app.set_status_message("请先切换到番茄钟标签页".to_string());
-            } else if app.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
-                app.set_status_message("番茄钟运行中，无法调整！先用:c取消".to_string());
+
+        // 任务评论: :comment 等待 Bob 回复
+        "comment" | "cm" => {
+            let body = cmd.splitn(2, char::is_whitespace).nth(1).unwrap_or("").trim();
+            if app.current_tab != 0 {
+                app.set_status_message("评论只能添加到任务".to_string());
+            } else if body.is_empty() {
+                app.open_task_detail()?;
             } else {
-                if app.pomodoro.work_duration > 5 {
-                    app.pomodoro.work_duration -= 5;
-                    if let Ok(db) = Database::open(&app.db_path) {
-                        let _ = db.save_pomodoro_config(app.pomodoro.work_duration, app.pomodoro.break_duration);
-                    }
-                    app.set_status_message(format!("工作时长: {}分钟", app.pomodoro.work_duration));
-                } else {
-                    app.set_status_message("工作时长最小为5分钟".to_string());
+                app.add_comment(body)?;
+            }
+        }
+
+        // 压缩数据库
+        "compact" => {
+            let db = &app.db;
+            let report = db.compact()?;
+            app.set_status_message(format!("数据库已压缩: {}", report.describe()));
+        }
+
+        // 慢查询记录: :timing on/off，:timing 200 设置阈值(毫秒)，:timing log 查看记录
+        "timing" => match parts.get(1).copied() {
+            Some("on") => {
+                crate::db::timing::set_enabled(true);
+                app.set_status_message(format!(
+                    "已开启耗时记录，阈值 {}ms",
+                    crate::db::timing::threshold().as_millis()
+                ));
+            }
+            Some("off") => {
+                crate::db::timing::set_enabled(false);
+                app.set_status_message("已关闭耗时记录".to_string());
+            }
+            Some("log") | None => app.show_dialog = DialogType::SlowLog,
+            Some(ms) => match ms.parse::<u64>() {
+                Ok(ms) => {
+                    crate::db::timing::set_threshold(std::time::Duration::from_millis(ms));
+                    crate::db::timing::set_enabled(true);
+                    app.set_status_message(format!("已开启耗时记录，阈值 {}ms", ms));
                 }
+                Err(_) => app.set_status_message("用法: :timing on|off|log|<毫秒>".to_string()),
+            },
+        },
+
+        "changelog" => {
+            app.changelog_scroll = 0;
+            app.show_dialog = DialogType::Changelog;
+        }
+
+        "reindex" => {
+            if app.reindex_job.is_some() {
+                app.set_status_message("正在重建索引".to_string());
+            } else {
+                let db = &app.db;
+                app.reindex_job = Some(crate::db::ReindexJob::new(&db)?);
+                app.set_status_message("开始重建索引".to_string());
             }
         }
 
-        // 调整休息时长命令
-        "break+" | "b+" => {
-            if app.current_tab != 2 {
-                app.set_status_message("请先切换到番茄钟标签页".to_string());
-            } else if app.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
-                app.set_status_message("番茄钟运行中，无法调整！先用:c取消".to_string());
+        // 复制便签到剪贴板
+        "yank" | "y" => {
+            let format = match parts.get(1).copied().map(clipboard::NoteFormat::parse) {
+                None => Some(clipboard::NoteFormat::Markdown),
+                Some(format) => format,
+            };
+            match (format, app.current_tab) {
+                (Some(format), 1) => app.yank_note(format),
+                (Some(_), _) => app.set_status_message("请在便签标签页使用 :yank".to_string()),
+                (None, _) => app.set_status_message("用法: :yank md|html|plain".to_string()),
+            }
+        }
+
+        "qr" => {
+            if app.current_tab == 0 {
+                app.show_task_qr();
             } else {
-                app.pomodoro.break_duration += 1;
-                if app.pomodoro.break_duration > 60 {
-                    app.pomodoro.break_duration = 60;
-                }
-                if let Ok(db) = Database::open(&app.db_path) {
-                    let _ = db.save_pomodoro_config(app.pomodoro.work_duration, app.pomodoro.break_duration);
-                }
-                app.set_status_message(format!("休息时长: {}分钟", app.pomodoro.break_duration));
+                app.set_status_message("请在任务标签页使用 :qr".to_string());
             }
         }
-        "break-" | "b-" => {
-            if app.current_tab != 2 {
-                app.set_status_message("请先切换到番茄钟标签页".to_string());
-            } else if app.pomodoro.state != crate::pomodoro::PomodoroState::Idle {
-                app.set_status_message("番茄钟运行中，无法调整！先用:c取消".to_string());
+
+        // 本地使用统计
+        "usage" => {
+            if parts.get(1) == Some(&"reset") {
+                let db = &app.db;
+                db.clear_usage()?;
+                app.pending_usage.clear();
+                app.set_status_message("已清空使用统计".to_string());
             } else {
-                if app.pomodoro.break_duration > 1 {
-                    app.pomodoro.break_duration -= 1;
-                    if let Ok(db) = Database::open(&app.db_path) {
-                        let _ = db.save_pomodoro_config(app.pomodoro.work_duration, app.pomodoro.break_duration);
-                    }
-                    app.set_status_message(format!("休息时长: {}分钟", app.pomodoro.break_duration));
-                } else {
-                    app.set_status_message("休息时长最小为1分钟".to_string());
-                }
+                app.show_usage()?;
             }
         }
 
         // 帮助命令
-        "h" | "help" | "?" => {
-            app.show_dialog = DialogType::Help;
-        }
+        "h" | "help" | "?" => match parts.get(1) {
+            Some(name) => match HelpTopic::parse(name) {
+                Some(topic) => app.open_help(Some(topic)),
+                None => {
+                    let topics: Vec<&str> = HelpTopic::ALL.iter().map(|t| t.name()).collect();
+                    app.set_status_message(format!("未知帮助主题: {}（可选 {}）", name, topics.join("/")));
+                }
+            },
+            None => app.open_help(None),
+        },
 
         // 排序命令
         "sort" => {
-            if app.current_tab == 0 {
+            if app.current_tab != 0 {
+                app.set_status_message("只有任务可以排序".to_string());
+            } else if parts.len() == 1 {
                 app.sort_tasks();
-                app.set_status_message("已排序任务".to_string());
+                let mode = app.task_sort.map_or_else(|| "默认（状态→优先级→DDL）".to_string(), |s| s.name());
+                app.set_status_message(format!(
+                    "已排序任务，当前: {} | 用法: :sort by {} [asc|desc]，:sort default 恢复默认",
+                    mode,
+                    sort::SortKey::NAMES.join("|")
+                ));
+            } else if matches!(parts[1], "default" | "reset" | "off") {
+                app.set_task_sort(None)?;
             } else {
-                app.set_status_message("只有任务可以排序".to_string());
+                match TaskSort::parse(&parts[1..]) {
+                    Some(sort) => app.set_task_sort(Some(sort))?,
+                    None => app.set_status_message(format!(
+                        "用法: :sort by {} [asc|desc]，:sort default 恢复默认",
+                        sort::SortKey::NAMES.join("|")
+                    )),
+                }
             }
         }
 
@@ -1625,12 +4757,51 @@ fn execute_command(app: &mut App) -> Result<()> {
     Ok(())
 }
 
+/// 按界面模式和键位方案把按键翻译成 vim 按键序列后交给 handle_key_event
+fn dispatch_key(app: &mut App, key: KeyEvent) -> Result<()> {
+    // 简单模式：主界面只响应提示中的按键，对话框和输入按原样处理
+    let simple = app.config.ui_mode == UiMode::Simple
+        && app.input_mode == InputMode::Normal
+        && app.show_dialog == DialogType::None;
+    let codes = if simple {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('q') {
+            app.should_quit = true;
+        }
+        keymap::translate_simple(key)
+    } else {
+        // Ctrl-r 重做：handle_key_event 只接收按键码，需要在这里区分 r 和 Ctrl-r
+        if app.input_mode == InputMode::Normal
+            && app.show_dialog == DialogType::None
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+            && key.code == KeyCode::Char('r')
+        {
+            if matches!(app.current_tab, 0 | 4) {
+                app.redo()?;
+            }
+            return Ok(());
+        }
+        // 非 vim 键位方案的别名只在 Normal 模式下翻译，输入文字时按原样处理
+        let alias = match app.input_mode {
+            InputMode::Normal => keymap::translate(app.keymap, key),
+            _ => None,
+        };
+        match alias {
+            Some(codes) => codes.to_vec(),
+            None => vec![key.code],
+        }
+    };
+    for code in codes {
+        handle_key_event(app, code)?;
+    }
+    Ok(())
+}
+
 /// 处理键盘事件
 fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
     // 对话框模式
     if app.show_dialog != DialogType::None {
-        // 特殊处理：SetDeadline dialog 使用方向键导航
-        if app.show_dialog == DialogType::SetDeadline {
+        // 特殊处理：SetDeadline / SetReminder dialog 使用方向键导航
+        if matches!(app.show_dialog, DialogType::SetDeadline | DialogType::SetReminder) {
             match key {
                 KeyCode::Left | KeyCode::Char('h') => {
                     app.datetime_picker_prev_field();
@@ -1652,15 +4823,26 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                     // 退格键：删除输入缓冲区的最后一个字符
                     app.datetime_picker_backspace();
                 }
+                KeyCode::Char('r') if app.show_dialog == DialogType::SetDeadline => {
+                    app.datetime_picker_cycle_recurrence();
+                }
+                KeyCode::Char('x') if app.show_dialog == DialogType::SetDeadline => {
+                    app.datetime_input_buffer.clear();
+                    app.apply_no_deadline()?;
+                }
                 KeyCode::Enter => {
-                    // 先应用当前输入，再保存DDL
+                    // 先应用当前输入，再保存DDL/提醒时间
                     app.datetime_picker_apply_input();
                     app.datetime_input_buffer.clear();
-                    app.apply_deadline()?;
+                    if app.show_dialog == DialogType::SetReminder {
+                        app.apply_reminder()?;
+                    } else {
+                        app.apply_deadline()?;
+                    }
                 }
                 KeyCode::Esc => {
-                    // 取消设置DDL，如果是新建任务的流程，也要清除pending_task_title
-                    app.pending_task_title = None;
+                    // 取消设置DDL，如果是新建任务的流程，也要清除pending_task
+                    app.pending_task = None;
                     app.datetime_input_buffer.clear();
                     app.show_dialog = DialogType::None;
                 }
@@ -1669,10 +4851,38 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
             return Ok(());
         }
 
-        // 特殊处理：Help dialog 支持滚动
+        // 帮助内搜索：输入关键字时实时过滤，Enter 确认，Esc 清除
+        if app.show_dialog == DialogType::Help && app.help_searching {
+            match key {
+                KeyCode::Char(c) => app.help_search.push(c),
+                KeyCode::Backspace => {
+                    app.help_search.pop();
+                }
+                KeyCode::Enter => app.help_searching = false,
+                KeyCode::Esc => {
+                    app.help_search.clear();
+                    app.help_searching = false;
+                }
+                _ => {}
+            }
+            app.help_scroll_offset = 0;
+            return Ok(());
+        }
+
+        // 特殊处理：Help dialog 支持滚动、搜索和切换主题
         if app.show_dialog == DialogType::Help {
             let max_scroll = app.get_help_max_scroll();
             match key {
+                KeyCode::Char('/') => {
+                    app.help_search.clear();
+                    app.help_searching = true;
+                }
+                KeyCode::Tab | KeyCode::BackTab | KeyCode::Char('l') | KeyCode::Char('h') => {
+                    let forward = matches!(key, KeyCode::Tab | KeyCode::Char('l'));
+                    app.help_topic = app.help_topic.cycle(forward);
+                    app.help_search.clear();
+                    app.help_scroll_offset = 0;
+                }
                 KeyCode::Up | KeyCode::Char('k') => {
                     if app.help_scroll_offset > 0 {
                         app.help_scroll_offset -= 1;
@@ -1693,6 +4903,10 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                 KeyCode::End | KeyCode::Char('G') => {
                     app.help_scroll_offset = max_scroll;
                 }
+                KeyCode::Esc if !app.help_search.is_empty() => {
+                    app.help_search.clear();
+                    app.help_scroll_offset = 0;
+                }
                 KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => {
                     app.help_scroll_offset = 0;
                     app.show_dialog = DialogType::None;
@@ -1702,8 +4916,117 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
             return Ok(());
         }
 
-        // 特殊处理：ViewNote dialog 支持滚动和编辑
-        if app.show_dialog == DialogType::ViewNote {
+        // 分诊：逐个接受/修改/跳过建议
+        if app.show_dialog == DialogType::Triage {
+            match key {
+                KeyCode::Enter | KeyCode::Char('y') => app.triage_next(true, None)?,
+                KeyCode::Char('3') | KeyCode::Char('H') => app.triage_next(true, Some(Priority::High))?,
+                KeyCode::Char('2') | KeyCode::Char('M') => app.triage_next(true, Some(Priority::Medium))?,
+                KeyCode::Char('1') | KeyCode::Char('L') => app.triage_next(true, Some(Priority::Low))?,
+                KeyCode::Char('n') | KeyCode::Char('s') => app.triage_next(false, None)?,
+                KeyCode::Esc | KeyCode::Char('q') => app.finish_triage()?,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // 番茄钟任务选择：j/k 移动，Enter 开始，Esc 取消
+        if app.show_dialog == DialogType::PickPomodoroTask {
+            match key {
+                KeyCode::Down | KeyCode::Char('j') => app.move_pomodoro_pick(1),
+                KeyCode::Up | KeyCode::Char('k') => app.move_pomodoro_pick(-1),
+                KeyCode::Enter | KeyCode::Char('s') => app.confirm_pomodoro_pick(),
+                KeyCode::Esc | KeyCode::Char('q') => app.show_dialog = DialogType::None,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // profile 切换框：j/k 移动，Enter 切换，Esc 取消
+        if app.show_dialog == DialogType::PickWorkspace {
+            match key {
+                KeyCode::Down | KeyCode::Char('j') => app.move_workspace_pick(1),
+                KeyCode::Up | KeyCode::Char('k') => app.move_workspace_pick(-1),
+                KeyCode::Enter => app.confirm_workspace_pick(),
+                KeyCode::Esc | KeyCode::Char('q') => app.show_dialog = DialogType::None,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // 回收站：j/k 移动，Enter / u / r 恢复，Esc 关闭
+        if app.show_dialog == DialogType::Trash {
+            match key {
+                KeyCode::Down | KeyCode::Char('j') => app.move_trash_pick(1),
+                KeyCode::Up | KeyCode::Char('k') => app.move_trash_pick(-1),
+                KeyCode::Enter | KeyCode::Char('u') | KeyCode::Char('r') => app.restore_trash_pick()?,
+                KeyCode::Esc | KeyCode::Char('q') => app.show_dialog = DialogType::None,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // 更新日志：j/k 滚动，其他键关闭
+        if app.show_dialog == DialogType::Changelog {
+            let max_scroll = crate::update::CHANGELOG.lines().count().saturating_sub(1);
+            match key {
+                KeyCode::Down | KeyCode::Char('j') => app.changelog_scroll = (app.changelog_scroll + 1).min(max_scroll),
+                KeyCode::Up | KeyCode::Char('k') => app.changelog_scroll = app.changelog_scroll.saturating_sub(1),
+                KeyCode::PageDown => app.changelog_scroll = (app.changelog_scroll + 10).min(max_scroll),
+                KeyCode::PageUp => app.changelog_scroll = app.changelog_scroll.saturating_sub(10),
+                KeyCode::Char('g') | KeyCode::Home => app.changelog_scroll = 0,
+                KeyCode::Char('G') | KeyCode::End => app.changelog_scroll = max_scroll,
+                _ => app.show_dialog = DialogType::None,
+            }
+            return Ok(());
+        }
+
+        // 上次未完成的番茄钟：y/Enter 继续，n/Esc 放弃
+        if app.show_dialog == DialogType::ResumePomodoro {
+            match key {
+                KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => app.resume_saved_pomodoro(),
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.discard_saved_pomodoro(),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // 使用统计/二维码/慢查询/设置/错误记录对话框：任意键关闭
+        if matches!(
+            app.show_dialog,
+            DialogType::Usage | DialogType::Qr | DialogType::SlowLog | DialogType::Settings | DialogType::Messages
+        ) {
+            app.show_dialog = DialogType::None;
+            return Ok(());
+        }
+
+        // 特殊处理：ViewTask dialog 显示完整标题和详情
+        if app.show_dialog == DialogType::ViewTask {
+            match key {
+                KeyCode::Char('e') => {
+                    // 编辑当前任务 - 使用 vim
+                    app.show_dialog = DialogType::None;
+                    if let Err(e) = app.init_edit_task_with_vim() {
+                        app.report_error("编辑", e);
+                    }
+                }
+                KeyCode::Char('c') => {
+                    // 添加评论：进入命令模式并预填 :comment
+                    app.show_dialog = DialogType::None;
+                    app.input_mode = InputMode::Command;
+                    app.input_buffer = "comment ".to_string();
+                    app.cursor_position = app.input_buffer.chars().count();
+                }
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                    app.show_dialog = DialogType::None;
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // 特殊处理：ViewNote dialog 支持滚动和编辑（输入 : 命令时交给命令模式处理）
+        if app.show_dialog == DialogType::ViewNote && app.input_mode != InputMode::Command {
             let max_scroll = app.get_view_note_max_scroll();
             match key {
                 KeyCode::Up | KeyCode::Char('k') => {
@@ -1727,17 +5050,39 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                     // 滚到底部
                     app.view_note_scroll_offset = max_scroll;
                 }
+                KeyCode::Left | KeyCode::Char('h') if !app.settings.note_wrap => {
+                    app.view_note_hscroll = app.view_note_hscroll.saturating_sub(8);
+                }
+                KeyCode::Right | KeyCode::Char('l') if !app.settings.note_wrap => {
+                    app.view_note_hscroll += 8;
+                }
+                KeyCode::Char('z') => {
+                    app.toggle_note_zoom()?;
+                    app.view_note_scroll_offset = app.view_note_scroll_offset.min(app.get_view_note_max_scroll());
+                }
+                KeyCode::Char('w') => {
+                    let message = app.apply_setting("wrap", value_str(!app.settings.note_wrap))?;
+                    app.view_note_scroll_offset = app.view_note_scroll_offset.min(app.get_view_note_max_scroll());
+                    app.set_status_message(message);
+                }
                 KeyCode::Char('e') => {
                     // 编辑当前便签 - 使用 vim
                     if !app.notes.is_empty() {
                         if let Err(e) = app.init_edit_note() {
-                            app.set_status_message(format!("编辑失败: {}", e));
+                            app.report_error("编辑", e);
                         }
                     }
                     app.show_dialog = DialogType::None;
                 }
+                KeyCode::Char(':') => {
+                    // 查看时可以直接执行命令，如 :yank md
+                    app.input_mode = InputMode::Command;
+                    app.input_buffer.clear();
+                    app.cursor_position = 0;
+                }
                 KeyCode::Esc | KeyCode::Char('q') => {
                     app.view_note_scroll_offset = 0;
+                    app.view_note_hscroll = 0;
                     app.show_dialog = DialogType::None;
                 }
                 _ => {}
@@ -1759,20 +5104,11 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                         match app.show_dialog {
                             DialogType::CreateTask => {
                                 // 新建任务时强制设定DDL
-                                if !app.input_buffer.is_empty() {
-                                    app.pending_task_title = Some(app.input_buffer.clone());
+                                let title = app.input_buffer.trim().to_string();
+                                if !app.input_buffer.is_empty() && app.begin_new_task(&title)? {
                                     app.input_buffer.clear();
                                     app.cursor_position = 0;
                                     app.input_mode = InputMode::Normal;
-                                    // 初始化datetime picker为当前时间
-                                    let now = chrono::Local::now();
-                                    app.datetime_year = now.year();
-                                    app.datetime_month = now.month();
-                                    app.datetime_day = now.day();
-                                    app.datetime_hour = now.hour();
-                                    app.datetime_minute = now.minute();
-                                    app.datetime_picker_field = 0;
-                                    app.show_dialog = DialogType::SetDeadline;
                                 }
                             }
                             DialogType::EditTask => app.save_edit_task()?,
@@ -1913,11 +5249,12 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
     if app.input_mode == InputMode::Command {
         match key {
             KeyCode::Enter => {
-                // 执行命令
+                // 执行命令（从可视模式进入时作用于选中的任务，执行后结束选择）
                 execute_command(app)?;
                 app.input_buffer.clear();
                 app.cursor_position = 0;
                 app.input_mode = InputMode::Normal;
+                app.visual_anchor = None;
             }
             KeyCode::Char(c) => {
                 app.input_buffer.push(c);
@@ -1929,15 +5266,41 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                 app.input_buffer.clear();
                 app.cursor_position = 0;
                 app.input_mode = InputMode::Normal;
+                app.visual_anchor = None;
             }
             _ => {}
         }
         return Ok(());
     }
 
+    if app.input_mode == InputMode::Visual {
+        return handle_visual_key(app, key);
+    }
+
     // 正常模式快捷键
     match app.input_mode {
         InputMode::Normal => {
+            if let KeyCode::Char(c) = key {
+                app.record_usage(format!("key:{}", c));
+            }
+
+            // z 前缀的快速过滤（zt/zi/zc/zz）和折叠（za），优先于单键操作
+            if app.last_key == Some(KeyCode::Char('z')) && app.current_tab == 0 {
+                if let KeyCode::Char(c) = key {
+                    let handled = if c == 'a' {
+                        app.toggle_completed_section()?;
+                        true
+                    } else {
+                        app.quick_filter(c)?
+                    };
+                    if handled {
+                        app.number_prefix.clear();
+                        app.last_key = None;
+                        return Ok(());
+                    }
+                }
+            }
+
             match key {
                 // vim风格命令模式: 按:进入
                 KeyCode::Char(':') => {
@@ -1950,8 +5313,8 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
 
                 // 数字前缀 (vim风格: 5j 向下移动5行)
                 KeyCode::Char(c @ '0'..='9') => {
-                    // 如果是在标签切换 (1/2/3) 且没有前缀，则切换标签
-                    if app.number_prefix.is_empty() && matches!(c, '1' | '2' | '3') {
+                    // 如果是在标签切换 (1-5) 且没有前缀，则切换标签
+                    if app.number_prefix.is_empty() && matches!(c, '1'..='5') {
                         app.goto_tab((c as u8 - b'1') as usize);
                         app.last_key = Some(key);
                     } else {
@@ -1961,7 +5324,12 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                     }
                 }
 
-                // 标签页切换: Tab, Shift+Tab
+                // 标签页切换: Tab, Shift+Tab（任务列表中 Tab 切换详情窗格）
+                KeyCode::Tab if app.current_tab == 0 => {
+                    app.toggle_task_pane()?;
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
                 KeyCode::Tab => {
                     app.next_tab();
                     app.number_prefix.clear();
@@ -1997,6 +5365,8 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                             let max_scroll = app.get_pomodoro_info_max_scroll();
                             app.pomodoro_scroll_offset = (app.pomodoro_scroll_offset + count).min(max_scroll);
                         }
+                        3 => app.calendar_move_days(7 * count as i64)?,
+                        4 => app.board_move_selection(count as i64),
                         _ => {}
                     }
                     app.number_prefix.clear();
@@ -2024,6 +5394,8 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                             // 番茄钟界面向上滚动
                             app.pomodoro_scroll_offset = app.pomodoro_scroll_offset.saturating_sub(count);
                         }
+                        3 => app.calendar_move_days(-7 * count as i64)?,
+                        4 => app.board_move_selection(-(count as i64)),
                         _ => {}
                     }
                     app.number_prefix.clear();
@@ -2033,6 +5405,10 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                 KeyCode::Right => {
                     if app.current_tab == 1 {  // notes 界面
                         app.next_note_same_row();
+                    } else if app.current_tab == 3 {
+                        app.calendar_move_days(1)?;
+                    } else if app.current_tab == 4 {
+                        app.board_move_column(1);
                     }
                     app.number_prefix.clear();
                     app.last_key = Some(key);
@@ -2040,10 +5416,38 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                 KeyCode::Left => {
                     if app.current_tab == 1 {  // notes 界面
                         app.previous_note_same_row();
+                    } else if app.current_tab == 3 {
+                        app.calendar_move_days(-1)?;
+                    } else if app.current_tab == 4 {
+                        app.board_move_column(-1);
                     }
                     app.number_prefix.clear();
                     app.last_key = Some(key);
                 }
+                // 日历中 h/l 前后移动一天
+                KeyCode::Char('h') | KeyCode::Char('l') if app.current_tab == 3 => {
+                    app.calendar_move_days(if key == KeyCode::Char('l') { 1 } else { -1 })?;
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
+                // 看板中 h/l 切换列，H/L（或 [ / ]）把选中的任务移到左/右一列
+                KeyCode::Char('h') | KeyCode::Char('l') if app.current_tab == 4 => {
+                    app.board_move_column(if key == KeyCode::Char('l') { 1 } else { -1 });
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
+                KeyCode::Char('H') | KeyCode::Char('L') | KeyCode::Char('[') | KeyCode::Char(']') if app.current_tab == 4 => {
+                    let forward = matches!(key, KeyCode::Char('L') | KeyCode::Char(']'));
+                    app.board_move_task(if forward { 1 } else { -1 })?;
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
+                // 日历中 [ / ] 切换月份
+                KeyCode::Char('[') | KeyCode::Char(']') if app.current_tab == 3 => {
+                    app.calendar_move_months(if key == KeyCode::Char(']') { 1 } else { -1 })?;
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
 
                 KeyCode::Char('g') => {
                     // gg: 双击g跳到顶部
@@ -2052,6 +5456,8 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                             0 => app.goto_first_task(),
                             1 => app.goto_first_note(),
                             2 => app.pomodoro_scroll_offset = 0, // 番茄钟滚动到顶部
+                            3 => app.calendar_select(chrono::Local::now().date_naive())?, // 日历回到今天
+                            4 => app.board_selected[app.board_column] = 0,
                             _ => {}
                         }
                         app.number_prefix.clear();
@@ -2061,12 +5467,53 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                         app.last_key = Some(key);
                     }
                 }
+                KeyCode::Char('z') => {
+                    // z: 等待快速过滤键
+                    app.last_key = Some(key);
+                }
+                KeyCode::Char('A') => {
+                    // 便签墙中归档/取消归档当前便签
+                    if app.current_tab == 1 && !app.notes.is_empty() {
+                        app.toggle_note_archived()?;
+                    }
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
+                KeyCode::Char('P') => {
+                    // 便签墙中置顶/取消置顶当前便签
+                    if app.current_tab == 1 && !app.notes.is_empty() {
+                        app.toggle_note_pinned()?;
+                    }
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
+                KeyCode::Char('<') | KeyCode::Char('>') => {
+                    // 任务列表中把DDL推迟/提前一天（3> 推迟三天）
+                    if app.current_tab == 0 && app.selected_task().is_some() {
+                        let days = app.number_prefix.parse::<i64>().unwrap_or(1).max(1);
+                        let days = if key == KeyCode::Char('<') { -days } else { days };
+                        app.defer_task(crate::models::Defer::Days(days))?;
+                    }
+                    // 便签墙中前移/后移当前便签
+                    if app.current_tab == 1 && !app.notes.is_empty() {
+                        let from = app.note_list_state.selected().unwrap_or(0);
+                        let to = if key == KeyCode::Char('<') {
+                            from.saturating_sub(1)
+                        } else {
+                            (from + 1).min(app.notes.len() - 1)
+                        };
+                        app.move_note(from, to)?;
+                    }
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
                 KeyCode::Char('G') => {
                     // G: 跳到末尾 (支持数字前缀如 5G 跳到第5行)
                     if app.number_prefix.is_empty() {
                         match app.current_tab {
                             0 => app.goto_last_task(),
                             1 => app.goto_last_note(),
+                            4 => app.board_move_selection(app.board_tasks.len() as i64),
                             _ => {}
                         }
                     } else {
@@ -2099,12 +5546,12 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                     match app.current_tab {
                         0 => {
                             if let Err(e) = app.create_task_with_vim() {
-                                app.set_status_message(format!("创建失败: {}", e));
+                                app.report_error("创建", e);
                             }
                         }
                         1 => {
                             if let Err(e) = app.create_note_with_vim() {
-                                app.set_status_message(format!("创建失败: {}", e));
+                                app.report_error("创建", e);
                             }
                         }
                         _ => {}
@@ -2113,10 +5560,15 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                     app.last_key = Some(key);
                 }
                 KeyCode::Enter => {
-                    // Enter: 便签界面查看详情
-                    if app.current_tab == 1 && !app.notes.is_empty() {
+                    // Enter: 查看任务详情（完整标题）/ 便签详情
+                    if app.current_tab == 0 && app.selected_task().is_some() {
+                        app.open_task_detail()?;
+                    } else if app.current_tab == 3 {
+                        app.calendar_open_day();
+                    } else if app.current_tab == 1 && !app.notes.is_empty() {
                         app.show_dialog = DialogType::ViewNote;
                         app.view_note_scroll_offset = 0; // 重置滚动位置
+                        app.view_note_hscroll = 0;
                     }
                     app.number_prefix.clear();
                     app.last_key = Some(key);
@@ -2127,14 +5579,14 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                         0 => {
                             if !app.tasks.is_empty() {
                                 if let Err(e) = app.init_edit_task_with_vim() {
-                                    app.set_status_message(format!("编辑失败: {}", e));
+                                    app.report_error("编辑", e);
                                 }
                             }
                         }
                         1 => {
                             if !app.notes.is_empty() {
                                 if let Err(e) = app.init_edit_note() {
-                                    app.set_status_message(format!("编辑失败: {}", e));
+                                    app.report_error("编辑", e);
                                 }
                             }
                         }
@@ -2154,8 +5606,12 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                 KeyCode::Char('d') => {
                     // 删除（高频）- dd删除，也可以用 :d 或 :delete
                     if app.last_key == Some(KeyCode::Char('d')) {
-                        // dd: 快速删除，直接显示确认对话框
-                        app.show_dialog = DialogType::DeleteConfirm;
+                        // dd: 任务直接归档（可撤销），便签和归档视图中的任务显示确认对话框
+                        if app.current_tab == 0 && !app.show_archived_tasks {
+                            app.archive_task()?;
+                        } else {
+                            app.confirm_delete()?;
+                        }
                         app.number_prefix.clear();
                         app.last_key = None;
                     } else {
@@ -2163,32 +5619,79 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                         app.last_key = Some(key);
                     }
                 }
-                KeyCode::Char('p') => {
-                    // 切换优先级（中频）- 也可以用 :p 或 :priority
-                    if app.current_tab == 0 {
-                        app.cycle_priority()?;
+                KeyCode::Char('V') | KeyCode::Char('v') => {
+                    // 可视模式：选择一段任务后批量操作
+                    if app.current_tab == 0 {
+                        app.enter_visual_mode();
+                    }
+                    app.number_prefix.clear();
+                    app.last_key = None;
+                }
+                KeyCode::Char('p') => {
+                    // 切换优先级（中频）- 也可以用 :p 或 :priority
+                    if app.current_tab == 0 {
+                        app.cycle_priority()?;
+                    }
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
+                // 日历中 t 回到今天
+                KeyCode::Char('t') if app.current_tab == 3 => {
+                    app.calendar_select(chrono::Local::now().date_naive())?;
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
+                KeyCode::Char('t') => {
+                    // 设置DDL时间（中频）- t=time/deadline，也可以用 :ddl
+                    if app.current_tab == 0 && !app.tasks.is_empty() {
+                        app.init_datetime_picker();
+                        app.show_dialog = DialogType::SetDeadline;
+                    }
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
+                KeyCode::Char('T') => {
+                    // 开始/停止给选中的任务计时 - 也可以用 :track start/stop
+                    if app.current_tab == 0 && !app.tasks.is_empty() {
+                        app.toggle_time_tracking()?;
+                    }
+                    app.number_prefix.clear();
+                    app.last_key = Some(key);
+                }
+                KeyCode::Char('u') => {
+                    // 撤销上一步任务修改或便签删除，Ctrl-r 重做 - 也可以用 :undo / :redo（看板中同样可用）
+                    if matches!(app.current_tab, 0 | 1 | 4) {
+                        app.undo()?;
                     }
                     app.number_prefix.clear();
                     app.last_key = Some(key);
                 }
-                KeyCode::Char('t') => {
-                    // 设置DDL时间（中频）- t=time/deadline，也可以用 :ddl
+                KeyCode::Char('r') => {
+                    // 设置提醒时间 - 也可以用 :remind
                     if app.current_tab == 0 && !app.tasks.is_empty() {
-                        app.init_datetime_picker();
-                        app.show_dialog = DialogType::SetDeadline;
+                        app.init_reminder_picker();
                     }
                     app.number_prefix.clear();
                     app.last_key = Some(key);
                 }
 
                 // 番茄钟操作（仅在番茄钟标签页有效）
+                KeyCode::Char('s') if app.last_key == Some(KeyCode::Char('g')) => {
+                    // gs: 为当前任务开始番茄钟（切换到番茄钟标签页）
+                    if app.current_tab == 0 {
+                        app.start_pomodoro_for_selected_task();
+                    }
+                    app.number_prefix.clear();
+                    app.last_key = None;
+                }
                 KeyCode::Char('s') => {
-                    // 开始/暂停番茄钟（高频）- 也可以用 :s 或 :start
-                    if app.current_tab == 2 {
+                    // 开始/暂停番茄钟（高频）- 也可以用 :s 或 :start；任务列表中为选中的任务开始番茄钟
+                    if app.current_tab == 0 {
+                        app.start_pomodoro_for_selected_task();
+                    } else if app.current_tab == 2 {
                         match app.pomodoro.state {
                             crate::pomodoro::PomodoroState::Idle => {
-                                app.pomodoro.start_work(None);
-                                app.set_status_message("番茄钟开始！".to_string());
+                                app.open_pomodoro_picker();
                             }
                             crate::pomodoro::PomodoroState::Working
                             | crate::pomodoro::PomodoroState::Break => {
@@ -2226,10 +5729,7 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                                 app.pomodoro.work_duration = 120; // 最大120分钟
                             }
                             // 保存配置到数据库
-                            if let Ok(db) = Database::open(&app.db_path) {
-                                let _ = db.save_pomodoro_config(app.pomodoro.work_duration, app.pomodoro.break_duration);
-                            }
-                            app.set_status_message(format!("工作时长: {}分钟 (已保存)", app.pomodoro.work_duration));
+                            app.save_pomodoro_config(format!("工作时长: {}分钟 (已保存)", app.pomodoro.work_duration));
                         } else {
                             app.set_status_message("番茄钟运行中，无法调整时长！按S或c取消后再调整".to_string());
                         }
@@ -2244,10 +5744,7 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                             if app.pomodoro.work_duration > 5 {
                                 app.pomodoro.work_duration -= 5;
                                 // 保存配置到数据库
-                                if let Ok(db) = Database::open(&app.db_path) {
-                                    let _ = db.save_pomodoro_config(app.pomodoro.work_duration, app.pomodoro.break_duration);
-                                }
-                                app.set_status_message(format!("工作时长: {}分钟 (已保存)", app.pomodoro.work_duration));
+                                app.save_pomodoro_config(format!("工作时长: {}分钟 (已保存)", app.pomodoro.work_duration));
                             } else {
                                 app.set_status_message("工作时长最小为5分钟".to_string());
                             }
@@ -2267,10 +5764,7 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                                 app.pomodoro.break_duration = 60; // 最大60分钟
                             }
                             // 保存配置到数据库
-                            if let Ok(db) = Database::open(&app.db_path) {
-                                let _ = db.save_pomodoro_config(app.pomodoro.work_duration, app.pomodoro.break_duration);
-                            }
-                            app.set_status_message(format!("休息时长: {}分钟 (已保存)", app.pomodoro.break_duration));
+                            app.save_pomodoro_config(format!("休息时长: {}分钟 (已保存)", app.pomodoro.break_duration));
                         } else {
                             app.set_status_message("番茄钟运行中，无法调整时长！按S或c取消后再调整".to_string());
                         }
@@ -2285,10 +5779,7 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                             if app.pomodoro.break_duration > 1 {
                                 app.pomodoro.break_duration -= 1;
                                 // 保存配置到数据库
-                                if let Ok(db) = Database::open(&app.db_path) {
-                                    let _ = db.save_pomodoro_config(app.pomodoro.work_duration, app.pomodoro.break_duration);
-                                }
-                                app.set_status_message(format!("休息时长: {}分钟 (已保存)", app.pomodoro.break_duration));
+                                app.save_pomodoro_config(format!("休息时长: {}分钟 (已保存)", app.pomodoro.break_duration));
                             } else {
                                 app.set_status_message("休息时长最小为1分钟".to_string());
                             }
@@ -2302,7 +5793,7 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
 
                 // 帮助
                 KeyCode::Char('?') => {
-                    app.show_dialog = DialogType::Help;
+                    app.open_help(None);
                     app.number_prefix.clear();
                     app.last_key = Some(key);
                 }
@@ -2312,6 +5803,7 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
                     app.number_prefix.clear();
                     app.last_key = None;
                     app.status_message = None;
+                    app.health_warnings.clear();
                 }
 
                 // // q键: 退出 - 现在使用 :q
@@ -2332,6 +5824,103 @@ fn handle_key_event(app: &mut App, key: KeyCode) -> Result<()> {
     Ok(())
 }
 
+/// 可视模式按键：j/k/gg/G 扩展选择范围，其余按键对选中的任务批量操作
+fn handle_visual_key(app: &mut App, key: KeyCode) -> Result<()> {
+    let count = app.number_prefix.parse::<usize>().unwrap_or(1);
+    match key {
+        KeyCode::Char(c @ '0'..='9') if !(c == '0' && app.number_prefix.is_empty()) => {
+            app.number_prefix.push(c);
+            return Ok(());
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            for _ in 0..count {
+                if app.task_list_state.selected().is_some_and(|i| i + 1 < app.tasks.len()) {
+                    app.next_task();
+                }
+            }
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            for _ in 0..count {
+                if app.task_list_state.selected().is_some_and(|i| i > 0) {
+                    app.previous_task();
+                }
+            }
+        }
+        KeyCode::Char('g') if app.last_key == Some(KeyCode::Char('g')) => {
+            app.goto_first_task();
+            app.last_key = None;
+            return Ok(());
+        }
+        KeyCode::Char('g') => {
+            app.last_key = Some(key);
+            return Ok(());
+        }
+        KeyCode::Char('G') => app.goto_last_task(),
+        KeyCode::Char(' ') | KeyCode::Char('x') => app.batch_complete()?,
+        KeyCode::Char('d') => app.batch_archive()?,
+        KeyCode::Char('H') => app.batch_set_priority(Priority::High)?,
+        KeyCode::Char('M') => app.batch_set_priority(Priority::Medium)?,
+        KeyCode::Char('L') => app.batch_set_priority(Priority::Low)?,
+        KeyCode::Char('#') | KeyCode::Char(':') => {
+            // 进入命令模式，保留选择；# 预填 :tag
+            app.input_mode = InputMode::Command;
+            app.input_buffer = if key == KeyCode::Char('#') { "tag ".to_string() } else { String::new() };
+            app.cursor_position = app.input_buffer.chars().count();
+        }
+        KeyCode::Esc | KeyCode::Char('V') | KeyCode::Char('v') => app.exit_visual_mode(),
+        _ => {}
+    }
+    app.number_prefix.clear();
+    app.last_key = None;
+    Ok(())
+}
+
+/// 便签墙鼠标事件：点击选中，按下-拖动-松开 移动便签
+fn handle_note_mouse(app: &mut App, mouse: MouseEvent) -> Result<()> {
+    let hit = app
+        .note_card_areas
+        .iter()
+        .find(|(_, rect)| {
+            mouse.column >= rect.x
+                && mouse.column < rect.x + rect.width
+                && mouse.row >= rect.y
+                && mouse.row < rect.y + rect.height
+        })
+        .map(|(index, _)| *index);
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some(index) = hit {
+                app.note_list_state.select(Some(index));
+                app.drag_note = Some(index);
+            }
+        }
+        MouseEventKind::Drag(MouseButton::Left) => {
+            // 拖动时高亮目标位置
+            if let (Some(from), Some(target)) = (app.drag_note, hit) {
+                app.note_list_state.select(Some(target));
+                if target != from {
+                    app.set_status_message(format!("松开鼠标将便签移动到第 {} 位", target + 1));
+                }
+            }
+        }
+        MouseEventKind::Up(MouseButton::Left) => {
+            if let Some(from) = app.drag_note.take() {
+                let to = hit.unwrap_or(from);
+                if to != from {
+                    app.move_note(from, to)?;
+                } else {
+                    app.note_list_state.select(Some(from));
+                }
+            }
+        }
+        MouseEventKind::ScrollDown => app.next_note(),
+        MouseEventKind::ScrollUp => app.previous_note(),
+        _ => {}
+    }
+    Ok(())
+}
+
 /// 处理鼠标事件 (支持响应式布局)
 fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> Result<()> {
     match mouse.kind {
@@ -2359,22 +5948,20 @@ fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> Result<()> {
                 if row >= tabs_area.y && row < tabs_area.y + tabs_area.height {
                     // 动态计算每个标签的宽度（考虑边框）
                     let inner_width = tabs_area.width.saturating_sub(2); // 减去左右边框
-                    let tab_width = inner_width / 3; // 3个标签平分宽度
+                    let tab_width = inner_width / TAB_COUNT as u16; // 各标签平分宽度
 
                     // 计算点击位置在标签内的相对列位置（排除左边框）
                     let relative_col = col.saturating_sub(tabs_area.x + 1);
 
-                    if relative_col < tab_width {
-                        app.goto_tab(0);
-                    } else if relative_col < tab_width * 2 {
-                        app.goto_tab(1);
-                    } else if relative_col < tab_width * 3 {
-                        app.goto_tab(2);
+                    if tab_width > 0 {
+                        app.goto_tab((relative_col / tab_width) as usize);
                     }
                 }
                 // 点击内容区域 - 选择列表项
                 else if row >= content_area.y && row < content_area.y + content_area.height {
                     match app.current_tab {
+                        // 今日视图的行与任务索引不对应，不处理点击
+                        0 if app.agenda_active() => {}
                         0 => {
                             // 任务列表: Block有上边框(1行) + 标题行(1行) = 2行偏移
                             // 底部还有边框(1行) + 帮助文本(1行，在边框内)
@@ -2451,6 +6038,7 @@ fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> Result<()> {
 
 /// 渲染UI
 fn ui(f: &mut Frame, app: &mut App) {
+    let theme = app.theme;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -2460,28 +6048,63 @@ fn ui(f: &mut Frame, app: &mut App) {
         ])
         .split(f.area());
 
+    // 健康检查警告横幅（占用内容区顶部）
+    let content_area = if app.health_warnings.is_empty() {
+        chunks[1]
+    } else {
+        let banner_height = app.health_warnings.len() as u16 + 2;
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(banner_height), Constraint::Min(0)])
+            .split(chunks[1]);
+        render_health_banner(f, app, parts[0]);
+        parts[1]
+    };
+
     // 标签页
-    let titles = vec!["📝 Tasks (1)", "📓 Notes (2)", "🍅 Pomodoro (3)"];
+    let titles = vec!["📝 Tasks (1)", "📓 Notes (2)", "🍅 Pomodoro (3)", "📅 Calendar (4)", "📋 Board (5)"];
+    let app_title = match &app.profile {
+        Some(name) => format!("Task Manager [{}]", name),
+        None => "Task Manager".to_string(),
+    };
     let tabs = Tabs::new(titles)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(Span::styled("Task Manager", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)))
+                .title(Span::styled(app_title, Style::default().fg(theme.border).add_modifier(Modifier::BOLD)))
         )
         .select(app.current_tab)
-        .style(Style::default().fg(Color::White))
+        .style(Style::default().fg(theme.text))
         .highlight_style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         );
     f.render_widget(tabs, chunks[0]);
 
     // 内容区域
     match app.current_tab {
-        0 => render_tasks(f, app, chunks[1]),
-        1 => render_notes(f, app, chunks[1]),
-        2 => render_pomodoro(f, app, chunks[1]),
+        0 => render_tasks(f, app, content_area),
+        1 => render_notes(f, app, content_area),
+        2 => render_pomodoro(f, app, content_area),
+        3 => calendar_view::render(
+            f,
+            content_area,
+            app.calendar_date,
+            &app.calendar_tasks,
+            &app.config.icons,
+            &app.config.capacity,
+            &theme,
+        ),
+        4 => board_view::render(
+            f,
+            content_area,
+            &app.board_tasks,
+            &app.blocked_task_ids,
+            (app.board_column, &app.board_selected),
+            &app.config.icons,
+            &theme,
+        ),
         _ => {}
     }
 
@@ -2494,15 +6117,78 @@ fn ui(f: &mut Frame, app: &mut App) {
     }
 }
 
+/// 渲染健康检查警告横幅
+fn render_health_banner(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let lines: Vec<Line> = app
+        .health_warnings
+        .iter()
+        .map(|w| {
+            Line::from(vec![
+                Span::styled(format!("⚠️ {}", w.message), Style::default().fg(theme.accent)),
+                Span::styled(format!("  → {}", w.suggestion), Style::default().fg(theme.muted)),
+            ])
+        })
+        .collect();
+    let banner = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title("启动检查（Esc 关闭，:health 重新检查）"),
+    );
+    f.render_widget(banner, area);
+}
+
 /// 渲染任务列表
 fn render_tasks(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    // 详情窗格：列表在左，选中任务的详情在右（终端太窄时不显示）
+    let area = if app.show_task_pane && area.width >= TASK_PANE_MIN_WIDTH {
+        let parts = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(area);
+        render_task_pane(f, app, parts[1]);
+        parts[0]
+    } else {
+        area
+    };
+
+    if app.agenda_active() {
+        agenda::render(f, area, &app.tasks, app.agenda_position(), &app.config.icons, &theme);
+        return;
+    }
+
+    if app.tasks.is_empty() && app.show_archived_tasks {
+        let help = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "🗄️ 归档任务",
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from("还没有归档的任务（任务列表中 dd 归档）"),
+            Line::from(""),
+            Line::from(":archive 返回任务列表"),
+        ])
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.muted))
+                .title(" 归档任务 ")
+        );
+        f.render_widget(help, area);
+        return;
+    }
+
     // 如果没有任务，显示欢迎提示
-    if app.tasks.is_empty() {
+    if app.tasks.is_empty() && app.hidden_completed == 0 {
         let help = Paragraph::new(vec![
             Line::from(""),
             Line::from(Span::styled(
                 "📝 欢迎使用任务管理器",
-                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
             )),
             Line::from(""),
             Line::from("快捷键:"),
@@ -2516,37 +6202,118 @@ fn render_tasks(f: &mut Frame, app: &mut App, area: Rect) {
             Block::default()
                 .borders(Borders::ALL)
                 .title(" 任务列表 ")
-                .border_style(Style::default().fg(Color::Cyan))
+                .border_style(Style::default().fg(theme.border))
         );
         f.render_widget(help, area);
         return;
     }
 
-    let items: Vec<ListItem> = app
-        .tasks
+    // 每行可用宽度：减去左右边框(2)和高亮符号"▶ "(2)
+    let row_width = area.width.saturating_sub(4) as usize;
+
+    let icons = &app.config.icons;
+    let rows = task_rows(app);
+    let visual = app.visual_range();
+    let now = Utc::now();
+    let items: Vec<ListItem> = rows
         .iter()
-        .map(|task| {
-            let priority_icon = match task.priority {
-                Priority::High => "🔴",
-                Priority::Medium => "🟡",
-                Priority::Low => "🟢",
+        .map(|row| {
+            let task = match row {
+                TaskRow::Header(text) => {
+                    return ListItem::new(Line::from(Span::styled(
+                        text.clone(),
+                        Style::default().fg(theme.muted).add_modifier(Modifier::BOLD),
+                    )));
+                }
+                TaskRow::Task(index) => &app.tasks[*index],
             };
-            let status_icon = match task.status {
-                TaskStatus::Completed => "✅",
-                TaskStatus::InProgress => "🔄",
-                TaskStatus::Todo => "⭕",
+            let status_icon = icons.status(task.status);
+            let priority_icon = icons.priority(task.priority);
+
+            // DDL：截止时间和相对时间（已完成的任务只显示截止时间），今年的日期省略年份
+            let ddl_info = match task.due_date {
+                Some(due_date) => {
+                    let local = due_date.with_timezone(&chrono::Local);
+                    let format = if local.year() == chrono::Local::now().year() { "%m-%d %H:%M" } else { "%Y-%m-%d %H:%M" };
+                    if task.status == TaskStatus::Completed {
+                        format!(" [DDL: {}]", local.format(format))
+                    } else {
+                        format!(" [DDL: {} · {}]", local.format(format), crate::humanize::due(due_date, now, app.config.locale))
+                    }
+                }
+                None => String::new(),
+            };
+            // 逾期标红，24 小时内到期标黄
+            let due_style = if task.is_overdue() {
+                Style::default().fg(theme.danger)
+            } else if task.is_due_within(chrono::Duration::hours(DUE_SOON_HOURS)) {
+                Style::default().fg(theme.warning)
+            } else {
+                Style::default()
             };
 
-            // 添加DDL显示
-            let ddl_info = if let Some(due_date) = task.due_date {
-                let local = due_date.with_timezone(&chrono::Local);
-                format!(" [DDL: {}]", local.format("%Y-%m-%d %H:%M"))
+            // 已完成的番茄钟数
+            let pomodoro_info = if task.pomodoro_count > 0 {
+                format!(" 🍅{}", task.pomodoro_count)
+            } else {
+                String::new()
+            };
+            // 累计计时，正在计时的任务加粗显示
+            let tracking = task.id.is_some() && app.time_tracking.as_ref().map(|e| e.task_id) == task.id;
+            let tracked = task.id.map_or(0, |id| app.tracked_for(id, now));
+            let tracked_info = if tracking || tracked > 0 {
+                format!(" ⏱{}", crate::models::format_tracked(tracked))
             } else {
                 String::new()
             };
+            let tracked_style = if tracking {
+                Style::default().fg(theme.success).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.info)
+            };
+
+            // 被未完成的依赖阻塞的任务变暗并显示 🔒
+            let blocked = task.id.is_some_and(|id| app.blocked_task_ids.contains(&id))
+                && task.status != TaskStatus::Completed;
+            let lock = if blocked { "🔒 " } else { "" };
+
+            // 标题按剩余显示宽度截断，完整标题可按 Enter 查看
+            let prefix_width = Span::raw(status_icon.icon.as_str()).width()
+                + Span::raw(priority_icon.icon.as_str()).width()
+                + Span::raw(lock).width()
+                + 2;
+            let fixed_width = prefix_width
+                + Span::raw(pomodoro_info.as_str()).width()
+                + Span::raw(tracked_info.as_str()).width()
+                + Span::raw(ddl_info.as_str()).width();
+            let title = truncate_to_width(&task.title, row_width.saturating_sub(fixed_width));
+            // 进行中的任务标题加粗并换色（逾期时仍以红色为准）
+            let title_style = if blocked {
+                Style::default().fg(theme.muted)
+            } else if task.status == TaskStatus::InProgress && !task.is_overdue() {
+                Style::default().fg(theme.info).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
 
-            let content = format!("{} {} {}{}", status_icon, priority_icon, task.title, ddl_info);
-            ListItem::new(content)
+            let item = ListItem::new(Line::from(vec![
+                icon_span(status_icon),
+                Span::raw(" "),
+                icon_span(priority_icon),
+                Span::raw(" "),
+                Span::styled(lock, Style::default().fg(theme.muted)),
+                Span::styled(title, title_style),
+                Span::styled(pomodoro_info, Style::default().fg(theme.danger)),
+                Span::styled(tracked_info, tracked_style),
+                Span::raw(ddl_info),
+            ]));
+            // 可视模式选中的任务
+            match (row, &visual) {
+                (TaskRow::Task(index), Some(range)) if range.contains(index) => {
+                    item.style(due_style.bg(theme.selection))
+                }
+                _ => item.style(due_style),
+            }
         })
         .collect();
 
@@ -2554,30 +6321,354 @@ fn render_tasks(f: &mut Frame, app: &mut App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
+                .border_style(Style::default().fg(theme.border))
                 .title(Span::styled(
-                    format!(" 任务列表 ({} 个) ", app.tasks.len()),
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    if app.show_archived_tasks {
+                        format!(" 🗄️ 归档任务 ({} 个) ", app.tasks.len())
+                    } else {
+                        format!(" 任务列表 ({} 个) ", app.tasks.len() + app.hidden_completed)
+                    },
+                    Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
                 )),
         )
         .highlight_style(
             Style::default()
-                .bg(Color::DarkGray)
+                .bg(theme.selection)
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol("▶ ");
 
-    f.render_stateful_widget(list, area, &mut app.task_list_state);
+    // 将选中的任务索引映射到显示行
+    let selected_row = app
+        .task_list_state
+        .selected()
+        .and_then(|selected| rows.iter().position(|row| matches!(row, TaskRow::Task(i) if *i == selected)));
+    app.task_view_state.select(selected_row);
+    f.render_stateful_widget(list, area, &mut app.task_view_state);
+
+    // 吸顶分组标题：分组标题滚出视野时，在边框右上角显示当前分组
+    let offset = app.task_view_state.offset();
+    if matches!(rows.get(offset), Some(TaskRow::Task(_))) {
+        if let Some(TaskRow::Header(text)) = rows[..offset]
+            .iter()
+            .rev()
+            .find(|row| matches!(row, TaskRow::Header(_)))
+        {
+            let sticky = Paragraph::new(Line::from(Span::styled(
+                format!(" {} ", text),
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+            )))
+            .alignment(Alignment::Right);
+            let header_area = Rect {
+                x: area.x + 1,
+                y: area.y,
+                width: area.width.saturating_sub(2),
+                height: 1,
+            };
+            f.render_widget(sticky, header_area);
+        }
+    }
+}
+
+/// 任务列表中截止时间在该小时数以内的任务标为即将到期
+const DUE_SOON_HOURS: i64 = 24;
+
+/// 终端宽度小于该值时不显示详情窗格
+const TASK_PANE_MIN_WIDTH: u16 = 80;
+
+/// 详情窗格中最多列出的番茄钟记录数
+const TASK_PANE_MAX_SESSIONS: usize = 8;
+
+/// 渲染任务详情窗格（Tab / :split）
+fn render_task_pane(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let lines = match app.selected_task() {
+        Some(task) => task_pane_lines(task, app),
+        None => vec![
+            Line::from(""),
+            Line::from(Span::styled("没有选中的任务", Style::default().fg(theme.muted))),
+        ],
+    };
+    let pane = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .title(Span::styled(" 详情 ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))),
+    );
+    f.render_widget(pane, area);
+}
+
+/// 详情窗格内容：描述、关联便签、番茄钟记录和时间
+fn task_pane_lines(task: &Task, app: &App) -> Vec<Line<'static>> {
+    let theme = &app.theme;
+    let icons = &app.config.icons;
+    let (notes, sessions) = (&app.task_pane_notes, &app.task_pane_pomodoros);
+    let format_time = |dt: chrono::DateTime<Utc>| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+    let heading = |text: String| Line::from(Span::styled(text, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)));
+    let muted = |text: &str| Line::from(Span::styled(format!("  {}", text), Style::default().fg(theme.muted)));
+
+    let mut lines = vec![
+        Line::from(vec![
+            icon_span(icons.status(task.status)),
+            Span::raw(" "),
+            icon_span(icons.priority(task.priority)),
+            Span::raw(" "),
+            Span::styled(task.title.clone(), Style::default().add_modifier(Modifier::BOLD)),
+        ]),
+    ];
+    if let Some(due) = task.due_date {
+        lines.push(Line::from(vec![
+            Span::raw("DDL: "),
+            Span::styled(format_time(due), Style::default().fg(theme.border)),
+        ]));
+    }
+    if !task.tags.is_empty() {
+        let tags: Vec<String> = task.tags.iter().map(|t| format!("#{}", t)).collect();
+        lines.push(Line::from(Span::styled(tags.join(" "), Style::default().fg(theme.highlight))));
+    }
+
+    // 依赖（:blocked-by），未完成的依赖阻塞该任务
+    let dependencies = &app.task_pane_dependencies;
+    if !dependencies.is_empty() {
+        let pending = dependencies.iter().filter(|t| t.status != TaskStatus::Completed).count();
+        lines.push(Line::from(""));
+        lines.push(heading(if pending > 0 {
+            format!("依赖 (🔒 {} 个未完成)", pending)
+        } else {
+            "依赖 (已全部完成)".to_string()
+        }));
+        for dependency in dependencies {
+            let (mark, color) = if dependency.status == TaskStatus::Completed {
+                ("✓", theme.success)
+            } else {
+                ("·", theme.warning)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {} ", mark), Style::default().fg(color)),
+                Span::raw(format!("#{} {}", dependency.id.unwrap_or(0), dependency.title)),
+            ]));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading("描述".to_string()));
+    match task.description.as_ref().filter(|d| !d.is_empty()) {
+        Some(desc) => lines.extend(desc.lines().map(|line| Line::from(format!("  {}", line)))),
+        None => lines.push(muted("无")),
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading(format!("便签 ({})", notes.len())));
+    if notes.is_empty() {
+        lines.push(muted("无"));
+    }
+    for note in notes {
+        let mut spans = vec![Span::raw(format!("  📓 {}", note.title))];
+        if note.archived {
+            spans.push(Span::styled(" (已归档)", Style::default().fg(theme.muted)));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let completed = sessions.iter().filter(|s| s.completed).count();
+    lines.push(Line::from(""));
+    lines.push(heading(format!("番茄钟 (完成 {} / 共 {})", completed, sessions.len())));
+    if sessions.is_empty() {
+        lines.push(muted("无"));
+    }
+    for session in sessions.iter().take(TASK_PANE_MAX_SESSIONS) {
+        let (mark, color) = if session.completed { ("✓", theme.success) } else { ("✗", theme.muted) };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} ", mark), Style::default().fg(color)),
+            Span::raw(format!("{}  {} 分钟", format_time(session.start_time), session.duration_minutes)),
+        ]));
+    }
+    if sessions.len() > TASK_PANE_MAX_SESSIONS {
+        lines.push(muted(&format!("… 还有 {} 条", sessions.len() - TASK_PANE_MAX_SESSIONS)));
+    }
+
+    let entries = &app.task_pane_time_entries;
+    let now = Utc::now();
+    lines.push(Line::from(""));
+    if entries.is_empty() {
+        lines.push(heading("计时".to_string()));
+        lines.push(muted("无（T 开始计时）"));
+    } else {
+        lines.push(heading(format!(
+            "计时 (共 {})",
+            crate::models::format_tracked(task.id.map_or(0, |id| app.tracked_for(id, now)))
+        )));
+    }
+    for entry in entries.iter().take(TASK_PANE_MAX_SESSIONS) {
+        let (mark, color, end) = match entry.ended_at {
+            Some(end) => ("·", theme.muted, end.with_timezone(&chrono::Local).format("%H:%M").to_string()),
+            None => ("▶", theme.success, "进行中".to_string()),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} ", mark), Style::default().fg(color)),
+            Span::raw(format!(
+                "{} – {}  {}",
+                format_time(entry.started_at),
+                end,
+                crate::models::format_tracked(entry.seconds(now))
+            )),
+        ]));
+    }
+    if entries.len() > TASK_PANE_MAX_SESSIONS {
+        lines.push(muted(&format!("… 还有 {} 条", entries.len() - TASK_PANE_MAX_SESSIONS)));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading("时间".to_string()));
+    lines.push(Line::from(format!("  创建: {}", format_time(task.created_at))));
+    lines.push(Line::from(format!("  更新: {}", format_time(task.updated_at))));
+    if let Some(completed_at) = task.completed_at {
+        lines.push(Line::from(format!("  完成: {}", format_time(completed_at))));
+    }
+    lines.push(muted(&crate::humanize::audit(task.created_at, task.updated_at, Utc::now(), app.config.locale)));
+
+    lines
+}
+
+/// 解析便签到期时间：日期（当天结束时到期）、相对时长（3d/12h）或 off
+/// 返回 None 表示格式无效，Some(None) 表示取消到期时间
+fn parse_expiry(s: &str) -> Option<Option<chrono::DateTime<Utc>>> {
+    if s == "off" || s == "none" {
+        return Some(None);
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        let end_of_day = date.and_hms_opt(23, 59, 59)?;
+        return chrono::Local
+            .from_local_datetime(&end_of_day)
+            .earliest()
+            .map(|t| Some(t.with_timezone(&Utc)));
+    }
+
+    // 按字符拆出单位（输入可能以多字节字符结尾，如 3天），数值过大时视为无效
+    let (index, unit) = s.char_indices().last()?;
+    let amount: i64 = s[..index].parse().ok().filter(|n| *n > 0)?;
+    let duration = match unit {
+        'd' => chrono::Duration::try_days(amount),
+        'h' => chrono::Duration::try_hours(amount),
+        'm' => chrono::Duration::try_minutes(amount),
+        _ => return None,
+    }?;
+    Utc::now().checked_add_signed(duration).map(Some)
+}
+
+/// 开关设置的显示值
+fn value_str(on: bool) -> &'static str {
+    if on {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// 任务列表中的一行：分组标题或任务（索引指向 app.tasks）
+enum TaskRow {
+    Header(String),
+    Task(usize),
+}
+
+/// 生成任务列表的显示行：未完成任务按截止日期分组（可关闭），
+/// 已完成任务位于列表底部的可折叠分组中
+fn task_rows(app: &App) -> Vec<TaskRow> {
+    let mut rows = Vec::new();
+    let completed_count = app
+        .tasks
+        .iter()
+        .filter(|t| t.status == TaskStatus::Completed)
+        .count();
+    let show_header = app.task_filter.status != Some(TaskStatus::Completed);
+    let now = Utc::now();
+
+    let mut header_added = false;
+    let mut current_group = None;
+    for (index, task) in app.tasks.iter().enumerate() {
+        if task.status == TaskStatus::Completed {
+            if show_header && !header_added {
+                rows.push(TaskRow::Header(format!("▾ 已完成 ({})  za 折叠", completed_count)));
+                header_added = true;
+            }
+        } else if app.settings.group_by_date && app.task_sort.is_none() {
+            let group = DateGroup::of(task, now);
+            if current_group != Some(group) {
+                rows.push(TaskRow::Header(group.label().to_string()));
+                current_group = Some(group);
+            }
+        }
+        rows.push(TaskRow::Task(index));
+    }
+
+    if app.hidden_completed > 0 {
+        rows.push(TaskRow::Header(format!("▸ 已完成 ({})  za 展开", app.hidden_completed)));
+    }
+    rows
 }
 
 /// 渲染便签列表 (平铺卡片式)
 fn render_notes(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    // 顶部标签栏（有标签或正在过滤时显示）
+    let area = if app.note_tag_counts.is_empty() && app.note_tag_filter.is_none() {
+        area
+    } else {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+        f.render_widget(Paragraph::new(note_tag_chips(app)), chunks[0]);
+        chunks[1]
+    };
+
+    if app.notes.is_empty() && app.note_tag_filter.is_some() {
+        let help = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(format!("没有带 #{} 的便签", app.note_tag_filter.as_deref().unwrap_or(""))),
+            Line::from(""),
+            Line::from(":filter 清除过滤"),
+        ])
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.muted))
+                .title(" 便签墙 ")
+        );
+        f.render_widget(help, area);
+        return;
+    }
+
+    if app.notes.is_empty() && app.show_archived_notes {
+        let help = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "🗄️ 归档便签",
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from("还没有归档的便签"),
+            Line::from(""),
+            Line::from(":archived 返回便签墙"),
+        ])
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.muted))
+                .title(" 归档便签 ")
+        );
+        f.render_widget(help, area);
+        return;
+    }
+
     if app.notes.is_empty() {
         let help = Paragraph::new(vec![
             Line::from(""),
             Line::from(Span::styled(
                 "📓 便签墙",
-                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
             )),
             Line::from(""),
             Line::from("这里还没有便签"),
@@ -2592,7 +6683,7 @@ fn render_notes(f: &mut Frame, app: &mut App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Magenta))
+                .border_style(Style::default().fg(theme.highlight))
                 .title(" 便签墙 ")
         );
         f.render_widget(help, area);
@@ -2647,6 +6738,7 @@ fn render_notes(f: &mut Frame, app: &mut App, area: Rect) {
 
     // 渲染可见的卡片行
     let selected_idx = app.note_list_state.selected().unwrap_or(0);
+    app.note_card_areas.clear();
 
     for (display_row_idx, row_idx) in (start_row..end_row).enumerate() {
         let start_idx = row_idx * cards_per_row;
@@ -2675,13 +6767,28 @@ fn render_notes(f: &mut Frame, app: &mut App, area: Rect) {
                 .take(2)
                 .collect();
 
+            // 便签自选的颜色用于标题和边框
+            let color = note.color.as_deref().and_then(|c| c.parse::<Color>().ok());
+
             let mut lines = vec![];
-            lines.push(Line::from(Span::styled(
-                &note.title,
+            let mut title_spans = vec![Span::styled(
+                note.title.as_str(),
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(color.unwrap_or(theme.accent))
                     .add_modifier(Modifier::BOLD),
-            )));
+            )];
+            for tag in &note.tags {
+                title_spans.push(Span::styled(format!(" #{}", tag), Style::default().fg(theme.info)));
+            }
+            lines.push(Line::from(title_spans));
+
+            // 到期时间（到期后自动归档）
+            if let Some(expires_at) = note.expires_at.filter(|_| !note.archived) {
+                lines.push(Line::from(Span::styled(
+                    format!("⏳ {} 到期", expires_at.with_timezone(&chrono::Local).format("%m-%d %H:%M")),
+                    Style::default().fg(theme.muted),
+                )));
+            }
 
             for line in content_preview {
                 let truncated = if line.chars().count() > 28 {
@@ -2693,24 +6800,31 @@ fn render_notes(f: &mut Frame, app: &mut App, area: Rect) {
                 };
                 lines.push(Line::from(Span::styled(
                     truncated,
-                    Style::default().fg(Color::Gray),
+                    Style::default().fg(theme.secondary),
                 )));
             }
 
             let (card_style, border_style) = if is_selected {
                 (
-                    Style::default().fg(Color::White),
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.text),
+                    Style::default().fg(color.unwrap_or(theme.accent)).add_modifier(Modifier::BOLD),
                 )
             } else {
                 (
-                    Style::default().fg(Color::Gray),
-                    Style::default().fg(Color::Magenta),
+                    Style::default().fg(theme.secondary),
+                    Style::default().fg(color.unwrap_or(theme.highlight)),
                 )
             };
 
             let symbol = if is_selected { "▶ " } else { "  " };
-            let title = format!("{}📝 便签 #{}", symbol, note_idx + 1);
+            let icon = if note.archived {
+                "🗄️"
+            } else if note.pinned {
+                "📌"
+            } else {
+                "📝"
+            };
+            let title = format!("{}{} 便签 #{}", symbol, icon, note_idx + 1);
 
             let card = Paragraph::new(lines)
                 .block(
@@ -2722,12 +6836,37 @@ fn render_notes(f: &mut Frame, app: &mut App, area: Rect) {
                 .wrap(Wrap { trim: true });
 
             f.render_widget(card, cols[col_idx]);
+            app.note_card_areas.push((note_idx, cols[col_idx]));
         }
     }
 }
 
+/// 便签墙顶部的标签栏：#标签(便签数)，正在过滤的标签高亮
+fn note_tag_chips(app: &App) -> Line<'static> {
+    let theme = app.theme;
+    let mut spans = vec![Span::styled(" 🏷 ", Style::default().fg(theme.muted))];
+    let mut chips = app.note_tag_counts.clone();
+    // 过滤的标签在当前视图中已没有便签时也显示出来，方便看出正在过滤
+    if let Some(tag) = app.note_tag_filter.as_ref().filter(|t| !chips.iter().any(|(c, _)| c == *t)) {
+        chips.push((tag.clone(), 0));
+    }
+    for (tag, count) in chips {
+        let style = if app.note_tag_filter.as_deref() == Some(tag.as_str()) {
+            Style::default().fg(theme.text).bg(theme.selection).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.info)
+        };
+        spans.push(Span::styled(format!(" #{}({}) ", tag, count), style));
+        spans.push(Span::raw(" "));
+    }
+    let hint = if app.note_tag_filter.is_some() { ":filter 清除过滤" } else { ":filter #标签 过滤" };
+    spans.push(Span::styled(hint, Style::default().fg(theme.muted)));
+    Line::from(spans)
+}
+
 /// 渲染番茄钟
 fn render_pomodoro(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
     // 左右分割布局：计时显示 + 信息面板
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -2741,14 +6880,17 @@ fn render_pomodoro(f: &mut Frame, app: &mut App, area: Rect) {
     let state_text = match app.pomodoro.state {
         crate::pomodoro::PomodoroState::Idle => "空闲",
         crate::pomodoro::PomodoroState::Working => "🔥 工作中",
+        crate::pomodoro::PomodoroState::Break if app.pomodoro.long_break => "🛋️ 长休息中",
         crate::pomodoro::PomodoroState::Break => "☕ 休息中",
+        crate::pomodoro::PomodoroState::Paused
+            if app.pomodoro.paused_from == Some(crate::pomodoro::PomodoroState::Break) => "⏸️ 休息暂停",
         crate::pomodoro::PomodoroState::Paused => "⏸️ 暂停",
     };
 
     let state_color = match app.pomodoro.state {
-        crate::pomodoro::PomodoroState::Working => Color::Red,
-        crate::pomodoro::PomodoroState::Break => Color::Green,
-        _ => Color::Gray,
+        crate::pomodoro::PomodoroState::Working => theme.danger,
+        crate::pomodoro::PomodoroState::Break => theme.success,
+        _ => theme.secondary,
     };
 
     let time_remaining = app.pomodoro.format_remaining();
@@ -2762,11 +6904,11 @@ fn render_pomodoro(f: &mut Frame, app: &mut App, area: Rect) {
 
     // 根据进度调整进度条颜色
     let progress_color = if progress < 30.0 {
-        Color::Green
+        theme.success
     } else if progress < 70.0 {
-        Color::Yellow
+        theme.accent
     } else {
-        Color::Red
+        theme.danger
     };
 
     let mut timer_display = vec![
@@ -2774,14 +6916,14 @@ fn render_pomodoro(f: &mut Frame, app: &mut App, area: Rect) {
         // 分隔线
         Line::from(Span::styled(
             "─────────────────",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
         )),
         Line::from(""),
         // 时间显示（大型、加粗、下划线）
         Line::from(Span::styled(
             format!("  ⏱ {}  ", &time_remaining),
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
         )),
         Line::from(""),
@@ -2794,7 +6936,7 @@ fn render_pomodoro(f: &mut Frame, app: &mut App, area: Rect) {
             ),
             Span::styled(
                 empty_blocks,
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(theme.muted),
             ),
         ]),
         Line::from(""),
@@ -2809,20 +6951,21 @@ fn render_pomodoro(f: &mut Frame, app: &mut App, area: Rect) {
             state_text,
             Style::default().fg(state_color).add_modifier(Modifier::BOLD),
         )),
-        Line::from(""),
+        // 本轮进度（满一轮后长休息）
+        Line::from(app.pomodoro.cycle_marks()),
         // 分隔线
         Line::from(Span::styled(
             "─────────────────",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
         )),
     ];
 
     let timer_block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD))
         .title(Span::styled(
             " ⏱️ ",
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
         ));
 
     let timer_para = Paragraph::new(timer_display)
@@ -2837,7 +6980,7 @@ fn render_pomodoro(f: &mut Frame, app: &mut App, area: Rect) {
         // 统计信息
         Line::from(Span::styled(
             "📊 今日统计",
-            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.success).add_modifier(Modifier::BOLD),
         )),
         Line::from(format!(
             "  完成: {} 个 · 时长: {} 分钟",
@@ -2848,7 +6991,7 @@ fn render_pomodoro(f: &mut Frame, app: &mut App, area: Rect) {
         // 配置信息
         Line::from(Span::styled(
             "⚙️ 计时配置",
-            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.border).add_modifier(Modifier::BOLD),
         )),
         Line::from(format!(
             "  工作: {} 分钟",
@@ -2858,38 +7001,102 @@ fn render_pomodoro(f: &mut Frame, app: &mut App, area: Rect) {
             "  休息: {} 分钟",
             app.pomodoro.break_duration
         )),
-        Line::from(""),
+    ];
+    // 长休息周期：本轮进度 🍅🍅🍅⚪
+    if app.pomodoro.cycle_length > 0 {
+        info_content.push(Line::from(format!(
+            "  长休息: {} 分钟（每 {} 个）",
+            app.pomodoro.long_break_duration, app.pomodoro.cycle_length
+        )));
+        info_content.push(Line::from(vec![
+            Span::raw("  本轮: "),
+            Span::raw(app.pomodoro.cycle_marks()),
+            Span::styled(
+                format!(" {}/{}", app.pomodoro.completed_in_cycle.min(app.pomodoro.cycle_length), app.pomodoro.cycle_length),
+                Style::default().fg(theme.muted),
+            ),
+        ]));
+    }
+    info_content.push(Line::from(""));
+
+    // 本周目标进度
+    if !app.weekly_goals.is_empty() {
+        info_content.push(Line::from(Span::styled(
+            "🎯 本周目标",
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        )));
+        for goal in &app.weekly_goals {
+            let color = if goal.hit() { theme.success } else { theme.border };
+            info_content.push(Line::from(vec![
+                Span::raw(format!("  #{:<12} ", goal.tag)),
+                Span::styled(goal.bar(15), Style::default().fg(color)),
+            ]));
+        }
+        info_content.push(Line::from(""));
+    }
+
+    // 今日日程（从日历导入的忙碌时段），进行中的事件高亮
+    if !app.busy_today.is_empty() {
+        let now = Utc::now();
+        info_content.push(Line::from(Span::styled(
+            "📅 今日日程",
+            Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
+        )));
+        for event in &app.busy_today {
+            let ongoing = event.start <= now && now < event.end;
+            let style = if ongoing {
+                Style::default().fg(theme.danger).add_modifier(Modifier::BOLD)
+            } else if event.end <= now {
+                Style::default().fg(theme.muted)
+            } else {
+                Style::default()
+            };
+            info_content.push(Line::from(Span::styled(
+                format!(
+                    "  {}-{} {}{}",
+                    event.start.with_timezone(&chrono::Local).format("%H:%M"),
+                    event.end.with_timezone(&chrono::Local).format("%H:%M"),
+                    event.summary,
+                    if ongoing { "（进行中）" } else { "" }
+                ),
+                style,
+            )));
+        }
+        info_content.push(Line::from(""));
+    }
+
+    info_content.extend(vec![
         // 快捷键提示
         Line::from(Span::styled(
             "⌨️ 快捷键",
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
         )),
         Line::from(vec![
-            Span::styled("  s", Style::default().fg(Color::Cyan)),
+            Span::styled("  s", Style::default().fg(theme.border)),
             Span::raw("     开始/暂停"),
         ]),
         Line::from(vec![
-            Span::styled("  S", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+            Span::styled("  S", Style::default().fg(theme.danger).add_modifier(Modifier::BOLD)),
             Span::raw("     "),
-            Span::styled("取消计时", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+            Span::styled("取消计时", Style::default().fg(theme.danger).add_modifier(Modifier::BOLD)),
         ]),
-    ];
+    ]);
 
     if app.pomodoro.state == crate::pomodoro::PomodoroState::Idle {
         info_content.push(Line::from(vec![
-            Span::styled("  +/-", Style::default().fg(Color::Cyan)),
+            Span::styled("  +/-", Style::default().fg(theme.border)),
             Span::raw("   调整工作时长"),
         ]));
         info_content.push(Line::from(vec![
-            Span::styled("  []", Style::default().fg(Color::Cyan)),
+            Span::styled("  []", Style::default().fg(theme.border)),
             Span::raw("    调整休息时长"),
         ]));
     } else {
         info_content.push(Line::from(""));
         info_content.push(Line::from(vec![
-            Span::styled("↑↓", Style::default().fg(Color::Cyan)),
+            Span::styled("↑↓", Style::default().fg(theme.border)),
             Span::raw(" 或 "),
-            Span::styled("j/k", Style::default().fg(Color::Cyan)),
+            Span::styled("j/k", Style::default().fg(theme.border)),
             Span::raw("    滚动信息"),
         ]));
     }
@@ -2901,10 +7108,10 @@ fn render_pomodoro(f: &mut Frame, app: &mut App, area: Rect) {
 
     let info_block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(theme.danger).add_modifier(Modifier::BOLD))
         .title(Span::styled(
             " 🍅 番茄钟 ",
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
         ));
 
     let info_para = Paragraph::new(info_content)
@@ -2916,14 +7123,24 @@ fn render_pomodoro(f: &mut Frame, app: &mut App, area: Rect) {
 
 /// 渲染状态栏
 fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let (mode_indicator, status_text, bar_style) = match app.input_mode {
         InputMode::Command => {
             // Command模式：显示正在输入的命令
-            ("COMMAND", format!(":{}", app.input_buffer), Style::default().bg(Color::Blue).fg(Color::White).add_modifier(Modifier::BOLD))
+            ("COMMAND", format!(":{}", app.input_buffer), Style::default().bg(theme.info).fg(theme.text).add_modifier(Modifier::BOLD))
         }
         InputMode::Insert => {
             // Insert模式：显示模式名称
-            ("INSERT", "正在编辑...".to_string(), Style::default().bg(Color::Green).fg(Color::Black).add_modifier(Modifier::BOLD))
+            ("INSERT", "正在编辑...".to_string(), Style::default().bg(theme.success).fg(theme.background).add_modifier(Modifier::BOLD))
+        }
+        InputMode::Visual => {
+            // Visual模式：显示选中数量和可用的批量操作
+            let selected = app.visual_range().map_or(0, |r| r.count());
+            (
+                "VISUAL",
+                format!("已选 {} 个任务 | x:完成 d:归档 H/M/L:优先级 #:标签 Esc:退出", selected),
+                Style::default().bg(theme.highlight).fg(theme.background).add_modifier(Modifier::BOLD),
+            )
         }
         InputMode::Normal => {
             // Normal模式：显示vim状态、数字前缀或状态消息
@@ -2934,11 +7151,17 @@ fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
                 parts.push(format!("[{}]", app.number_prefix));
             }
 
+            // 有未查看的错误时提示 :messages
+            if app.messages.unread > 0 {
+                parts.push(format!("❌ {} 个错误（:messages 查看）", app.messages.unread));
+            }
+
             // 显示等待中的按键（如 'g' 或 'd'）
             if let Some(last_key) = app.last_key {
                 match last_key {
                     KeyCode::Char('g') => parts.push("[g]".to_string()),
                     KeyCode::Char('d') => parts.push("[d]".to_string()),
+                    KeyCode::Char('z') => parts.push("[z]".to_string()),
                     _ => {}
                 }
             }
@@ -2946,23 +7169,53 @@ fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
             // 显示状态消息或默认帮助
             let message = if let Some(ref msg) = app.status_message {
                 msg.clone()
+            } else if parts.is_empty() && app.config.ui_mode == UiMode::Simple {
+                "简单模式 · 操作提示见下方".to_string()
             } else if parts.is_empty() {
                 "按 ? 显示帮助 | 按 : 进入命令模式".to_string()
             } else {
                 parts.join(" ")
             };
 
-            ("NORMAL", message, Style::default().bg(Color::DarkGray).fg(Color::White))
+            ("NORMAL", message, Style::default().bg(theme.selection).fg(theme.text))
         }
     };
 
-    let status_content = vec![
+    let mut status_content = vec![
         Span::styled(format!(" {} ", mode_indicator), bar_style),
         Span::raw(" "),
-        Span::raw(status_text),
     ];
+    // 绑定任务的番茄钟计时期间始终显示任务标题和剩余时间
+    if let (Some(title), true) = (
+        &app.pomodoro_task_title,
+        app.pomodoro.state != crate::pomodoro::PomodoroState::Idle,
+    ) {
+        status_content.push(Span::styled(
+            format!(" 🍅 {} {} ", title, app.pomodoro.format_remaining()),
+            Style::default().bg(theme.danger).fg(theme.text),
+        ));
+        status_content.push(Span::raw(" "));
+    }
+    // 过滤生效时始终显示，避免误以为任务丢失
+    if app.task_filter.is_active() {
+        status_content.push(Span::styled(
+            format!(" 🔍 {} ", app.task_filter.describe()),
+            Style::default().bg(theme.accent).fg(theme.background),
+        ));
+        status_content.push(Span::raw(" "));
+    }
+    status_content.push(Span::raw(status_text));
+
+    // 简单模式在第二行常驻显示当前标签页可用的操作
+    let mut status_lines = vec![Line::from(status_content)];
+    if app.config.ui_mode == UiMode::Simple {
+        status_lines.push(Line::from(Span::styled(
+            keymap::simple_hints(app.current_tab),
+            Style::default().fg(theme.border),
+        )));
+    }
 
-    let status_bar = Paragraph::new(Line::from(status_content))
+    let status_bar = Paragraph::new(status_lines)
         .block(Block::default());
 
     f.render_widget(status_bar, area);
@@ -2970,9 +7223,28 @@ fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
 
 /// 渲染对话框
 fn render_dialog(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
     // 对于 ViewNote，使用更大的高度以容纳长内容
-    let (width_percent, height_percent) = if app.show_dialog == DialogType::ViewNote {
+    let (width_percent, height_percent) = if app.show_dialog == DialogType::Qr {
+        (90, 95)  // 二维码：尽量大，避免被换行截断
+    } else if app.show_dialog == DialogType::ViewNote && app.note_zoom {
+        (98, 95)  // ViewNote 放大模式：接近全屏
+    } else if matches!(app.show_dialog, DialogType::ViewNote | DialogType::Changelog) {
         (80, 85)  // ViewNote: 更大的尺寸便于阅读长内容
+    } else if matches!(
+        app.show_dialog,
+        DialogType::ViewTask
+            | DialogType::Usage
+            | DialogType::Triage
+            | DialogType::SlowLog
+            | DialogType::Settings
+            | DialogType::Messages
+            | DialogType::PickPomodoroTask
+            | DialogType::Trash
+            | DialogType::SetDeadline
+            | DialogType::SetReminder
+    ) {
+        (70, 60)  // ViewTask: 容纳完整标题和描述；SetDeadline: 日期、容量和重复规则
     } else {
         (60, 40)  // 其他对话框保持原尺寸
     };
@@ -2980,16 +7252,30 @@ fn render_dialog(f: &mut Frame, app: &App) {
 
     let (title, content) = match app.show_dialog {
         DialogType::CreateTask => {
+            // 快速输入记号的解析结果，带 @DDL 时按 Enter 直接创建
+            let quick = quick_add::QuickAdd::parse(&app.input_buffer, chrono::Local::now());
+            let (preview, hint) = if quick.has_tokens() {
+                (
+                    Span::styled(quick.describe(), Style::default().fg(theme.highlight)),
+                    if quick.due.is_some() { "按 Enter 创建, Esc 取消" } else { "按 Enter 设置DDL, Esc 取消" },
+                )
+            } else {
+                (
+                    Span::styled("可用 !high #标签 @明天 18:00 设置优先级、标签和DDL", Style::default().fg(theme.muted)),
+                    "按 Enter 确认, Esc 取消",
+                )
+            };
             ("创建新任务", vec![
                 Line::from(""),
                 Line::from("请输入任务标题:"),
                 Line::from(""),
                 Line::from(Span::styled(
                     &app.input_buffer,
-                    Style::default().fg(Color::Yellow),
+                    Style::default().fg(theme.accent),
                 )),
+                Line::from(preview),
                 Line::from(""),
-                Line::from("按 Enter 确认, Esc 取消"),
+                Line::from(hint),
             ])
         }
         DialogType::CreateNote => {
@@ -3032,46 +7318,46 @@ fn render_dialog(f: &mut Frame, app: &App) {
             ("创建新便签", vec![
                 Line::from(""),
                 Line::from(vec![
-                    Span::styled("第1步: ", Style::default().fg(Color::Gray)),
+                    Span::styled("第1步: ", Style::default().fg(theme.secondary)),
                     Span::styled(
                         "便签标题",
                         if app.input_title.is_empty() {
-                            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
                         } else {
-                            Style::default().fg(Color::Green)
+                            Style::default().fg(theme.success)
                         }
                     ),
                 ]),
                 Line::from(vec![
                     Span::styled(
                         format!("  {}", title_display),
-                        Style::default().fg(Color::Yellow),
+                        Style::default().fg(theme.accent),
                     ),
                     if app.input_title.is_empty() && app.input_buffer.len() > 40 {
-                        Span::styled(" (有更多内容)", Style::default().fg(Color::DarkGray))
+                        Span::styled(" (有更多内容)", Style::default().fg(theme.muted))
                     } else {
                         Span::raw("")
                     }
                 ]),
                 Line::from(""),
                 Line::from(vec![
-                    Span::styled("第2步: ", Style::default().fg(Color::Gray)),
+                    Span::styled("第2步: ", Style::default().fg(theme.secondary)),
                     Span::styled(
                         "便签内容",
                         if !app.input_title.is_empty() {
-                            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
                         } else {
-                            Style::default().fg(Color::DarkGray)
+                            Style::default().fg(theme.muted)
                         }
                     ),
                 ]),
                 Line::from(vec![
                     Span::styled(
                         format!("  {}", content_display),
-                        Style::default().fg(Color::Cyan),
+                        Style::default().fg(theme.border),
                     ),
                     if !app.input_title.is_empty() && app.input_buffer.len() > 40 {
-                        Span::styled(" (有更多内容)", Style::default().fg(Color::DarkGray))
+                        Span::styled(" (有更多内容)", Style::default().fg(theme.muted))
                     } else {
                         Span::raw("")
                     }
@@ -3079,7 +7365,7 @@ fn render_dialog(f: &mut Frame, app: &App) {
                 Line::from(""),
                 Line::from(vec![
                     Span::raw("当前: "),
-                    Span::styled(current_field, Style::default().fg(Color::Green)),
+                    Span::styled(current_field, Style::default().fg(theme.success)),
                 ]),
                 Line::from(""),
                 Line::from(instructions),
@@ -3093,7 +7379,7 @@ fn render_dialog(f: &mut Frame, app: &App) {
                 Line::from(""),
                 Line::from(Span::styled(
                     &app.input_buffer,
-                    Style::default().fg(Color::Yellow),
+                    Style::default().fg(theme.accent),
                 )),
                 Line::from(""),
                 Line::from("按 Enter 保存, Esc 取消"),
@@ -3161,34 +7447,34 @@ fn render_dialog(f: &mut Frame, app: &App) {
             ("编辑便签", vec![
                 Line::from(""),
                 Line::from(vec![
-                    Span::styled("标题: ", Style::default().fg(Color::Gray)),
+                    Span::styled("标题: ", Style::default().fg(theme.secondary)),
                 ]),
                 Line::from(Span::styled(
                     title_display,
                     if app.note_edit_field == 0 && app.input_mode == InputMode::Insert {
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                        Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
                     } else if app.note_edit_field == 0 {
-                        Style::default().fg(Color::Yellow)
+                        Style::default().fg(theme.accent)
                     } else {
-                        Style::default().fg(Color::Gray)
+                        Style::default().fg(theme.secondary)
                     }
                 )),
                 Line::from(""),
                 Line::from(vec![
-                    Span::styled("内容: ", Style::default().fg(Color::Gray)),
+                    Span::styled("内容: ", Style::default().fg(theme.secondary)),
                 ]),
                 Line::from(Span::styled(
                     content_display,
                     if app.note_edit_field == 1 && app.input_mode == InputMode::Insert {
-                        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+                        Style::default().fg(theme.border).add_modifier(Modifier::BOLD)
                     } else if app.note_edit_field == 1 {
-                        Style::default().fg(Color::Cyan)
+                        Style::default().fg(theme.border)
                     } else {
-                        Style::default().fg(Color::Gray)
+                        Style::default().fg(theme.secondary)
                     }
                 )),
                 Line::from(""),
-                Line::from(Span::styled(mode_hint, Style::default().fg(Color::Green))),
+                Line::from(Span::styled(mode_hint, Style::default().fg(theme.success))),
             ])
         }
         DialogType::DeleteConfirm => {
@@ -3200,11 +7486,11 @@ fn render_dialog(f: &mut Frame, app: &App) {
 
             ("确认删除", vec![
                 Line::from(""),
-                Line::from("确定要删除以下项目吗？"),
+                Line::from(if app.current_tab == 0 { "确定要删除以下任务吗？（移到回收站，不再进入归档）" } else { "确定要删除以下项目吗？（移到回收站）" }),
                 Line::from(""),
                 Line::from(Span::styled(
                     item_name,
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.danger).add_modifier(Modifier::BOLD),
                 )),
                 Line::from(""),
                 Line::from(""),
@@ -3213,133 +7499,36 @@ fn render_dialog(f: &mut Frame, app: &App) {
             ])
         }
         DialogType::Help => {
-            // 根据当前标签页显示不同的帮助内容
-            match app.current_tab {
-                0 => {
-                    // 任务界面帮助
-                    ("任务管理 - 快捷键帮助", vec![
-                        Line::from(""),
-                        Line::from(Span::styled("━━━ 导航 ━━━", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
-                        Line::from("  j/k / ↓/↑     上下移动"),
-                        Line::from("  h/l / Tab     切换标签"),
-                        Line::from("  gg / G        首行/末行"),
-                        Line::from("  5j / 10G      数字前缀跳转"),
-                        Line::from(""),
-                        Line::from(Span::styled("━━━ 任务操作 ━━━", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))),
-                        Line::from("  n / a / o     新建任务"),
-                        Line::from("  e             编辑任务"),
-                        Line::from("  dd            删除任务(双击d)"),
-                        Line::from("  Space / x     切换完成状态"),
-                        Line::from("  p             切换优先级"),
-                        Line::from("  t             设置DDL时间"),
-                        Line::from(""),
-                        Line::from(Span::styled("━━━ 命令模式 ━━━", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
-                        Line::from("  :new 标题     直接创建任务"),
-                        Line::from("  :p [1/2/3]    设置优先级 (1=Low, 2=Med, 3=High)"),
-                        Line::from("  :t / :ddl     设置DDL"),
-                        Line::from("  :sort         排序任务"),
-                        Line::from("  :q / :wq      退出"),
-                        Line::from("  :5            跳转第5行"),
-                        Line::from(""),
-                        Line::from(Span::styled("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━", Style::default().fg(Color::DarkGray))),
-                        Line::from(vec![
-                            Span::styled("j/k ↓/↑", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 滚动 | ", Style::default().fg(Color::DarkGray)),
-                            Span::styled("PgUp/PgDn", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 翻页 | ", Style::default().fg(Color::DarkGray)),
-                            Span::styled("g/Home", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 顶部 | ", Style::default().fg(Color::DarkGray)),
-                            Span::styled("Esc/q/?", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 关闭", Style::default().fg(Color::DarkGray)),
-                        ]),
-                    ])
-                }
-                1 => {
-                    // 便签界面帮助
-                    ("便签墙 - 快捷键帮助", vec![
-                        Line::from(""),
-                        Line::from(Span::styled("━━━ 导航 ━━━", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
-                        Line::from("  j/k / ↓/↑     上下移动"),
-                        Line::from("  h/l / Tab     切换标签"),
-                        Line::from("  gg / G        首行/末行"),
-                        Line::from(""),
-                        Line::from(Span::styled("━━━ 便签操作 ━━━", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD))),
-                        Line::from("  n / a / o     新建便签"),
-                        Line::from("  e             编辑便签"),
-                        Line::from("  dd            删除便签(双击d)"),
-                        Line::from(""),
-                        Line::from(Span::styled("━━━ 编辑便签 ━━━", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
-                        Line::from("  ↑/↓ 或 k/j    选择编辑字段(标题/内容)"),
-                        Line::from("  i             进入编辑模式"),
-                        Line::from("  Enter         保存当前字段"),
-                        Line::from("  Esc           取消编辑"),
-                        Line::from(""),
-                        Line::from(Span::styled("━━━ 命令模式 ━━━", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
-                        Line::from("  :new 内容     直接创建便签"),
-                        Line::from("  :q / :wq      退出"),
-                        Line::from(""),
-                        Line::from(Span::styled("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━", Style::default().fg(Color::DarkGray))),
-                        Line::from(vec![
-                            Span::styled("j/k ↓/↑", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 滚动 | ", Style::default().fg(Color::DarkGray)),
-                            Span::styled("PgUp/PgDn", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 翻页 | ", Style::default().fg(Color::DarkGray)),
-                            Span::styled("g/Home", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 顶部 | ", Style::default().fg(Color::DarkGray)),
-                            Span::styled("Esc/q/?", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 关闭", Style::default().fg(Color::DarkGray)),
-                        ]),
-                    ])
-                }
-                2 => {
-                    // 番茄钟界面帮助
-                    ("番茄钟 - 快捷键帮助", vec![
-                        Line::from(""),
-                        Line::from(Span::styled("━━━ 导航 ━━━", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
-                        Line::from("  h/l / Tab     切换标签"),
-                        Line::from("  1/2/3         快速跳转"),
-                        Line::from(""),
-                        Line::from(Span::styled("━━━ 番茄钟控制 ━━━", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))),
-                        Line::from("  s             开始/暂停"),
-                        Line::from("  S / c         停止/取消"),
-                        Line::from(""),
-                        Line::from(Span::styled("━━━ 时长调整（仅空闲时）━━━", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
-                        Line::from("  + / -         调整工作时长 (±5分钟)"),
-                        Line::from("  [ / ]         调整休息时长 (±1分钟)"),
-                        Line::from(""),
-                        Line::from(Span::styled("━━━ 命令模式 ━━━", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
-                        Line::from("  :s / :start   开始/暂停"),
-                        Line::from("  :c / :cancel  停止/取消"),
-                        Line::from("  :pomo w=25 b=5 设置时长并保存"),
-                        Line::from("  :q / :wq      退出"),
-                        Line::from(""),
-                        Line::from(Span::styled("提示: 工作25分钟 → 休息5分钟为标准番茄钟", Style::default().fg(Color::Gray))),
-                        Line::from(""),
-                        Line::from(Span::styled("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━", Style::default().fg(Color::DarkGray))),
-                        Line::from(vec![
-                            Span::styled("j/k ↓/↑", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 滚动 | ", Style::default().fg(Color::DarkGray)),
-                            Span::styled("PgUp/PgDn", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 翻页 | ", Style::default().fg(Color::DarkGray)),
-                            Span::styled("g/Home", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 顶部 | ", Style::default().fg(Color::DarkGray)),
-                            Span::styled("Esc/q/?", Style::default().fg(Color::Yellow)),
-                            Span::styled(" 关闭", Style::default().fg(Color::DarkGray)),
-                        ]),
-                    ])
-                }
-                _ => {
-                    // 默认帮助（不应该到这里）
-                    ("快捷键帮助", vec![
-                        Line::from(""),
-                        Line::from("按 ? 查看帮助"),
-                        Line::from(""),
-                        Line::from(Span::styled("按任意键关闭", Style::default().fg(Color::DarkGray))),
-                    ])
-                }
+            // 帮助内容由 help 模块的注册表生成
+            let title = if app.help_search.is_empty() && !app.help_searching {
+                app.help_topic.dialog_title()
+            } else {
+                "帮助 - 搜索"
+            };
+            let mut content = help::page_lines(app.help_topic, &app.help_search, app.keymap, theme);
+            content.push(Line::from(""));
+            content.push(Line::from(Span::styled("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━", Style::default().fg(theme.muted))));
+            if app.help_searching {
+                content.push(Line::from(vec![
+                    Span::styled(format!("/{}_", app.help_search), Style::default().fg(theme.accent)),
+                    Span::styled("  Enter 确认 | Esc 清除", Style::default().fg(theme.muted)),
+                ]));
+            } else {
+                content.push(Line::from(vec![
+                    Span::styled("j/k", Style::default().fg(theme.accent)),
+                    Span::styled(" 滚动 | ", Style::default().fg(theme.muted)),
+                    Span::styled("Tab/h/l", Style::default().fg(theme.accent)),
+                    Span::styled(" 主题 | ", Style::default().fg(theme.muted)),
+                    Span::styled("/", Style::default().fg(theme.accent)),
+                    Span::styled(" 搜索 | ", Style::default().fg(theme.muted)),
+                    Span::styled("Esc/q/?", Style::default().fg(theme.accent)),
+                    Span::styled(" 关闭", Style::default().fg(theme.muted)),
+                ]));
             }
+            (title, content)
         }
-        DialogType::SetDeadline => {
+        DialogType::SetDeadline | DialogType::SetReminder => {
+            let is_reminder = app.show_dialog == DialogType::SetReminder;
             // 构建日期时间选择器显示
             let field_names = ["年", "月", "日", "时", "分"];
             let values = [
@@ -3365,7 +7554,7 @@ fn render_dialog(f: &mut Frame, app: &App) {
                     datetime_spans.push(Span::styled(
                         display_value,
                         Style::default()
-                            .fg(Color::Yellow)
+                            .fg(theme.accent)
                             .add_modifier(Modifier::BOLD)
                             .add_modifier(Modifier::UNDERLINED),
                     ));
@@ -3422,14 +7611,14 @@ fn render_dialog(f: &mut Frame, app: &App) {
                         Line::from(""),
                         Line::from(Span::styled(
                             "════════════════════════════════════════",
-                            Style::default().fg(Color::DarkGray),
+                            Style::default().fg(theme.muted),
                         )),
                         Line::from(""),
                         Line::from(vec![
                             Span::raw("  "),
                             Span::styled(
                                 "待设定时间:",
-                                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                                Style::default().fg(theme.border).add_modifier(Modifier::BOLD),
                             ),
                         ]),
                         Line::from(""),
@@ -3446,98 +7635,135 @@ fn render_dialog(f: &mut Frame, app: &App) {
                             Span::raw("  当前调整: "),
                             Span::styled(
                                 field_names[app.datetime_picker_field],
-                                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
                             ),
                             Span::raw("  ("),
-                            Span::styled(time_diff, Style::default().fg(Color::Green)),
+                            Span::styled(time_diff, Style::default().fg(theme.success)),
                             Span::raw(")"),
                         ]),
-                        Line::from(""),
+                    ]);
+                    if is_reminder {
+                        content.push(reminder_due_line(app.selected_task(), selected_dt, theme));
+                    } else {
+                        content.push(deadline_load_line(app, selected_dt.map(|dt| dt.date_naive())));
+                        content.push(Line::from(vec![
+                            Span::raw("  重复: "),
+                            Span::styled(
+                                app.datetime_recurrence.map(|r| r.describe()).unwrap_or_else(|| "不重复".to_string()),
+                                Style::default().fg(theme.highlight),
+                            ),
+                        ]));
+                    }
+                    content.extend(vec![
                         Line::from(Span::styled(
                             "════════════════════════════════════════",
-                            Style::default().fg(Color::DarkGray),
+                            Style::default().fg(theme.muted),
                         )),
                         Line::from(""),
                         Line::from("操作:"),
                         Line::from("  0-9 直接输入数字  Backspace 删除"),
                         Line::from("  ↑/k 增加  ↓/j 减少"),
                         Line::from("  ←/h/→/l/Tab 切换字段"),
+                        Line::from(if is_reminder { "  :remind 30m 截止前提醒" } else { "  r 切换重复规则  x 不设DDL" }),
                         Line::from(""),
                         Line::from(vec![
-                            Span::styled("Enter", Style::default().fg(Color::Green)),
+                            Span::styled("Enter", Style::default().fg(theme.success)),
                             Span::raw(" 确认  "),
-                            Span::styled("Esc", Style::default().fg(Color::Red)),
+                            Span::styled("Esc", Style::default().fg(theme.danger)),
                             Span::raw(" 取消"),
                         ]),
                     ]);
 
-                    ("设置DDL时间", content)
+                    (if is_reminder { "设置提醒时间" } else { "设置DDL时间" }, content)
                 }
             }
         }
+        DialogType::Usage => ("使用统计（仅保存在本地）", usage_lines(&app.usage_stats, theme)),
+        DialogType::SlowLog => ("慢查询", slow_log_lines(theme)),
+        DialogType::Settings => ("设置（:set 名称=值 修改）", settings_lines(app)),
+        DialogType::Messages => ("错误记录（:messages clear 清空）", message_lines(app)),
+        DialogType::ResumePomodoro => ("继续番茄钟？", resume_pomodoro_lines(app)),
+        DialogType::Changelog => (
+            "更新日志 (j/k 滚动, q 关闭)",
+            changelog_lines(crate::update::CHANGELOG, theme),
+        ),
+        DialogType::Triage => ("分诊", triage_lines(app)),
+        DialogType::PickPomodoroTask => ("为番茄钟选择任务", pomodoro_pick_lines(app)),
+        DialogType::Trash => ("回收站", trash_lines(app)),
+        DialogType::PickWorkspace => ("切换 profile", workspace_pick_lines(app)),
+        DialogType::Qr => {
+            let mut content: Vec<Line> = app.qr_code.lines().map(Line::from).collect();
+            content.push(Line::from(Span::styled("用手机扫描 · 任意键关闭", Style::default().fg(theme.muted))));
+            ("任务二维码", content)
+        }
+        DialogType::ViewTask => {
+            if let Some(task) = app.selected_task() {
+                let project = task
+                    .project_id
+                    .and_then(|id| app.projects.iter().find(|p| p.id == Some(id)))
+                    .map(|p| p.name.as_str());
+                (
+                    "任务详情",
+                    task_detail_lines(task, project, task.id.map_or(0, |id| app.tracked_for(id, Utc::now())), &app.task_comments, &app.config.icons, app.config.locale, theme),
+                )
+            } else {
+                ("任务详情", vec![Line::from("没有选中的任务")])
+            }
+        }
         DialogType::ViewNote => {
             if let Some(note) = app.selected_note() {
                 let mut content = vec![
                     Line::from(""),
                     Line::from(Span::styled(
                         &note.title,
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                        Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
                     )),
                     Line::from(""),
-                    Line::from(Span::styled("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━", Style::default().fg(Color::DarkGray))),
+                    Line::from(Span::styled("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━", Style::default().fg(theme.muted))),
                     Line::from(""),
                 ];
 
-                // 添加便签内容，自动换行处理长行
-                let note_lines: Vec<&str> = note.content.lines().collect();
-                for line in note_lines {
-                    // 使用字符计数而非字节计数（正确处理UTF-8中文）
-                    let char_count = line.chars().count();
-                    if char_count > 50 {
-                        // 长行分割显示
-                        let chars: Vec<char> = line.chars().collect();
-                        let mut char_idx = 0;
-                        while char_idx < chars.len() {
-                            let end_idx = (char_idx + 50).min(chars.len());
-                            let chunk: String = chars[char_idx..end_idx].iter().collect();
-                            content.push(Line::from(format!("  {}", chunk)));
-                            char_idx = end_idx;
-                        }
-                    } else if line.is_empty() {
-                        content.push(Line::from(""));
-                    } else {
-                        content.push(Line::from(format!("  {}", line)));
-                    }
-                }
+                // 便签内容按 Markdown 显示，开启换行时按内容换行
+                content.extend(markdown::render(&note.content, app.note_wrap_width(), &theme));
 
                 content.extend(vec![
                     Line::from(""),
-                    Line::from(Span::styled("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━", Style::default().fg(Color::DarkGray))),
+                    Line::from(Span::styled("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━", Style::default().fg(theme.muted))),
                     Line::from(""),
                     Line::from(vec![
                         Span::raw("创建: "),
                         Span::styled(
                             note.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string(),
-                            Style::default().fg(Color::Cyan),
+                            Style::default().fg(theme.border),
                         ),
                         Span::raw("  更新: "),
                         Span::styled(
                             note.updated_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string(),
-                            Style::default().fg(Color::Cyan),
+                            Style::default().fg(theme.border),
                         ),
                     ]),
+                    Line::from(Span::styled(
+                        crate::humanize::audit(note.created_at, note.updated_at, Utc::now(), app.config.locale),
+                        Style::default().fg(theme.muted),
+                    )),
                     Line::from(""),
                     Line::from(vec![
-                        Span::styled("e", Style::default().fg(Color::Green)),
+                        Span::styled("e", Style::default().fg(theme.success)),
                         Span::raw(" 编辑  "),
-                        Span::styled("Esc/q", Style::default().fg(Color::Yellow)),
-                        Span::raw(" 关闭"),
+                        Span::styled("Esc/q", Style::default().fg(theme.accent)),
+                        Span::raw(" 关闭  "),
+                        Span::styled(":yank md/html/plain", Style::default().fg(theme.accent)),
+                        Span::raw(" 复制"),
                     ]),
                     Line::from(vec![
-                        Span::styled("j/k", Style::default().fg(Color::Yellow)),
+                        Span::styled("j/k", Style::default().fg(theme.accent)),
                         Span::raw(" 滚动  "),
-                        Span::styled("g/G", Style::default().fg(Color::Yellow)),
-                        Span::raw(" 首/末"),
+                        Span::styled("g/G", Style::default().fg(theme.accent)),
+                        Span::raw(" 首/末  "),
+                        Span::styled("z", Style::default().fg(theme.accent)),
+                        Span::raw(if app.note_zoom { " 还原  " } else { " 放大  " }),
+                        Span::styled("w", Style::default().fg(theme.accent)),
+                        Span::raw(if app.settings.note_wrap { " 不换行" } else { " 换行  h/l 横向滚动" }),
                     ]),
                 ]);
 
@@ -3552,10 +7778,10 @@ fn render_dialog(f: &mut Frame, app: &App) {
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::Black).fg(Color::White));
+        .style(Style::default().bg(theme.background).fg(theme.text));
 
-    let mut paragraph = if app.show_dialog == DialogType::ViewNote {
-        // ViewNote: 不使用自动换行（已手动处理），只支持垂直滚动
+    let mut paragraph = if matches!(app.show_dialog, DialogType::ViewNote | DialogType::Qr | DialogType::Changelog) {
+        // ViewNote: 不使用自动换行（已手动处理），只支持垂直滚动；二维码换行后无法扫描；更新日志需要保留缩进
         Paragraph::new(content).block(block)
     } else {
         // 其他对话框：使用自动换行
@@ -3567,15 +7793,456 @@ fn render_dialog(f: &mut Frame, app: &App) {
         paragraph = paragraph.scroll((app.help_scroll_offset as u16, 0));
     }
 
+    if app.show_dialog == DialogType::Changelog {
+        paragraph = paragraph.scroll((app.changelog_scroll as u16, 0));
+    }
+
     // 为ViewNote对话框添加滚动支持
     if app.show_dialog == DialogType::ViewNote {
-        paragraph = paragraph.scroll((app.view_note_scroll_offset as u16, 0));
+        paragraph = paragraph.scroll((app.view_note_scroll_offset as u16, app.view_note_hscroll as u16));
     }
 
     f.render_widget(Clear, area);
     f.render_widget(paragraph, area);
 }
 
+/// DDL选择器中显示所选日期的任务负载，超出容量时标红提示
+fn deadline_load_line(app: &App, date: Option<chrono::NaiveDate>) -> Line<'static> {
+    let theme = &app.theme;
+    let date = match date {
+        Some(date) => date,
+        None => return Line::from(""),
+    };
+    let (day_count, week_count) = app.planned_load(date);
+    let capacity = &app.config.capacity;
+    match capacity.warning(day_count, week_count) {
+        Some(warning) => Line::from(Span::styled(
+            format!("  ⚠️ {}", warning),
+            Style::default().fg(theme.danger).add_modifier(Modifier::BOLD),
+        )),
+        None => {
+            let limit = |count: usize, cap: Option<usize>| match cap {
+                Some(cap) => format!("{}/{}", count, cap),
+                None => count.to_string(),
+            };
+            Line::from(Span::styled(
+                format!(
+                    "  当天 {} 个任务，当周 {} 个",
+                    limit(day_count, capacity.daily),
+                    limit(week_count, capacity.weekly)
+                ),
+                Style::default().fg(theme.muted),
+            ))
+        }
+    }
+}
+
+/// 提醒时间选择器中显示与截止时间的关系
+fn reminder_due_line(task: Option<&Task>, selected: Option<chrono::DateTime<chrono::Local>>, theme: &Theme) -> Line<'static> {
+    let due = match task.and_then(|t| t.due_date) {
+        Some(due) => due,
+        None => return Line::from(Span::styled("  （任务没有DDL）", Style::default().fg(theme.muted))),
+    };
+    let due_text = due.with_timezone(&chrono::Local).format("%m-%d %H:%M");
+    match selected.map(|at| due.signed_duration_since(at).num_minutes()) {
+        Some(minutes) if minutes > 0 => Line::from(Span::styled(
+            format!("  截止 {}，提前 {}", due_text, crate::models::format_estimate(minutes)),
+            Style::default().fg(theme.muted),
+        )),
+        Some(_) => Line::from(Span::styled(
+            format!("  ⚠️ 晚于截止时间 {}", due_text),
+            Style::default().fg(theme.accent),
+        )),
+        None => Line::from(""),
+    }
+}
+
+/// 分诊对话框内容：当前任务、建议的优先级和截止时间及理由
+fn triage_lines(app: &App) -> Vec<Line<'static>> {
+    let theme = &app.theme;
+    let proposal = match app.triage_queue.first() {
+        Some(p) => p,
+        None => return vec![Line::from("没有待处理的建议")],
+    };
+    let priority_text = match proposal.priority {
+        Priority::High => "高",
+        Priority::Medium => "中",
+        Priority::Low => "低",
+    };
+    let due_text = proposal
+        .due_date
+        .map(|d| d.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "无".to_string());
+
+    vec![
+        Line::from(Span::styled(
+            format!("剩余 {} 个 · 已更新 {} 个", app.triage_queue.len(), app.triage_accepted),
+            Style::default().fg(theme.muted),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            proposal.title.clone(),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("建议优先级: "),
+            icon_span(app.config.icons.priority(proposal.priority)),
+            Span::styled(format!(" {}", priority_text), Style::default().fg(theme.border)),
+        ]),
+        Line::from(vec![
+            Span::raw("建议DDL: "),
+            Span::styled(due_text, Style::default().fg(theme.border)),
+        ]),
+        Line::from(Span::styled(
+            format!("理由: {}", proposal.reasons.join("，")),
+            Style::default().fg(theme.secondary),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Enter/y", Style::default().fg(theme.success)),
+            Span::raw(" 接受  "),
+            Span::styled("3/2/1", Style::default().fg(theme.accent)),
+            Span::raw(" 改为高/中/低并接受  "),
+            Span::styled("n", Style::default().fg(theme.accent)),
+            Span::raw(" 跳过  "),
+            Span::styled("Esc", Style::default().fg(theme.danger)),
+            Span::raw(" 结束"),
+        ]),
+    ]
+}
+
+/// profile 切换框内容：当前 profile 标记为 ●，其他为 ○
+fn workspace_pick_lines(app: &App) -> Vec<Line<'static>> {
+    let theme = &app.theme;
+    let current = app.profile.as_deref().unwrap_or("default");
+    let mut lines = vec![Line::from("")];
+    for (i, name) in app.workspaces.iter().enumerate() {
+        let style = if i == app.workspace_pick {
+            Style::default().bg(theme.selection).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let marker = if name == current { "● " } else { "○ " };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {}", marker), Style::default().fg(theme.success)),
+            Span::styled(name.clone(), style),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  tasks profile create <名称> 新建",
+        Style::default().fg(theme.muted),
+    )));
+    lines.push(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(theme.accent)),
+        Span::raw(" 选择  "),
+        Span::styled("Enter", Style::default().fg(theme.success)),
+        Span::raw(" 切换  "),
+        Span::styled("Esc", Style::default().fg(theme.danger)),
+        Span::raw(" 取消"),
+    ]));
+    lines
+}
+
+/// 回收站内容：类型、标题、删除时间和剩余保留天数
+fn trash_lines(app: &App) -> Vec<Line<'static>> {
+    let theme = &app.theme;
+    let mut lines = vec![Line::from("")];
+    if app.trash.is_empty() {
+        lines.push(Line::from(Span::styled("  回收站是空的", Style::default().fg(theme.muted))));
+    }
+    let now = Utc::now();
+    for (i, item) in app.trash.iter().enumerate() {
+        let style = if i == app.trash_pick {
+            Style::default().bg(theme.selection).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let kind = if item.is_task() { "📋 任务" } else { "📓 便签" };
+        let days_left = (crate::db::TRASH_RETENTION_DAYS - (now - item.deleted_at).num_days()).max(0);
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} #{} ", kind, item.entity_id), Style::default().fg(theme.secondary)),
+            Span::styled(item.title.clone(), style),
+            Span::styled(
+                format!(
+                    "  {} 删除 · {} 天后清除",
+                    item.deleted_at.with_timezone(&chrono::Local).format("%m-%d %H:%M"),
+                    days_left
+                ),
+                Style::default().fg(theme.muted),
+            ),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(theme.accent)),
+        Span::raw(" 选择  "),
+        Span::styled("Enter / u", Style::default().fg(theme.success)),
+        Span::raw(" 恢复  "),
+        Span::styled("Esc", Style::default().fg(theme.danger)),
+        Span::raw(" 关闭"),
+    ]));
+    lines
+}
+
+/// 番茄钟任务选择框内容：第一行为不关联任务，其后为未完成的任务及其已完成的番茄钟数
+fn pomodoro_pick_lines(app: &App) -> Vec<Line<'static>> {
+    let theme = &app.theme;
+    let row_style = |i: usize| {
+        if i == app.pomodoro_pick {
+            Style::default().bg(theme.selection).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        }
+    };
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled("  （不关联任务）", row_style(0).fg(theme.secondary))),
+    ];
+    for (i, task) in app.pomodoro_candidates().into_iter().enumerate() {
+        let mut spans = vec![
+            Span::styled("  ", row_style(i + 1)),
+            icon_span(app.config.icons.priority(task.priority)),
+            Span::styled(format!(" {}", task.title), row_style(i + 1)),
+        ];
+        if task.pomodoro_count > 0 {
+            spans.push(Span::styled(format!(" 🍅{}", task.pomodoro_count), Style::default().fg(theme.danger)));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(theme.accent)),
+        Span::raw(" 选择  "),
+        Span::styled("Enter", Style::default().fg(theme.success)),
+        Span::raw(" 开始  "),
+        Span::styled("Esc", Style::default().fg(theme.danger)),
+        Span::raw(" 取消"),
+    ]));
+    lines
+}
+
+/// 使用统计对话框内容：最常用的命令和按键，以及从未用过的命令
+/// 把内置的 Markdown 更新日志渲染为带样式的行（标题着色，列表项换成圆点）
+fn changelog_lines(changelog: &str, theme: &Theme) -> Vec<Line<'static>> {
+    changelog
+        .lines()
+        .map(|line| {
+            if let Some(title) = line.strip_prefix("### ") {
+                Line::from(Span::styled(format!("  {}", title), Style::default().fg(theme.success).add_modifier(Modifier::BOLD)))
+            } else if let Some(title) = line.strip_prefix("## ") {
+                Line::from(Span::styled(title.to_string(), Style::default().fg(theme.border).add_modifier(Modifier::BOLD)))
+            } else if let Some(title) = line.strip_prefix("# ") {
+                Line::from(Span::styled(title.to_string(), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)))
+            } else if let Some(item) = line.strip_prefix("- ") {
+                Line::from(format!("    • {}", item))
+            } else {
+                Line::from(line.to_string())
+            }
+        })
+        .collect()
+}
+
+/// :messages 对话框：最近的错误在前，同一错误连续出现时显示次数
+fn message_lines(app: &App) -> Vec<Line<'static>> {
+    let theme = &app.theme;
+    if app.messages.is_empty() {
+        return vec![Line::from(Span::styled("（暂无错误）", Style::default().fg(theme.muted)))];
+    }
+    app.messages
+        .recent()
+        .map(|message| {
+            let mut spans = vec![
+                Span::styled(format!("{} ", message.at.format("%H:%M:%S")), Style::default().fg(theme.muted)),
+                Span::styled(format!("{}失败: ", message.context), Style::default().fg(theme.danger).add_modifier(Modifier::BOLD)),
+                Span::raw(message.error.clone()),
+            ];
+            if message.count > 1 {
+                spans.push(Span::styled(format!(" ×{}", message.count), Style::default().fg(theme.warning)));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// 启动时询问是否继续的番茄钟：阶段、任务和剩余时间
+fn resume_pomodoro_lines(app: &App) -> Vec<Line<'static>> {
+    let theme = &app.theme;
+    let Some(snapshot) = &app.pending_pomodoro else {
+        return Vec::new();
+    };
+    let phase = match snapshot.phase {
+        crate::pomodoro::PomodoroState::Break if snapshot.long_break => "🛋️ 长休息",
+        crate::pomodoro::PomodoroState::Break => "☕ 休息",
+        _ => "🔥 工作",
+    };
+    let remaining = match (snapshot.paused_remaining, snapshot.ends_at) {
+        (Some(seconds), _) => format!("{:02}:{:02}（已暂停）", seconds / 60, seconds % 60),
+        (None, Some(end)) if end > Utc::now() => {
+            let seconds = (end - Utc::now()).num_seconds();
+            format!("{:02}:{:02}", seconds / 60, seconds % 60)
+        }
+        _ if snapshot.phase == crate::pomodoro::PomodoroState::Working => "已在关闭期间结束（继续后记为完成）".to_string(),
+        _ => "已在关闭期间结束".to_string(),
+    };
+    let task = snapshot
+        .task_id
+        .map(|id| {
+            app.tasks
+                .iter()
+                .find(|t| t.id == Some(id))
+                .map_or_else(|| format!("#{}", id), |t| t.title.clone())
+        })
+        .unwrap_or_else(|| "（未关联任务）".to_string());
+    let row = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<6}", label), Style::default().fg(theme.secondary)),
+            Span::styled(value, Style::default().add_modifier(Modifier::BOLD)),
+        ])
+    };
+    vec![
+        Line::from(""),
+        Line::from("上次退出时有未完成的番茄钟："),
+        Line::from(""),
+        row("阶段", phase.to_string()),
+        row("任务", task),
+        row("剩余", remaining),
+        Line::from(""),
+        Line::from(""),
+        Line::from("y / Enter - 继续"),
+        Line::from("n / Esc   - 放弃"),
+    ]
+}
+
+/// :set? 对话框：各项设置的当前值和说明
+fn settings_lines(app: &App) -> Vec<Line<'static>> {
+    let theme = &app.theme;
+    let sound = crate::sound::SoundSettings::load(&app.db).unwrap_or_default();
+    let mut rows: Vec<(&str, String, String)> = settings::SWITCHES
+        .iter()
+        .map(|(name, _, desc)| (*name, app.settings.describe(name), desc.to_string()))
+        .collect();
+    let (min, max) = settings::TICK_RATE_RANGE;
+    let poll_ms = app.config.intervals.poll_ms;
+    rows.push((
+        "tick_rate",
+        app.settings.describe("tick_rate"),
+        format!("界面刷新间隔 {}-{} 毫秒，default 使用配置文件（{}ms）", min, max, poll_ms),
+    ));
+    rows.push(("sound", value_str(sound.enabled).to_string(), "番茄钟结束和提醒时播放提示音".to_string()));
+    rows.push(("volume", sound.volume.to_string(), format!("提示音音量 0-{}", crate::sound::MAX_VOLUME)));
+    rows.push(("keymap", app.keymap.name().to_string(), "键位方案 vim/emacs/arrows".to_string()));
+
+    let mut lines: Vec<Line<'static>> = rows
+        .into_iter()
+        .map(|(name, value, desc)| {
+            Line::from(vec![
+                Span::styled(format!("{:<15}", name), Style::default().fg(theme.accent)),
+                Span::styled(format!("{:<9}", value), Style::default().fg(theme.success).add_modifier(Modifier::BOLD)),
+                Span::styled(desc, Style::default().fg(theme.muted)),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("任意键关闭", Style::default().fg(theme.muted))));
+    lines
+}
+
+/// 慢查询对话框内容（最新的在前）
+fn slow_log_lines(theme: &Theme) -> Vec<Line<'static>> {
+    let threshold = crate::db::timing::threshold().as_millis();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!(
+                "记录: {}  阈值: {}ms",
+                if crate::db::timing::enabled() { "开启" } else { "关闭" },
+                threshold
+            ),
+            Style::default().fg(theme.muted),
+        )),
+        Line::from(""),
+    ];
+    let queries = crate::db::timing::recent();
+    if queries.is_empty() {
+        lines.push(Line::from("  （暂无记录，:timing on 开启）"));
+    }
+    for query in queries {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", query.at.with_timezone(&chrono::Local).format("%H:%M:%S")),
+                Style::default().fg(theme.muted),
+            ),
+            Span::styled(
+                format!("{:>6}ms  ", query.elapsed.as_millis()),
+                Style::default().fg(theme.danger).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(query.sql.chars().take(120).collect::<String>()),
+        ]));
+    }
+    lines
+}
+
+fn usage_lines(stats: &[UsageStat], theme: &Theme) -> Vec<Line<'static>> {
+    let header = |text: &str| {
+        Line::from(Span::styled(
+            text.to_string(),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        ))
+    };
+    let stat_line = |name: &str, stat: &UsageStat| {
+        Line::from(vec![
+            Span::styled(format!("  {:<12}", name), Style::default().fg(theme.border)),
+            Span::raw(format!("{:>5} 次  ", stat.count)),
+            Span::styled(
+                stat.last_used.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string(),
+                Style::default().fg(theme.muted),
+            ),
+        ])
+    };
+
+    let mut lines = vec![Line::from(""), header("━━━ 常用命令 ━━━")];
+    let commands: Vec<&UsageStat> = stats.iter().filter(|s| s.name.starts_with(':')).take(8).collect();
+    if commands.is_empty() {
+        lines.push(Line::from("  （暂无记录）"));
+    }
+    for stat in commands {
+        lines.push(stat_line(&stat.name, stat));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(header("━━━ 常用按键 ━━━"));
+    let keys: Vec<&UsageStat> = stats.iter().filter(|s| s.name.starts_with("key:")).take(8).collect();
+    if keys.is_empty() {
+        lines.push(Line::from("  （暂无记录）"));
+    }
+    for stat in keys {
+        lines.push(stat_line(stat.name.trim_start_matches("key:"), stat));
+    }
+
+    let unused: Vec<&str> = help::command_names()
+        .into_iter()
+        .filter(|name| !stats.iter().any(|s| s.name == *name))
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(header("━━━ 从未用过的命令 ━━━"));
+    if unused.is_empty() {
+        lines.push(Line::from("  所有命令都用过了 🎉"));
+    } else {
+        lines.push(Line::from(format!("  {}", unused.join("  "))));
+        lines.push(Line::from(Span::styled(
+            "  用 :help commands 查看说明",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "统计只保存在本地数据库中 | :usage reset 清空 | 任意键关闭",
+        Style::default().fg(theme.muted),
+    )));
+    lines
+}
+
 /// 居中矩形
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -3596,3 +8263,180 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+/// 按显示宽度截断字符串（中文/emoji 按双宽计算），超出时以 … 结尾
+fn truncate_to_width(s: &str, max_width: usize) -> String {
+    if Span::raw(s).width() <= max_width {
+        return s.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let mut truncated = String::new();
+    let mut width = 0;
+    for ch in s.chars() {
+        let ch_width = Span::raw(ch.to_string()).width();
+        // 为末尾的 … 预留1列
+        if width + ch_width + 1 > max_width {
+            break;
+        }
+        truncated.push(ch);
+        width += ch_width;
+    }
+    truncated.push('…');
+    truncated
+}
+
+/// 按配置的颜色渲染图标
+fn icon_span(style: &IconStyle) -> Span<'static> {
+    let color = style.color.as_deref().and_then(|c| c.parse::<Color>().ok());
+    match color {
+        Some(color) => Span::styled(style.icon.clone(), Style::default().fg(color)),
+        None => Span::raw(style.icon.clone()),
+    }
+}
+
+/// 构建任务详情内容（完整标题、状态、时间、描述）
+fn task_detail_lines(
+    task: &Task,
+    project: Option<&str>,
+    tracked_seconds: i64,
+    comments: &[Comment],
+    icons: &crate::config::IconConfig,
+    locale: crate::config::Locale,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let status_text = task.status.label();
+    let priority_text = match task.priority {
+        Priority::High => "高",
+        Priority::Medium => "中",
+        Priority::Low => "低",
+    };
+    let format_time = |dt: Option<chrono::DateTime<Utc>>| {
+        dt.map(|d| d.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "无".to_string())
+    };
+    let tracked = if tracked_seconds > 0 {
+        crate::models::format_tracked(tracked_seconds)
+    } else {
+        "无".to_string()
+    };
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            task.title.clone(),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("状态: "),
+            icon_span(icons.status(task.status)),
+            Span::styled(format!(" {}", status_text), Style::default().fg(theme.border)),
+            Span::raw("  优先级: "),
+            icon_span(icons.priority(task.priority)),
+            Span::raw(format!(" {}", priority_text)),
+        ]),
+        Line::from(vec![
+            Span::raw("DDL: "),
+            Span::styled(format_time(task.due_date), Style::default().fg(theme.border)),
+            Span::raw("  提醒: "),
+            Span::styled(format_time(task.reminder_time), Style::default().fg(theme.border)),
+        ]),
+        Line::from(match task.estimate_minutes {
+            Some(minutes) => format!(
+                "番茄钟: {} 个  计时: {}  预计: {}",
+                task.pomodoro_count,
+                tracked,
+                crate::models::format_estimate(minutes)
+            ),
+            None => format!(
+                "番茄钟: {} 个  计时: {}",
+                task.pomodoro_count,
+                tracked
+            ),
+        }),
+    ];
+
+    if let Some(rule) = task.recurrence {
+        lines.push(Line::from(vec![
+            Span::raw("重复: "),
+            Span::styled(rule.describe(), Style::default().fg(theme.highlight)),
+        ]));
+    }
+
+    if task.status == TaskStatus::Waiting {
+        lines.push(Line::from(vec![
+            Span::raw("等待自: "),
+            Span::styled(format_time(task.waiting_since), Style::default().fg(theme.border)),
+            Span::raw("  跟进: "),
+            Span::styled(format_time(task.follow_up_at), Style::default().fg(theme.border)),
+        ]));
+    }
+
+    if !task.tags.is_empty() {
+        let tags: Vec<String> = task.tags.iter().map(|t| format!("#{}", t)).collect();
+        lines.push(Line::from(vec![
+            Span::raw("标签: "),
+            Span::styled(tags.join(" "), Style::default().fg(theme.highlight)),
+        ]));
+    }
+    if let Some(project) = project {
+        lines.push(Line::from(vec![
+            Span::raw("项目: "),
+            Span::styled(format!("📁 {}", project), Style::default().fg(theme.info)),
+        ]));
+    }
+
+    if let Some(desc) = task.description.as_ref().filter(|d| !d.is_empty()) {
+        lines.push(Line::from(""));
+        lines.push(Line::from("描述:"));
+        for line in desc.lines() {
+            lines.push(Line::from(format!("  {}", line)));
+        }
+    }
+
+    if !comments.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("评论 ({}):", comments.len())));
+        for comment in comments {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!(
+                        "  {} ({}) ",
+                        comment.created_at.with_timezone(&chrono::Local).format("%m-%d %H:%M"),
+                        crate::humanize::age(comment.created_at, Utc::now(), locale)
+                    ),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::raw(comment.body.clone()),
+            ]));
+        }
+    }
+
+    lines.extend(vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("创建: "),
+            Span::styled(format_time(Some(task.created_at)), Style::default().fg(theme.border)),
+            Span::raw("  更新: "),
+            Span::styled(format_time(Some(task.updated_at)), Style::default().fg(theme.border)),
+        ]),
+        Line::from(Span::styled(
+            crate::humanize::audit(task.created_at, task.updated_at, Utc::now(), locale),
+            Style::default().fg(theme.muted),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("e", Style::default().fg(theme.success)),
+            Span::raw(" 编辑  "),
+            Span::styled("c", Style::default().fg(theme.success)),
+            Span::raw(" 评论  "),
+            Span::styled("Enter/Esc/q", Style::default().fg(theme.accent)),
+            Span::raw(" 关闭"),
+        ]),
+    ]);
+
+    lines
+}
@@ -0,0 +1,120 @@
+// 新建任务的快速输入语法：标题中的 !high 设置优先级、#标签 添加标签、@明天 18:00 设置DDL，
+// 如 "Buy milk !high #errands @tomorrow 18:00"。无法识别的记号原样留在标题中（如 #123、邮箱地址）
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::models::{Priority, Task};
+
+/// @ 之后最多读取的词数（"next friday 5:30 pm"）
+const MAX_DUE_WORDS: usize = 4;
+
+/// 从输入中解析出的标题和任务属性
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuickAdd {
+    pub title: String,
+    pub priority: Option<Priority>,
+    pub tags: Vec<String>,
+    pub due: Option<DateTime<Utc>>,
+}
+
+impl QuickAdd {
+    pub fn parse(input: &str, now: DateTime<Local>) -> Self {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let mut result = Self::default();
+        let mut title = Vec::new();
+        let mut i = 0;
+        while i < words.len() {
+            let word = words[i];
+            i += 1;
+            if let Some(priority) = word.strip_prefix('!').and_then(parse_priority) {
+                result.priority = Some(priority);
+                continue;
+            }
+            // 纯数字的 #123 多为编号，留在标题中
+            if let Some(tag) = word.strip_prefix('#').filter(|t| is_tag(t)) {
+                if !result.tags.iter().any(|t| t == tag) {
+                    result.tags.push(tag.to_string());
+                }
+                continue;
+            }
+            if let Some(first) = word.strip_prefix('@').filter(|w| !w.is_empty() && result.due.is_none()) {
+                if let Some((due, used)) = parse_due(first, &words[i..], now) {
+                    result.due = Some(due);
+                    i += used;
+                    continue;
+                }
+            }
+            title.push(word);
+        }
+        result.title = title.join(" ");
+        result
+    }
+
+    /// 是否包含任何记号
+    pub fn has_tokens(&self) -> bool {
+        self.priority.is_some() || !self.tags.is_empty() || self.due.is_some()
+    }
+
+    /// 把优先级和标签应用到新任务上（在自动标签规则之后调用，以输入的为准）
+    pub fn apply(&self, task: &mut Task) {
+        if let Some(priority) = self.priority {
+            task.priority = priority;
+        }
+        for tag in &self.tags {
+            task.add_tag(tag);
+        }
+    }
+
+    /// 新建任务对话框中的预览
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(priority) = self.priority {
+            let name = match priority {
+                Priority::High => "高",
+                Priority::Medium => "中",
+                Priority::Low => "低",
+            };
+            parts.push(format!("优先级: {}", name));
+        }
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|t| format!("#{}", t)).collect();
+            parts.push(format!("标签: {}", tags.join(" ")));
+        }
+        if let Some(due) = self.due {
+            parts.push(format!("DDL: {}", due.with_timezone(&Local).format("%Y-%m-%d %H:%M")));
+        }
+        parts.join("  ")
+    }
+}
+
+/// !high / !h / !3 为高，!medium / !m / !2 为中，!low / !l / !1 为低
+fn parse_priority(s: &str) -> Option<Priority> {
+    match s {
+        "3" => Some(Priority::High),
+        "2" => Some(Priority::Medium),
+        "1" => Some(Priority::Low),
+        _ => Priority::parse(s),
+    }
+}
+
+fn is_tag(s: &str) -> bool {
+    !s.is_empty() && !s.starts_with('#') && !s.chars().all(|c| c.is_ascii_digit())
+}
+
+/// 从 @ 后的词开始，取能解析为截止时间的最长的一段，返回时间和 first 之后用掉的词数
+fn parse_due(first: &str, rest: &[&str], now: DateTime<Local>) -> Option<(DateTime<Utc>, usize)> {
+    // 遇到下一个记号就停止
+    let available = rest
+        .iter()
+        .take(MAX_DUE_WORDS - 1)
+        .take_while(|w| !w.starts_with(['!', '#', '@']))
+        .count();
+    (0..=available).rev().find_map(|used| {
+        // 末尾的 at / on 会被日期解析忽略，不把它从标题中拿走
+        if used > 0 && matches!(rest[used - 1].to_lowercase().as_str(), "at" | "on") {
+            return None;
+        }
+        let text = std::iter::once(first).chain(rest[..used].iter().copied()).collect::<Vec<_>>().join(" ");
+        crate::dateparse::parse(&text, now).map(|due| (due, used))
+    })
+}
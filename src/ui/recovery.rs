@@ -0,0 +1,252 @@
+// 启动恢复界面：数据库无法打开（被移动、被锁定或损坏）时，让用户选择另一个路径、
+// 从备份恢复或新建数据库，而不是直接报错退出
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Terminal,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::db::Database;
+
+const OPTIONS: [&str; 4] = ["打开其他路径的数据库", "从最近的备份恢复", "新建空数据库", "退出"];
+
+/// 检查数据库能否打开，返回问题描述
+///
+/// `explicit` 表示路径由 --db-path 指定：此时文件不存在视为被移动，而不是首次运行
+pub fn probe(db_path: &Path, explicit: bool) -> Option<String> {
+    if explicit && !db_path.exists() {
+        return Some(format!("数据库文件不存在: {}", db_path.display()));
+    }
+    match Database::open(db_path) {
+        Ok(_) => None,
+        Err(e) => Some(format!("无法打开数据库 {}: {}", db_path.display(), e)),
+    }
+}
+
+/// 同目录下最新的备份文件（文件名以数据库文件名开头且包含 "bak"，如 tasks.db.bak、tasks.db.20250101.bak）
+pub fn latest_backup(db_path: &Path) -> Option<PathBuf> {
+    let dir = db_path.parent()?;
+    let name = db_path.file_name()?.to_string_lossy().to_string();
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let file = entry.file_name().to_string_lossy().to_string();
+            file != name && file.starts_with(&name) && file.contains("bak")
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// 数据库文件旁边的日志文件：WAL 模式的 -wal/-shm 和回滚日志 -journal
+const JOURNAL_SUFFIXES: [&str; 3] = ["-wal", "-shm", "-journal"];
+
+/// 把无法打开的数据库文件改名保留（tasks.db → tasks.db.broken-20250101-120000），返回新文件名
+///
+/// 日志文件一起改名，避免旧的日志被应用到恢复或新建的数据库上；数据库文件已经不存在时
+/// 残留的日志文件没有对应的数据，直接删除
+fn move_aside(db_path: &Path) -> Result<Option<PathBuf>> {
+    let journal = |path: &Path, suffix: &str| PathBuf::from(format!("{}{}", path.display(), suffix));
+    if !db_path.exists() {
+        for suffix in JOURNAL_SUFFIXES {
+            let journal = journal(db_path, suffix);
+            if journal.exists() {
+                fs::remove_file(&journal)?;
+            }
+        }
+        return Ok(None);
+    }
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let aside = PathBuf::from(format!("{}.broken-{}", db_path.display(), stamp));
+    fs::rename(db_path, &aside)?;
+    for suffix in JOURNAL_SUFFIXES {
+        let journal_path = journal(db_path, suffix);
+        if journal_path.exists() {
+            fs::rename(&journal_path, journal(&aside, suffix))?;
+        }
+    }
+    Ok(Some(aside))
+}
+
+struct RecoveryState {
+    db_path: PathBuf,
+    problem: String,
+    selected: usize,
+    /// 正在输入其他路径
+    path_input: Option<String>,
+    message: Option<String>,
+}
+
+impl RecoveryState {
+    /// 执行选中的操作，成功时返回可用的数据库路径
+    fn apply(&mut self) -> Result<Option<PathBuf>> {
+        match self.selected {
+            0 => {
+                self.path_input = Some(String::new());
+                Ok(None)
+            }
+            1 => {
+                let backup = match latest_backup(&self.db_path) {
+                    Some(backup) => backup,
+                    None => {
+                        self.message = Some("没有找到备份文件（与数据库同目录、文件名包含 bak）".to_string());
+                        return Ok(None);
+                    }
+                };
+                move_aside(&self.db_path)?;
+                fs::copy(&backup, &self.db_path)?;
+                self.check(self.db_path.clone(), false, &format!("已从 {} 恢复", backup.display()))
+            }
+            2 => {
+                if let Some(parent) = self.db_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let aside = move_aside(&self.db_path)?;
+                let note = match aside {
+                    Some(aside) => format!("原文件已保留为 {}", aside.display()),
+                    None => "已新建数据库".to_string(),
+                };
+                self.check(self.db_path.clone(), true, &note)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 确认路径可以打开（`create` 时文件不存在则新建），失败时更新问题描述
+    fn check(&mut self, path: PathBuf, create: bool, success: &str) -> Result<Option<PathBuf>> {
+        match probe(&path, !create) {
+            None => {
+                self.message = Some(success.to_string());
+                Ok(Some(path))
+            }
+            Some(problem) => {
+                self.problem = problem;
+                self.message = None;
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// 显示恢复界面直到数据库可以打开，返回最终使用的路径和操作说明；用户选择退出时返回 None
+pub fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    db_path: &Path,
+    problem: String,
+) -> Result<Option<(PathBuf, String)>> {
+    let mut state = RecoveryState {
+        db_path: db_path.to_path_buf(),
+        problem,
+        selected: 0,
+        path_input: None,
+        message: None,
+    };
+
+    loop {
+        terminal.draw(|f| render(f, &state))?;
+
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key.code,
+            _ => continue,
+        };
+
+        // 输入其他路径
+        if let Some(input) = state.path_input.as_mut() {
+            match key {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Esc => state.path_input = None,
+                KeyCode::Enter => {
+                    let path = PathBuf::from(input.trim());
+                    state.path_input = None;
+                    if let Some(path) = state.check(path, false, "已打开")? {
+                        return Ok(Some((path, state.message.take().unwrap_or_default())));
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => state.selected = state.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => state.selected = (state.selected + 1).min(OPTIONS.len() - 1),
+            KeyCode::Char(c @ '1'..='4') => {
+                state.selected = c as usize - '1' as usize;
+                if state.selected == OPTIONS.len() - 1 {
+                    return Ok(None);
+                }
+                if let Some(path) = state.apply()? {
+                    return Ok(Some((path, state.message.take().unwrap_or_default())));
+                }
+            }
+            KeyCode::Enter => {
+                if state.selected == OPTIONS.len() - 1 {
+                    return Ok(None);
+                }
+                if let Some(path) = state.apply()? {
+                    return Ok(Some((path, state.message.take().unwrap_or_default())));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+fn render(f: &mut ratatui::Frame, state: &RecoveryState) {
+    let area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(20), Constraint::Min(14), Constraint::Percentage(20)])
+        .split(f.area())[1];
+    let area = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(15), Constraint::Percentage(70), Constraint::Percentage(15)])
+        .split(area)[1];
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(format!("⚠️ {}", state.problem), Style::default().fg(Color::Yellow))),
+        Line::from(""),
+    ];
+    for (i, option) in OPTIONS.iter().enumerate() {
+        let style = if i == state.selected {
+            Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(Span::styled(format!("  {}. {}", i + 1, option), style)));
+    }
+    lines.push(Line::from(""));
+    match &state.path_input {
+        Some(input) => lines.push(Line::from(vec![
+            Span::raw("路径: "),
+            Span::styled(format!("{}_", input), Style::default().fg(Color::Yellow)),
+            Span::styled("  Enter 打开 | Esc 返回", Style::default().fg(Color::DarkGray)),
+        ])),
+        None => lines.push(Line::from(Span::styled(
+            "j/k 选择 | Enter 或数字确认 | q 退出",
+            Style::default().fg(Color::DarkGray),
+        ))),
+    }
+    if let Some(message) = &state.message {
+        lines.push(Line::from(Span::styled(message.clone(), Style::default().fg(Color::Green))));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red))
+        .title(" 数据库恢复 ");
+    f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: true }), area);
+}
@@ -0,0 +1,294 @@
+// 远程模式的界面（tasks --remote <url>）：通过 taskd 的 HTTP 接口查看、新建和完成任务。
+// 远程接口只提供这几种操作，编辑、便签、番茄钟等请在服务器上使用完整的 TUI
+
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+use std::io;
+use std::time::{Duration, Instant};
+
+use super::quick_add::QuickAdd;
+use crate::config::Config;
+use crate::models::{Task, TaskStatus};
+use crate::remote::RemoteClient;
+
+/// 自动重新获取任务列表的间隔
+const REFRESH_SECS: u64 = 30;
+
+struct RemoteApp {
+    client: RemoteClient,
+    config: Config,
+    tasks: Vec<Task>,
+    selected: usize,
+    show_completed: bool,
+    /// 新建任务时的输入内容
+    input: Option<String>,
+    status: Option<String>,
+    loaded_at: Option<DateTime<Utc>>,
+    refreshed: Instant,
+}
+
+impl RemoteApp {
+    /// 获取任务列表：未完成的在前，按DDL和优先级排序
+    fn load(&mut self) -> crate::error::Result<()> {
+        self.refreshed = Instant::now();
+        let mut tasks = self.client.get_all_tasks()?;
+        tasks.sort_by_key(|t| {
+            (
+                t.status == TaskStatus::Completed,
+                t.due_date.is_none(),
+                t.due_date,
+                std::cmp::Reverse(t.priority as i32),
+            )
+        });
+        self.tasks = tasks;
+        self.loaded_at = Some(Utc::now());
+        self.clamp_selection();
+        Ok(())
+    }
+
+    /// 重新获取任务；失败时保留上次的数据并在状态栏显示错误
+    fn refresh(&mut self) {
+        if let Err(e) = self.load() {
+            self.status = Some(format!("刷新失败: {}", e));
+        }
+    }
+
+    fn visible(&self) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|t| self.show_completed || t.status != TaskStatus::Completed)
+            .collect()
+    }
+
+    fn clamp_selection(&mut self) {
+        let len = self.visible().len();
+        self.selected = self.selected.min(len.saturating_sub(1));
+    }
+
+    fn complete_selected(&mut self) {
+        let Some((id, title)) = self
+            .visible()
+            .get(self.selected)
+            .filter(|t| t.status != TaskStatus::Completed)
+            .and_then(|t| t.id.map(|id| (id, t.title.clone())))
+        else {
+            return;
+        };
+        match self.client.complete_task(id) {
+            Ok(()) => {
+                self.status = Some(format!("已完成: {}", title));
+                self.refresh();
+            }
+            Err(e) => self.status = Some(format!("操作失败: {}", e)),
+        }
+    }
+
+    /// 按快速输入语法（!high #标签 @明天 18:00）新建任务
+    fn create(&mut self, input: &str) {
+        let parsed = QuickAdd::parse(input, Local::now());
+        let mut task = Task::new(parsed.title.clone());
+        self.config.apply_rules(&mut task);
+        parsed.apply(&mut task);
+        task.due_date = parsed.due;
+        if let Err(e) = task.validate() {
+            self.status = Some(format!("无法创建: {}", e));
+            return;
+        }
+        match self.client.create_task(&task) {
+            Ok(id) => {
+                self.status = Some(format!("已创建任务 #{}", id));
+                self.refresh();
+            }
+            Err(e) => self.status = Some(format!("创建失败: {}", e)),
+        }
+    }
+}
+
+/// 启动远程模式的界面
+pub fn run(client: RemoteClient, config: Config) -> Result<()> {
+    let mut app = RemoteApp {
+        client,
+        config,
+        tasks: Vec::new(),
+        selected: 0,
+        show_completed: false,
+        input: None,
+        status: None,
+        loaded_at: None,
+        refreshed: Instant::now(),
+    };
+    // 先取一次数据，连不上服务器时直接报错，不进入全屏
+    app.load()?;
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+    terminal.hide_cursor()?;
+
+    let res = event_loop(&mut terminal, &mut app);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    res
+}
+
+fn event_loop<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut RemoteApp) -> Result<()> {
+    loop {
+        if app.input.is_none() && app.refreshed.elapsed() >= Duration::from_secs(REFRESH_SECS) {
+            app.refresh();
+        }
+        terminal.draw(|f| render(f, app))?;
+
+        if !event::poll(Duration::from_millis(500))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(());
+        }
+
+        if let Some(input) = app.input.as_mut() {
+            match key.code {
+                KeyCode::Esc => app.input = None,
+                KeyCode::Enter => {
+                    let input = app.input.take().unwrap_or_default();
+                    if !input.trim().is_empty() {
+                        app.create(input.trim());
+                    }
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            continue;
+        }
+
+        app.status = None;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('j') | KeyCode::Down => {
+                app.selected = (app.selected + 1).min(app.visible().len().saturating_sub(1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => app.selected = app.selected.saturating_sub(1),
+            KeyCode::Char('g') | KeyCode::Home => app.selected = 0,
+            KeyCode::Char('G') | KeyCode::End => app.selected = app.visible().len().saturating_sub(1),
+            KeyCode::Char('a') | KeyCode::Char('o') => app.input = Some(String::new()),
+            KeyCode::Char('x') | KeyCode::Char(' ') => app.complete_selected(),
+            KeyCode::Char('c') => {
+                app.show_completed = !app.show_completed;
+                app.clamp_selection();
+            }
+            KeyCode::Char('r') => {
+                app.refresh();
+                if app.status.is_none() {
+                    app.status = Some("已刷新".to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn render(f: &mut Frame, app: &RemoteApp) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)])
+        .split(f.area());
+
+    // 顶部：服务器地址和任务数
+    let visible = app.visible();
+    let open = app.tasks.iter().filter(|t| t.status != TaskStatus::Completed).count();
+    let header = Line::from(vec![
+        Span::styled(" 远程 ", Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::raw(" "),
+        Span::styled(app.client.url(), Style::default().fg(Color::Gray)),
+        Span::styled(format!("  未完成 {} / 共 {}", open, app.tasks.len()), Style::default().fg(Color::DarkGray)),
+    ]);
+    f.render_widget(Paragraph::new(header), chunks[0]);
+
+    let today = Local::now().date_naive();
+    let items: Vec<ListItem> = visible
+        .iter()
+        .map(|task| {
+            let done = task.status == TaskStatus::Completed;
+            let mut spans = vec![
+                Span::raw(format!("{} ", app.config.icons.status(task.status).icon)),
+                Span::raw(format!("{} ", app.config.icons.priority(task.priority).icon)),
+                Span::styled(
+                    task.title.clone(),
+                    if done {
+                        Style::default().fg(Color::DarkGray).add_modifier(Modifier::CROSSED_OUT)
+                    } else {
+                        Style::default()
+                    },
+                ),
+            ];
+            for tag in &task.tags {
+                spans.push(Span::styled(format!(" #{}", tag), Style::default().fg(Color::Cyan)));
+            }
+            if let Some(due) = task.due_date {
+                let due = due.with_timezone(&Local);
+                let color = if !done && due.date_naive() < today { Color::Red } else { Color::Yellow };
+                spans.push(Span::styled(format!("  📅 {}", due.format("%m-%d %H:%M")), Style::default().fg(color)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let title = if app.show_completed { " 全部任务 " } else { " 未完成任务 " };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default();
+    if !visible.is_empty() {
+        state.select(Some(app.selected));
+    }
+    f.render_stateful_widget(list, chunks[1], &mut state);
+
+    // 底部：输入框、状态消息或按键提示
+    let footer = if let Some(input) = &app.input {
+        Line::from(vec![
+            Span::styled("新建任务: ", Style::default().fg(Color::Cyan)),
+            Span::raw(input.clone()),
+            Span::styled("█", Style::default().fg(Color::Gray)),
+        ])
+    } else if let Some(status) = &app.status {
+        Line::from(Span::styled(status.clone(), Style::default().fg(Color::Yellow)))
+    } else {
+        let loaded = app
+            .loaded_at
+            .map(|t| t.with_timezone(&Local).format("%H:%M:%S").to_string())
+            .unwrap_or_default();
+        Line::from(Span::styled(
+            format!(
+                "a 新建 · x/Space 完成 · c 显示已完成 · r 刷新 · q 退出 · 数据更新于 {}",
+                loaded
+            ),
+            Style::default().fg(Color::DarkGray),
+        ))
+    };
+    f.render_widget(Paragraph::new(footer), chunks[2]);
+}
@@ -0,0 +1,131 @@
+// :set 可修改的界面设置，保存在数据库的 config 表中（ui_ 开头的键），启动和切换工作区时加载；
+// :set? 列出全部设置。键位方案、主题和提示音有各自的保存方式，也可以通过 :set 修改
+
+use crate::db::Storage;
+use crate::error::Result;
+
+/// 开关设置：(名称, 数据库中的键, 说明)
+pub const SWITCHES: [(&str, &str, &str); 8] = [
+    ("group", "ui_group", "按截止日期分组显示任务"),
+    ("wrap", "ui_note_wrap", "查看便签时长行自动换行"),
+    ("notify", "ui_notify", "番茄钟结束时发送桌面通知"),
+    ("bell", "ui_bell", "番茄钟结束时终端响铃"),
+    ("require_ddl", "ui_require_ddl", "新建任务时必须设置DDL"),
+    ("agenda", "ui_agenda", "启动时显示今日视图"),
+    ("auto_sort", "ui_auto_sort", "修改任务后自动重新排序（关闭时保持当前顺序，:sort 手动排序）"),
+    ("confirm_delete", "ui_confirm_delete", "删除到回收站前确认"),
+];
+
+/// 界面刷新间隔的范围（毫秒）
+pub const TICK_RATE_RANGE: (u64, u64) = (10, 1000);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    pub group_by_date: bool,
+    pub note_wrap: bool,
+    pub notify: bool,
+    pub bell: bool,
+    pub require_ddl: bool,
+    pub agenda: bool,
+    pub auto_sort: bool,
+    pub confirm_delete: bool,
+    /// 界面刷新间隔（毫秒），None 时使用 config.toml 中 [intervals] 的 poll_ms
+    pub tick_rate: Option<u64>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            group_by_date: true,
+            note_wrap: true,
+            notify: true,
+            bell: false,
+            require_ddl: true,
+            agenda: true,
+            auto_sort: true,
+            confirm_delete: true,
+            tick_rate: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(db: &dyn Storage) -> Result<Self> {
+        let mut settings = Self::default();
+        for (name, key, _) in SWITCHES {
+            if let (Some(value), Some(stored)) = (settings.switch_mut(name), db.get_config(key)?) {
+                *value = stored == "on";
+            }
+        }
+        settings.tick_rate = db.get_config("ui_tick_rate")?.and_then(|v| v.parse().ok()).map(clamp_tick_rate);
+        Ok(settings)
+    }
+
+    fn switch_mut(&mut self, name: &str) -> Option<&mut bool> {
+        match name {
+            "group" => Some(&mut self.group_by_date),
+            "wrap" => Some(&mut self.note_wrap),
+            "notify" => Some(&mut self.notify),
+            "bell" => Some(&mut self.bell),
+            "require_ddl" => Some(&mut self.require_ddl),
+            "agenda" => Some(&mut self.agenda),
+            "auto_sort" => Some(&mut self.auto_sort),
+            "confirm_delete" => Some(&mut self.confirm_delete),
+            _ => None,
+        }
+    }
+
+    /// 开关设置的当前值，名称未知时为 None
+    pub fn switch(&self, name: &str) -> Option<bool> {
+        let mut settings = *self;
+        settings.switch_mut(name).map(|value| *value)
+    }
+
+    /// 修改一项设置并保存：返回 Ok(Err(提示)) 表示名称或值无效，未做修改
+    pub fn set(&mut self, db: &dyn Storage, name: &str, value: &str) -> Result<std::result::Result<(), String>> {
+        if name == "tick_rate" {
+            if matches!(value, "default" | "off") {
+                self.tick_rate = None;
+                db.delete_config("ui_tick_rate")?;
+                return Ok(Ok(()));
+            }
+            let (min, max) = TICK_RATE_RANGE;
+            return match value.trim_end_matches("ms").parse::<u64>() {
+                Ok(ms) if (min..=max).contains(&ms) => {
+                    self.tick_rate = Some(ms);
+                    db.set_config("ui_tick_rate", &ms.to_string())?;
+                    Ok(Ok(()))
+                }
+                _ => Ok(Err(format!("无效的刷新间隔: {}（{}-{} 毫秒，default 使用配置文件）", value, min, max))),
+            };
+        }
+        let key = match SWITCHES.iter().find(|(n, ..)| *n == name) {
+            Some((_, key, ..)) => *key,
+            None => return Ok(Err(format!("未知设置: {}（:set? 查看全部设置）", name))),
+        };
+        let on = match value {
+            "on" | "true" | "1" => true,
+            "off" | "false" | "0" => false,
+            _ => return Ok(Err(format!("无效的值: {}（可选 on/off）", value))),
+        };
+        if let Some(switch) = self.switch_mut(name) {
+            *switch = on;
+        }
+        db.set_config(key, if on { "on" } else { "off" })?;
+        Ok(Ok(()))
+    }
+
+    /// 设置的当前值，用于状态栏显示
+    pub fn describe(&self, name: &str) -> String {
+        match (name, self.switch(name)) {
+            ("tick_rate", _) => self.tick_rate.map_or_else(|| "default".to_string(), |ms| format!("{}ms", ms)),
+            (_, Some(true)) => "on".to_string(),
+            (_, Some(false)) => "off".to_string(),
+            (_, None) => String::new(),
+        }
+    }
+}
+
+fn clamp_tick_rate(ms: u64) -> u64 {
+    ms.clamp(TICK_RATE_RANGE.0, TICK_RATE_RANGE.1)
+}
@@ -0,0 +1,100 @@
+// 自定义任务排序：:sort by due|priority|created|title [asc|desc]，保存在 config 表的 ui_sort 中，
+// 生效时代替默认的 状态→优先级→DDL 规则（已完成的任务仍排在最后，不再按日期分组显示）
+
+use std::cmp::Ordering;
+
+use crate::models::{Task, TaskStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Due,
+    Priority,
+    Created,
+    Title,
+}
+
+impl SortKey {
+    pub const NAMES: [&'static str; 4] = ["due", "priority", "created", "title"];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "due" | "ddl" => Some(SortKey::Due),
+            "priority" | "p" => Some(SortKey::Priority),
+            "created" => Some(SortKey::Created),
+            "title" => Some(SortKey::Title),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SortKey::Due => "due",
+            SortKey::Priority => "priority",
+            SortKey::Created => "created",
+            SortKey::Title => "title",
+        }
+    }
+
+    /// 不指定方向时：DDL 早的、优先级高的、新建的在前，标题按字母顺序
+    fn default_descending(self) -> bool {
+        matches!(self, SortKey::Priority | SortKey::Created)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskSort {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl TaskSort {
+    /// 解析 :sort 的参数（"by" 可省略）：due / priority desc / by title asc
+    pub fn parse(args: &[&str]) -> Option<Self> {
+        let args = match args.first() {
+            Some(&"by") => &args[1..],
+            _ => args,
+        };
+        let key = SortKey::parse(args.first()?)?;
+        let descending = match args.get(1).map(|s| s.to_lowercase()) {
+            None => key.default_descending(),
+            Some(dir) if dir == "asc" => false,
+            Some(dir) if dir == "desc" => true,
+            Some(_) => return None,
+        };
+        if args.len() > 2 {
+            return None;
+        }
+        Some(TaskSort { key, descending })
+    }
+
+    /// 保存到数据库的形式，如 "priority desc"
+    pub fn name(self) -> String {
+        format!("{} {}", self.key.name(), if self.descending { "desc" } else { "asc" })
+    }
+
+    /// 已完成的任务排在最后；没有 DDL 的任务无论方向都排在有 DDL 的之后；相同时按 DDL、ID
+    pub fn compare(self, a: &Task, b: &Task) -> Ordering {
+        let completed = |t: &Task| t.status == TaskStatus::Completed;
+        let by_due = |a: &Task, b: &Task| match (a.due_date, b.due_date) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        let direction = |ordering: Ordering| if self.descending { ordering.reverse() } else { ordering };
+        let primary = match self.key {
+            SortKey::Due => match (a.due_date, b.due_date) {
+                (Some(x), Some(y)) => direction(x.cmp(&y)),
+                _ => by_due(a, b),
+            },
+            SortKey::Priority => direction((a.priority as i32).cmp(&(b.priority as i32))),
+            SortKey::Created => direction(a.created_at.cmp(&b.created_at)),
+            SortKey::Title => direction(a.title.to_lowercase().cmp(&b.title.to_lowercase())),
+        };
+        completed(a)
+            .cmp(&completed(b))
+            .then(primary)
+            .then_with(|| by_due(a, b))
+            .then_with(|| a.id.cmp(&b.id))
+    }
+}
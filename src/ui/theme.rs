@@ -0,0 +1,133 @@
+// 配色主题：界面中的颜色都按用途从 Theme 中取，内置 dark（默认）/ light / solarized 三套，
+// 可在 config.toml 的 [theme] 中选择并覆盖单个颜色，界面中用 :theme <名称> 切换
+
+use ratatui::style::Color;
+
+use crate::config::ThemeConfig;
+
+/// 按用途划分的颜色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// 标题、强调
+    pub accent: Color,
+    /// 边框、数值
+    pub border: Color,
+    /// 提示文字、已完成任务
+    pub muted: Color,
+    /// 次要文字
+    pub secondary: Color,
+    /// 正文
+    pub text: Color,
+    /// 对话框背景，以及亮色背景上的文字
+    pub background: Color,
+    /// 选中行背景
+    pub selection: Color,
+    /// 成功、进行中
+    pub success: Color,
+    /// 错误、逾期
+    pub danger: Color,
+    /// 即将到期
+    pub warning: Color,
+    /// 标签、番茄钟
+    pub highlight: Color,
+    /// 项目、命令行
+    pub info: Color,
+}
+
+impl Theme {
+    pub const NAMES: [&'static str; 3] = ["dark", "light", "solarized"];
+
+    /// 深色终端（默认）
+    pub fn dark() -> Self {
+        Self {
+            accent: Color::Yellow,
+            border: Color::Cyan,
+            muted: Color::DarkGray,
+            secondary: Color::Gray,
+            text: Color::White,
+            background: Color::Black,
+            selection: Color::DarkGray,
+            success: Color::Green,
+            danger: Color::Red,
+            warning: Color::Yellow,
+            highlight: Color::Magenta,
+            info: Color::Blue,
+        }
+    }
+
+    /// 浅色终端：黄色、青色在白底上看不清，换成深色
+    pub fn light() -> Self {
+        Self {
+            accent: Color::Rgb(175, 95, 0),
+            border: Color::Blue,
+            muted: Color::Gray,
+            secondary: Color::DarkGray,
+            text: Color::Black,
+            background: Color::White,
+            selection: Color::Rgb(215, 215, 215),
+            success: Color::Rgb(0, 135, 0),
+            danger: Color::Red,
+            warning: Color::Rgb(175, 135, 0),
+            highlight: Color::Magenta,
+            info: Color::Blue,
+        }
+    }
+
+    /// Solarized Dark 配色
+    pub fn solarized() -> Self {
+        Self {
+            accent: Color::Rgb(0xb5, 0x89, 0x00),
+            border: Color::Rgb(0x2a, 0xa1, 0x98),
+            muted: Color::Rgb(0x58, 0x6e, 0x75),
+            secondary: Color::Rgb(0x93, 0xa1, 0xa1),
+            text: Color::Rgb(0x83, 0x94, 0x96),
+            background: Color::Rgb(0x00, 0x2b, 0x36),
+            selection: Color::Rgb(0x07, 0x36, 0x42),
+            success: Color::Rgb(0x85, 0x99, 0x00),
+            danger: Color::Rgb(0xdc, 0x32, 0x2f),
+            warning: Color::Rgb(0xcb, 0x4b, 0x16),
+            highlight: Color::Rgb(0xd3, 0x36, 0x82),
+            info: Color::Rgb(0x26, 0x8b, 0xd2),
+        }
+    }
+
+    /// 按名称取内置主题
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            _ => None,
+        }
+    }
+
+    /// 内置主题加上 config.toml 中覆盖的颜色；无法识别的颜色名忽略
+    pub fn with_overrides(mut self, config: &ThemeConfig) -> Self {
+        let overrides = [
+            (&mut self.accent, &config.accent),
+            (&mut self.border, &config.border),
+            (&mut self.muted, &config.muted),
+            (&mut self.secondary, &config.secondary),
+            (&mut self.text, &config.text),
+            (&mut self.background, &config.background),
+            (&mut self.selection, &config.selection),
+            (&mut self.success, &config.success),
+            (&mut self.danger, &config.danger),
+            (&mut self.warning, &config.warning),
+            (&mut self.highlight, &config.highlight),
+            (&mut self.info, &config.info),
+        ];
+        for (color, value) in overrides {
+            if let Some(parsed) = value.as_deref().and_then(|v| v.parse::<Color>().ok()) {
+                *color = parsed;
+            }
+        }
+        self
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
@@ -0,0 +1,97 @@
+// :triage 分诊助手：为未安排的任务建议优先级和截止时间，逐个确认或修改
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+
+use crate::models::{Priority, Task, TaskStatus};
+
+/// 超过该天数仍未安排的任务视为积压
+const STALE_DAYS: i64 = 14;
+
+/// 提升为高优先级的标签
+const URGENT_TAGS: &[&str] = &["urgent", "紧急", "asap"];
+/// 降为低优先级的标签
+const SOMEDAY_TAGS: &[&str] = &["someday", "maybe", "以后"];
+
+/// 对单个任务的建议
+#[derive(Debug, Clone)]
+pub struct Proposal {
+    pub task_id: i64,
+    pub title: String,
+    pub priority: Priority,
+    /// 建议的截止时间；任务已有截止时间时保持不变
+    pub due_date: Option<DateTime<Utc>>,
+    /// 建议理由（显示给用户）
+    pub reasons: Vec<String>,
+}
+
+/// 是否需要分诊：未完成，且没有截止时间或临近截止却不是高优先级
+fn needs_triage(task: &Task, now: DateTime<Utc>) -> bool {
+    if matches!(task.status, TaskStatus::Completed | TaskStatus::Waiting) {
+        return false;
+    }
+    match task.due_date {
+        None => true,
+        Some(due) => due - now < Duration::days(1) && task.priority != Priority::High,
+    }
+}
+
+/// 根据截止时间、任务年龄和标签生成建议
+pub fn propose(task: &Task, now: DateTime<Utc>) -> Option<Proposal> {
+    if !needs_triage(task, now) {
+        return None;
+    }
+    let mut reasons = Vec::new();
+    let has_tag = |tags: &[&str]| task.tags.iter().any(|t| tags.contains(&t.to_lowercase().as_str()));
+
+    let priority = if has_tag(URGENT_TAGS) {
+        reasons.push("带有紧急标签".to_string());
+        Priority::High
+    } else if let Some(due) = task.due_date {
+        if due < now {
+            reasons.push("已逾期".to_string());
+        } else {
+            reasons.push("24 小时内截止".to_string());
+        }
+        Priority::High
+    } else if has_tag(SOMEDAY_TAGS) {
+        reasons.push("带有以后再说标签".to_string());
+        Priority::Low
+    } else if now - task.created_at > Duration::days(STALE_DAYS) {
+        reasons.push(format!("已积压 {} 天", (now - task.created_at).num_days()));
+        Priority::Medium
+    } else {
+        reasons.push("尚未安排".to_string());
+        task.priority
+    };
+
+    // 没有截止时间时按优先级安排：高 → 今天，中 → 3 天后，低 → 一周后（当天 18:00）
+    let due_date = match task.due_date {
+        Some(due) => Some(due),
+        None => {
+            let days = match priority {
+                Priority::High => 0,
+                Priority::Medium => 3,
+                Priority::Low => 7,
+            };
+            reasons.push(format!("建议 {} 天内完成", days));
+            schedule_at(now, days)
+        }
+    };
+
+    Some(Proposal {
+        task_id: task.id?,
+        title: task.title.clone(),
+        priority,
+        due_date,
+        reasons,
+    })
+}
+
+/// days 天后本地 18:00
+fn schedule_at(now: DateTime<Utc>, days: i64) -> Option<DateTime<Utc>> {
+    let date = now.with_timezone(&Local).date_naive() + Duration::days(days);
+    Local
+        .from_local_datetime(&date.and_hms_opt(18, 0, 0)?)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
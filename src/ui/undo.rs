@@ -0,0 +1,205 @@
+// 撤销/重做（u / Ctrl-r）：每次修改任务（或删除便签）时记录修改前后的快照，
+// 撤销时把数据库恢复到修改前的状态，重做时再写回修改后的状态；只保存在内存中，退出后清空
+
+use crate::db::{Database, TaskSnapshot};
+use crate::error::{Error, Result};
+use crate::models::{Note, Task};
+
+/// 最多保留的撤销步数
+const MAX_HISTORY: usize = 100;
+
+/// 对单个任务或便签的修改（快照装箱，撤销栈中的每一项都很小）
+#[derive(Debug, Clone)]
+pub enum Change {
+    /// 编辑、完成、改优先级等
+    Update { before: Box<Task>, after: Box<Task> },
+    /// 新建任务（如完成重复任务时创建的下一次）
+    Create(Box<Task>),
+    /// 删除任务（移到回收站），保存 trash_task 返回的快照，撤销时连同评论、计时、依赖等一起恢复
+    Delete(Box<TaskSnapshot>),
+    /// 删除便签（移到回收站）
+    DeleteNote(Box<Note>),
+}
+
+impl Change {
+    pub fn update(before: Task, after: Task) -> Self {
+        Change::Update { before: Box::new(before), after: Box::new(after) }
+    }
+
+    pub fn create(task: Task) -> Self {
+        Change::Create(Box::new(task))
+    }
+
+    fn task_id(task: &Task) -> Result<i64> {
+        task.id.ok_or_else(|| Error::Validation("任务缺少 ID".to_string()))
+    }
+
+    /// 撤销：恢复到修改前
+    fn revert(&self, db: &Database) -> Result<()> {
+        match self {
+            Change::Update { before, .. } => db.update_task(before),
+            Change::Create(task) => db.delete_task(Self::task_id(task)?),
+            Change::Delete(snapshot) => db.restore_task(snapshot),
+            Change::DeleteNote(note) => db.restore_note(note),
+        }
+    }
+
+    /// 重做：再次应用修改
+    fn apply(&self, db: &Database) -> Result<()> {
+        match self {
+            Change::Update { after, .. } => db.update_task(after),
+            Change::Create(task) => db.restore_task(&TaskSnapshot::new(Task::clone(task))),
+            Change::Delete(snapshot) => db.trash_task(Self::task_id(&snapshot.task)?).map(|_| ()),
+            Change::DeleteNote(note) => db.trash_note(
+                note.id.ok_or_else(|| Error::Validation("便签缺少 ID".to_string()))?,
+            ),
+        }
+    }
+}
+
+/// 一步操作（如"完成任务"），可能包含多个任务的修改
+#[derive(Debug, Clone)]
+struct Entry {
+    label: String,
+    changes: Vec<Change>,
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Entry>,
+    redo: Vec<Entry>,
+}
+
+impl History {
+    /// 记录一步操作；新的操作会清空重做栈
+    pub fn record(&mut self, label: impl Into<String>, changes: Vec<Change>) {
+        if changes.is_empty() {
+            return;
+        }
+        self.undo.push(Entry { label: label.into(), changes });
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// 撤销最近一步，返回该步的说明；没有可撤销的操作时返回 None
+    ///
+    /// 一步中的修改在同一个事务中撤销；失败时（如任务已被其他进程删除）已撤销的部分回滚，
+    /// 该步留在撤销栈中
+    pub fn undo(&mut self, db: &Database) -> Result<Option<String>> {
+        let entry = match self.undo.pop() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let result = db.in_transaction(|db| entry.changes.iter().rev().try_for_each(|change| change.revert(db)));
+        if let Err(e) = result {
+            self.undo.push(entry);
+            return Err(e);
+        }
+        let label = entry.label.clone();
+        self.redo.push(entry);
+        Ok(Some(label))
+    }
+
+    /// 重做最近撤销的一步；失败时回滚，该步留在重做栈中
+    pub fn redo(&mut self, db: &Database) -> Result<Option<String>> {
+        let entry = match self.redo.pop() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let result = db.in_transaction(|db| entry.changes.iter().try_for_each(|change| change.apply(db)));
+        if let Err(e) = result {
+            self.redo.push(entry);
+            return Err(e);
+        }
+        let label = entry.label.clone();
+        self.undo.push(entry);
+        Ok(Some(label))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn create(db: &Database, title: &str) -> Task {
+        let id = db.create_task(&Task::new(title.to_string())).unwrap();
+        db.get_task(id).unwrap()
+    }
+
+    #[test]
+    fn failed_undo_rolls_back_and_keeps_entry() {
+        let db = Database::open_in_memory().unwrap();
+        let first = create(&db, "first");
+        let second = create(&db, "second");
+        let renamed = |task: &Task| Task { title: format!("{} (edited)", task.title), ..task.clone() };
+        db.update_task(&renamed(&first)).unwrap();
+        db.update_task(&renamed(&second)).unwrap();
+
+        let mut history = History::default();
+        history.record(
+            "edit",
+            vec![
+                Change::update(second.clone(), renamed(&second)),
+                Change::update(first.clone(), renamed(&first)),
+            ],
+        );
+        // 其他进程删除了 second：撤销时先恢复 first，再恢复 second 失败
+        db.delete_task(second.id.unwrap()).unwrap();
+
+        assert!(history.undo(&db).is_err());
+        assert_eq!(db.get_task(first.id.unwrap()).unwrap().title, "first (edited)");
+        assert_eq!(history.undo.len(), 1);
+        assert!(history.redo.is_empty());
+    }
+
+    #[test]
+    fn undo_and_redo_delete_inside_transaction() {
+        let db = Database::open_in_memory().unwrap();
+        let task = create(&db, "task");
+        let id = task.id.unwrap();
+        let snapshot = db.trash_task(id).unwrap();
+
+        let mut history = History::default();
+        history.record("delete", vec![Change::Delete(Box::new(snapshot))]);
+        assert_eq!(history.undo(&db).unwrap().as_deref(), Some("delete"));
+        assert!(db.get_task(id).is_ok());
+        // 重做调用的 trash_task 自己也开始写事务，在外层事务中改用保存点
+        assert_eq!(history.redo(&db).unwrap().as_deref(), Some("delete"));
+        assert!(db.get_task(id).is_err());
+    }
+
+    #[test]
+    fn undo_delete_restores_time_entries_and_dependencies() {
+        let db = Database::open_in_memory().unwrap();
+        let task = create(&db, "task");
+        let blocker = create(&db, "blocker");
+        let blocked = create(&db, "blocked");
+        let (id, blocker_id, blocked_id) = (task.id.unwrap(), blocker.id.unwrap(), blocked.id.unwrap());
+        db.add_dependency(id, blocker_id).unwrap();
+        db.add_dependency(blocked_id, id).unwrap();
+        let started = Utc::now() - Duration::hours(1);
+        db.start_time_entry(id, started).unwrap();
+        db.stop_time_entry(started + Duration::minutes(30)).unwrap();
+        db.log_reminder(id, started, started).unwrap();
+
+        // 外键的 ON DELETE CASCADE 会删除计时、依赖和提醒记录
+        let snapshot = db.trash_task(id).unwrap();
+        assert!(db.get_task_time_entries(id).unwrap().is_empty());
+        assert!(db.get_dependents(blocker_id).unwrap().is_empty());
+
+        let mut history = History::default();
+        history.record("delete", vec![Change::Delete(Box::new(snapshot))]);
+        history.undo(&db).unwrap();
+
+        let entries = db.get_task_time_entries(id).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].seconds(Utc::now()), 30 * 60);
+        assert_eq!(db.get_dependencies(id).unwrap()[0].id, Some(blocker_id));
+        assert_eq!(db.get_dependencies(blocked_id).unwrap()[0].id, Some(id));
+        assert!(db.reminder_sent(id, started).unwrap());
+        assert!(db.get_trash().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+// 版本更新检查（tasks self check-update）和内置的更新日志（:changelog）
+// 只在用户手动执行命令时访问 GitHub，不会在后台自动检查
+
+use std::process::Command;
+
+use crate::error::{Error, Result};
+
+/// 编译时内置的更新日志
+pub const CHANGELOG: &str = include_str!("../../CHANGELOG.md");
+
+/// 当前版本
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const RELEASES_API: &str = "https://api.github.com/repos/stibiums/terminator_task_manager/releases/latest";
+
+/// GitHub 上的最新发布
+pub struct Release {
+    pub version: String,
+    pub url: String,
+    pub notes: String,
+}
+
+/// 查询最新发布（通过 curl 访问 GitHub API）
+pub fn latest_release() -> Result<Release> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "-m", "10", "-H", "Accept: application/vnd.github+json", RELEASES_API])
+        .output()
+        .map_err(|e| Error::Remote(format!("无法运行 curl: {}", e)))?;
+    if !output.status.success() {
+        return Err(Error::Remote(format!(
+            "查询失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let value: serde_json::Value =
+        serde_json::from_slice(&output.stdout).map_err(|e| Error::Remote(format!("无效的响应: {}", e)))?;
+    let tag = value["tag_name"]
+        .as_str()
+        .ok_or_else(|| Error::Remote("响应中没有版本号".to_string()))?;
+    Ok(Release {
+        version: tag.trim_start_matches('v').to_string(),
+        url: value["html_url"].as_str().unwrap_or_default().to_string(),
+        notes: value["body"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+/// `latest` 是否比 `current` 新（按点分隔的数字逐段比较，忽略预发布后缀）
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    let parse = |v: &str| -> Vec<u64> {
+        v.split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+    parse(latest) > parse(current)
+}